@@ -0,0 +1,369 @@
+//! Inventory of the SVG features a document uses, to find out what keeps artwork from
+//! converting cleanly before migrating a whole icon set.
+
+use std::{collections::BTreeMap, fmt};
+
+use roxmltree::{Document, Node};
+use svgtypes::{PathSegment, TransformListToken};
+
+use crate::{
+    color::{Color, ColorAdjustment, PebbleColor, TruncateColor},
+    error::Svg2PdcResult,
+    paint::{Paint, property, resolve_paint},
+    svg_converter::SvgConverter,
+};
+
+/// An SVG feature that is lost or approximated in a draw command image.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Feature {
+    /// Bezier curve segments, reduced to their end points unless curves are flattened.
+    Curves,
+    /// Elliptical arc segments, reduced to their end points unless curves are flattened.
+    Arcs,
+    /// Rotations, scales, skews and matrices, only translations are applied.
+    Transforms,
+    Gradients,
+    Text,
+    ClipPaths,
+    Masks,
+    Images,
+    /// A drawn element without a draw command equivalent, by tag name.
+    UnsupportedTag(String),
+}
+
+impl fmt::Display for Feature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Curves => f.write_str("curves"),
+            Self::Arcs => f.write_str("arcs"),
+            Self::Transforms => f.write_str("transforms other than translate"),
+            Self::Gradients => f.write_str("gradients"),
+            Self::Text => f.write_str("text"),
+            Self::ClipPaths => f.write_str("clip paths"),
+            Self::Masks => f.write_str("masks"),
+            Self::Images => f.write_str("images"),
+            Self::UnsupportedTag(tag) => write!(f, "unsupported <{tag}>"),
+        }
+    }
+}
+
+/// How often every feature occurs in a document.
+///
+/// ```rust
+/// use svg2pdc::{analyze::{Feature, features}, roxmltree::Document};
+///
+/// let document = Document::parse(
+///     r#"<svg xmlns="http://www.w3.org/2000/svg"><path d="M0 0 C1 1 2 2 3 3 Q4 4 5 5"/><text>Hi</text></svg>"#,
+/// ).unwrap();
+/// let usage = features(&document).unwrap();
+/// assert_eq!(usage[&Feature::Curves], 2);
+/// assert_eq!(usage[&Feature::Text], 1);
+/// ```
+pub fn features(document: &Document<'_>) -> Svg2PdcResult<BTreeMap<Feature, usize>> {
+    let mut usage = BTreeMap::new();
+    visit(document.root_element(), true, &mut usage)?;
+    Ok(usage)
+}
+
+/// Count the features of `node` and its descendants. Only `drawn` elements end up in the
+/// image, the content of `<defs>` or a `<clipPath>` is never converted by itself.
+fn visit(
+    node: Node<'_, '_>,
+    drawn: bool,
+    usage: &mut BTreeMap<Feature, usize>,
+) -> Svg2PdcResult<()> {
+    let mut count = |feature| *usage.entry(feature).or_insert(0) += 1;
+    if drawn
+        && property(node, "display").is_some_and(|display| display.eq_ignore_ascii_case("none"))
+    {
+        return Ok(());
+    }
+    if let Some(transform) = node.attribute("transform") {
+        for token in svgtypes::TransformListParser::from(transform) {
+            if !matches!(token?, TransformListToken::Translate { .. }) {
+                count(Feature::Transforms);
+                break;
+            }
+        }
+    }
+
+    let mut children_drawn = drawn;
+    match node.tag_name().name() {
+        "svg" | "g" => {}
+        "path" => {
+            for segment in svgtypes::PathParser::from(node.attribute("d").unwrap_or("")) {
+                match segment? {
+                    PathSegment::CurveTo { .. }
+                    | PathSegment::SmoothCurveTo { .. }
+                    | PathSegment::Quadratic { .. }
+                    | PathSegment::SmoothQuadratic { .. } => count(Feature::Curves),
+                    PathSegment::EllipticalArc { .. } => count(Feature::Arcs),
+                    _ => {}
+                }
+            }
+        }
+        "circle" | "polyline" | "polygon" | "line" | "rect" => {}
+        "linearGradient" | "radialGradient" => {
+            count(Feature::Gradients);
+            children_drawn = false;
+        }
+        "text" => {
+            count(Feature::Text);
+            children_drawn = false;
+        }
+        "clipPath" => {
+            count(Feature::ClipPaths);
+            children_drawn = false;
+        }
+        "mask" => {
+            count(Feature::Masks);
+            children_drawn = false;
+        }
+        "image" => count(Feature::Images),
+        tag if SvgConverter::DEFAULT_IGNORED_TAGS.contains(&tag) => children_drawn = false,
+        tag => {
+            if drawn {
+                count(Feature::UnsupportedTag(tag.to_string()));
+            }
+            children_drawn = false;
+        }
+    }
+
+    for child in node.children().filter(|child| is_svg_element(*child)) {
+        visit(child, children_drawn, usage)?;
+    }
+    Ok(())
+}
+
+/// Elements of other namespaces, like editor metadata, are never drawn.
+fn is_svg_element(node: Node<'_, '_>) -> bool {
+    node.is_element()
+        && matches!(
+            node.tag_name().namespace(),
+            None | Some("http://www.w3.org/2000/svg")
+        )
+}
+
+/// Feature usage aggregated over many documents.
+#[derive(Debug, Clone, Default)]
+pub struct FeatureSummary {
+    /// Number of documents added.
+    pub documents: usize,
+    /// Total occurrences of every feature.
+    pub occurrences: BTreeMap<Feature, usize>,
+    /// Number of documents using every feature.
+    pub documents_using: BTreeMap<Feature, usize>,
+}
+
+impl FeatureSummary {
+    pub fn add(&mut self, usage: &BTreeMap<Feature, usize>) {
+        self.documents += 1;
+        for (feature, count) in usage {
+            *self.occurrences.entry(feature.clone()).or_insert(0) += count;
+            *self.documents_using.entry(feature.clone()).or_insert(0) += 1;
+        }
+    }
+
+    /// The features ordered by the number of documents using them, most common first.
+    pub fn by_documents(&self) -> Vec<(&Feature, usize)> {
+        let mut features: Vec<_> = self
+            .documents_using
+            .iter()
+            .map(|(feature, documents)| (feature, *documents))
+            .collect();
+        features.sort_by(|(_, a), (_, b)| b.cmp(a));
+        features
+    }
+}
+
+/// Source colors further apart than this look different, see [`Color::distance`].
+pub const COLLISION_DISTANCE: f32 = 0.1;
+
+/// A palette color of an image and the SVG colors that were quantized to it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PaletteEntry {
+    pub color: PebbleColor,
+    /// Distinct fill and stroke colors of the document, in document order.
+    pub sources: Vec<Color>,
+}
+
+/// Two source colors that look different but end up as the same palette color.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PaletteCollision {
+    pub color: PebbleColor,
+    pub first: Color,
+    pub second: Color,
+}
+
+/// Written as `#EE1111 and #AA2222 both become Red`.
+impl fmt::Display for PaletteCollision {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let hex = |color: Color| format!("#{:02X}{:02X}{:02X}", color.r, color.g, color.b);
+        write!(
+            f,
+            "{} and {} both become ",
+            hex(self.first),
+            hex(self.second)
+        )?;
+        match self.color.name() {
+            Some(name) => f.write_str(name),
+            None => f.write_str(&self.color.to_hex()),
+        }
+    }
+}
+
+impl PaletteEntry {
+    /// Pairs of sources that are visibly different, [`COLLISION_DISTANCE`] or more apart.
+    pub fn collisions(&self) -> Vec<PaletteCollision> {
+        let mut collisions = Vec::new();
+        for (index, &first) in self.sources.iter().enumerate() {
+            for &second in &self.sources[index + 1..] {
+                if first.distance(&second) >= COLLISION_DISTANCE {
+                    collisions.push(PaletteCollision {
+                        color: self.color,
+                        first,
+                        second,
+                    });
+                }
+            }
+        }
+        collisions
+    }
+}
+
+/// The palette colors the fills and strokes of a document are quantized to, ordered by their
+/// Pebble color byte, with the source colors of each.
+///
+/// Colors are quantized at full opacity, the way the converter does with `truncate_color` and
+/// `adjustment`. Fills that become black are drawn as nothing, so they are listed under
+/// [`PebbleColor::CLEAR`].
+///
+/// ```rust
+/// use svg2pdc::{analyze::palette, color::{ColorAdjustment, PebbleColor, TruncateColor}, roxmltree::Document};
+///
+/// let document = Document::parse(
+///     r##"<svg xmlns="http://www.w3.org/2000/svg">
+///         <rect fill="#fe5050" stroke="#0000ff"/>
+///         <circle fill="#aa0000"/>
+///     </svg>"##,
+/// ).unwrap();
+/// let palette = palette(&document, &TruncateColor::Truncate, &ColorAdjustment::default());
+/// assert_eq!(palette.len(), 2);
+/// assert_eq!(palette[0].color, PebbleColor::BLUE);
+/// assert_eq!(palette[1].color, PebbleColor::DARK_CANDY_APPLE_RED);
+/// assert_eq!(palette[1].sources.len(), 2);
+/// assert_eq!(palette[1].collisions().len(), 1);
+/// ```
+pub fn palette(
+    document: &Document<'_>,
+    truncate_color: &TruncateColor,
+    adjustment: &ColorAdjustment,
+) -> Vec<PaletteEntry> {
+    let mut entries = BTreeMap::new();
+    collect_paints(document, document.root_element(), &mut |property, color| {
+        let quantized = Paint::Color(color).quantize(255, adjustment, truncate_color);
+        // This is a pebble caveat, a black fill is treated as transparent
+        let quantized = if property == "fill" && quantized.is_black() {
+            PebbleColor::CLEAR
+        } else {
+            quantized
+        };
+        let entry = entries
+            .entry(quantized.inner())
+            .or_insert_with(|| PaletteEntry {
+                color: quantized,
+                sources: Vec::new(),
+            });
+        if !entry.sources.contains(&color) {
+            entry.sources.push(color);
+        }
+    });
+    entries.into_values().collect()
+}
+
+/// Call `paint` with every fill and stroke color of the drawn elements under `node`.
+fn collect_paints(
+    document: &Document<'_>,
+    node: Node<'_, '_>,
+    paint: &mut impl FnMut(&str, Color),
+) {
+    if property(node, "display").is_some_and(|display| display.eq_ignore_ascii_case("none")) {
+        return;
+    }
+    for name in ["fill", "stroke"] {
+        if let Some(Paint::Color(color)) =
+            property(node, name).map(|value| resolve_paint(document, value))
+        {
+            paint(name, color);
+        }
+    }
+    if matches!(node.tag_name().name(), "svg" | "g") {
+        for child in node.children().filter(|child| is_svg_element(*child)) {
+            collect_paints(document, child, paint);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_only_drawn_elements_count_as_unsupported() {
+        let document = Document::parse(
+            r##"<svg xmlns="http://www.w3.org/2000/svg"
+                    xmlns:sodipodi="http://sodipodi.sourceforge.net/DTD/sodipodi-0.dtd">
+                <sodipodi:namedview/>
+                <metadata><title>Icon</title></metadata>
+                <defs>
+                    <linearGradient id="shade"><stop offset="0"/></linearGradient>
+                    <clipPath id="frame"><ellipse rx="2" ry="3"/></clipPath>
+                </defs>
+                <g transform="translate(1, 1) rotate(45)">
+                    <ellipse rx="2" ry="3"/>
+                    <use href="#frame"/>
+                </g>
+                <ellipse display="none" rx="2" ry="3"/>
+            </svg>"##,
+        )
+        .unwrap();
+        let usage = features(&document).unwrap();
+        assert_eq!(
+            usage.into_iter().collect::<Vec<_>>(),
+            vec![
+                (Feature::Transforms, 1),
+                (Feature::Gradients, 1),
+                (Feature::ClipPaths, 1),
+                (Feature::UnsupportedTag("ellipse".to_string()), 1),
+                (Feature::UnsupportedTag("use".to_string()), 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_palette_of_drawn_paints() {
+        let document = Document::parse(
+            r##"<svg xmlns="http://www.w3.org/2000/svg">
+                <defs>
+                    <linearGradient id="shade"><stop offset="0" stop-color="#00ff00"/></linearGradient>
+                    <rect fill="#ffff00"/>
+                </defs>
+                <g stroke="#fefefe">
+                    <rect fill="url(#shade)" stroke="#ffffff"/>
+                    <circle fill="#000000"/>
+                </g>
+                <rect display="none" fill="#ff00ff"/>
+            </svg>"##,
+        )
+        .unwrap();
+        let palette = palette(&document, &TruncateColor::Keep, &ColorAdjustment::default());
+        let colors: Vec<_> = palette.iter().map(|entry| entry.color).collect();
+        assert_eq!(
+            colors,
+            vec![PebbleColor::CLEAR, PebbleColor::GREEN, PebbleColor::WHITE]
+        );
+        // Nearly identical colors don't collide
+        assert_eq!(palette[2].sources.len(), 2);
+        assert!(palette[2].collisions().is_empty());
+    }
+}
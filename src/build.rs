@@ -0,0 +1,188 @@
+//! Converting SVGs from build scripts, for programs that embed their draw command images.
+//!
+//! ```rust,no_run
+//! // build.rs
+//! use svg2pdc::prelude::*;
+//!
+//! fn main() {
+//!     let out_dir = std::path::PathBuf::from(std::env::var("OUT_DIR").unwrap());
+//!     let converter = SvgConverter::new(Precision::Normal);
+//!     let manifest = svg2pdc::build::convert_dir("icons", out_dir.join("icons"), &converter)
+//!         .unwrap();
+//!     std::fs::write(out_dir.join("icons.rs"), manifest.rust_module()).unwrap();
+//! }
+//! ```
+//!
+//! The program then includes the images with
+//! `include!(concat!(env!("OUT_DIR"), "/icons.rs"));`.
+
+use std::path::{Path, PathBuf};
+
+use crate::{
+    error::{Svg2PdcError, Svg2PdcResult},
+    pack::identifier,
+    svg_converter::SvgConverter,
+};
+
+/// File of the output directory of [`convert_dir`] with the hash of the options its images
+/// were converted with.
+const OPTIONS_FILE_NAME: &str = ".svg2pdc-options";
+
+/// An image written by [`convert_dir`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GeneratedImage {
+    /// Path of the SVG relative to the source directory, without extension and with `/`
+    /// separators, e.g. `weather/sun`.
+    pub name: String,
+    pub source: PathBuf,
+    pub output: PathBuf,
+    /// Whether the image was converted, not kept because it was newer than its SVG and
+    /// converted with the same options.
+    pub converted: bool,
+}
+
+/// The images written by [`convert_dir`], ordered by name.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BuildManifest {
+    pub images: Vec<GeneratedImage>,
+}
+
+impl BuildManifest {
+    pub fn get(&self, name: &str) -> Option<&GeneratedImage> {
+        self.images.iter().find(|image| image.name == name)
+    }
+
+    /// Rust source with one `pub const` byte slice per image, named after the image in
+    /// uppercase and filled with `include_bytes!`, so that the program is rebuilt with the
+    /// images.
+    ///
+    /// ```rust
+    /// use svg2pdc::build::{BuildManifest, GeneratedImage};
+    ///
+    /// let manifest = BuildManifest {
+    ///     images: vec![GeneratedImage {
+    ///         name: "weather/sun".to_string(),
+    ///         source: "icons/weather/sun.svg".into(),
+    ///         output: "/out/weather/sun.pdc".into(),
+    ///         converted: true,
+    ///     }],
+    /// };
+    /// assert!(manifest.rust_module().contains(
+    ///     "pub const WEATHER_SUN: &[u8] = include_bytes!(\"/out/weather/sun.pdc\");"
+    /// ));
+    /// ```
+    pub fn rust_module(&self) -> String {
+        let mut module = String::new();
+        for image in &self.images {
+            module.push_str(&format!(
+                "/// Draw command image converted from `{}`.\npub const {}: &[u8] = include_bytes!({:?});\n",
+                image.source.display(),
+                identifier(&image.name).to_uppercase(),
+                image.output.display().to_string(),
+            ));
+        }
+        module
+    }
+}
+
+/// Convert the SVGs of `source` and its subdirectories with `converter` into PDC files
+/// mirroring them in `output`, for use in `build.rs`.
+///
+/// An SVG is only converted when it is newer than its image, so a build converts the SVGs
+/// that changed since the last one. A hash of the options of `converter` is kept in `output`,
+/// and all SVGs are converted again when they change. Cargo is told to run the build script again when
+/// anything in `source` changes, and the warnings of the conversions are passed on as Cargo
+/// warnings, as the output of build scripts is hidden.
+pub fn convert_dir(
+    source: impl AsRef<Path>,
+    output: impl AsRef<Path>,
+    converter: &SvgConverter,
+) -> Svg2PdcResult<BuildManifest> {
+    let (source, output) = (source.as_ref(), output.as_ref());
+    println!("cargo:rerun-if-changed={}", source.display());
+    let options = format!(
+        "{:016x}",
+        fnv1a(format!("{} {converter:?}", env!("CARGO_PKG_VERSION")).as_bytes())
+    );
+    let options_file = output.join(OPTIONS_FILE_NAME);
+    let options_changed = std::fs::read_to_string(&options_file).ok() != Some(options.clone());
+    let mut manifest = BuildManifest::default();
+    for file in svg_files(source)? {
+        let relative = file.strip_prefix(source).unwrap_or(&file);
+        let pdc = output.join(relative).with_extension("pdc");
+        let image = GeneratedImage {
+            name: relative
+                .with_extension("")
+                .components()
+                .map(|component| component.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/"),
+            converted: options_changed || is_outdated(&file, &pdc),
+            output: pdc,
+            source: file,
+        };
+        if image.converted {
+            convert_file(&image, converter).map_err(|error| Svg2PdcError::InFile {
+                path: image.source.clone(),
+                source: Box::new(error),
+            })?;
+        }
+        manifest.images.push(image);
+    }
+    if options_changed {
+        std::fs::create_dir_all(output)?;
+        std::fs::write(options_file, options)?;
+    }
+    Ok(manifest)
+}
+
+fn convert_file(image: &GeneratedImage, converter: &SvgConverter) -> Svg2PdcResult<()> {
+    let content = std::fs::read_to_string(&image.source)?;
+    let (pdc, report) = converter.parse_svg_image_with_report(
+        &content,
+        &converter.options.truncate_color,
+        &converter.options.conversion,
+    )?;
+    for warning in &report.warnings {
+        println!("cargo:warning={}: {warning}", image.source.display());
+    }
+    if let Some(directory) = image.output.parent() {
+        std::fs::create_dir_all(directory)?;
+    }
+    pdc.serialize(&mut std::fs::File::create(&image.output)?)
+}
+
+/// Whether `output` is missing or older than `source`.
+fn is_outdated(source: &Path, output: &Path) -> bool {
+    let modified = |path: &Path| std::fs::metadata(path).and_then(|metadata| metadata.modified());
+    match (modified(source), modified(output)) {
+        (Ok(source), Ok(output)) => source > output,
+        _ => true,
+    }
+}
+
+/// 64 bit FNV-1a hash of `bytes`, stable across builds unlike the hashers of the standard
+/// library.
+pub fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// The SVGs of `directory` and its subdirectories, sorted.
+fn svg_files(directory: &Path) -> Svg2PdcResult<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for entry in std::fs::read_dir(directory)? {
+        let entry = entry?.path();
+        if entry.is_dir() {
+            files.extend(svg_files(&entry)?);
+        } else if entry
+            .extension()
+            .is_some_and(|extension| extension == "svg")
+        {
+            files.push(entry);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
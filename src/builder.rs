@@ -0,0 +1,138 @@
+//! Authoring PDC images in code, without an SVG to convert.
+
+use crate::{
+    color::PebbleColor,
+    image::{DrawCommand, DrawOptions, PebbleImage},
+    point::{PebblePoint, Precision},
+};
+
+/// Builds a [`PebbleImage`] one command at a time. Styles apply to the command added last,
+/// commands are drawn in the order they are added.
+///
+/// ```rust
+/// use svg2pdc::prelude::*;
+///
+/// let (white, red) = (PebbleColor::WHITE, PebbleColor::RED);
+/// let image = PebbleImage::builder()
+///     .size(50, 50)
+///     .path([(5, 5), (45, 5), (25, 45)])
+///     .stroke(white, 2)
+///     .fill(red)
+///     .close()
+///     .circle((25, 20), 6)
+///     .fill(white)
+///     .build();
+///
+/// assert_eq!(image.commands.len(), 2);
+/// let DrawCommand::Path { open, options, .. } = &image.commands[0] else { unreachable!() };
+/// assert!(!open);
+/// assert_eq!((options.stroke_width, options.fill_color), (2, red.inner()));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct PebbleImageBuilder {
+    size: PebblePoint,
+    precision: Precision,
+    commands: Vec<DrawCommand>,
+}
+
+impl PebbleImage {
+    /// Start building an image in code, see [`PebbleImageBuilder`].
+    pub fn builder() -> PebbleImageBuilder {
+        PebbleImageBuilder::default()
+    }
+}
+
+impl PebbleImageBuilder {
+    /// Size of the canvas in pixels.
+    pub fn size(self, width: i16, height: i16) -> Self {
+        Self {
+            size: PebblePoint {
+                x: width,
+                y: height,
+            },
+            ..self
+        }
+    }
+
+    /// Precision of the paths added from now on. Precise paths take their points in eighths
+    /// of a pixel.
+    pub fn precision(self, precision: Precision) -> Self {
+        Self { precision, ..self }
+    }
+
+    /// Add an open path through `points`, without a stroke or a fill until they are set.
+    pub fn path(mut self, points: impl IntoIterator<Item = impl Into<PebblePoint>>) -> Self {
+        self.commands.push(DrawCommand::Path {
+            points: points.into_iter().map(Into::into).collect(),
+            open: true,
+            options: DrawOptions {
+                precision: self.precision,
+                ..Default::default()
+            },
+        });
+        self
+    }
+
+    /// Add a circle, without a stroke or a fill until they are set.
+    pub fn circle(mut self, center: impl Into<PebblePoint>, radius: u16) -> Self {
+        self.commands.push(DrawCommand::Circle {
+            center: center.into(),
+            radius,
+            options: DrawOptions::default(),
+        });
+        self
+    }
+
+    /// Stroke the last command with `color`, `width` pixels wide.
+    ///
+    /// # Panics
+    ///
+    /// When no command was added yet.
+    pub fn stroke(mut self, color: PebbleColor, width: u8) -> Self {
+        let options = self.last_options("stroke");
+        options.stroke_color = color.inner();
+        options.stroke_width = width;
+        self
+    }
+
+    /// Fill the last command with `color`.
+    ///
+    /// # Panics
+    ///
+    /// When no command was added yet.
+    pub fn fill(mut self, color: PebbleColor) -> Self {
+        self.last_options("fill").fill_color = color.inner();
+        self
+    }
+
+    /// Connect the last point of the last path back to its first. Circles are left as they
+    /// are.
+    ///
+    /// # Panics
+    ///
+    /// When no command was added yet.
+    pub fn close(mut self) -> Self {
+        match self.commands.last_mut() {
+            Some(DrawCommand::Path { open, .. }) => *open = false,
+            Some(DrawCommand::Circle { .. }) => {}
+            None => panic!("cannot close before adding a path"),
+        }
+        self
+    }
+
+    pub fn build(self) -> PebbleImage {
+        PebbleImage {
+            size: self.size,
+            commands: self.commands,
+        }
+    }
+
+    fn last_options(&mut self, action: &str) -> &mut DrawOptions {
+        match self.commands.last_mut() {
+            Some(DrawCommand::Path { options, .. } | DrawCommand::Circle { options, .. }) => {
+                options
+            }
+            None => panic!("cannot {action} before adding a path or a circle"),
+        }
+    }
+}
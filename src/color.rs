@@ -1,9 +1,22 @@
+use std::str::FromStr;
+
 use crate::error::{Svg2PdcError, Svg2PdcResult};
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Default)]
 pub enum TruncateColor {
     Truncate,
+    #[default]
     Keep,
+    /// Only black, white and transparent, for monochrome Pebbles. Colors with a
+    /// [`Color::luminance`] of at least `threshold` become white.
+    BlackAndWhite {
+        threshold: f32,
+    },
+}
+
+impl TruncateColor {
+    /// Luminance threshold of [`TruncateColor::BlackAndWhite`] when none is given.
+    pub const DEFAULT_BW_THRESHOLD: f32 = 0.5;
 }
 
 /// A color in the format of a 32-bit RGBA color.
@@ -41,6 +54,9 @@ impl Color {
     /// ```
     pub fn try_from_hex(hex: &str) -> Svg2PdcResult<Self> {
         let hex = hex.trim_start_matches('#');
+        if !matches!(hex.len(), 6 | 8) || !hex.is_ascii() {
+            return Err(Svg2PdcError::InvalidColor(hex.to_string()));
+        }
         let r = u8::from_str_radix(&hex[0..2], 16)
             .map_err(|_| Svg2PdcError::InvalidColor(hex.to_string()))?;
         let g = u8::from_str_radix(&hex[2..4], 16)
@@ -55,6 +71,32 @@ impl Color {
         Ok(Self { r, g, b, a })
     }
 
+    /// Parse a CSS color: 3, 4, 6 or 8 digit hex, a named color, `rgb()`, `rgba()`, `hsl()` or
+    /// `hsla()`. Hex digits without the `#` are accepted too, like [`Self::try_from_hex`] does.
+    ///
+    /// ```rust
+    /// use svg2pdc::color::Color;
+    ///
+    /// let red = Color::try_from_hex("#ff0000").unwrap();
+    /// for value in ["red", "#f00", "#F00F", "rgb(255, 0, 0)", "rgba(100%, 0%, 0%, 1)", "hsl(0, 100%, 50%)"] {
+    ///     assert_eq!(Color::parse(value).unwrap(), red, "{value}");
+    /// }
+    /// assert_eq!(Color::parse("hsla(0, 100%, 50%, 0.5)").unwrap(), red.with_opacity(128));
+    /// assert_eq!(Color::parse("transparent").unwrap().a, 0);
+    /// assert!(Color::parse("reddish").is_err());
+    /// ```
+    pub fn parse(value: &str) -> Svg2PdcResult<Self> {
+        match svgtypes::Color::from_str(value.trim()) {
+            Ok(color) => Ok(Self {
+                r: color.red,
+                g: color.green,
+                b: color.blue,
+                a: color.alpha,
+            }),
+            Err(_) => Self::try_from_hex(value.trim()),
+        }
+    }
+
     /// Modify the opacity of a color.
     ///
     /// ```rust
@@ -76,6 +118,216 @@ impl Color {
             a: opacity,
         }
     }
+
+    /// Apply a gamma and brightness adjustment to the color channels. The opacity is kept,
+    /// unless the adjustment forces it to be opaque.
+    ///
+    /// ```rust
+    /// use svg2pdc::color::{Color, ColorAdjustment};
+    ///
+    /// let gray = Color::try_from_hex("#404040").unwrap();
+    /// let lighter = gray.adjusted(&ColorAdjustment { gamma: 2.0, ..Default::default() });
+    /// assert_eq!(lighter, Color::try_from_hex("#808080").unwrap());
+    /// assert_eq!(gray.adjusted(&ColorAdjustment::default()), gray);
+    ///
+    /// let opaque = ColorAdjustment { force_opaque: true, ..Default::default() };
+    /// assert_eq!(gray.with_opacity(250).adjusted(&opaque), gray);
+    /// assert_eq!(gray.with_opacity(0).adjusted(&opaque).a, 0);
+    /// ```
+    pub fn adjusted(&self, adjustment: &ColorAdjustment) -> Self {
+        if *adjustment == ColorAdjustment::default() {
+            return *self;
+        }
+        let channel = |value: u8| {
+            let linear = (value as f32 / 255.0).powf(1.0 / adjustment.gamma);
+            (linear * adjustment.brightness * 255.0)
+                .round()
+                .clamp(0.0, 255.0) as u8
+        };
+        let a = match self.a {
+            0 => 0,
+            _ if adjustment.force_opaque => 255,
+            a => a,
+        };
+        Self {
+            r: channel(self.r),
+            g: channel(self.g),
+            b: channel(self.b),
+            a,
+        }
+    }
+
+    /// Make the color lighter by `amount` (0 to 1) of the perceptual lightness range.
+    ///
+    /// ```rust
+    /// use svg2pdc::color::Color;
+    ///
+    /// let gray = Color::try_from_hex("#808080").unwrap();
+    /// let white = Color::try_from_hex("#ffffff").unwrap();
+    /// assert_eq!(gray.lighten(1.0), white);
+    /// assert!(gray.lighten(0.1).r > gray.r);
+    /// ```
+    pub fn lighten(&self, amount: f32) -> Self {
+        let [l, a, b] = self.oklab();
+        Self::from_oklab([(l + amount).clamp(0.0, 1.0), a, b], self.a)
+    }
+
+    /// Make the color darker by `amount` (0 to 1) of the perceptual lightness range.
+    ///
+    /// ```rust
+    /// use svg2pdc::color::Color;
+    ///
+    /// let gray = Color::try_from_hex("#808080").unwrap();
+    /// let black = Color::try_from_hex("#000000").unwrap();
+    /// assert_eq!(gray.darken(1.0), black);
+    /// assert_eq!(gray.lighten(0.2).darken(0.2), gray);
+    /// ```
+    pub fn darken(&self, amount: f32) -> Self {
+        self.lighten(-amount)
+    }
+
+    /// The complementary color, every channel mirrored, keeping the opacity.
+    ///
+    /// ```rust
+    /// use svg2pdc::color::Color;
+    ///
+    /// let orange = Color::try_from_hex("#ff8000").unwrap();
+    /// assert_eq!(orange.invert(), Color::try_from_hex("#007fff").unwrap());
+    /// ```
+    pub fn invert(&self) -> Self {
+        Self {
+            r: 255 - self.r,
+            g: 255 - self.g,
+            b: 255 - self.b,
+            a: self.a,
+        }
+    }
+
+    /// Blend towards `other`, `ratio` 0 is this color and 1 is `other`.
+    ///
+    /// Colors are interpolated in a perceptual space, so halfway between two colors looks
+    /// halfway to the eye instead of muddy or too dark.
+    ///
+    /// ```rust
+    /// use svg2pdc::color::Color;
+    ///
+    /// let red = Color::try_from_hex("#ff0000").unwrap();
+    /// let blue = Color::try_from_hex("#0000ff").unwrap();
+    /// assert_eq!(red.mix(&blue, 0.0), red);
+    /// assert_eq!(red.mix(&blue, 1.0), blue);
+    /// let purple = red.mix(&blue, 0.5);
+    /// assert!(purple.r > 0 && purple.b > 0);
+    /// ```
+    pub fn mix(&self, other: &Self, ratio: f32) -> Self {
+        let ratio = ratio.clamp(0.0, 1.0);
+        let [l1, a1, b1] = self.oklab();
+        let [l2, a2, b2] = other.oklab();
+        let lerp = |from: f32, to: f32| from + (to - from) * ratio;
+        Self::from_oklab(
+            [lerp(l1, l2), lerp(a1, a2), lerp(b1, b2)],
+            lerp(self.a as f32, other.a as f32).round() as u8,
+        )
+    }
+
+    /// Luminance of the color from 0 for black to 1 for white, ignoring alpha. Green looks
+    /// brighter than red, which looks brighter than blue.
+    ///
+    /// ```rust
+    /// use svg2pdc::color::Color;
+    ///
+    /// assert_eq!(Color::try_from_hex("#ffffff").unwrap().luminance(), 1.0);
+    /// let gray = Color::try_from_hex("#808080").unwrap().luminance();
+    /// assert!((gray - 0.5).abs() < 0.01);
+    /// let green = Color::try_from_hex("#00ff00").unwrap().luminance();
+    /// let blue = Color::try_from_hex("#0000ff").unwrap().luminance();
+    /// assert!(green > blue);
+    /// ```
+    pub fn luminance(&self) -> f32 {
+        (0.2126 * self.r as f32 + 0.7152 * self.g as f32 + 0.0722 * self.b as f32) / 255.0
+    }
+
+    /// Perceptual distance to `other`, ignoring alpha. Colors closer than about 0.02 are hard
+    /// to tell apart, black and white are 1 apart.
+    ///
+    /// ```rust
+    /// use svg2pdc::color::Color;
+    ///
+    /// let black = Color::try_from_hex("#000000").unwrap();
+    /// let white = Color::try_from_hex("#ffffff").unwrap();
+    /// assert!((black.distance(&white) - 1.0).abs() < 0.01);
+    /// assert_eq!(white.distance(&white.with_opacity(0)), 0.0);
+    /// ```
+    pub fn distance(&self, other: &Self) -> f32 {
+        let ([l1, a1, b1], [l2, a2, b2]) = (self.oklab(), other.oklab());
+        ((l1 - l2).powi(2) + (a1 - a2).powi(2) + (b1 - b2).powi(2)).sqrt()
+    }
+
+    /// The color in the OKLab space, `[lightness, a, b]`.
+    fn oklab(&self) -> [f32; 3] {
+        let linear = |channel: u8| {
+            let channel = channel as f32 / 255.0;
+            if channel <= 0.04045 {
+                channel / 12.92
+            } else {
+                ((channel + 0.055) / 1.055).powf(2.4)
+            }
+        };
+        let (r, g, b) = (linear(self.r), linear(self.g), linear(self.b));
+        let l = (0.412_221_46 * r + 0.536_332_55 * g + 0.051_445_995 * b).cbrt();
+        let m = (0.211_903_5 * r + 0.680_699_5 * g + 0.107_396_96 * b).cbrt();
+        let s = (0.088_302_46 * r + 0.281_718_85 * g + 0.629_978_7 * b).cbrt();
+        [
+            0.210_454_26 * l + 0.793_617_8 * m - 0.004_072_047 * s,
+            1.977_998_5 * l - 2.428_592_2 * m + 0.450_593_7 * s,
+            0.025_904_037 * l + 0.782_771_77 * m - 0.808_675_77 * s,
+        ]
+    }
+
+    fn from_oklab([lightness, a, b]: [f32; 3], alpha: u8) -> Self {
+        let l = (lightness + 0.396_337_78 * a + 0.215_803_76 * b).powi(3);
+        let m = (lightness - 0.105_561_346 * a - 0.063_854_17 * b).powi(3);
+        let s = (lightness - 0.089_484_18 * a - 1.291_485_5 * b).powi(3);
+        let gamma = |channel: f32| {
+            let channel = channel.clamp(0.0, 1.0);
+            let channel = if channel <= 0.003_130_8 {
+                channel * 12.92
+            } else {
+                1.055 * channel.powf(1.0 / 2.4) - 0.055
+            };
+            (channel * 255.0).round().clamp(0.0, 255.0) as u8
+        };
+        Self {
+            r: gamma(4.076_741_7 * l - 3.307_711_6 * m + 0.230_969_94 * s),
+            g: gamma(-1.268_438 * l + 2.609_757_4 * m - 0.341_319_38 * s),
+            b: gamma(-0.004_196_086_3 * l - 0.703_418_6 * m + 1.707_614_7 * s),
+            a: alpha,
+        }
+    }
+}
+
+/// Global adjustment of colors before they are mapped to the Pebble palette.
+///
+/// Artwork tuned for desktop displays often lands on a washed-out or overly dark part of the
+/// palette, a small adjustment fixes a whole icon set at once.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ColorAdjustment {
+    /// Values above 1 lighten the midtones, values below 1 darken them.
+    pub gamma: f32,
+    /// Factor every channel is multiplied with after the gamma correction.
+    pub brightness: f32,
+    /// Make every color that isn't fully transparent opaque, so unintended 99% opacities
+    /// don't end up on a lower alpha level.
+    pub force_opaque: bool,
+}
+
+impl Default for ColorAdjustment {
+    fn default() -> Self {
+        Self {
+            gamma: 1.0,
+            brightness: 1.0,
+            force_opaque: false,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
@@ -93,21 +345,248 @@ impl Color {
 /// ```
 pub struct PebbleColor(u8);
 
+/// Names of the opaque palette colors in the Pebble SDK, without their `GColor` prefix,
+/// ordered by their color byte like [`PebbleColor::palette`].
+const PALETTE_NAMES: [&str; 64] = [
+    "Black",
+    "OxfordBlue",
+    "DukeBlue",
+    "Blue",
+    "DarkGreen",
+    "MidnightGreen",
+    "CobaltBlue",
+    "BlueMoon",
+    "IslamicGreen",
+    "JaegerGreen",
+    "TiffanyBlue",
+    "VividCerulean",
+    "Green",
+    "Malachite",
+    "MediumSpringGreen",
+    "Cyan",
+    "BulgarianRose",
+    "ImperialPurple",
+    "Indigo",
+    "ElectricUltramarine",
+    "ArmyGreen",
+    "DarkGray",
+    "Liberty",
+    "VeryLightBlue",
+    "KellyGreen",
+    "MayGreen",
+    "CadetBlue",
+    "PictonBlue",
+    "BrightGreen",
+    "ScreaminGreen",
+    "MediumAquamarine",
+    "ElectricBlue",
+    "DarkCandyAppleRed",
+    "JazzberryJam",
+    "Purple",
+    "VividViolet",
+    "WindsorTan",
+    "RoseVale",
+    "Purpureus",
+    "LavenderIndigo",
+    "Limerick",
+    "Brass",
+    "LightGray",
+    "BabyBlueEyes",
+    "SpringBud",
+    "Inchworm",
+    "MintGreen",
+    "Celeste",
+    "Red",
+    "Folly",
+    "FashionMagenta",
+    "Magenta",
+    "Orange",
+    "SunsetOrange",
+    "BrilliantRose",
+    "ShockingPink",
+    "ChromeYellow",
+    "Rajah",
+    "Melon",
+    "RichBrilliantLavender",
+    "Yellow",
+    "Icterine",
+    "PastelYellow",
+    "White",
+];
+
 impl PebbleColor {
+    /// `GColorClear`, fully transparent.
+    pub const CLEAR: Self = Self(0);
+
+    // The opaque colors of the palette, named like the `GColor` constants of the Pebble SDK
+    pub const BLACK: Self = Self::from_argb(3, 0, 0, 0);
+    pub const OXFORD_BLUE: Self = Self::from_argb(3, 0, 0, 1);
+    pub const DUKE_BLUE: Self = Self::from_argb(3, 0, 0, 2);
+    pub const BLUE: Self = Self::from_argb(3, 0, 0, 3);
+    pub const DARK_GREEN: Self = Self::from_argb(3, 0, 1, 0);
+    pub const MIDNIGHT_GREEN: Self = Self::from_argb(3, 0, 1, 1);
+    pub const COBALT_BLUE: Self = Self::from_argb(3, 0, 1, 2);
+    pub const BLUE_MOON: Self = Self::from_argb(3, 0, 1, 3);
+    pub const ISLAMIC_GREEN: Self = Self::from_argb(3, 0, 2, 0);
+    pub const JAEGER_GREEN: Self = Self::from_argb(3, 0, 2, 1);
+    pub const TIFFANY_BLUE: Self = Self::from_argb(3, 0, 2, 2);
+    pub const VIVID_CERULEAN: Self = Self::from_argb(3, 0, 2, 3);
+    pub const GREEN: Self = Self::from_argb(3, 0, 3, 0);
+    pub const MALACHITE: Self = Self::from_argb(3, 0, 3, 1);
+    pub const MEDIUM_SPRING_GREEN: Self = Self::from_argb(3, 0, 3, 2);
+    pub const CYAN: Self = Self::from_argb(3, 0, 3, 3);
+    pub const BULGARIAN_ROSE: Self = Self::from_argb(3, 1, 0, 0);
+    pub const IMPERIAL_PURPLE: Self = Self::from_argb(3, 1, 0, 1);
+    pub const INDIGO: Self = Self::from_argb(3, 1, 0, 2);
+    pub const ELECTRIC_ULTRAMARINE: Self = Self::from_argb(3, 1, 0, 3);
+    pub const ARMY_GREEN: Self = Self::from_argb(3, 1, 1, 0);
+    pub const DARK_GRAY: Self = Self::from_argb(3, 1, 1, 1);
+    pub const LIBERTY: Self = Self::from_argb(3, 1, 1, 2);
+    pub const VERY_LIGHT_BLUE: Self = Self::from_argb(3, 1, 1, 3);
+    pub const KELLY_GREEN: Self = Self::from_argb(3, 1, 2, 0);
+    pub const MAY_GREEN: Self = Self::from_argb(3, 1, 2, 1);
+    pub const CADET_BLUE: Self = Self::from_argb(3, 1, 2, 2);
+    pub const PICTON_BLUE: Self = Self::from_argb(3, 1, 2, 3);
+    pub const BRIGHT_GREEN: Self = Self::from_argb(3, 1, 3, 0);
+    pub const SCREAMIN_GREEN: Self = Self::from_argb(3, 1, 3, 1);
+    pub const MEDIUM_AQUAMARINE: Self = Self::from_argb(3, 1, 3, 2);
+    pub const ELECTRIC_BLUE: Self = Self::from_argb(3, 1, 3, 3);
+    pub const DARK_CANDY_APPLE_RED: Self = Self::from_argb(3, 2, 0, 0);
+    pub const JAZZBERRY_JAM: Self = Self::from_argb(3, 2, 0, 1);
+    pub const PURPLE: Self = Self::from_argb(3, 2, 0, 2);
+    pub const VIVID_VIOLET: Self = Self::from_argb(3, 2, 0, 3);
+    pub const WINDSOR_TAN: Self = Self::from_argb(3, 2, 1, 0);
+    pub const ROSE_VALE: Self = Self::from_argb(3, 2, 1, 1);
+    pub const PURPUREUS: Self = Self::from_argb(3, 2, 1, 2);
+    pub const LAVENDER_INDIGO: Self = Self::from_argb(3, 2, 1, 3);
+    pub const LIMERICK: Self = Self::from_argb(3, 2, 2, 0);
+    pub const BRASS: Self = Self::from_argb(3, 2, 2, 1);
+    pub const LIGHT_GRAY: Self = Self::from_argb(3, 2, 2, 2);
+    pub const BABY_BLUE_EYES: Self = Self::from_argb(3, 2, 2, 3);
+    pub const SPRING_BUD: Self = Self::from_argb(3, 2, 3, 0);
+    pub const INCHWORM: Self = Self::from_argb(3, 2, 3, 1);
+    pub const MINT_GREEN: Self = Self::from_argb(3, 2, 3, 2);
+    pub const CELESTE: Self = Self::from_argb(3, 2, 3, 3);
+    pub const RED: Self = Self::from_argb(3, 3, 0, 0);
+    pub const FOLLY: Self = Self::from_argb(3, 3, 0, 1);
+    pub const FASHION_MAGENTA: Self = Self::from_argb(3, 3, 0, 2);
+    pub const MAGENTA: Self = Self::from_argb(3, 3, 0, 3);
+    pub const ORANGE: Self = Self::from_argb(3, 3, 1, 0);
+    pub const SUNSET_ORANGE: Self = Self::from_argb(3, 3, 1, 1);
+    pub const BRILLIANT_ROSE: Self = Self::from_argb(3, 3, 1, 2);
+    pub const SHOCKING_PINK: Self = Self::from_argb(3, 3, 1, 3);
+    pub const CHROME_YELLOW: Self = Self::from_argb(3, 3, 2, 0);
+    pub const RAJAH: Self = Self::from_argb(3, 3, 2, 1);
+    pub const MELON: Self = Self::from_argb(3, 3, 2, 2);
+    pub const RICH_BRILLIANT_LAVENDER: Self = Self::from_argb(3, 3, 2, 3);
+    pub const YELLOW: Self = Self::from_argb(3, 3, 3, 0);
+    pub const ICTERINE: Self = Self::from_argb(3, 3, 3, 1);
+    pub const PASTEL_YELLOW: Self = Self::from_argb(3, 3, 3, 2);
+    pub const WHITE: Self = Self::from_argb(3, 3, 3, 3);
+
     pub const fn nothing() -> Self {
         Self(0)
     }
 
-    /// Pack a color into a PebbleColor.
+    /// Name of the color in the Pebble SDK without its `GColor` prefix, `None` for a partly
+    /// transparent color, which has no name.
+    ///
+    /// ```rust
+    /// use svg2pdc::color::PebbleColor;
+    ///
+    /// assert_eq!(PebbleColor::PICTON_BLUE.name(), Some("PictonBlue"));
+    /// assert_eq!(PebbleColor::nothing().name(), Some("Clear"));
+    /// assert_eq!(PebbleColor::from_argb(1, 3, 0, 0).name(), None);
+    /// ```
+    pub const fn name(&self) -> Option<&'static str> {
+        match self.get_a() {
+            3 => Some(PALETTE_NAMES[(self.0 & 0b11_1111) as usize]),
+            _ if self.0 == Self::CLEAR.0 => Some("Clear"),
+            _ => None,
+        }
+    }
+
+    /// The color with a Pebble SDK name, with or without its `GColor` prefix and in any case.
+    ///
+    /// ```rust
+    /// use svg2pdc::color::PebbleColor;
+    ///
+    /// assert_eq!(PebbleColor::from_name("GColorPictonBlue"), Some(PebbleColor::PICTON_BLUE));
+    /// assert_eq!(PebbleColor::from_name("red"), Some(PebbleColor::RED));
+    /// assert_eq!(PebbleColor::from_name("Scarlet"), None);
+    /// assert!(PebbleColor::palette().all(|color| PebbleColor::from_name(color.name().unwrap()) == Some(color)));
+    /// ```
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.trim();
+        let name = match name.get(..6) {
+            Some(prefix) if prefix.eq_ignore_ascii_case("gcolor") => &name[6..],
+            _ => name,
+        };
+        if name.eq_ignore_ascii_case("clear") {
+            return Some(Self::CLEAR);
+        }
+        Self::palette()
+            .zip(PALETTE_NAMES)
+            .find_map(|(color, palette_name)| {
+                palette_name.eq_ignore_ascii_case(name).then_some(color)
+            })
+    }
+
+    /// Pack a color into a PebbleColor, keeping the top 2 bits of every channel.
     ///
     /// Don't use this function directly, use `from_color_with_convert` or `from_color_with_truncate` instead.
     const fn from_color(Color { r, g, b, a }: Color) -> Self {
-        Self((a << 6) | (r << 4) | (g << 2) | b)
+        Self::from_argb(a >> 6, r >> 6, g >> 6, b >> 6)
+    }
+
+    /// Create a PebbleColor from its 2-bit channels.
+    ///
+    /// ```rust
+    /// use svg2pdc::color::PebbleColor;
+    ///
+    /// let color = PebbleColor::from_argb(3, 0, 2, 1);
+    /// assert_eq!(color.get_r(), 0);
+    /// assert_eq!(color.get_g(), 2);
+    /// assert_eq!(color.get_b(), 1);
+    /// assert_eq!(color.inner(), 0b1100_1001);
+    /// ```
+    pub const fn from_argb(a: u8, r: u8, g: u8, b: u8) -> Self {
+        Self(((a & 0b11) << 6) | ((r & 0b11) << 4) | ((g & 0b11) << 2) | (b & 0b11))
+    }
+
+    /// Wrap a raw color byte, e.g. one stored in [`crate::image::DrawOptions`].
+    pub const fn from_inner(inner: u8) -> Self {
+        Self(inner)
+    }
+
+    /// The 64 opaque colors of the Pebble palette, ordered by their color byte.
+    ///
+    /// ```rust
+    /// use svg2pdc::color::PebbleColor;
+    ///
+    /// let palette: Vec<_> = PebbleColor::palette().collect();
+    /// assert_eq!(palette.len(), 64);
+    /// assert_eq!(palette[0].inner(), 0b1100_0000);
+    /// assert_eq!(palette[63].inner(), 0xff);
+    /// ```
+    pub fn palette() -> impl Iterator<Item = Self> {
+        (0..64).map(|rgb| Self(0b1100_0000 | rgb))
     }
 
     /// Create a new PebbleColor from a Color.
     ///
     /// Will truncate the color with rounding.
+    ///
+    /// ```rust
+    /// use svg2pdc::color::{PebbleColor, Color};
+    ///
+    /// let green = Color::try_from_hex("#00c000").unwrap();
+    /// let green = PebbleColor::from_color_with_truncate(green);
+    ///
+    /// assert_eq!(green.get_r(), 0);
+    /// assert_eq!(green.get_g(), 2);
+    /// assert_eq!(green.get_b(), 0);
+    /// ```
     pub const fn from_color_with_truncate(Color { r, g, b, a }: Color) -> Self {
         let a = (a / 85) * 85;
 
@@ -138,18 +617,42 @@ impl PebbleColor {
     /// assert_eq!(pebble_white.get_a(), 3);
     /// ```
     pub const fn from_color_with_convert(Color { r, g, b, a }: Color) -> Self {
-        let a = (((a as f32 + 42_f32) / 85_f32) * 85_f32) as u8;
+        let a = nearest_channel(a);
         if a == 0 {
             return Self(0);
         }
 
-        let r = (((r as f32 + 42_f32) / 85_f32) * 85_f32) as u8;
-        let g = (((g as f32 + 42_f32) / 85_f32) * 85_f32) as u8;
-        let b = (((b as f32 + 42_f32) / 85_f32) * 85_f32) as u8;
+        let r = nearest_channel(r);
+        let g = nearest_channel(g);
+        let b = nearest_channel(b);
 
         Self::from_color(Color { r, g, b, a })
     }
 
+    /// Create a new PebbleColor from a Color for a monochrome display.
+    ///
+    /// Colors less than half opaque become transparent, the others opaque white when their
+    /// [`Color::luminance`] is at least `threshold` and opaque black otherwise.
+    ///
+    /// ```rust
+    /// use svg2pdc::color::{Color, PebbleColor};
+    ///
+    /// let orange = Color::try_from_hex("#ff8800").unwrap();
+    /// assert_eq!(PebbleColor::from_color_black_and_white(orange, 0.5), PebbleColor::WHITE);
+    /// assert_eq!(PebbleColor::from_color_black_and_white(orange, 0.7), PebbleColor::BLACK);
+    /// let faint = orange.with_opacity(100);
+    /// assert_eq!(PebbleColor::from_color_black_and_white(faint, 0.5), PebbleColor::CLEAR);
+    /// ```
+    pub fn from_color_black_and_white(color: Color, threshold: f32) -> Self {
+        if color.a < 128 {
+            Self::CLEAR
+        } else if color.luminance() >= threshold {
+            Self::WHITE
+        } else {
+            Self::BLACK
+        }
+    }
+
     /// Get the alpha component of the color.
     ///
     /// The alpha component is stored as 2 bits.
@@ -255,11 +758,120 @@ impl PebbleColor {
     ///
     /// let pebble_red = PebbleColor::from_color_with_truncate(red);
     ///
-    /// assert_eq!(pebble_red.inner(), 192 + 48;
+    /// assert_eq!(pebble_red.inner(), 192 + 48);
     /// ```
     pub const fn inner(&self) -> u8 {
         self.0
     }
 
+    /// The color the watch displays, with every 2-bit channel spread over a byte.
+    ///
+    /// Converting the result back with [`PebbleColor::from_color_with_truncate`] or
+    /// [`PebbleColor::from_color_with_convert`] gives the same color.
+    ///
+    /// ```rust
+    /// use svg2pdc::color::{Color, PebbleColor};
+    ///
+    /// let color = PebbleColor::from_argb(2, 3, 1, 0).to_color();
+    /// assert_eq!(color, Color { r: 255, g: 85, b: 0, a: 170 });
+    /// assert!(PebbleColor::palette().all(|color| {
+    ///     PebbleColor::from_color_with_truncate(color.to_color()) == color
+    ///         && PebbleColor::from_color_with_convert(color.to_color()) == color
+    /// }));
+    /// ```
+    pub const fn to_color(self) -> Color {
+        Color {
+            r: self.get_r() * 85,
+            g: self.get_g() * 85,
+            b: self.get_b() * 85,
+            a: self.get_a() * 85,
+        }
+    }
+
+    /// [`Color::lighten`] snapped back to the nearest palette color.
+    ///
+    /// Small amounts may not be enough to reach the next palette color.
+    ///
+    /// ```rust
+    /// use svg2pdc::color::PebbleColor;
+    ///
+    /// let dark_gray = PebbleColor::from_argb(3, 1, 1, 1);
+    /// assert_eq!(dark_gray.lighten(0.2), PebbleColor::from_argb(3, 2, 2, 2));
+    /// assert_eq!(dark_gray.lighten(0.01), dark_gray);
+    /// ```
+    pub fn lighten(self, amount: f32) -> Self {
+        Self::from_color_with_convert(self.to_color().lighten(amount))
+    }
+
+    /// [`Color::darken`] snapped back to the nearest palette color.
+    ///
+    /// ```rust
+    /// use svg2pdc::color::PebbleColor;
+    ///
+    /// let light_gray = PebbleColor::from_argb(3, 2, 2, 2);
+    /// assert_eq!(light_gray.darken(0.2), PebbleColor::from_argb(3, 1, 1, 1));
+    /// ```
+    pub fn darken(self, amount: f32) -> Self {
+        Self::from_color_with_convert(self.to_color().darken(amount))
+    }
+
+    /// [`Color::invert`], which always stays inside the palette.
+    ///
+    /// ```rust
+    /// use svg2pdc::color::PebbleColor;
+    ///
+    /// assert_eq!(
+    ///     PebbleColor::from_argb(3, 3, 1, 0).invert(),
+    ///     PebbleColor::from_argb(3, 0, 2, 3)
+    /// );
+    /// ```
+    pub fn invert(self) -> Self {
+        Self::from_color_with_convert(self.to_color().invert())
+    }
+
+    /// [`Color::mix`] snapped back to the nearest palette color.
+    ///
+    /// ```rust
+    /// use svg2pdc::color::PebbleColor;
+    ///
+    /// let black = PebbleColor::from_argb(3, 0, 0, 0);
+    /// let white = PebbleColor::from_argb(3, 3, 3, 3);
+    /// assert_eq!(black.mix(white, 0.0), black);
+    /// assert_eq!(black.mix(white, 1.0), white);
+    /// ```
+    pub fn mix(self, other: Self, ratio: f32) -> Self {
+        Self::from_color_with_convert(self.to_color().mix(&other.to_color(), ratio))
+    }
+
+    /// Hex notation (`#RRGGBBAA`) of [`PebbleColor::to_color`], as read by
+    /// [`Color::try_from_hex`].
+    ///
+    /// ```rust
+    /// use svg2pdc::color::{Color, PebbleColor};
+    ///
+    /// let orange = PebbleColor::from_argb(3, 3, 1, 0);
+    /// assert_eq!(orange.to_hex(), "#FF5500FF");
+    /// assert_eq!(Color::try_from_hex(&orange.to_hex()).unwrap(), orange.to_color());
+    /// ```
+    pub fn to_hex(&self) -> String {
+        let Color { r, g, b, a } = self.to_color();
+        format!("#{r:02X}{g:02X}{b:02X}{a:02X}")
+    }
+
+    /// Hex notation (`#RRGGBB`) of the color the watch displays, ignoring alpha.
+    pub(crate) fn rgb_hex(&self) -> String {
+        format!(
+            "#{:02X}{:02X}{:02X}",
+            self.get_r() * 85,
+            self.get_g() * 85,
+            self.get_b() * 85
+        )
+    }
+
     // fn truncate_to_pebble_palette
 }
+
+/// Round a channel to the nearest of the 4 levels (0, 85, 170, 255) of the Pebble palette.
+const fn nearest_channel(channel: u8) -> u8 {
+    ((channel as u16 + 42) / 85 * 85) as u8
+}
@@ -0,0 +1,252 @@
+//! Comparison of PDC images, e.g. of this converter's output against the original tool's.
+//!
+//! Byte differences tell whether two files are interchangeable, the semantic differences
+//! tell which command and which property causes them.
+
+use crate::{
+    color::PebbleColor,
+    image::{DrawCommand, PebbleImage},
+    point::{PebblePoint, Precision},
+};
+
+/// Offset of the first byte where `actual` differs from `expected`, including one of them
+/// ending early.
+///
+/// ```
+/// use svg2pdc::compare::first_byte_difference;
+///
+/// assert_eq!(first_byte_difference(b"PDCI", b"PDCI"), None);
+/// assert_eq!(first_byte_difference(b"PDCI", b"PDXI"), Some(2));
+/// assert_eq!(first_byte_difference(b"PDCI", b"PDC"), Some(3));
+/// ```
+pub fn first_byte_difference(expected: &[u8], actual: &[u8]) -> Option<usize> {
+    expected
+        .iter()
+        .zip(actual)
+        .position(|(expected, actual)| expected != actual)
+        .or_else(|| (expected.len() != actual.len()).then(|| expected.len().min(actual.len())))
+}
+
+/// Human readable descriptions of how `actual` differs from `expected`, one per property of
+/// the image or of one of its commands. Empty when the images are the same.
+pub fn semantic_differences(expected: &PebbleImage, actual: &PebbleImage) -> Vec<String> {
+    let mut differences = Vec::new();
+    if expected.size != actual.size {
+        differences.push(format!(
+            "size: expected {}x{}, found {}x{}",
+            expected.size.x, expected.size.y, actual.size.x, actual.size.y
+        ));
+    }
+    if expected.commands.len() != actual.commands.len() {
+        differences.push(format!(
+            "command count: expected {}, found {}",
+            expected.commands.len(),
+            actual.commands.len()
+        ));
+    }
+    for (index, (expected, actual)) in expected.commands.iter().zip(&actual.commands).enumerate() {
+        differences.extend(
+            command_differences(expected, actual)
+                .into_iter()
+                .map(|difference| format!("command {index}: {difference}")),
+        );
+    }
+    let common = expected.commands.len().min(actual.commands.len());
+    for (index, command) in expected.commands.iter().enumerate().skip(common) {
+        differences.push(format!("command {index}: missing {}", kind(command)));
+    }
+    for (index, command) in actual.commands.iter().enumerate().skip(common) {
+        differences.push(format!("command {index}: unexpected {}", kind(command)));
+    }
+    differences
+}
+
+fn command_differences(expected: &DrawCommand, actual: &DrawCommand) -> Vec<String> {
+    let mut differences = Vec::new();
+    if kind(expected) != kind(actual) {
+        differences.push(format!(
+            "expected a {}, found a {}",
+            kind(expected),
+            kind(actual)
+        ));
+        return differences;
+    }
+
+    let (expected_options, actual_options) = (expected.options(), actual.options());
+    for (property, expected, actual) in [
+        (
+            "fill color",
+            expected_options.fill_color,
+            actual_options.fill_color,
+        ),
+        (
+            "stroke color",
+            expected_options.stroke_color,
+            actual_options.stroke_color,
+        ),
+    ] {
+        if expected != actual {
+            differences.push(format!(
+                "{property}: expected {}, found {}",
+                color_name(expected),
+                color_name(actual)
+            ));
+        }
+    }
+    if expected_options.stroke_width != actual_options.stroke_width {
+        differences.push(format!(
+            "stroke width: expected {}, found {}",
+            expected_options.stroke_width, actual_options.stroke_width
+        ));
+    }
+
+    match (expected, actual) {
+        (
+            DrawCommand::Path {
+                points: expected_points,
+                open: expected_open,
+                ..
+            },
+            DrawCommand::Path {
+                points: actual_points,
+                open: actual_open,
+                ..
+            },
+        ) => {
+            if expected_open != actual_open {
+                differences.push(format!(
+                    "expected an {} path, found an {} one",
+                    open_name(*expected_open),
+                    open_name(*actual_open)
+                ));
+            }
+            if expected_points.len() != actual_points.len() {
+                differences.push(format!(
+                    "point count: expected {}, found {}",
+                    expected_points.len(),
+                    actual_points.len()
+                ));
+            }
+            for (index, (expected, actual)) in expected_points.iter().zip(actual_points).enumerate()
+            {
+                if expected != actual {
+                    differences.push(format!("point {index}: {}", moved(expected, actual)));
+                }
+            }
+        }
+        (
+            DrawCommand::Circle {
+                center: expected_center,
+                radius: expected_radius,
+                ..
+            },
+            DrawCommand::Circle {
+                center: actual_center,
+                radius: actual_radius,
+                ..
+            },
+        ) => {
+            if expected_center != actual_center {
+                differences.push(format!("center: {}", moved(expected_center, actual_center)));
+            }
+            if expected_radius != actual_radius {
+                differences.push(format!(
+                    "radius: expected {expected_radius}, found {actual_radius}"
+                ));
+            }
+        }
+        _ => unreachable!("commands of the same kind"),
+    }
+    differences
+}
+
+fn kind(command: &DrawCommand) -> &'static str {
+    match command {
+        DrawCommand::Path { options, .. } if options.precision == Precision::Precise => {
+            "precise path"
+        }
+        DrawCommand::Path { .. } => "path",
+        DrawCommand::Circle { .. } => "circle",
+    }
+}
+
+/// Both positions of a point and how far it moved, e.g. `expected (5, 1), found (5, 2), off
+/// by (+0, +1)`.
+fn moved(expected: &PebblePoint, actual: &PebblePoint) -> String {
+    format!(
+        "expected ({}, {}), found ({}, {}), off by ({:+}, {:+})",
+        expected.x,
+        expected.y,
+        actual.x,
+        actual.y,
+        i32::from(actual.x) - i32::from(expected.x),
+        i32::from(actual.y) - i32::from(expected.y)
+    )
+}
+
+fn open_name(open: bool) -> &'static str {
+    if open { "open" } else { "closed" }
+}
+
+fn color_name(color: u8) -> String {
+    let color = PebbleColor::from_inner(color);
+    if color.get_a() == 0 {
+        "none".to_string()
+    } else {
+        format!("{} (0x{:02X})", color.rgb_hex(), color.inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::image::DrawOptions;
+
+    #[test]
+    fn test_differences_name_command_and_property() {
+        let expected = PebbleImage {
+            size: PebblePoint { x: 10, y: 10 },
+            commands: vec![DrawCommand::Path {
+                points: vec![PebblePoint { x: 1, y: 1 }, PebblePoint { x: 5, y: 1 }],
+                open: true,
+                options: DrawOptions {
+                    stroke_width: 2,
+                    stroke_color: PebbleColor::from_argb(3, 0, 0, 0).inner(),
+                    ..Default::default()
+                },
+            }],
+        };
+        assert!(semantic_differences(&expected, &expected).is_empty());
+
+        let mut actual = expected.clone();
+        if let DrawCommand::Path {
+            points, options, ..
+        } = &mut actual.commands[0]
+        {
+            points[1].y = 2;
+            options.stroke_width = 1;
+        }
+        assert_eq!(
+            semantic_differences(&expected, &actual),
+            vec![
+                "command 0: stroke width: expected 2, found 1".to_string(),
+                "command 0: point 1: expected (5, 1), found (5, 2), off by (+0, +1)".to_string(),
+            ]
+        );
+
+        actual.commands.push(DrawCommand::Circle {
+            center: PebblePoint { x: 5, y: 5 },
+            radius: 2,
+            options: DrawOptions::default(),
+        });
+        assert_eq!(
+            semantic_differences(&expected, &actual),
+            vec![
+                "command count: expected 1, found 2".to_string(),
+                "command 0: stroke width: expected 2, found 1".to_string(),
+                "command 0: point 1: expected (5, 1), found (5, 2), off by (+0, +1)".to_string(),
+                "command 1: unexpected circle".to_string(),
+            ]
+        );
+    }
+}
@@ -0,0 +1,249 @@
+//! `svg2pdc.toml`, defaults for the conversions run in its directory and below it.
+//!
+//! The keys are named after the options of the command line, which win over the file. Paths
+//! are relative to the directory of the file, and the tables of `files` override the settings
+//! for single SVGs:
+//!
+//! ```toml
+//! precise = true
+//! truncate-color = true
+//! convert = true
+//! fit = "144x168"
+//! output-dir = "build/images"
+//!
+//! [colors]
+//! "#ff0000" = "#aa0000"
+//!
+//! [files."icons/logo.svg"]
+//! precise = false
+//! fit = "50x50"
+//! ```
+
+use std::path::{Path, PathBuf};
+
+use toml::{Table, Value};
+
+use crate::{
+    color::Color,
+    error::{Svg2PdcError, Svg2PdcResult},
+    point::PebblePoint,
+};
+
+/// Name of the config file.
+pub const FILE_NAME: &str = "svg2pdc.toml";
+
+/// Settings of a conversion, for all SVGs or a single one. Settings left out keep the default
+/// of the converter.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Settings {
+    pub precise: Option<bool>,
+    pub truncate_color: Option<bool>,
+    /// Convert coordinates to Pebble's format instead of requiring exact ones.
+    pub convert: Option<bool>,
+    /// Canvas size the artwork is scaled to fit.
+    pub fit: Option<PebblePoint>,
+    /// Colors replaced by others, see [`SvgConverter::color_map`].
+    ///
+    /// [`SvgConverter::color_map`]: crate::svg_converter::SvgConverter::color_map
+    pub colors: Vec<(Color, Color)>,
+}
+
+impl Settings {
+    /// The settings of `self`, with those of `fallback` where `self` leaves them out. The
+    /// colors of both are kept, those of `self` first, so that they win.
+    pub fn or(self, fallback: &Self) -> Self {
+        Self {
+            precise: self.precise.or(fallback.precise),
+            truncate_color: self.truncate_color.or(fallback.truncate_color),
+            convert: self.convert.or(fallback.convert),
+            fit: self.fit.or(fallback.fit),
+            colors: self
+                .colors
+                .into_iter()
+                .chain(fallback.colors.iter().copied())
+                .collect(),
+        }
+    }
+
+    /// Read the settings from a table, with `context` naming it in errors. Keys that aren't
+    /// settings are left to the caller.
+    fn from_table(table: &Table, context: &str) -> Svg2PdcResult<Self> {
+        let flag = |key: &str| {
+            table
+                .get(key)
+                .map(|value| {
+                    value
+                        .as_bool()
+                        .ok_or_else(|| invalid(context, key, "expected true or false"))
+                })
+                .transpose()
+        };
+        let fit = table
+            .get("fit")
+            .map(|value| {
+                value
+                    .as_str()
+                    .and_then(parse_size)
+                    .ok_or_else(|| invalid(context, "fit", "expected a size like \"144x168\""))
+            })
+            .transpose()?;
+        let colors = match table.get("colors") {
+            None => Vec::new(),
+            Some(Value::Table(colors)) => colors
+                .iter()
+                .map(|(from, to)| {
+                    let to = to.as_str().unwrap_or_default();
+                    match (Color::try_from_hex(from), Color::try_from_hex(to)) {
+                        (Ok(from), Ok(to)) => Ok((from, to)),
+                        _ => Err(invalid(
+                            context,
+                            "colors",
+                            &format!("expected \"#RRGGBB\" = \"#RRGGBB\", found {from} = {to}"),
+                        )),
+                    }
+                })
+                .collect::<Svg2PdcResult<_>>()?,
+            Some(_) => return Err(invalid(context, "colors", "expected a table")),
+        };
+        Ok(Self {
+            precise: flag("precise")?,
+            truncate_color: flag("truncate-color")?,
+            convert: flag("convert")?,
+            fit,
+            colors,
+        })
+    }
+}
+
+/// The contents of a config file.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Config {
+    /// Directory of the file, the paths in it are relative to it.
+    pub root: PathBuf,
+    pub settings: Settings,
+    /// Directory for the output when none is given.
+    pub output_dir: Option<PathBuf>,
+    /// Settings of single SVGs, with their paths relative to [`Self::root`].
+    pub files: Vec<(PathBuf, Settings)>,
+}
+
+impl Config {
+    /// Keys of the file that aren't [`Settings`].
+    const KEYS: [&str; 2] = ["output-dir", "files"];
+    const SETTINGS: [&str; 5] = ["precise", "truncate-color", "convert", "fit", "colors"];
+
+    /// The config file of `directory` or the closest of its ancestors, if there is one.
+    pub fn discover(directory: &Path) -> Svg2PdcResult<Option<Self>> {
+        match directory
+            .ancestors()
+            .map(|directory| directory.join(FILE_NAME))
+            .find(|path| path.is_file())
+        {
+            Some(path) => Self::load(&path).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    pub fn load(path: &Path) -> Svg2PdcResult<Self> {
+        let root = path.parent().unwrap_or(Path::new(".")).to_path_buf();
+        Self::parse(&std::fs::read_to_string(path)?, root).map_err(|error| match error {
+            Svg2PdcError::ParseError(message) => {
+                Svg2PdcError::ParseError(format!("{}: {message}", path.display()))
+            }
+            error => error,
+        })
+    }
+
+    /// Parse the contents of a config file in `root`.
+    ///
+    /// ```rust
+    /// use svg2pdc::config::Config;
+    ///
+    /// let config = Config::parse("precise = true\n[files.\"logo.svg\"]\nprecise = false", "icons")
+    ///     .unwrap();
+    /// assert_eq!(config.settings_for("icons/logo.svg".as_ref()).precise, Some(false));
+    /// assert_eq!(config.settings_for("icons/sun.svg".as_ref()).precise, Some(true));
+    /// ```
+    pub fn parse(content: &str, root: impl Into<PathBuf>) -> Svg2PdcResult<Self> {
+        let root = root.into();
+        let table: Table = content
+            .parse()
+            .map_err(|error: toml::de::Error| Svg2PdcError::ParseError(error.to_string()))?;
+        check_keys(&table, "", &[&Self::KEYS[..], &Self::SETTINGS[..]].concat())?;
+
+        let output_dir = match table.get("output-dir") {
+            None => None,
+            Some(Value::String(directory)) => Some(root.join(directory)),
+            Some(_) => return Err(invalid("", "output-dir", "expected a path")),
+        };
+        let files = match table.get("files") {
+            None => Vec::new(),
+            Some(Value::Table(files)) => files
+                .iter()
+                .map(|(path, settings)| {
+                    let context = format!("files.\"{path}\".");
+                    let Value::Table(settings) = settings else {
+                        return Err(invalid(&context, "", "expected a table"));
+                    };
+                    check_keys(settings, &context, &Self::SETTINGS)?;
+                    Ok((
+                        PathBuf::from(path),
+                        Settings::from_table(settings, &context)?,
+                    ))
+                })
+                .collect::<Svg2PdcResult<_>>()?,
+            Some(_) => return Err(invalid("", "files", "expected a table")),
+        };
+        Ok(Self {
+            settings: Settings::from_table(&table, "")?,
+            output_dir,
+            files,
+            root,
+        })
+    }
+
+    /// The settings for the SVG at `file`, relative to the working directory: the overrides
+    /// for it, falling back to [`Self::settings`].
+    pub fn settings_for(&self, file: &Path) -> Settings {
+        let absolute = |path: &Path| std::path::absolute(path).unwrap_or(path.to_path_buf());
+        let file = absolute(file);
+        let overrides = file
+            .strip_prefix(absolute(&self.root))
+            .ok()
+            .and_then(|relative| {
+                self.files
+                    .iter()
+                    .find(|(path, _)| path.components().eq(relative.components()))
+            });
+        match overrides {
+            Some((_, settings)) => settings.clone().or(&self.settings),
+            None => self.settings.clone(),
+        }
+    }
+}
+
+/// Reject the keys of `table` that aren't `known`, likely misspelled.
+fn check_keys(table: &Table, context: &str, known: &[&str]) -> Svg2PdcResult<()> {
+    match table.keys().find(|key| !known.contains(&key.as_str())) {
+        Some(key) => Err(invalid(
+            context,
+            key,
+            &format!("unknown key, expected one of {}", known.join(", ")),
+        )),
+        None => Ok(()),
+    }
+}
+
+fn invalid(context: &str, key: &str, reason: &str) -> Svg2PdcError {
+    Svg2PdcError::ParseError(format!("`{context}{key}`: {reason}"))
+}
+
+/// A size like `144x168`.
+fn parse_size(size: &str) -> Option<PebblePoint> {
+    let (width, height) = size.split_once('x')?;
+    let parse = |value: &str| value.trim().parse::<i16>().ok().filter(|size| *size >= 0);
+    Some(PebblePoint {
+        x: parse(width)?,
+        y: parse(height)?,
+    })
+}
@@ -0,0 +1,433 @@
+//! Codes identifying the warnings emitted during conversion.
+//!
+//! Every warning is printed with its code, e.g. `warning[W001]`, and a longer description
+//! of each code is available through `svg2pdc explain W001`. Library users can collect the
+//! warnings of a conversion in a [`ConversionReport`] instead, see
+//! [`crate::svg_converter::SvgConverter::parse_svg_image_with_report`].
+
+use std::{
+    cell::RefCell,
+    fmt,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use roxmltree::Node;
+
+/// Number of warnings emitted so far by this process.
+static WARNINGS: AtomicUsize = AtomicUsize::new(0);
+
+thread_local! {
+    /// Warnings of the conversion running on this thread, when they are collected.
+    static COLLECTED: RefCell<Option<Vec<Warning>>> = const { RefCell::new(None) };
+    /// The element being converted, while warnings are collected.
+    static ELEMENT: RefCell<Option<ElementLocation>> = const { RefCell::new(None) };
+}
+
+/// Number of warnings emitted so far, e.g. to attribute warnings to the file being converted.
+pub fn warning_count() -> usize {
+    WARNINGS.load(Ordering::Relaxed)
+}
+
+/// An element of a document, the one a warning was emitted for or an error occurred at.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ElementLocation {
+    /// Tag name without namespace, e.g. `path`.
+    pub tag: String,
+    pub id: Option<String>,
+    /// Byte offset of the element in the document.
+    pub offset: usize,
+    /// 1-based line of the element in the document.
+    pub line: u32,
+    /// 1-based column of the element in the document, in characters.
+    pub column: u32,
+}
+
+impl ElementLocation {
+    /// The location of `node`, with its line and column.
+    pub(crate) fn of(node: Node<'_, '_>) -> Self {
+        let position = node.document().text_pos_at(node.range().start);
+        Self {
+            line: position.row,
+            column: position.col,
+            ..Self::unlocated(node)
+        }
+    }
+
+    /// The location of `node` without its line and column, which are expensive to compute.
+    fn unlocated(node: Node<'_, '_>) -> Self {
+        Self {
+            tag: node.tag_name().name().to_string(),
+            id: node.attribute("id").map(str::to_string),
+            offset: node.range().start,
+            line: 0,
+            column: 0,
+        }
+    }
+}
+
+/// Written as `line 42: <rect id="frame">`, to start error messages with.
+impl fmt::Display for ElementLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: <{}", self.line, self.tag)?;
+        if let Some(id) = &self.id {
+            write!(f, " id=\"{id}\"")?;
+        }
+        write!(f, ">")
+    }
+}
+
+/// A warning emitted during a conversion.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Warning {
+    pub code: DiagnosticCode,
+    pub message: String,
+    /// The element being converted, if the warning concerns a single one.
+    pub element: Option<ElementLocation>,
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "warning[{}]: {}", self.code, self.message)
+    }
+}
+
+/// The warnings of a conversion, in the order they were emitted.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConversionReport {
+    pub warnings: Vec<Warning>,
+}
+
+impl ConversionReport {
+    /// Number of warnings with `code`.
+    pub fn count(&self, code: DiagnosticCode) -> usize {
+        self.warnings
+            .iter()
+            .filter(|warning| warning.code == code)
+            .count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.warnings.is_empty()
+    }
+
+    /// Fill in the lines and columns of the elements from the text of their document.
+    pub(crate) fn locate(&mut self, document: &str) {
+        for element in self
+            .warnings
+            .iter_mut()
+            .filter_map(|warning| warning.element.as_mut())
+        {
+            let before = &document[..element.offset.min(document.len())];
+            let line_start = before.rfind('\n').map_or(0, |newline| newline + 1);
+            element.line = before.matches('\n').count() as u32 + 1;
+            element.column = before[line_start..].chars().count() as u32 + 1;
+        }
+    }
+}
+
+/// Run `f` with the warnings emitted on this thread collected instead of printed.
+pub(crate) fn collect<T>(f: impl FnOnce() -> T) -> (T, ConversionReport) {
+    let previous = COLLECTED.replace(Some(Vec::new()));
+    let result = f();
+    let warnings = COLLECTED.replace(previous).unwrap_or_default();
+    (result, ConversionReport { warnings })
+}
+
+/// Whether the warnings emitted on this thread are collected.
+pub(crate) fn is_collecting() -> bool {
+    COLLECTED.with_borrow(Option::is_some)
+}
+
+/// Pass on warnings collected on another thread, e.g. one converting a group in parallel.
+pub(crate) fn forward(report: ConversionReport) {
+    for warning in report.warnings {
+        emit(warning);
+    }
+}
+
+fn emit(warning: Warning) {
+    COLLECTED.with_borrow_mut(|collected| match collected {
+        Some(collected) => collected.push(warning),
+        None => eprintln!("{warning}"),
+    });
+}
+
+/// Attributes the warnings emitted on this thread to an element until it is dropped.
+pub(crate) struct ElementScope {
+    previous: Option<ElementLocation>,
+}
+
+impl ElementScope {
+    pub(crate) fn enter(node: Node<'_, '_>) -> Self {
+        // Locating an element is only worth it when the warnings are kept
+        if !is_collecting() {
+            return Self {
+                previous: ELEMENT.take(),
+            };
+        }
+        // The line and column are filled in by `ConversionReport::locate`, only for the
+        // elements that end up with a warning
+        let location = ElementLocation::unlocated(node);
+        Self {
+            previous: ELEMENT.replace(Some(location)),
+        }
+    }
+}
+
+impl Drop for ElementScope {
+    fn drop(&mut self) {
+        ELEMENT.set(self.previous.take());
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DiagnosticCode {
+    /// A point does not lie on the coordinate grid of the chosen precision.
+    InvalidPoint,
+    /// An element type that has no draw command equivalent was skipped.
+    UnsupportedTag,
+    /// A stroke width was truncated, rounded, raised or clamped to fit a draw command.
+    StrokeWidthChanged,
+    /// Geometry lies outside of the visible area of a round display.
+    OutsideDisplay,
+    /// A circle was replaced by a precise path to keep its sub-pixel center.
+    CircleApproximated,
+    /// A `fill` or `stroke` value is not a color and draws nothing.
+    InvalidColor,
+    /// Bézier curves or elliptical arcs of a path were reduced to their end points.
+    CurveSimplified,
+    /// Clip paths, masks or markers were left out, so elements are drawn unclipped.
+    ClippingOmitted,
+    /// An `<image>` was skipped, raster images have no draw command equivalent.
+    RasterImage,
+    /// The holes of a filled path are filled on the watch, which fills subpaths on their own.
+    FilledHoles,
+}
+
+impl DiagnosticCode {
+    pub const ALL: &[Self] = &[
+        Self::InvalidPoint,
+        Self::UnsupportedTag,
+        Self::StrokeWidthChanged,
+        Self::OutsideDisplay,
+        Self::CircleApproximated,
+        Self::InvalidColor,
+        Self::CurveSimplified,
+        Self::ClippingOmitted,
+        Self::RasterImage,
+        Self::FilledHoles,
+    ];
+
+    /// The short code, e.g. `W001`.
+    pub const fn code(&self) -> &'static str {
+        match self {
+            Self::InvalidPoint => "W001",
+            Self::UnsupportedTag => "W002",
+            Self::StrokeWidthChanged => "W003",
+            Self::OutsideDisplay => "W004",
+            Self::CircleApproximated => "W005",
+            Self::InvalidColor => "W006",
+            Self::CurveSimplified => "W007",
+            Self::ClippingOmitted => "W008",
+            Self::RasterImage => "W009",
+            Self::FilledHoles => "W010",
+        }
+    }
+
+    /// Look up a code, ignoring case.
+    ///
+    /// ```rust
+    /// use svg2pdc::diagnostic::DiagnosticCode;
+    ///
+    /// assert_eq!(DiagnosticCode::from_code("w001"), Some(DiagnosticCode::InvalidPoint));
+    /// assert_eq!(DiagnosticCode::from_code("W999"), None);
+    /// ```
+    pub fn from_code(code: &str) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|diagnostic| diagnostic.code().eq_ignore_ascii_case(code.trim()))
+    }
+
+    /// Print a warning with this code to stderr, or add it to the collected warnings.
+    pub(crate) fn warn(&self, message: fmt::Arguments<'_>) {
+        WARNINGS.fetch_add(1, Ordering::Relaxed);
+        emit(Warning {
+            code: *self,
+            message: message.to_string(),
+            element: ELEMENT.with_borrow(Clone::clone),
+        });
+    }
+
+    /// A one line summary of the warning.
+    pub const fn summary(&self) -> &'static str {
+        match self {
+            Self::InvalidPoint => "point is not a valid Pebble coordinate",
+            Self::UnsupportedTag => "unsupported element skipped",
+            Self::StrokeWidthChanged => "stroke width changed to fit a draw command",
+            Self::OutsideDisplay => "geometry outside of the round display",
+            Self::CircleApproximated => "circle drawn as a precise path",
+            Self::InvalidColor => "paint is not a color",
+            Self::CurveSimplified => "curves reduced to their end points",
+            Self::ClippingOmitted => "clipping, masking and markers left out",
+            Self::RasterImage => "raster image skipped",
+            Self::FilledHoles => "holes of a path filled",
+        }
+    }
+
+    /// A detailed description of the warning, why it matters and how to fix it.
+    pub const fn explanation(&self) -> &'static str {
+        match self {
+            Self::InvalidPoint => {
+                "\
+A point of the converted image does not lie on the Pebble coordinate grid.
+
+Pebble draw commands store coordinates as 16-bit integers. Normal precision paths
+and circles can only address whole and half pixels, precise paths can address
+eighths of a pixel. Any other coordinate is rounded to the grid when the image is
+written, which can visibly shift or distort thin shapes on the watch.
+
+Without `--convert` the conversion fails with an error instead of rounding.
+
+To fix it, snap the artwork to the pixel grid in your editor (in Inkscape: enable
+snapping to the page grid, in Illustrator: \"Align to Pixel Grid\"), or convert with
+`--precise` if the shape needs sub-pixel placement."
+            }
+            Self::UnsupportedTag => {
+                "\
+An element of the SVG has no equivalent Pebble draw command and was skipped.
+
+Pebble draw command images only support paths, polylines, polygons, lines,
+rectangles and circles, grouped with `<g>`. Anything else, such as text,
+gradients or editor metadata, does not end up on the watch, so the converted image
+may be missing parts of the artwork. Text is outlined with `--text-font` when
+svg2pdc is built with the `text` feature.
+
+To fix it, convert the element to a path in your editor (in Inkscape:
+Path > Object to Path, in Illustrator: Object > Expand) and save again. Elements
+that never hold artwork, like editor metadata, are skipped without this warning;
+list more of them with `--ignore-tags`. With `--strict` this is an error instead."
+            }
+            Self::StrokeWidthChanged => {
+                "\
+The stroke width of an element could not be stored as requested.
+
+Draw commands store the stroke width as a whole number of pixels between 0 and
+255. Like the original tool, fractional widths are truncated, so a `0.75px`
+stroke disappears and a `2.9px` stroke becomes `2px`. Widths outside of the range
+are clamped. Choose another rounding with `--stroke-rounding`, and keep thin
+strokes visible with `--min-stroke-width`.
+
+Small differences are expected and only reported when the stored width is further
+from the requested one than the tolerance, see `--stroke-width-tolerance`.
+
+To fix it, use whole pixel stroke widths in your editor."
+            }
+            Self::OutsideDisplay => {
+                "\
+Part of an element lies outside of the visible area of a round display.
+
+The Pebble Time Round (chalk) has a 180px wide round display, so everything
+outside of the circle inscribed in the screen is cut off. The check assumes the
+image is drawn centered on the screen, so square full-screen artwork loses its
+corners.
+
+With `--clip-to-display` the points outside of the circle are pulled onto its
+edge and elements that end up entirely off-screen are dropped.
+
+To fix it, move the element inside the circle in your editor, or design the
+round variant of the artwork separately."
+            }
+            Self::CircleApproximated => {
+                "\
+A circle was replaced by a closed precise path approximating it.
+
+Circle draw commands only support normal precision, so their center can only be
+placed on whole and half pixels, even in a precise image. With
+`--circles-as-paths`, a circle whose center is off that grid is sampled into a
+polygon instead, which keeps the sub-pixel center. The polygon takes more space
+than a circle command and its outline is slightly faceted.
+
+To avoid it, place the center of the circle on a whole or half pixel."
+            }
+            Self::InvalidColor => {
+                "\
+The `fill` or `stroke` of an element is neither `none`, a color nor a reference
+to a gradient or pattern, so it draws nothing.
+
+Colors can be written as hex (`#rgb`, `#rrggbb`), with `rgb()`, `hsl()` or as CSS
+color names. Anything else, like a misspelled name, leaves the element without
+that paint, which may make it invisible on the watch.
+
+With `--strict` this is an error instead.
+
+To fix it, correct the color in the SVG."
+            }
+            Self::CurveSimplified => {
+                "\
+Bézier curves or elliptical arcs of a path were replaced by straight lines
+between their end points.
+
+Pebble draw commands only have straight segments. Like the original tool, curves
+are reduced to their end points by default, which turns a rounded shape into a
+polygon of its corners and can lose most of a shape drawn with few segments.
+
+With `--strict` this is an error instead.
+
+To fix it, convert with `--flatten-curves` to replace every curve by lines that
+follow it closely, or flatten the curves in your editor (in Inkscape:
+Extensions > Modify Path > Flatten Beziers)."
+            }
+            Self::ClippingOmitted => {
+                "\
+Elements of the SVG are clipped, masked or have markers, which draw commands
+can't express.
+
+The content of `<clipPath>`, `<mask>` and `<marker>` elements is never drawn by
+itself, so it is left out of the image. Clipping and masking are approximated by
+omission: the clipped or masked elements are drawn in full, and markers, like
+arrow heads, are missing. This is reported once per image.
+
+To fix it, apply the clip or the mask in your editor (in Inkscape:
+Path > Intersection, or Object > Clip > Release and edit the shapes), and convert
+markers to paths (Path > Stroke to Path)."
+            }
+            Self::RasterImage => {
+                "\
+An `<image>` element embeds or links a raster image, like a PNG or a JPEG, which
+was skipped.
+
+PDC images are vector-only: they hold paths and circles, never pixels. An SVG
+that is mostly a raster image converts to an image that is empty where the
+picture was.
+
+With `--strict` this is an error instead.
+
+To fix it, trace the picture into paths in your editor (in Inkscape:
+Path > Trace Bitmap, in Illustrator: Object > Image Trace) and delete the image,
+or ship the picture as a bitmap resource of the watchapp instead."
+            }
+            Self::FilledHoles => {
+                "\
+A filled path has holes, like the inside of a donut or of the letter O, which
+are filled on the watch.
+
+In SVG, a subpath inside another one cuts a hole into it, depending on the
+`fill-rule` of the path: with `evenodd` every nested subpath does, with `nonzero`
+the ones running the other way around. Pebble fills every subpath of a path on
+its own, so the hole is filled as well and the shape turns solid.
+
+To fix it, convert with `--holes bridge` to join every hole to the subpath
+around it with a zero-width cut, which fills like the original, or with
+`--holes outline` to draw only the outline of the path. In your editor, you can
+also split the shape into pieces without holes."
+            }
+        }
+    }
+}
+
+impl fmt::Display for DiagnosticCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.code())
+    }
+}
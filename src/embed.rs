@@ -0,0 +1,60 @@
+//! Source code embedding serialized images, for firmware and programs that include their
+//! draw command images without a resource pack.
+
+use crate::pack::identifier;
+
+/// Bytes per line of the generated arrays.
+const BYTES_PER_LINE: usize = 12;
+
+/// A C header defining `bytes` as a `static const uint8_t` array named after `symbol`, and
+/// its length as `<symbol>_length`.
+///
+/// The symbol is turned into a C identifier, with everything but letters and digits
+/// replaced by underscores.
+///
+/// ```rust
+/// use svg2pdc::embed::c_header;
+///
+/// let header = c_header("weather-icon", &[0x50, 0x44, 0x43, 0x49]);
+/// assert!(header.contains("static const uint8_t weather_icon[] = {\n  0x50, 0x44, 0x43, 0x49,\n};"));
+/// assert!(header.contains("static const uint32_t weather_icon_length = 4;"));
+/// ```
+pub fn c_header(symbol: &str, bytes: &[u8]) -> String {
+    let symbol = identifier(symbol);
+    let mut header = String::new();
+    header.push_str("#pragma once\n\n#include <stdint.h>\n\n");
+    header.push_str(&format!("static const uint8_t {symbol}[] = {{\n"));
+    for line in bytes.chunks(BYTES_PER_LINE) {
+        let line: Vec<_> = line.iter().map(|byte| format!("0x{byte:02x}")).collect();
+        header.push_str(&format!("  {},\n", line.join(", ")));
+    }
+    header.push_str("};\n");
+    header.push_str(&format!(
+        "static const uint32_t {symbol}_length = {};\n",
+        bytes.len()
+    ));
+    header
+}
+
+/// Rust source defining `bytes` as a `pub const` slice named after `symbol` in uppercase,
+/// to be included with `include!` or as a module.
+///
+/// ```rust
+/// use svg2pdc::embed::rust_module;
+///
+/// let module = rust_module("icon", &[0x50, 0x44, 0x43, 0x49]);
+/// assert!(module.contains("pub const ICON: &[u8] = &[\n    0x50, 0x44, 0x43, 0x49,\n];"));
+/// ```
+pub fn rust_module(symbol: &str, bytes: &[u8]) -> String {
+    let mut module = format!(
+        "/// Serialized draw command image, {} bytes.\npub const {}: &[u8] = &[\n",
+        bytes.len(),
+        identifier(symbol).to_uppercase()
+    );
+    for line in bytes.chunks(BYTES_PER_LINE) {
+        let line: Vec<_> = line.iter().map(|byte| format!("0x{byte:02x}")).collect();
+        module.push_str(&format!("    {},\n", line.join(", ")));
+    }
+    module.push_str("];\n");
+    module
+}
@@ -1,4 +1,4 @@
-use crate::point::FPoint;
+use crate::{diagnostic::ElementLocation, point::FPoint};
 
 #[derive(Debug, thiserror::Error)]
 pub enum Svg2PdcError {
@@ -7,22 +7,60 @@ pub enum Svg2PdcError {
         point: FPoint,
         nearest_valid: FPoint,
     },
+    #[error("Coordinate out of range: {0:?} doesn't fit into a signed 16 bit Pebble coordinate")]
+    CoordinateOutOfRange(FPoint),
     #[error("IO error: `{0}`")]
     Io(#[from] std::io::Error),
     #[error("XML error: `{0}`")]
     XmlError(#[from] roxmltree::Error),
     #[error("Invalid viewBox: `{0}`")]
     InvalidViewBox(#[from] svgtypes::ViewBoxError),
-    #[error("Invalid polyline: `{0}`")]
-    InvalidPolyline(String),
     #[error("SvgTypes error: `{0}`")]
     SvgTypesError(#[from] svgtypes::Error),
+    #[error("{element} invalid number in `{attribute}`: `{value}`")]
+    InvalidNumber {
+        attribute: String,
+        value: String,
+        element: ElementLocation,
+    },
+    #[error("{element} invalid `{attribute}`: {reason}")]
+    InvalidAttribute {
+        attribute: String,
+        element: ElementLocation,
+        reason: String,
+    },
+    #[error("{element} missing `{attribute}`")]
+    MissingAttribute {
+        attribute: String,
+        element: ElementLocation,
+    },
     #[error("Invalid color string: `{0}`")]
     InvalidColor(String),
-    #[error("Unsupported circle format")]
-    UnsupportedCircle,
+    #[error("{0} unsupported element")]
+    UnsupportedTag(ElementLocation),
+    #[error(
+        "{0} embedded raster image, PDC images are vector-only: trace it into paths or use a bitmap resource"
+    )]
+    RasterImage(ElementLocation),
+    #[error("{0} unsupported circle format, it has neither `r` nor `z`")]
+    UnsupportedCircle(ElementLocation),
     #[error("Parse Error {0}")]
     ParseError(String),
+    #[error("Invalid PDC data: `{0}`")]
+    InvalidPdc(String),
+    #[error("Incompatible canvas: `{0}`")]
+    IncompatibleCanvas(String),
+    #[error("Invalid PDF: `{0}`")]
+    InvalidPdf(String),
+    #[error("Invalid font: `{0}`")]
+    InvalidFont(String),
+    #[error("Render error: `{0}`")]
+    Render(String),
+    #[error("{}: {source}", path.display())]
+    InFile {
+        path: std::path::PathBuf,
+        source: Box<Svg2PdcError>,
+    },
     #[error("Unsupported Operation `{0}`")]
     UnsupportedOperation(String),
 }
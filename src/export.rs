@@ -0,0 +1,111 @@
+//! Conversion of PDC images back to SVG, to inspect and edit existing assets in an SVG editor.
+//!
+//! Stored coordinates are written at the pixel positions the watch draws them at, so that
+//! converting the SVG again with the pixel corner convention and the precision of the image
+//! gives back the same commands.
+
+use std::fmt::Write;
+
+use crate::{
+    color::PebbleColor,
+    image::{DrawCommand, PebbleImage},
+    point::Precision,
+};
+
+/// Write an image as an SVG document the size of its canvas.
+///
+/// ```rust
+/// use svg2pdc::{export::to_svg, point::PebblePoint, prelude::*};
+///
+/// let image = PebbleImage {
+///     size: PebblePoint { x: 10, y: 10 },
+///     commands: vec![DrawCommand::Circle {
+///         center: PebblePoint { x: 4, y: 4 },
+///         radius: 3,
+///         options: DrawOptions {
+///             fill_color: PebbleColor::from_argb(3, 3, 0, 0).inner(),
+///             ..DrawOptions::default()
+///         },
+///     }],
+/// };
+/// let svg = to_svg(&image);
+/// assert!(svg.contains(r##"<circle cx="4.5" cy="4.5" r="3" fill="#ff0000" stroke="none"/>"##));
+/// ```
+pub fn to_svg(image: &PebbleImage) -> String {
+    let (width, height) = (image.size.x, image.size.y);
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" \
+         viewBox=\"0 0 {width} {height}\">\n"
+    );
+    for command in &image.commands {
+        svg.push_str("  ");
+        match command {
+            DrawCommand::Path {
+                points,
+                open,
+                options,
+            } => {
+                let mut d = String::new();
+                for (index, point) in points.iter().enumerate() {
+                    let point = point.pixel_position(&options.precision);
+                    let segment = if index == 0 { "M" } else { " L" };
+                    write!(d, "{segment}{} {}", point.x, point.y).unwrap();
+                }
+                if !open {
+                    d.push_str(" Z");
+                }
+                write!(svg, "<path d=\"{d}\"").unwrap();
+            }
+            // Circle centers are always stored with normal precision
+            DrawCommand::Circle { center, radius, .. } => {
+                let center = center.pixel_position(&Precision::Normal);
+                write!(
+                    svg,
+                    "<circle cx=\"{}\" cy=\"{}\" r=\"{radius}\"",
+                    center.x, center.y
+                )
+                .unwrap();
+            }
+        }
+        let options = command.options();
+        svg.push_str(&paint("fill", PebbleColor::from_inner(options.fill_color)));
+        if options.stroke_width > 0 {
+            svg.push_str(&paint(
+                "stroke",
+                PebbleColor::from_inner(options.stroke_color),
+            ));
+            write!(
+                svg,
+                " stroke-width=\"{}\" stroke-linecap=\"round\" stroke-linejoin=\"round\"",
+                options.stroke_width
+            )
+            .unwrap();
+        } else {
+            svg.push_str(" stroke=\"none\"");
+        }
+        svg.push_str("/>\n");
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// The `fill` or `stroke` attributes of a color, with an opacity for partial alpha.
+fn paint(property: &str, color: PebbleColor) -> String {
+    let color = color.to_color();
+    let mut attributes = match color.a {
+        0 => return format!(" {property}=\"none\""),
+        _ => format!(
+            " {property}=\"#{:02x}{:02x}{:02x}\"",
+            color.r, color.g, color.b
+        ),
+    };
+    if color.a < 255 {
+        write!(
+            attributes,
+            " {property}-opacity=\"{:.3}\"",
+            color.a as f32 / 255.0
+        )
+        .unwrap();
+    }
+    attributes
+}
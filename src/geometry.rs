@@ -0,0 +1,1158 @@
+//! Geometry operations on converted images.
+//!
+//! These work directly on the Pebble coordinates of the draw commands, so they can be
+//! applied to images converted from SVG as well as to images read from PDC files.
+
+use crate::{
+    color::PebbleColor,
+    error::{Svg2PdcError, Svg2PdcResult},
+    image::{DrawCommand, PebbleImage},
+    point::{FPoint, PebblePoint, Precision},
+};
+
+/// Corner of the bounding box of a path, used to pick its first point.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StartCorner {
+    TopLeft,
+    TopRight,
+    BottomRight,
+    BottomLeft,
+}
+
+/// Clockwise rotation by a multiple of a quarter turn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rotation {
+    Cw90,
+    Cw180,
+    Cw270,
+}
+
+/// Axis-aligned rectangle on the canvas, in watch pixels.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BoundingBox {
+    pub min: FPoint,
+    pub max: FPoint,
+}
+
+impl BoundingBox {
+    pub fn contains(&self, point: FPoint) -> bool {
+        (self.min.x..=self.max.x).contains(&point.x) && (self.min.y..=self.max.y).contains(&point.y)
+    }
+}
+
+/// Longest distance a vertex moves relative to the offset, keeps sharp corners from spiking.
+const MITER_LIMIT: f32 = 4.0;
+
+impl PebbleImage {
+    /// Reverse the direction of every path, see [`DrawCommand::reverse`].
+    pub fn reverse(&mut self) {
+        for command in &mut self.commands {
+            command.reverse();
+        }
+    }
+
+    /// Start every closed path at the point closest to `corner`, see [`DrawCommand::start_at`].
+    pub fn start_at(&mut self, corner: StartCorner) {
+        for command in &mut self.commands {
+            command.start_at(corner);
+        }
+    }
+
+    /// Grow (positive `delta`) or shrink (negative `delta`) every closed shape by `delta` pixels.
+    ///
+    /// Open paths are left untouched. Points past the range of the coordinates are clamped to it.
+    pub fn offset(&mut self, delta: f32) {
+        for command in &mut self.commands {
+            command.offset(delta);
+        }
+    }
+
+    /// Remove the points of every path that don't change its shape, see
+    /// [`DrawCommand::optimize`]. Returns how many points were removed.
+    pub fn optimize(&mut self, collinear_tolerance: Option<f32>) -> usize {
+        self.commands
+            .iter_mut()
+            .map(|command| command.optimize(collinear_tolerance))
+            .sum()
+    }
+
+    /// Remove the commands that paint nothing, see [`DrawCommand::is_visible`]. Returns how
+    /// many bytes they took up once serialized.
+    pub fn strip_invisible(&mut self) -> usize {
+        let mut saved = 0;
+        self.commands.retain(|command| {
+            let visible = command.is_visible();
+            if !visible {
+                saved += command.serialized_size();
+            }
+            visible
+        });
+        saved
+    }
+
+    /// Join consecutive open paths without a fill into one path when they are drawn with the
+    /// same stroke and one starts where the other starts or ends. Returns how many commands
+    /// were removed.
+    ///
+    /// The drawing order is kept, only neighbouring commands are joined.
+    ///
+    /// ```
+    /// # use svg2pdc::{point::PebblePoint, prelude::*};
+    /// let segment = |from: (i16, i16), to: (i16, i16)| DrawCommand::Path {
+    ///     points: vec![PebblePoint { x: from.0, y: from.1 }, PebblePoint { x: to.0, y: to.1 }],
+    ///     open: true,
+    ///     options: DrawOptions { stroke_width: 1, stroke_color: 0xC0, ..Default::default() },
+    /// };
+    /// let mut image = PebbleImage {
+    ///     size: PebblePoint { x: 10, y: 10 },
+    ///     commands: vec![segment((0, 0), (5, 0)), segment((5, 5), (5, 0)), segment((0, 9), (9, 9))],
+    /// };
+    /// assert_eq!(image.merge_paths(), 1);
+    ///
+    /// let DrawCommand::Path { points, .. } = &image.commands[0] else { unreachable!() };
+    /// assert_eq!(points.len(), 3);
+    /// assert_eq!(points[2], PebblePoint { x: 5, y: 5 });
+    /// ```
+    pub fn merge_paths(&mut self) -> usize {
+        let count = self.commands.len();
+        let mut merged: Vec<DrawCommand> = Vec::with_capacity(count);
+        for command in self.commands.drain(..) {
+            if let Some(previous) = merged.last_mut()
+                && join_paths(previous, &command)
+            {
+                continue;
+            }
+            merged.push(command);
+        }
+        self.commands = merged;
+        count - self.commands.len()
+    }
+
+    /// Scale the image and its canvas by `factor`. Circle radii and stroke widths are scaled
+    /// as well, stroked commands keep a stroke of at least one pixel.
+    ///
+    /// Stroke widths are already whole pixels, scale documents while converting them with
+    /// [`crate::svg_converter::SvgConverter::with_scale`] to round them only once.
+    ///
+    /// ```
+    /// # use svg2pdc::{point::PebblePoint, prelude::*};
+    /// let mut image = PebbleImage::builder()
+    ///     .size(40, 40)
+    ///     .circle((20, 20), 10)
+    ///     .stroke(PebbleColor::WHITE, 3)
+    ///     .build();
+    /// image.scale(0.25);
+    /// assert_eq!(image.size, PebblePoint { x: 10, y: 10 });
+    /// assert_eq!(image.commands[0].options().stroke_width, 1);
+    /// ```
+    pub fn scale(&mut self, factor: f32) {
+        for command in &mut self.commands {
+            command.map_points(|point| point * factor);
+            if let DrawCommand::Circle { radius, .. } = command {
+                *radius = (*radius as f32 * factor)
+                    .round()
+                    .clamp(0.0, u16::MAX as f32) as u16;
+            }
+            let (DrawCommand::Path { options, .. } | DrawCommand::Circle { options, .. }) = command;
+            if options.stroke_width > 0 {
+                options.stroke_width = (options.stroke_width as f32 * factor)
+                    .round()
+                    .clamp(1.0, 255.0) as u8;
+            }
+        }
+        self.size = to_pebble_point(FPoint::from(self.size) * factor);
+    }
+
+    /// Rotate the image clockwise, swapping the width and height of the canvas for quarter turns.
+    ///
+    /// ```
+    /// # use svg2pdc::{geometry::Rotation, point::PebblePoint, prelude::*};
+    /// let mut image = PebbleImage { size: PebblePoint { x: 30, y: 10 }, commands: vec![] };
+    /// image.rotate(Rotation::Cw90);
+    /// assert_eq!(image.size, PebblePoint { x: 10, y: 30 });
+    /// ```
+    pub fn rotate(&mut self, rotation: Rotation) {
+        let FPoint {
+            x: width,
+            y: height,
+        } = self.size.into();
+        for command in &mut self.commands {
+            command.map_points(|FPoint { x, y }| match rotation {
+                Rotation::Cw90 => FPoint::new(height - y, x),
+                Rotation::Cw180 => FPoint::new(width - x, height - y),
+                Rotation::Cw270 => FPoint::new(y, width - x),
+            });
+        }
+        if rotation != Rotation::Cw180 {
+            self.size = PebblePoint {
+                x: self.size.y,
+                y: self.size.x,
+            };
+        }
+    }
+
+    /// Mirror the image along its vertical axis.
+    pub fn flip_horizontal(&mut self) {
+        let width = self.size.x as f32;
+        for command in &mut self.commands {
+            command.map_points(|FPoint { x, y }| FPoint::new(width - x, y));
+        }
+    }
+
+    /// Mirror the image along its horizontal axis.
+    pub fn flip_vertical(&mut self) {
+        let height = self.size.y as f32;
+        for command in &mut self.commands {
+            command.map_points(|FPoint { x, y }| FPoint::new(x, height - y));
+        }
+    }
+
+    /// Move every command by `offset` pixels, keeping the canvas size.
+    ///
+    /// Points moved past the top or left edge get negative coordinates, which the watch draws
+    /// off the canvas. Only points past the range of the coordinates are clamped to it.
+    pub fn move_by(&mut self, offset: FPoint) {
+        for command in &mut self.commands {
+            command.map_points(|point| point + offset);
+        }
+    }
+
+    /// Remove the first command if it is a rectangle filled with `color` covering the whole canvas.
+    /// Its stroke is not checked, it would only outline the edge of the canvas.
+    ///
+    /// Exported artwork often starts with such a background rectangle, which only costs space
+    /// and draw time on the watch. The removed command is returned.
+    ///
+    /// ```
+    /// # use svg2pdc::{point::PebblePoint, prelude::*};
+    /// let white = PebbleColor::from_argb(3, 3, 3, 3);
+    /// let mut image = PebbleImage {
+    ///     size: PebblePoint { x: 10, y: 10 },
+    ///     commands: vec![DrawCommand::Path {
+    ///         points: vec![
+    ///             PebblePoint { x: 0, y: 0 },
+    ///             PebblePoint { x: 10, y: 0 },
+    ///             PebblePoint { x: 10, y: 10 },
+    ///             PebblePoint { x: 0, y: 10 },
+    ///         ],
+    ///         open: false,
+    ///         options: DrawOptions { fill_color: white.inner(), ..Default::default() },
+    ///     }],
+    /// };
+    ///
+    /// assert!(image.strip_background(PebbleColor::nothing()).is_none());
+    /// assert!(image.strip_background(white).is_some());
+    /// assert!(image.commands.is_empty());
+    /// ```
+    pub fn strip_background(&mut self, color: PebbleColor) -> Option<DrawCommand> {
+        let background = self.commands.first()?;
+        let DrawCommand::Path {
+            points,
+            open: false,
+            options,
+        } = background
+        else {
+            return None;
+        };
+        if options.fill_color != color.inner() || points.len() != 4 {
+            return None;
+        }
+
+        let is_rectangle = points
+            .iter()
+            .zip(points.iter().cycle().skip(1))
+            .all(|(a, b)| a.x == b.x || a.y == b.y);
+        // In pixels, so the check doesn't depend on the precision
+        let scale = options.precision.coordinate_scale();
+        let min_x = points.iter().map(|point| point.x).min()? as f32 / scale;
+        let max_x = points.iter().map(|point| point.x).max()? as f32 / scale;
+        let min_y = points.iter().map(|point| point.y).min()? as f32 / scale;
+        let max_y = points.iter().map(|point| point.y).max()? as f32 / scale;
+        // Every pixel center of the canvas has to be inside
+        let covers_canvas = min_x <= 0.0
+            && min_y <= 0.0
+            && max_x >= self.size.x as f32 - 1.0
+            && max_y >= self.size.y as f32 - 1.0;
+        if !is_rectangle || !covers_canvas {
+            return None;
+        }
+        Some(self.commands.remove(0))
+    }
+
+    /// Draw the commands of `layer` on top of this image, moved by `offset` pixels.
+    ///
+    /// The layer has to fit on this image's canvas once moved, so the offset can't be
+    /// negative, otherwise an [`Svg2PdcError::IncompatibleCanvas`] error is returned and the
+    /// image is left unchanged.
+    ///
+    /// ```
+    /// # use svg2pdc::{point::PebblePoint, prelude::*};
+    /// let mut icon = PebbleImage { size: PebblePoint { x: 32, y: 32 }, commands: vec![] };
+    /// let badge = PebbleImage { size: PebblePoint { x: 8, y: 8 }, commands: vec![] };
+    ///
+    /// icon.overlay(badge.clone(), PebblePoint { x: 24, y: 0 }).unwrap();
+    /// assert!(icon.overlay(badge.clone(), PebblePoint { x: 25, y: 0 }).is_err());
+    /// assert!(icon.overlay(badge, PebblePoint { x: -1, y: 0 }).is_err());
+    /// ```
+    pub fn overlay(&mut self, layer: PebbleImage, offset: PebblePoint) -> Svg2PdcResult<()> {
+        let commands = self.layer_commands(layer, offset)?;
+        self.commands.extend(commands);
+        Ok(())
+    }
+
+    /// Draw the commands of `layer` below this image, moved by `offset` pixels.
+    ///
+    /// Same as [`PebbleImage::overlay`] except the layer is drawn first.
+    pub fn underlay(&mut self, layer: PebbleImage, offset: PebblePoint) -> Svg2PdcResult<()> {
+        let commands = self.layer_commands(layer, offset)?;
+        self.commands.splice(0..0, commands);
+        Ok(())
+    }
+
+    /// Indices of the commands that paint `(x, y)`, a position in watch pixels, in drawing
+    /// order, so the last one is on top.
+    ///
+    /// ```rust
+    /// use svg2pdc::{color::PebbleColor, image::DrawOptions, point::PebblePoint, prelude::*};
+    ///
+    /// let disc = |x, radius| DrawCommand::Circle {
+    ///     center: PebblePoint { x, y: 10 },
+    ///     radius,
+    ///     options: DrawOptions {
+    ///         fill_color: PebbleColor::from_argb(3, 3, 3, 3).inner(),
+    ///         ..Default::default()
+    ///     },
+    /// };
+    /// let image = PebbleImage {
+    ///     size: PebblePoint { x: 30, y: 20 },
+    ///     commands: vec![disc(10, 8), disc(14, 2), disc(25, 2)],
+    /// };
+    /// assert_eq!(image.hit_test(14.5, 10.5), vec![0, 1]);
+    /// assert_eq!(image.hit_test(2.5, 10.5), vec![0]);
+    /// assert!(image.hit_test(20.5, 2.5).is_empty());
+    /// ```
+    pub fn hit_test(&self, x: f32, y: f32) -> Vec<usize> {
+        let point = FPoint::new(x, y);
+        self.commands
+            .iter()
+            .enumerate()
+            .filter(|(_, command)| command.covers(point))
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    fn layer_commands(
+        &self,
+        layer: PebbleImage,
+        offset: PebblePoint,
+    ) -> Svg2PdcResult<Vec<DrawCommand>> {
+        let fits = |offset: i16, size: i16, canvas: i16| {
+            offset >= 0 && i32::from(offset) + i32::from(size) <= i32::from(canvas)
+        };
+        if !fits(offset.x, layer.size.x, self.size.x) || !fits(offset.y, layer.size.y, self.size.y)
+        {
+            return Err(Svg2PdcError::IncompatibleCanvas(format!(
+                "a {}x{} layer at {},{} does not fit on a {}x{} canvas",
+                layer.size.x, layer.size.y, offset.x, offset.y, self.size.x, self.size.y
+            )));
+        }
+        layer
+            .commands
+            .into_iter()
+            .map(|mut command| {
+                command.translate(offset)?;
+                Ok(command)
+            })
+            .collect()
+    }
+}
+
+impl DrawCommand {
+    /// Apply `transform` to every point of the command, in pixels of the source document.
+    ///
+    /// The stored coordinates are mapped back to the document using the precision and pixel
+    /// convention of the command, so the transform doesn't need to know about either.
+    pub fn map_points(&mut self, transform: impl Fn(FPoint) -> FPoint) {
+        let (points, scale, offset) = match self {
+            Self::Path {
+                points, options, ..
+            } => (
+                points.as_mut_slice(),
+                options.precision.coordinate_scale(),
+                options.pixel_convention.offset(),
+            ),
+            // Circle centers are always stored with normal precision
+            Self::Circle {
+                center, options, ..
+            } => (
+                std::slice::from_mut(center),
+                1.0,
+                options.pixel_convention.offset(),
+            ),
+        };
+        let offset = FPoint::new(offset, offset);
+        for point in points {
+            let document = FPoint::from(*point) / scale - offset;
+            *point = to_pebble_point((transform(document) + offset) * scale);
+        }
+    }
+
+    /// Move the command by `offset` pixels.
+    ///
+    /// Fails with [`Svg2PdcError::CoordinateOutOfRange`] when a moved point doesn't fit into
+    /// a Pebble coordinate, the command is left unchanged then.
+    pub fn translate(&mut self, offset: PebblePoint) -> Svg2PdcResult<()> {
+        // Circle centers are always stored with normal precision
+        let scale = match self {
+            Self::Path { options, .. } => options.precision.coordinate_scale() as i16,
+            Self::Circle { .. } => 1,
+        };
+        let moved = |point: &PebblePoint| {
+            let x = offset
+                .x
+                .checked_mul(scale)
+                .and_then(|x| point.x.checked_add(x));
+            let y = offset
+                .y
+                .checked_mul(scale)
+                .and_then(|y| point.y.checked_add(y));
+            match (x, y) {
+                (Some(x), Some(y)) => Ok(PebblePoint { x, y }),
+                _ => Err(Svg2PdcError::CoordinateOutOfRange(
+                    *point + FPoint::from(offset) * f32::from(scale),
+                )),
+            }
+        };
+        match self {
+            Self::Path { points, .. } => {
+                *points = points.iter().map(moved).collect::<Svg2PdcResult<_>>()?;
+            }
+            Self::Circle { center, .. } => *center = moved(center)?,
+        }
+        Ok(())
+    }
+
+    /// Reverse the direction of a path. Circles are left untouched.
+    ///
+    /// Closed paths keep their first point, so only the direction of travel changes.
+    pub fn reverse(&mut self) {
+        match self {
+            Self::Path {
+                points, open: true, ..
+            } => points.reverse(),
+            Self::Path {
+                points,
+                open: false,
+                ..
+            } => {
+                if let Some(rest) = points.get_mut(1..) {
+                    rest.reverse();
+                }
+            }
+            Self::Circle { .. } => {}
+        }
+    }
+
+    /// Rotate the points of a closed path so it starts at the point closest to `corner`.
+    ///
+    /// Open paths and circles are left untouched, as their first point is significant.
+    ///
+    /// ```rust
+    /// use svg2pdc::{geometry::StartCorner, image::{DrawCommand, DrawOptions}, point::PebblePoint};
+    ///
+    /// let mut triangle = DrawCommand::Path {
+    ///     points: vec![
+    ///         PebblePoint { x: 10, y: 0 },
+    ///         PebblePoint { x: 20, y: 20 },
+    ///         PebblePoint { x: 0, y: 20 },
+    ///     ],
+    ///     open: false,
+    ///     options: DrawOptions::default(),
+    /// };
+    /// triangle.start_at(StartCorner::BottomLeft);
+    ///
+    /// let DrawCommand::Path { points, .. } = triangle else { unreachable!() };
+    /// assert_eq!(points[0], PebblePoint { x: 0, y: 20 });
+    /// assert_eq!(points[1], PebblePoint { x: 10, y: 0 });
+    /// ```
+    pub fn start_at(&mut self, corner: StartCorner) {
+        let Self::Path {
+            points,
+            open: false,
+            ..
+        } = self
+        else {
+            return;
+        };
+        let (Some(min_x), Some(max_x), Some(min_y), Some(max_y)) = (
+            points.iter().map(|point| point.x).min(),
+            points.iter().map(|point| point.x).max(),
+            points.iter().map(|point| point.y).min(),
+            points.iter().map(|point| point.y).max(),
+        ) else {
+            return;
+        };
+        let target = match corner {
+            StartCorner::TopLeft => FPoint::new(min_x as f32, min_y as f32),
+            StartCorner::TopRight => FPoint::new(max_x as f32, min_y as f32),
+            StartCorner::BottomRight => FPoint::new(max_x as f32, max_y as f32),
+            StartCorner::BottomLeft => FPoint::new(min_x as f32, max_y as f32),
+        };
+        let distance = |point: &PebblePoint| {
+            let delta = FPoint::from(*point) - target;
+            delta.x * delta.x + delta.y * delta.y
+        };
+        // The first of several equally close points wins, keeping the rotation stable
+        let start = points
+            .iter()
+            .enumerate()
+            .fold(
+                (0, f32::INFINITY),
+                |(best, best_distance), (index, point)| {
+                    let distance = distance(point);
+                    if distance < best_distance {
+                        (index, distance)
+                    } else {
+                        (best, best_distance)
+                    }
+                },
+            )
+            .0;
+        points.rotate_left(start);
+    }
+
+    /// Remove repeated points of a path, including the last point of a closed path when it
+    /// repeats the first, and with a `collinear_tolerance` the interior points that lie within
+    /// that many pixels of the straight line replacing them. Returns how many points were
+    /// removed.
+    ///
+    /// The first and last points of open paths are always kept. Circles are left untouched.
+    ///
+    /// ```rust
+    /// use svg2pdc::{image::{DrawCommand, DrawOptions}, point::PebblePoint};
+    ///
+    /// let mut line = DrawCommand::Path {
+    ///     points: vec![
+    ///         PebblePoint { x: 0, y: 0 },
+    ///         PebblePoint { x: 0, y: 0 },
+    ///         PebblePoint { x: 5, y: 1 },
+    ///         PebblePoint { x: 10, y: 0 },
+    ///     ],
+    ///     open: true,
+    ///     options: DrawOptions::default(),
+    /// };
+    /// assert_eq!(line.clone().optimize(None), 1);
+    /// assert_eq!(line.optimize(Some(1.0)), 2);
+    ///
+    /// let DrawCommand::Path { points, .. } = line else { unreachable!() };
+    /// assert_eq!(points, [PebblePoint { x: 0, y: 0 }, PebblePoint { x: 10, y: 0 }]);
+    /// ```
+    pub fn optimize(&mut self, collinear_tolerance: Option<f32>) -> usize {
+        let Self::Path {
+            points,
+            open,
+            options,
+        } = self
+        else {
+            return 0;
+        };
+        let count = points.len();
+        points.dedup();
+        if !*open && points.len() > 1 && points.first() == points.last() {
+            points.pop();
+        }
+
+        if let Some(tolerance) = collinear_tolerance {
+            let tolerance = tolerance * options.precision.coordinate_scale();
+            let kept = without_collinear(points, *open, tolerance);
+            // A closed path needs three points to enclose anything
+            if *open || kept.len() >= 3 {
+                *points = kept;
+            }
+        }
+        count - points.len()
+    }
+
+    /// Grow (positive `delta`) or shrink (negative `delta`) a closed shape by `delta` pixels.
+    ///
+    /// Closed paths move every edge outwards along its normal, circles change their radius.
+    /// Open paths are left untouched.
+    ///
+    /// ```rust
+    /// use svg2pdc::{image::{DrawCommand, DrawOptions}, point::PebblePoint};
+    ///
+    /// let mut square = DrawCommand::Path {
+    ///     points: vec![
+    ///         PebblePoint { x: 10, y: 10 },
+    ///         PebblePoint { x: 20, y: 10 },
+    ///         PebblePoint { x: 20, y: 20 },
+    ///         PebblePoint { x: 10, y: 20 },
+    ///     ],
+    ///     open: false,
+    ///     options: DrawOptions::default(),
+    /// };
+    /// square.offset(-2.0);
+    ///
+    /// let DrawCommand::Path { points, .. } = square else { unreachable!() };
+    /// assert_eq!(points[0], PebblePoint { x: 12, y: 12 });
+    /// assert_eq!(points[2], PebblePoint { x: 18, y: 18 });
+    /// ```
+    pub fn offset(&mut self, delta: f32) {
+        match self {
+            Self::Path {
+                points,
+                open: false,
+                options,
+            } => {
+                let delta = delta * options.precision.coordinate_scale();
+                let polygon = points.iter().map(|point| FPoint::from(*point)).collect();
+                *points = offset_polygon(polygon, delta)
+                    .into_iter()
+                    .map(to_pebble_point)
+                    .collect();
+            }
+            Self::Path { open: true, .. } => {}
+            Self::Circle { radius, .. } => {
+                *radius = (*radius as f32 + delta).round().clamp(0.0, u16::MAX as f32) as u16;
+            }
+        }
+    }
+}
+
+impl DrawCommand {
+    /// Whether the command paints anything: a fill, or a stroke of some width, in a color
+    /// that isn't fully transparent.
+    pub fn is_visible(&self) -> bool {
+        let options = self.options();
+        let stroke =
+            options.stroke_width > 0 && PebbleColor::from_inner(options.stroke_color).get_a() > 0;
+        stroke || PebbleColor::from_inner(options.fill_color).get_a() > 0
+    }
+
+    /// Smallest rectangle around everything the command paints, including half of the
+    /// stroke, in watch pixels like [`PebblePoint::pixel_position`]. `None` for a path
+    /// without points.
+    pub fn bounding_box(&self) -> Option<BoundingBox> {
+        let margin = self.stroke_reach();
+        let (min, max) = match self {
+            Self::Path {
+                points, options, ..
+            } => {
+                let mut points = points
+                    .iter()
+                    .map(|point| point.pixel_position(&options.precision));
+                let first = points.next()?;
+                points.fold((first, first), |(min, max), point| {
+                    (
+                        FPoint::new(min.x.min(point.x), min.y.min(point.y)),
+                        FPoint::new(max.x.max(point.x), max.y.max(point.y)),
+                    )
+                })
+            }
+            Self::Circle { center, radius, .. } => {
+                let center = center.pixel_position(&Precision::Normal);
+                let radius = FPoint::new(*radius as f32, *radius as f32);
+                (center - radius, center + radius)
+            }
+        };
+        let margin = FPoint::new(margin, margin);
+        Some(BoundingBox {
+            min: min - margin,
+            max: max + margin,
+        })
+    }
+
+    /// Whether the fill or the stroke of the command paints `point`, a position in watch
+    /// pixels. Fills use the even-odd rule, like the watch.
+    pub fn covers(&self, point: FPoint) -> bool {
+        if !self
+            .bounding_box()
+            .is_some_and(|bounds| bounds.contains(point))
+        {
+            return false;
+        }
+        let filled = PebbleColor::from_inner(self.options().fill_color).get_a() != 0;
+        let reach = self.stroke_reach();
+        match self {
+            Self::Path {
+                points,
+                open,
+                options,
+            } => {
+                let polygon: Vec<_> = points
+                    .iter()
+                    .map(|point| point.pixel_position(&options.precision))
+                    .collect();
+                let edges = polygon.len() - usize::from(*open && polygon.len() > 1);
+                (filled && polygon_contains(&polygon, point))
+                    || (reach > 0.0
+                        && (0..edges).any(|index| {
+                            let (a, b) = (polygon[index], polygon[(index + 1) % polygon.len()]);
+                            segment_distance(point, a, b) <= reach
+                        }))
+            }
+            Self::Circle { center, radius, .. } => {
+                let offset = point - center.pixel_position(&Precision::Normal);
+                let distance = (offset.x * offset.x + offset.y * offset.y).sqrt();
+                (filled && distance <= *radius as f32)
+                    || (reach > 0.0 && (distance - *radius as f32).abs() <= reach)
+            }
+        }
+    }
+
+    /// How far the visible stroke reaches past the outline.
+    fn stroke_reach(&self) -> f32 {
+        let options = self.options();
+        if PebbleColor::from_inner(options.stroke_color).get_a() == 0 {
+            0.0
+        } else {
+            options.stroke_width as f32 / 2.0
+        }
+    }
+}
+
+/// Even-odd test whether `point` lies inside `polygon`.
+pub(crate) fn polygon_contains(polygon: &[FPoint], point: FPoint) -> bool {
+    let mut inside = false;
+    for (index, a) in polygon.iter().enumerate() {
+        let b = polygon[(index + 1) % polygon.len()];
+        if (a.y > point.y) != (b.y > point.y)
+            && point.x < a.x + (point.y - a.y) / (b.y - a.y) * (b.x - a.x)
+        {
+            inside = !inside;
+        }
+    }
+    inside
+}
+
+/// Distance from `point` to the closest point of the segment from `a` to `b`.
+fn segment_distance(point: FPoint, a: FPoint, b: FPoint) -> f32 {
+    let direction = b - a;
+    let length_squared = direction.x * direction.x + direction.y * direction.y;
+    let t = if length_squared == 0.0 {
+        0.0
+    } else {
+        (((point.x - a.x) * direction.x + (point.y - a.y) * direction.y) / length_squared)
+            .clamp(0.0, 1.0)
+    };
+    let offset = point - (a + direction * t);
+    (offset.x * offset.x + offset.y * offset.y).sqrt()
+}
+
+/// Append `next` to `path` when both are open paths without a fill, drawn alike and sharing
+/// an end point, reversing `next` if needed.
+fn join_paths(path: &mut DrawCommand, next: &DrawCommand) -> bool {
+    let (
+        DrawCommand::Path {
+            points,
+            open: true,
+            options,
+        },
+        DrawCommand::Path {
+            points: next_points,
+            open: true,
+            options: next_options,
+        },
+    ) = (path, next)
+    else {
+        return false;
+    };
+    let alike = options.stroke_color == next_options.stroke_color
+        && options.stroke_width == next_options.stroke_width
+        && options.fill_color == next_options.fill_color
+        && options.precision == next_options.precision;
+    // Joining filled paths would change the area they fill
+    let unfilled = PebbleColor::from_inner(options.fill_color).get_a() == 0;
+    let fits = points.len() + next_points.len() <= u16::MAX as usize + 1;
+    let (Some(&first), Some(&last), Some(&next_first), Some(&next_last)) = (
+        points.first(),
+        points.last(),
+        next_points.first(),
+        next_points.last(),
+    ) else {
+        return false;
+    };
+    if !alike || !unfilled || !fits {
+        return false;
+    }
+
+    if last == next_first {
+        points.extend(&next_points[1..]);
+    } else if last == next_last {
+        points.extend(next_points.iter().rev().skip(1));
+    } else if first == next_last {
+        points.splice(..0, next_points[..next_points.len() - 1].iter().copied());
+    } else if first == next_first {
+        points.splice(
+            ..0,
+            next_points
+                .iter()
+                .rev()
+                .take(next_points.len() - 1)
+                .copied(),
+        );
+    } else {
+        return false;
+    }
+    true
+}
+
+/// The points of a path without the interior points that lie within `tolerance` of the
+/// segment replacing them. A closed path wraps around to its first point, which is kept.
+fn without_collinear(points: &[PebblePoint], open: bool, tolerance: f32) -> Vec<PebblePoint> {
+    let Some(&first) = points.first() else {
+        return Vec::new();
+    };
+    let end = if open { points.len() - 1 } else { points.len() };
+    let point = |index: usize| FPoint::from(points[index % points.len()]);
+    let mut kept = vec![first];
+    let mut start = 0;
+    for (candidate, &current) in points.iter().enumerate().take(end).skip(1) {
+        // Drop the candidate when every point skipped since the last kept one, the candidate
+        // included, stays close to the segment from that point to the next
+        let next = point(candidate + 1);
+        let straight = (start + 1..=candidate)
+            .all(|skipped| segment_distance(point(skipped), point(start), next) <= tolerance);
+        if !straight {
+            kept.push(current);
+            start = candidate;
+        }
+    }
+    if open && points.len() > 1 {
+        kept.push(points[end]);
+    }
+    kept
+}
+
+/// Round a point back onto the Pebble coordinate grid, clamping it to the representable range.
+pub(crate) fn to_pebble_point(point: FPoint) -> PebblePoint {
+    let range = i16::MIN as f32..=i16::MAX as f32;
+    PebblePoint {
+        x: point.x.round().clamp(*range.start(), *range.end()) as i16,
+        y: point.y.round().clamp(*range.start(), *range.end()) as i16,
+    }
+}
+
+/// Twice the signed area of a polygon, positive when it winds clockwise on screen.
+fn signed_area(polygon: &[FPoint]) -> f32 {
+    polygon
+        .iter()
+        .zip(polygon.iter().cycle().skip(1))
+        .map(|(a, b)| a.x * b.y - b.x * a.y)
+        .sum()
+}
+
+/// Unit normal of the edge from `a` to `b`, pointing to the right of the direction of travel.
+fn edge_normal(a: FPoint, b: FPoint) -> FPoint {
+    let direction = b - a;
+    let length = (direction.x * direction.x + direction.y * direction.y).sqrt();
+    FPoint::new(direction.y / length, -direction.x / length)
+}
+
+fn offset_polygon(mut polygon: Vec<FPoint>, delta: f32) -> Vec<FPoint> {
+    // Repeated points have no edge direction, the offset is defined by their neighbours
+    polygon.dedup();
+    while polygon.len() > 1 && polygon.first() == polygon.last() {
+        polygon.pop();
+    }
+    if polygon.len() < 3 {
+        return polygon;
+    }
+
+    let outwards = signed_area(&polygon).signum();
+    let count = polygon.len();
+    (0..count)
+        .map(|index| {
+            let previous = polygon[(index + count - 1) % count];
+            let current = polygon[index];
+            let next = polygon[(index + 1) % count];
+
+            let n1 = edge_normal(previous, current) * outwards;
+            let n2 = edge_normal(current, next) * outwards;
+            let cosine = n1.x * n2.x + n1.y * n2.y;
+            // The miter of two edge normals has length 1 / cos(half angle)
+            let miter = if 1.0 + cosine < 2.0 / (MITER_LIMIT * MITER_LIMIT) {
+                n1 * MITER_LIMIT
+            } else {
+                (n1 + n2) / (1.0 + cosine)
+            };
+            current + miter * delta
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{image::DrawOptions, point::Precision};
+
+    fn path(points: &[(i16, i16)], open: bool, precision: Precision) -> DrawCommand {
+        DrawCommand::Path {
+            points: points.iter().map(|&(x, y)| PebblePoint { x, y }).collect(),
+            open,
+            options: DrawOptions {
+                precision,
+                ..Default::default()
+            },
+        }
+    }
+
+    fn points(command: &DrawCommand) -> Vec<(i16, i16)> {
+        match command {
+            DrawCommand::Path { points, .. } => points.iter().map(|p| (p.x, p.y)).collect(),
+            DrawCommand::Circle { .. } => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_layer_with_negative_offset_is_rejected() {
+        let mut image = PebbleImage {
+            size: PebblePoint { x: 10, y: 10 },
+            commands: vec![],
+        };
+        let layer = PebbleImage {
+            size: PebblePoint { x: 4, y: 4 },
+            commands: vec![path(&[(0, 0), (3, 3)], true, Precision::Normal)],
+        };
+        for offset in [(-1, 0), (0, -1), (i16::MIN, i16::MIN)] {
+            let offset = PebblePoint {
+                x: offset.0,
+                y: offset.1,
+            };
+            assert!(image.overlay(layer.clone(), offset).is_err());
+            assert!(image.underlay(layer.clone(), offset).is_err());
+        }
+        assert!(image.commands.is_empty());
+        image.overlay(layer, PebblePoint { x: 6, y: 6 }).unwrap();
+        assert_eq!(points(&image.commands[0]), vec![(6, 6), (9, 9)]);
+    }
+
+    #[test]
+    fn test_reverse() {
+        let mut open = path(&[(0, 0), (5, 0), (5, 5)], true, Precision::Normal);
+        open.reverse();
+        assert_eq!(points(&open), vec![(5, 5), (5, 0), (0, 0)]);
+
+        let mut closed = path(&[(0, 0), (5, 0), (5, 5)], false, Precision::Normal);
+        closed.reverse();
+        assert_eq!(points(&closed), vec![(0, 0), (5, 5), (5, 0)]);
+    }
+
+    #[test]
+    fn test_optimize_closed_paths() {
+        // A square with a repeated corner, a midpoint on its top edge and its start repeated
+        let mut square = path(
+            &[(0, 0), (5, 0), (10, 0), (10, 0), (10, 10), (0, 10), (0, 0)],
+            false,
+            Precision::Normal,
+        );
+        assert_eq!(square.clone().optimize(None), 2);
+        assert_eq!(square.optimize(Some(0.5)), 3);
+        assert_eq!(points(&square), vec![(0, 0), (10, 0), (10, 10), (0, 10)]);
+
+        // The tolerance is in pixels, precise coordinates are eighths of one
+        let bump = [(0, 0), (40, 4), (80, 0), (80, 80)];
+        let mut precise = path(&bump, false, Precision::Precise);
+        assert_eq!(precise.optimize(Some(0.25)), 0);
+        assert_eq!(precise.optimize(Some(0.5)), 1);
+
+        // A flat polygon keeps its points rather than collapsing to a line
+        let mut flat = path(&[(0, 0), (5, 0), (10, 0)], false, Precision::Normal);
+        assert_eq!(flat.optimize(Some(1.0)), 0);
+    }
+
+    #[test]
+    fn test_merge_paths_keeps_styles_and_fills_apart() {
+        let filled = DrawCommand::Path {
+            points: vec![PebblePoint { x: 5, y: 5 }, PebblePoint { x: 9, y: 9 }],
+            open: true,
+            options: DrawOptions {
+                fill_color: 0xFF,
+                ..Default::default()
+            },
+        };
+        let mut image = PebbleImage {
+            size: PebblePoint { x: 10, y: 10 },
+            commands: vec![
+                path(&[(0, 0), (5, 5)], true, Precision::Normal),
+                path(&[(0, 0), (0, 9)], true, Precision::Normal),
+                path(&[(0, 9), (40, 72)], true, Precision::Precise),
+                path(&[(5, 5), (9, 0)], false, Precision::Normal),
+                filled.clone(),
+                filled,
+            ],
+        };
+        assert_eq!(image.merge_paths(), 1);
+        assert_eq!(points(&image.commands[0]), vec![(0, 9), (0, 0), (5, 5)]);
+        assert_eq!(image.commands.len(), 5);
+    }
+
+    #[test]
+    fn test_strip_invisible() {
+        let styled = |stroke_width, stroke_color, fill_color| DrawCommand::Circle {
+            center: PebblePoint { x: 5, y: 5 },
+            radius: 3,
+            options: DrawOptions {
+                stroke_width,
+                stroke_color,
+                fill_color,
+                ..Default::default()
+            },
+        };
+        let mut image = PebbleImage {
+            size: PebblePoint { x: 10, y: 10 },
+            commands: vec![
+                styled(0, 0xC0, 0),
+                styled(2, 0x3F, 0x00),
+                styled(2, 0xC0, 0),
+                styled(0, 0, 0xFF),
+            ],
+        };
+        assert_eq!(image.strip_invisible(), 26);
+        assert_eq!(image.commands.len(), 2);
+        assert!(image.commands.iter().all(DrawCommand::is_visible));
+    }
+
+    #[test]
+    fn test_translate_scales_precise_paths() {
+        let mut normal = path(&[(1, 2)], true, Precision::Normal);
+        normal.translate(PebblePoint { x: 3, y: 4 }).unwrap();
+        assert_eq!(points(&normal), vec![(4, 6)]);
+
+        let mut precise = path(&[(8, 16)], true, Precision::Precise);
+        precise.translate(PebblePoint { x: 3, y: 4 }).unwrap();
+        assert_eq!(points(&precise), vec![(32, 48)]);
+    }
+
+    #[test]
+    fn test_translate_out_of_range_fails() {
+        let mut normal = path(&[(1, 2)], true, Precision::Normal);
+        normal
+            .translate(PebblePoint {
+                x: i16::MAX - 1,
+                y: 0,
+            })
+            .unwrap();
+        assert_eq!(points(&normal), vec![(i16::MAX, 2)]);
+        assert!(normal.translate(PebblePoint { x: 1, y: 0 }).is_err());
+        assert_eq!(points(&normal), vec![(i16::MAX, 2)]);
+
+        // Scaling the offset to eighths of a pixel overflows on its own
+        let mut precise = path(&[(0, 0)], true, Precision::Precise);
+        assert!(
+            precise
+                .translate(PebblePoint {
+                    x: 0,
+                    y: i16::MAX / 2
+                })
+                .is_err()
+        );
+        assert_eq!(points(&precise), vec![(0, 0)]);
+    }
+
+    #[test]
+    fn test_move_by_and_offset_clamp_to_the_coordinate_range() {
+        let mut image = PebbleImage {
+            size: PebblePoint { x: 10, y: 10 },
+            commands: vec![path(&[(1, 2)], true, Precision::Normal)],
+        };
+        image.move_by(FPoint::new(f32::from(i16::MAX), 0.0));
+        assert_eq!(points(&image.commands[0]), vec![(i16::MAX, 2)]);
+
+        let mut image = PebbleImage {
+            size: PebblePoint { x: 10, y: 10 },
+            commands: vec![path(
+                &[(0, 0), (i16::MAX - 1, 0), (i16::MAX - 1, 4), (0, 4)],
+                false,
+                Precision::Normal,
+            )],
+        };
+        image.offset(4.0);
+        assert!(
+            points(&image.commands[0])
+                .iter()
+                .any(|&(x, _)| x == i16::MAX)
+        );
+    }
+
+    #[test]
+    fn test_flip_and_rotate_keep_pixels_on_the_canvas() {
+        let mut image = PebbleImage {
+            size: PebblePoint { x: 10, y: 4 },
+            commands: vec![
+                path(&[(0, 0), (9, 3)], true, Precision::Normal),
+                path(&[(0, 0), (72, 24)], true, Precision::Precise),
+            ],
+        };
+
+        image.flip_horizontal();
+        assert_eq!(points(&image.commands[0]), vec![(9, 0), (0, 3)]);
+        assert_eq!(points(&image.commands[1]), vec![(72, 0), (0, 24)]);
+
+        image.rotate(Rotation::Cw90);
+        assert_eq!(image.size, PebblePoint { x: 4, y: 10 });
+        assert_eq!(points(&image.commands[0]), vec![(3, 9), (0, 0)]);
+        assert_eq!(points(&image.commands[1]), vec![(24, 72), (0, 0)]);
+    }
+
+    #[test]
+    fn test_offset_ignores_winding() {
+        let mut command = path(
+            &[(10, 10), (10, 20), (20, 20), (20, 10)],
+            false,
+            Precision::Normal,
+        );
+        command.offset(1.0);
+        assert_eq!(points(&command), vec![(9, 9), (9, 21), (21, 21), (21, 9)]);
+    }
+
+    #[test]
+    fn test_offset_scales_precise_paths() {
+        let mut command = path(
+            &[(80, 80), (160, 80), (160, 160), (80, 160)],
+            false,
+            Precision::Precise,
+        );
+        command.offset(-1.0);
+        assert_eq!(
+            points(&command),
+            vec![(88, 88), (152, 88), (152, 152), (88, 152)]
+        );
+    }
+
+    #[test]
+    fn test_offset_keeps_open_paths_and_grows_circles() {
+        let mut line = path(&[(0, 0), (10, 10)], true, Precision::Normal);
+        line.offset(2.0);
+        assert_eq!(points(&line), vec![(0, 0), (10, 10)]);
+
+        let mut circle = DrawCommand::Circle {
+            center: PebblePoint { x: 5, y: 5 },
+            radius: 3,
+            options: DrawOptions::default(),
+        };
+        circle.offset(-5.0);
+        assert!(matches!(circle, DrawCommand::Circle { radius: 0, .. }));
+    }
+
+    #[test]
+    fn test_open_paths_are_hit_on_their_stroke_only() {
+        let mut line = path(&[(0, 0), (10, 0), (10, 10)], true, Precision::Normal);
+        if let DrawCommand::Path { options, .. } = &mut line {
+            options.stroke_width = 2;
+            options.stroke_color = PebbleColor::from_argb(3, 3, 3, 3).inner();
+        }
+        assert_eq!(
+            line.bounding_box(),
+            Some(BoundingBox {
+                min: FPoint::new(-0.5, -0.5),
+                max: FPoint::new(11.5, 11.5),
+            })
+        );
+        assert!(line.covers(FPoint::new(5.5, 1.0)));
+        assert!(line.covers(FPoint::new(11.0, 8.0)));
+        // The closing edge is not stroked and nothing is filled
+        assert!(!line.covers(FPoint::new(5.5, 5.5)));
+        assert!(!line.covers(FPoint::new(3.0, 3.0)));
+    }
+}
@@ -0,0 +1,416 @@
+//! Import of font glyphs, e.g. icons from an icon font.
+//!
+//! Glyph outlines are flattened into polygons and fitted onto the canvas. Pebble paths can't
+//! have holes, so every hole (the inside of an "O") is joined to the contour around it by a
+//! zero-width cut, which fills the same as the original outline.
+//!
+//! With the `text` feature, whole lines of text are outlined the same way with a
+//! [`TextFont`], to convert `<text>` elements.
+
+#[cfg(feature = "text")]
+use std::{fmt, sync::Arc};
+
+#[cfg(feature = "text")]
+use ttf_parser::GlyphId;
+use ttf_parser::{Face, OutlineBuilder};
+
+use crate::{
+    color::Color,
+    error::{Svg2PdcError, Svg2PdcResult},
+    geometry::polygon_contains,
+    point::{FPoint, PebblePoint},
+};
+
+/// Largest distance in pixels between a curve and the lines replacing it.
+const FLATTENING_TOLERANCE: f32 = 0.25;
+
+/// The flattened outline of the glyph for `codepoint`, scaled to fit `size` and centered on it,
+/// as one polygon per filled region.
+pub fn glyph_polygons(
+    font: &[u8],
+    codepoint: char,
+    size: PebblePoint,
+) -> Svg2PdcResult<Vec<Vec<FPoint>>> {
+    let face =
+        Face::parse(font, 0).map_err(|error| Svg2PdcError::InvalidFont(error.to_string()))?;
+    let glyph = face.glyph_index(codepoint).ok_or_else(|| {
+        Svg2PdcError::InvalidFont(format!(
+            "no glyph for U+{:04X} in the font",
+            codepoint as u32
+        ))
+    })?;
+    let bounds = face.glyph_bounding_box(glyph).ok_or_else(|| {
+        Svg2PdcError::InvalidFont(format!("the glyph for U+{:04X} is empty", codepoint as u32))
+    })?;
+
+    // Fit the glyph on the canvas keeping its aspect ratio, font units point up
+    let (width, height) = (bounds.width() as f32, bounds.height() as f32);
+    let scale = (size.x as f32 / width).min(size.y as f32 / height);
+    let offset = FPoint::new(
+        (size.x as f32 - width * scale) / 2.0 - bounds.x_min as f32 * scale,
+        (size.y as f32 - height * scale) / 2.0 + bounds.y_max as f32 * scale,
+    );
+    let mut outline = Outline {
+        scale,
+        offset,
+        contours: Vec::new(),
+        current: Vec::new(),
+    };
+    face.outline_glyph(glyph, &mut outline);
+    outline.close();
+
+    Ok(join_holes(outline.contours))
+}
+
+/// A font to outline `<text>` elements with, shared by the clones of a converter.
+#[cfg(feature = "text")]
+#[derive(Clone)]
+pub struct TextFont {
+    data: Arc<[u8]>,
+}
+
+#[cfg(feature = "text")]
+impl TextFont {
+    /// Load a TrueType or OpenType font, failing when `data` is neither.
+    ///
+    /// ```rust
+    /// use svg2pdc::{glyph::TextFont, prelude::*};
+    ///
+    /// assert!(matches!(
+    ///     TextFont::new(b"<svg/>".to_vec()),
+    ///     Err(Svg2PdcError::InvalidFont(_))
+    /// ));
+    /// ```
+    pub fn new(data: impl Into<Vec<u8>>) -> Svg2PdcResult<Self> {
+        let data = data.into();
+        Face::parse(&data, 0).map_err(|error| Svg2PdcError::InvalidFont(error.to_string()))?;
+        Ok(Self { data: data.into() })
+    }
+
+    fn face(&self) -> Face<'_> {
+        Face::parse(&self.data, 0).expect("the font is checked when it is loaded")
+    }
+
+    /// The advance of `text` set at `size` pixels per em, e.g. to center it.
+    pub fn advance(&self, text: &str, size: f32) -> f32 {
+        let face = self.face();
+        layout(&face, text, size / face.units_per_em() as f32).1
+    }
+
+    /// The flattened outline of `text` set at `size` pixels per em with its baseline starting
+    /// at `origin`, as one polygon per filled region.
+    ///
+    /// Glyphs are placed by their advances and the pairs of the `kern` table, there is no
+    /// further shaping. Characters missing from the font take the space of its `.notdef`
+    /// glyph without being drawn.
+    pub fn text_polygons(&self, text: &str, size: f32, origin: FPoint) -> Vec<Vec<FPoint>> {
+        let face = self.face();
+        let scale = size / face.units_per_em() as f32;
+        let mut polygons = Vec::new();
+        for (glyph, x) in layout(&face, text, scale).0 {
+            if glyph == GlyphId(0) {
+                continue;
+            }
+            let mut outline = Outline {
+                scale,
+                offset: FPoint::new(origin.x + x, origin.y),
+                contours: Vec::new(),
+                current: Vec::new(),
+            };
+            face.outline_glyph(glyph, &mut outline);
+            outline.close();
+            polygons.extend(join_holes(outline.contours));
+        }
+        polygons
+    }
+}
+
+#[cfg(feature = "text")]
+impl fmt::Debug for TextFont {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "TextFont({} bytes)", self.data.len())
+    }
+}
+
+/// The glyphs of `text` with their offsets along the baseline in pixels, and the advance of
+/// the whole text.
+#[cfg(feature = "text")]
+fn layout(face: &Face<'_>, text: &str, scale: f32) -> (Vec<(GlyphId, f32)>, f32) {
+    let mut glyphs = Vec::new();
+    let mut pen = 0.0;
+    let mut previous = None;
+    for character in text.chars() {
+        let glyph = face.glyph_index(character).unwrap_or(GlyphId(0));
+        if let Some(previous) = previous {
+            pen += kerning(face, previous, glyph) * scale;
+        }
+        glyphs.push((glyph, pen));
+        pen += face.glyph_hor_advance(glyph).unwrap_or(0) as f32 * scale;
+        previous = Some(glyph);
+    }
+    (glyphs, pen)
+}
+
+/// The horizontal kerning between two glyphs in font units, from the first `kern` subtable
+/// with the pair.
+#[cfg(feature = "text")]
+fn kerning(face: &Face<'_>, left: GlyphId, right: GlyphId) -> f32 {
+    face.tables()
+        .kern
+        .into_iter()
+        .flat_map(|table| table.subtables)
+        .filter(|subtable| subtable.horizontal && !subtable.variable && !subtable.has_cross_stream)
+        .find_map(|subtable| subtable.glyphs_kerning(left, right))
+        .map_or(0.0, f32::from)
+}
+
+/// SVG markup with one filled `<path>` per polygon of the glyph, for
+/// [`crate::svg_converter::SvgConverter::parse_svg_fragment`].
+pub fn glyph_fragment(
+    font: &[u8],
+    codepoint: char,
+    size: PebblePoint,
+    fill: Color,
+) -> Svg2PdcResult<String> {
+    let Color { r, g, b, a } = fill;
+    Ok(glyph_polygons(font, codepoint, size)?
+        .iter()
+        .map(|polygon| {
+            let points: Vec<_> = polygon
+                .iter()
+                .map(|point| format!("{} {}", point.x, point.y))
+                .collect();
+            format!(
+                r##"<path d="M{}Z" fill="#{r:02x}{g:02x}{b:02x}{a:02x}" stroke-width="0"/>"##,
+                points.join(" L")
+            )
+        })
+        .collect())
+}
+
+/// Collects the contours of a glyph, transformed to canvas pixels.
+struct Outline {
+    scale: f32,
+    offset: FPoint,
+    contours: Vec<Vec<FPoint>>,
+    current: Vec<FPoint>,
+}
+
+impl Outline {
+    fn point(&self, x: f32, y: f32) -> FPoint {
+        FPoint::new(
+            x * self.scale + self.offset.x,
+            self.offset.y - y * self.scale,
+        )
+    }
+
+    fn last(&self) -> FPoint {
+        self.current.last().copied().unwrap_or_default()
+    }
+
+    /// Add a curve as lines, `curve` maps 0..=1 to the points of the curve.
+    fn flatten(&mut self, control_polygon_length: f32, curve: impl Fn(f32) -> FPoint) {
+        let segments = (control_polygon_length / FLATTENING_TOLERANCE)
+            .sqrt()
+            .ceil()
+            .clamp(1.0, 64.0) as usize;
+        for segment in 1..=segments {
+            self.current.push(curve(segment as f32 / segments as f32));
+        }
+    }
+}
+
+impl OutlineBuilder for Outline {
+    fn move_to(&mut self, x: f32, y: f32) {
+        self.close();
+        self.current.push(self.point(x, y));
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        self.current.push(self.point(x, y));
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        let (start, control, end) = (self.last(), self.point(x1, y1), self.point(x, y));
+        let length = distance(start, control) + distance(control, end);
+        self.flatten(length, |t| {
+            start * ((1.0 - t) * (1.0 - t)) + control * (2.0 * (1.0 - t) * t) + end * (t * t)
+        });
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        let (start, first, second, end) = (
+            self.last(),
+            self.point(x1, y1),
+            self.point(x2, y2),
+            self.point(x, y),
+        );
+        let length = distance(start, first) + distance(first, second) + distance(second, end);
+        self.flatten(length, |t| {
+            let u = 1.0 - t;
+            start * (u * u * u)
+                + first * (3.0 * u * u * t)
+                + second * (3.0 * u * t * t)
+                + end * (t * t * t)
+        });
+    }
+
+    fn close(&mut self) {
+        let mut contour = std::mem::take(&mut self.current);
+        if contour.len() > 1 && contour.first() == contour.last() {
+            contour.pop();
+        }
+        if contour.len() > 2 {
+            self.contours.push(contour);
+        }
+    }
+}
+
+/// Join every hole to the contour directly around it, so each filled region is one polygon.
+///
+/// A contour is a hole when it lies inside an odd number of other contours.
+fn join_holes(contours: Vec<Vec<FPoint>>) -> Vec<Vec<FPoint>> {
+    let holes = find_holes(&contours, true);
+    bridge_holes(contours, &holes)
+}
+
+/// The number of other contours every contour lies in.
+fn contour_depths(contours: &[Vec<FPoint>]) -> Vec<usize> {
+    contours
+        .iter()
+        .enumerate()
+        .map(|(index, contour)| {
+            contours
+                .iter()
+                .enumerate()
+                .filter(|(other, outer)| *other != index && polygon_contains(outer, contour[0]))
+                .count()
+        })
+        .collect()
+}
+
+/// Which contours of a filled outline are holes: with `even_odd` the ones inside an odd
+/// number of other contours, otherwise the ones whose winding cancels out the winding of the
+/// contours around them (`fill-rule="nonzero"`).
+pub(crate) fn find_holes(contours: &[Vec<FPoint>], even_odd: bool) -> Vec<bool> {
+    contours
+        .iter()
+        .enumerate()
+        .map(|(index, contour)| {
+            let around = contours
+                .iter()
+                .enumerate()
+                .filter(|(other, outer)| *other != index && polygon_contains(outer, contour[0]));
+            if even_odd {
+                around.count() % 2 == 1
+            } else {
+                let winding: i32 = around.map(|(_, outer)| orientation(outer)).sum();
+                winding != 0 && winding + orientation(contour) == 0
+            }
+        })
+        .collect()
+}
+
+/// Join every contour marked in `holes` to the contour directly around it, so each filled
+/// region is one polygon. Holes without a filled contour around them are dropped.
+pub(crate) fn bridge_holes(contours: Vec<Vec<FPoint>>, holes: &[bool]) -> Vec<Vec<FPoint>> {
+    let depths = contour_depths(&contours);
+    let mut polygons: Vec<Option<Vec<FPoint>>> = contours
+        .iter()
+        .zip(holes)
+        .map(|(contour, hole)| (!hole).then(|| contour.clone()))
+        .collect();
+    for (index, hole) in contours
+        .iter()
+        .enumerate()
+        .filter(|(index, _)| holes[*index])
+    {
+        // The region around a hole is the containing contour one level further out
+        let Some(parent) = contours
+            .iter()
+            .zip(&depths)
+            .position(|(outer, outer_depth)| {
+                *outer_depth + 1 == depths[index] && polygon_contains(outer, hole[0])
+            })
+        else {
+            continue;
+        };
+        if let Some(polygon) = &mut polygons[parent] {
+            *polygon = bridge(polygon, hole);
+        }
+    }
+    polygons.into_iter().flatten().collect()
+}
+
+/// 1 for a contour running clockwise on the screen, -1 for a counterclockwise one.
+fn orientation(contour: &[FPoint]) -> i32 {
+    let area: f32 = contour
+        .iter()
+        .zip(contour.iter().cycle().skip(1))
+        .map(|(a, b)| a.x * b.y - b.x * a.y)
+        .sum();
+    if area > 0.0 {
+        1
+    } else if area < 0.0 {
+        -1
+    } else {
+        0
+    }
+}
+
+/// Walk around `outer` and, at the vertex closest to `hole`, around the hole and back.
+fn bridge(outer: &[FPoint], hole: &[FPoint]) -> Vec<FPoint> {
+    let (outer_index, hole_index) = (0..outer.len())
+        .flat_map(|outer_index| (0..hole.len()).map(move |hole_index| (outer_index, hole_index)))
+        .min_by(|&(a_outer, a_hole), &(b_outer, b_hole)| {
+            distance(outer[a_outer], hole[a_hole])
+                .total_cmp(&distance(outer[b_outer], hole[b_hole]))
+        })
+        .unwrap_or_default();
+
+    let mut polygon = Vec::with_capacity(outer.len() + hole.len() + 2);
+    polygon.extend_from_slice(&outer[..=outer_index]);
+    polygon.extend_from_slice(&hole[hole_index..]);
+    polygon.extend_from_slice(&hole[..=hole_index]);
+    polygon.extend_from_slice(&outer[outer_index..]);
+    polygon
+}
+
+fn distance(a: FPoint, b: FPoint) -> f32 {
+    ((a.x - b.x).powi(2) + (a.y - b.y).powi(2)).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn square(from: f32, to: f32) -> Vec<FPoint> {
+        vec![
+            FPoint::new(from, from),
+            FPoint::new(to, from),
+            FPoint::new(to, to),
+            FPoint::new(from, to),
+        ]
+    }
+
+    #[test]
+    fn test_holes_are_joined_to_the_contour_around_them() {
+        // An "O" next to a square with a filled island in a hole
+        let contours = vec![
+            square(0.0, 10.0),
+            square(3.0, 7.0),
+            square(20.0, 30.0),
+            square(21.0, 29.0),
+            square(24.0, 26.0),
+        ];
+        let polygons = join_holes(contours);
+        assert_eq!(polygons.len(), 3);
+        assert_eq!(polygons[0].len(), 4 + 4 + 2);
+        assert_eq!(polygons[1].len(), 4 + 4 + 2);
+        assert_eq!(polygons[2], square(24.0, 26.0));
+
+        // The hole is empty and the ring around it is filled
+        assert!(!polygon_contains(&polygons[0], FPoint::new(5.0, 5.0)));
+        assert!(polygon_contains(&polygons[0], FPoint::new(1.5, 5.0)));
+    }
+}
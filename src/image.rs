@@ -1,12 +1,19 @@
-use byteorder::{LittleEndian, WriteBytesExt};
-use std::io::Write;
+use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
+use std::{
+    io::{Read, Write},
+    time::Duration,
+};
 
 use crate::{
-    error::Svg2PdcResult,
-    point::{Conversion, FPoint, PebblePoint, Precision},
+    color::PebbleColor,
+    error::{Svg2PdcError, Svg2PdcResult},
+    point::{
+        Conversion, FPoint, OutputMode, PebblePoint, PixelConvention, Precision, RoundingMode,
+    },
 };
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 /// A PebbleImage is a Pebble Draw Command Image.
 ///
 /// It contains a size and a list of draw commands.
@@ -18,39 +25,386 @@ pub struct PebbleImage {
 
 impl PebbleImage {
     const DRAW_COMMAND_VERSION: u8 = 1;
+    const IMAGE_MAGIC: &[u8; 4] = b"PDCI";
 
     fn serialize_header<W: Write>(&self, writer: &mut W) -> Svg2PdcResult<()> {
         writer.write_u8(Self::DRAW_COMMAND_VERSION)?;
         writer.write_u8(0)?; // reserved byte
-        writer.write_u16::<LittleEndian>(self.size.x)?;
-        writer.write_u16::<LittleEndian>(self.size.y)?;
+        writer.write_i16::<LittleEndian>(self.size.x)?;
+        writer.write_i16::<LittleEndian>(self.size.y)?;
         Ok(())
     }
 
     pub fn serialize<W: Write>(&self, writer: &mut W) -> Svg2PdcResult<()> {
-        let mut buf_writer = std::io::BufWriter::new(Vec::new());
-        self.serialize_header(&mut buf_writer)?;
-        buf_writer.write_u16::<LittleEndian>(self.commands.len() as u16)?;
+        let mut buf = Vec::new();
+        self.serialize_raw(&mut buf)?;
+
+        writer.write_all(Self::IMAGE_MAGIC)?;
+        writer.write_u32::<LittleEndian>(buf.len() as u32)?;
+        writer.write_all(&buf)?;
+
+        Ok(())
+    }
+
+    /// Write only the image payload (version, size and command list), without the `PDCI`
+    /// magic and length prefix, for embedding in custom containers.
+    pub fn serialize_raw<W: Write>(&self, writer: &mut W) -> Svg2PdcResult<()> {
+        self.serialize_header(writer)?;
+        writer.write_u16::<LittleEndian>(self.commands.len() as u16)?;
         for command in &self.commands {
-            command.serialize(&mut buf_writer)?;
+            command.serialize(writer)?;
+        }
+        Ok(())
+    }
+
+    /// Size of the image once written with [`PebbleImage::serialize`], in bytes.
+    pub fn serialized_size(&self) -> usize {
+        // Magic, payload length, version, reserved byte, canvas size and command count
+        let header = 4 + 4 + 1 + 1 + 4 + 2;
+        header
+            + self
+                .commands
+                .iter()
+                .map(DrawCommand::serialized_size)
+                .sum::<usize>()
+    }
+
+    /// Read a PDC image previously written with [`PebbleImage::serialize`] or
+    /// [`PebbleImage::serialize_raw`].
+    ///
+    /// The commands of the returned image carry the serialized coordinates as-is,
+    /// so serializing it again produces the same bytes.
+    pub fn deserialize<R: Read>(reader: &mut R) -> Svg2PdcResult<Self> {
+        let mut magic = [0; 4];
+        reader.read_exact(&mut magic)?;
+        if &magic != Self::IMAGE_MAGIC {
+            // A raw payload starts with the version and a reserved byte instead of the magic
+            if magic[0] == Self::DRAW_COMMAND_VERSION && magic[1] == 0 {
+                return Self::deserialize_raw(&mut magic.as_slice().chain(reader));
+            }
+            return Err(Svg2PdcError::InvalidPdc(format!(
+                "expected magic {:?}, found {:?}",
+                Self::IMAGE_MAGIC,
+                magic
+            )));
+        }
+        let length = reader.read_u32::<LittleEndian>()?;
+        let mut payload = reader.take(length as u64);
+        let image = Self::deserialize_raw(&mut payload)?;
+
+        if payload.limit() != 0 {
+            return Err(Svg2PdcError::InvalidPdc(format!(
+                "{} trailing bytes after the last command",
+                payload.limit()
+            )));
+        }
+
+        Ok(image)
+    }
+
+    /// Read an image payload written with [`PebbleImage::serialize_raw`].
+    pub fn deserialize_raw<R: Read>(reader: &mut R) -> Svg2PdcResult<Self> {
+        let version = reader.read_u8()?;
+        if version != Self::DRAW_COMMAND_VERSION {
+            return Err(Svg2PdcError::InvalidPdc(format!(
+                "unsupported version {version}"
+            )));
+        }
+        reader.read_u8()?; // reserved byte
+        let size = PebblePoint {
+            x: reader.read_i16::<LittleEndian>()?,
+            y: reader.read_i16::<LittleEndian>()?,
+        };
+        let command_count = reader.read_u16::<LittleEndian>()?;
+        let commands = (0..command_count)
+            .map(|_| DrawCommand::deserialize(reader))
+            .collect::<Svg2PdcResult<Vec<_>>>()?;
+
+        Ok(Self { size, commands })
+    }
+
+    /// Distinct colors used by the commands of the image, ordered by their color byte.
+    ///
+    /// Transparent stroke and fill colors are left out.
+    pub fn colors(&self) -> Vec<PebbleColor> {
+        let mut colors: Vec<_> = self
+            .commands
+            .iter()
+            .flat_map(|command| {
+                let options = command.options();
+                [options.stroke_color, options.fill_color]
+            })
+            .filter(|&color| color != PebbleColor::nothing().inner())
+            .collect();
+        colors.sort_unstable();
+        colors.dedup();
+        colors.into_iter().map(PebbleColor::from_inner).collect()
+    }
+
+    /// One image per draw command, each on the canvas of this image, to isolate a command
+    /// while debugging.
+    ///
+    /// ```
+    /// use svg2pdc::{point::PebblePoint, prelude::*};
+    ///
+    /// let circle = DrawCommand::Circle {
+    ///     center: PebblePoint { x: 5, y: 5 },
+    ///     radius: 3,
+    ///     options: DrawOptions::default(),
+    /// };
+    /// let image = PebbleImage {
+    ///     size: PebblePoint { x: 10, y: 10 },
+    ///     commands: vec![circle.clone(), circle],
+    /// };
+    /// let parts = image.explode();
+    /// assert_eq!(parts.len(), 2);
+    /// assert!(parts.iter().all(|part| part.size == image.size && part.commands.len() == 1));
+    /// ```
+    pub fn explode(&self) -> Vec<PebbleImage> {
+        self.commands
+            .iter()
+            .map(|command| PebbleImage {
+                size: self.size,
+                commands: vec![command.clone()],
+            })
+            .collect()
+    }
+
+    /// The size and every field of every command, one per line.
+    pub fn describe(&self) -> String {
+        let mut description = format!("Size: {:?}\nCommands:\n", self.size);
+        for command in &self.commands {
+            description.push_str(&command.describe());
+        }
+        description
+    }
+
+    /// One line per command with its index, shape, colors and stroke width, matching the
+    /// labels drawn by `render::annotate`.
+    pub fn legend(&self) -> String {
+        let mut legend = String::new();
+        for (index, command) in self.commands.iter().enumerate() {
+            let options = command.options();
+            let shape = match command {
+                DrawCommand::Path { points, open, .. } => format!(
+                    "{} path, {} points",
+                    if *open { "open" } else { "closed" },
+                    points.len()
+                ),
+                DrawCommand::Circle { radius, .. } => format!("circle, radius {radius}"),
+            };
+            let anchor = command.anchor();
+            legend.push_str(&format!(
+                "{index:>3}  {shape:<24} fill {:<21} stroke {:<21} width {:<3} at ({}, {})\n",
+                color_name(options.fill_color),
+                color_name(options.stroke_color),
+                options.stroke_width,
+                anchor.x,
+                anchor.y,
+            ));
+        }
+        legend
+    }
+
+    pub fn inspect(&self) {
+        eprint!("{}", self.describe());
+    }
+}
+
+#[cfg(feature = "serde")]
+impl PebbleImage {
+    /// The image as pretty-printed JSON, to edit or review the commands as text.
+    ///
+    /// ```
+    /// use svg2pdc::{point::PebblePoint, prelude::*};
+    ///
+    /// let image = PebbleImage {
+    ///     size: PebblePoint { x: 10, y: 10 },
+    ///     commands: vec![DrawCommand::Circle {
+    ///         center: PebblePoint { x: 5, y: 5 },
+    ///         radius: 3,
+    ///         options: DrawOptions::default(),
+    ///     }],
+    /// };
+    /// let json = image.to_json();
+    /// assert!(json.contains("\"circle\""));
+    /// assert_eq!(PebbleImage::from_json(&json).unwrap().commands.len(), 1);
+    /// ```
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("images serialize to JSON")
+    }
+
+    /// Read an image written with [`PebbleImage::to_json`].
+    ///
+    /// Options left out of a command take their default values.
+    pub fn from_json(json: &str) -> Svg2PdcResult<Self> {
+        serde_json::from_str(json)
+            .map_err(|error| Svg2PdcError::ParseError(format!("invalid image JSON: {error}")))
+    }
+}
+
+/// A frame of a [`PebbleSequence`], drawn for `duration` milliseconds.
+#[derive(Debug, Clone)]
+pub struct SequenceFrame {
+    pub duration: u16,
+    pub commands: Vec<DrawCommand>,
+}
+
+/// A Pebble Draw Command Sequence, an animation of draw command frames of the same size.
+#[derive(Debug, Clone)]
+pub struct PebbleSequence {
+    pub size: PebblePoint,
+    /// How many times the animation plays, [`Self::PLAY_FOREVER`] loops it.
+    pub play_count: u16,
+    pub frames: Vec<SequenceFrame>,
+}
+
+impl PebbleSequence {
+    const SEQUENCE_MAGIC: &[u8; 4] = b"PDCS";
+    /// Play count of an animation that loops until it is stopped.
+    pub const PLAY_FOREVER: u16 = u16::MAX;
+
+    /// Combine images into an animation lasting `duration`, shared equally by the frames.
+    ///
+    /// All images must have the same size, otherwise a
+    /// [`Svg2PdcError::IncompatibleCanvas`] error names the first one that doesn't.
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    /// use svg2pdc::{image::PebbleSequence, point::PebblePoint, prelude::*};
+    ///
+    /// let frame = PebbleImage { size: PebblePoint { x: 10, y: 10 }, commands: Vec::new() };
+    /// let sequence =
+    ///     PebbleSequence::from_images(vec![frame.clone(), frame], Duration::from_secs(1), 3)
+    ///         .unwrap();
+    /// assert_eq!(sequence.frames[1].duration, 500);
+    /// ```
+    pub fn from_images(
+        images: Vec<PebbleImage>,
+        duration: Duration,
+        play_count: u16,
+    ) -> Svg2PdcResult<Self> {
+        let frame_duration = (duration.as_secs_f64() * 1000.0 / images.len().max(1) as f64).round();
+        if frame_duration > u16::MAX as f64 {
+            return Err(Svg2PdcError::UnsupportedOperation(format!(
+                "frames of {frame_duration}ms, a frame lasts at most {}ms",
+                u16::MAX
+            )));
         }
+        let frames = images
+            .into_iter()
+            .map(|image| (image, frame_duration as u16))
+            .collect();
+        Self::from_timed_images(frames, play_count)
+    }
 
-        let buf = buf_writer.into_inner().unwrap();
+    /// Combine images into an animation, each shown for its own duration in milliseconds.
+    ///
+    /// All images must have the same size, like for [`Self::from_images`].
+    pub fn from_timed_images(
+        images: Vec<(PebbleImage, u16)>,
+        play_count: u16,
+    ) -> Svg2PdcResult<Self> {
+        let Some(size) = images.first().map(|(image, _)| image.size) else {
+            return Err(Svg2PdcError::IncompatibleCanvas(
+                "a sequence needs at least one frame".to_string(),
+            ));
+        };
+        if let Some((index, (image, _))) = images
+            .iter()
+            .enumerate()
+            .find(|(_, (image, _))| image.size != size)
+        {
+            return Err(Svg2PdcError::IncompatibleCanvas(format!(
+                "frame {index} is {}x{}, the first frame is {}x{}",
+                image.size.x, image.size.y, size.x, size.y
+            )));
+        }
+        Ok(Self {
+            size,
+            play_count,
+            frames: images
+                .into_iter()
+                .map(|(image, duration)| SequenceFrame {
+                    duration,
+                    commands: image.commands,
+                })
+                .collect(),
+        })
+    }
 
-        let _ = writer.write("PDCI".as_bytes())?;
+    pub fn serialize<W: Write>(&self, writer: &mut W) -> Svg2PdcResult<()> {
+        let mut buf = Vec::new();
+        buf.write_u8(PebbleImage::DRAW_COMMAND_VERSION)?;
+        buf.write_u8(0)?; // reserved byte
+        buf.write_i16::<LittleEndian>(self.size.x)?;
+        buf.write_i16::<LittleEndian>(self.size.y)?;
+        buf.write_u16::<LittleEndian>(self.play_count)?;
+        buf.write_u16::<LittleEndian>(self.frames.len() as u16)?;
+        for frame in &self.frames {
+            buf.write_u16::<LittleEndian>(frame.duration)?;
+            buf.write_u16::<LittleEndian>(frame.commands.len() as u16)?;
+            for command in &frame.commands {
+                command.serialize(&mut buf)?;
+            }
+        }
+
+        writer.write_all(Self::SEQUENCE_MAGIC)?;
         writer.write_u32::<LittleEndian>(buf.len() as u32)?;
         writer.write_all(&buf)?;
-
         Ok(())
     }
 
-    pub fn inspect(&self) {
-        // println!("{:#?}", self);
-        eprintln!("Size: {:?}", self.size);
-        eprintln!("Commands:");
-        for command in &self.commands {
-            command.inspect();
+    /// Read a sequence previously written with [`PebbleSequence::serialize`].
+    pub fn deserialize<R: Read>(reader: &mut R) -> Svg2PdcResult<Self> {
+        let mut magic = [0; 4];
+        reader.read_exact(&mut magic)?;
+        if &magic != Self::SEQUENCE_MAGIC {
+            return Err(Svg2PdcError::InvalidPdc(format!(
+                "expected magic {:?}, found {:?}",
+                Self::SEQUENCE_MAGIC,
+                magic
+            )));
         }
+        let length = reader.read_u32::<LittleEndian>()?;
+        let reader = &mut reader.take(length as u64);
+
+        let version = reader.read_u8()?;
+        if version != PebbleImage::DRAW_COMMAND_VERSION {
+            return Err(Svg2PdcError::InvalidPdc(format!(
+                "unsupported version {version}"
+            )));
+        }
+        reader.read_u8()?; // reserved byte
+        let size = PebblePoint {
+            x: reader.read_i16::<LittleEndian>()?,
+            y: reader.read_i16::<LittleEndian>()?,
+        };
+        let play_count = reader.read_u16::<LittleEndian>()?;
+        let frame_count = reader.read_u16::<LittleEndian>()?;
+        let frames = (0..frame_count)
+            .map(|_| {
+                let duration = reader.read_u16::<LittleEndian>()?;
+                let command_count = reader.read_u16::<LittleEndian>()?;
+                let commands = (0..command_count)
+                    .map(|_| DrawCommand::deserialize(reader))
+                    .collect::<Svg2PdcResult<Vec<_>>>()?;
+                Ok(SequenceFrame { duration, commands })
+            })
+            .collect::<Svg2PdcResult<Vec<_>>>()?;
+
+        if reader.limit() != 0 {
+            return Err(Svg2PdcError::InvalidPdc(format!(
+                "{} trailing bytes after the last frame",
+                reader.limit()
+            )));
+        }
+
+        Ok(Self {
+            size,
+            play_count,
+            frames,
+        })
     }
 }
 
@@ -58,16 +412,30 @@ pub type StrokeColor = u8;
 pub type FillColor = u8;
 
 #[derive(Debug, Clone, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
 pub struct DrawOptions {
+    /// Translation that was applied to the points of the command during conversion.
     pub translate: FPoint,
     pub stroke_width: u8,
     pub stroke_color: StrokeColor,
     pub fill_color: FillColor,
     pub precision: Precision,
     pub conversion: Conversion,
+    pub output_mode: OutputMode,
+    pub pixel_convention: PixelConvention,
+    pub rounding_mode: RoundingMode,
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
 pub enum DrawCommand {
     Path {
         points: Vec<PebblePoint>,
@@ -88,6 +456,13 @@ impl DrawCommand {
 
     const DRAW_COMMAND_HEADER_SIZE: u32 = 9;
 
+    /// Drawing options shared by every kind of command.
+    pub fn options(&self) -> &DrawOptions {
+        match self {
+            Self::Path { options, .. } | Self::Circle { options, .. } => options,
+        }
+    }
+
     pub fn serialize<W: Write>(&self, writer: &mut W) -> Svg2PdcResult<u32> {
         // writer.write_u8(Self::DRAW_COMMAND_VERSION)?;
 
@@ -109,11 +484,9 @@ impl DrawCommand {
                 writer.write_u8(if *open { 1 } else { 0 })?; // path is open
                 writer.write_u8(0)?; // reserved byte
                 writer.write_u16::<LittleEndian>(points.len() as u16)?;
-                for point in points.iter().map(|point| *point + options.translate) {
-                    let point =
-                        point.pebble_coordinates(&options.precision, &options.conversion)?;
-                    writer.write_u16::<LittleEndian>(point.x)?;
-                    writer.write_u16::<LittleEndian>(point.y)?;
+                for point in points {
+                    writer.write_i16::<LittleEndian>(point.x)?;
+                    writer.write_i16::<LittleEndian>(point.y)?;
                 }
 
                 Ok(Self::DRAW_COMMAND_HEADER_SIZE + points.len() as u32 * 4)
@@ -123,63 +496,164 @@ impl DrawCommand {
                 radius,
                 options,
             } => {
-                let center = *center + options.translate;
-                let center = center.pebble_coordinates(&options.precision, &options.conversion)?;
-
                 writer.write_u8(Self::DRAW_COMMAND_TYPE_CIRCLE)?;
                 writer.write_u8(0)?; // reserved byte
                 writer.write_u8(options.stroke_color)?;
                 writer.write_u8(options.stroke_width)?;
                 writer.write_u8(options.fill_color)?;
                 writer.write_u16::<LittleEndian>(*radius)?;
-                writer.write_u16::<LittleEndian>(center.x)?;
-                writer.write_u16::<LittleEndian>(center.y)?;
+                // Circles have a point count like paths, always 1 for the center. Without it the
+                // watch reads the center from the wrong bytes
+                writer.write_u16::<LittleEndian>(1)?;
+                writer.write_i16::<LittleEndian>(center.x)?;
+                writer.write_i16::<LittleEndian>(center.y)?;
 
-                Ok(Self::DRAW_COMMAND_HEADER_SIZE + 6)
+                Ok(Self::DRAW_COMMAND_HEADER_SIZE + 4)
             }
         }
     }
 
-    pub fn inspect(&self) {
-        match self {
+    pub fn deserialize<R: Read>(reader: &mut R) -> Svg2PdcResult<Self> {
+        let draw_command_type = reader.read_u8()?;
+        reader.read_u8()?; // reserved byte
+        let stroke_color = reader.read_u8()?;
+        let stroke_width = reader.read_u8()?;
+        let fill_color = reader.read_u8()?;
+
+        let precision = match draw_command_type {
+            Self::DRAW_COMMAND_TYPE_PATH | Self::DRAW_COMMAND_TYPE_CIRCLE => Precision::Normal,
+            Self::DRAW_COMMAND_TYPE_PRECISE_PATH => Precision::Precise,
+            draw_command_type => {
+                return Err(Svg2PdcError::InvalidPdc(format!(
+                    "unknown draw command type {draw_command_type}"
+                )));
+            }
+        };
+        let options = DrawOptions {
+            stroke_width,
+            stroke_color,
+            fill_color,
+            precision,
+            ..Default::default()
+        };
+
+        if draw_command_type == Self::DRAW_COMMAND_TYPE_CIRCLE {
+            let radius = reader.read_u16::<LittleEndian>()?;
+            let point_count = reader.read_u16::<LittleEndian>()?;
+            if point_count != 1 {
+                return Err(Svg2PdcError::InvalidPdc(format!(
+                    "circle with {point_count} points"
+                )));
+            }
+            let center = Self::deserialize_point(reader)?;
+            return Ok(Self::Circle {
+                center,
+                radius,
+                options,
+            });
+        }
+
+        let open = reader.read_u8()? != 0;
+        reader.read_u8()?; // reserved byte
+        let point_count = reader.read_u16::<LittleEndian>()?;
+        let points = (0..point_count)
+            .map(|_| Self::deserialize_point(reader))
+            .collect::<Svg2PdcResult<Vec<_>>>()?;
+
+        Ok(Self::Path {
+            points,
+            open,
+            options,
+        })
+    }
+
+    fn deserialize_point<R: Read>(reader: &mut R) -> Svg2PdcResult<PebblePoint> {
+        Ok(PebblePoint {
+            x: reader.read_i16::<LittleEndian>()?,
+            y: reader.read_i16::<LittleEndian>()?,
+        })
+    }
+
+    /// Size of the command once serialized, in bytes.
+    pub fn serialized_size(&self) -> usize {
+        let points = match self {
+            Self::Path { points, .. } => points.len(),
+            Self::Circle { .. } => 1,
+        };
+        Self::DRAW_COMMAND_HEADER_SIZE as usize + points * 4
+    }
+
+    /// Every field of the command, one per line.
+    pub fn describe(&self) -> String {
+        let mut description = String::new();
+        let options = match self {
             Self::Path {
                 points,
                 open,
                 options,
             } => {
-                eprintln!("Path:");
-                eprintln!("  Points (transalted):");
-                for point in points.iter().map(|point| *point + options.translate) {
-                    eprintln!("    {:?}", point);
+                description.push_str("Path:\n  Points:\n");
+                for point in points {
+                    description.push_str(&format!("    {point:?}\n"));
                 }
-                eprintln!("  Open: {}", open);
-                eprintln!("  Options:");
-                eprintln!("    Translate: {:?}", options.translate);
-                eprintln!("    Stroke Width: {}", options.stroke_width);
-                eprintln!("    Stroke Color: {}", options.stroke_color);
-                eprintln!("    Fill Color: {}", options.fill_color);
-                eprintln!("    Precision: {:?}", options.precision);
-                eprintln!("    Conversion: {:?}", options.conversion);
+                description.push_str(&format!("  Open: {open}\n"));
+                options
             }
             Self::Circle {
                 center,
                 radius,
                 options,
             } => {
-                let center = *center + options.translate;
-                eprintln!("Circle:");
-                eprintln!("  Center: {:?}", center);
-                eprintln!("  Radius: {}", radius);
-                eprintln!("  Options:");
-                eprintln!("    Translate: {:?}", options.translate);
-                eprintln!("    Stroke Width: {}", options.stroke_width);
-                eprintln!("    Stroke Color: {}", options.stroke_color);
-                eprintln!("    Fill Color: {}", options.fill_color);
-                eprintln!("    Precision: {:?}", options.precision);
-                eprintln!("    Conversion: {:?}", options.conversion);
+                description.push_str(&format!(
+                    "Circle:\n  Center: {center:?}\n  Radius: {radius}\n"
+                ));
+                options
             }
+        };
+        description.push_str(&format!(
+            "  Options:\n    Translate: {:?}\n    Stroke Width: {}\n    Stroke Color: {}\n    \
+             Fill Color: {}\n    Precision: {:?}\n    Conversion: {:?}\n    Output Mode: {:?}\n    \
+             Pixel Convention: {:?}\n    Rounding Mode: {:?}\n",
+            options.translate,
+            options.stroke_width,
+            options.stroke_color,
+            options.fill_color,
+            options.precision,
+            options.conversion,
+            options.output_mode,
+            options.pixel_convention,
+            options.rounding_mode
+        ));
+        description
+    }
+
+    /// Where the command is labelled in the legend of its image, its first point or center, in
+    /// watch pixels.
+    pub(crate) fn anchor(&self) -> FPoint {
+        match self {
+            Self::Path {
+                points, options, ..
+            } => points
+                .first()
+                .map(|point| point.pixel_position(&options.precision))
+                .unwrap_or_default(),
+            Self::Circle { center, .. } => center.pixel_position(&Precision::Normal),
         }
     }
+
+    pub fn inspect(&self) {
+        eprint!("{}", self.describe());
+    }
+}
+
+/// The name of a color in the Pebble SDK, hex for partly transparent colors.
+fn color_name(color: u8) -> String {
+    let color = PebbleColor::from_inner(color);
+    match color.name() {
+        _ if color.get_a() == 0 => "none".to_string(),
+        Some(name) => name.to_string(),
+        None => color.rgb_hex(),
+    }
 }
 
 #[cfg(test)]
@@ -230,6 +704,9 @@ mod tests {
                     fill_color: 4,
                     precision: Precision::Normal,
                     conversion: Conversion::RequireExact,
+                    output_mode: OutputMode::PythonCompatible,
+                    pixel_convention: PixelConvention::PixelCorner,
+                    rounding_mode: RoundingMode::Legacy,
                 },
             }],
         };
@@ -263,6 +740,96 @@ mod tests {
         // assert_eq!(buffer[31..33], 46u16.to_le_bytes()); // Point 2 Y (40 + 6)
     }
 
+    #[test]
+    fn test_deserialize_image_with_circle() {
+        let image = PebbleImage {
+            size: PebblePoint { x: 100, y: 200 },
+            commands: vec![DrawCommand::Circle {
+                center: PebblePoint { x: 50, y: 60 },
+                radius: 25,
+                options: DrawOptions {
+                    stroke_width: 2,
+                    stroke_color: 3,
+                    fill_color: 4,
+                    ..Default::default()
+                },
+            }],
+        };
+
+        let mut buffer = Vec::new();
+        image.serialize(&mut buffer).unwrap();
+        assert_eq!(buffer[23..25], 1u16.to_le_bytes()); // Point Count
+
+        let deserialized = PebbleImage::deserialize(&mut buffer.as_slice()).unwrap();
+        assert_eq!(deserialized.size, image.size);
+        match &deserialized.commands[..] {
+            [
+                DrawCommand::Circle {
+                    center,
+                    radius,
+                    options,
+                },
+            ] => {
+                assert_eq!(*center, PebblePoint { x: 50, y: 60 });
+                assert_eq!(*radius, 25);
+                assert_eq!(options.stroke_width, 2);
+                assert_eq!(options.stroke_color, 3);
+                assert_eq!(options.fill_color, 4);
+            }
+            commands => panic!("unexpected commands {commands:?}"),
+        }
+    }
+
+    #[test]
+    fn test_raw_payload_round_trip() {
+        let image = PebbleImage {
+            size: PebblePoint { x: 10, y: 20 },
+            commands: vec![DrawCommand::Path {
+                points: vec![PebblePoint { x: 1, y: 2 }, PebblePoint { x: 3, y: 4 }],
+                open: true,
+                options: DrawOptions::default(),
+            }],
+        };
+
+        let mut raw = Vec::new();
+        image.serialize_raw(&mut raw).unwrap();
+        let mut enveloped = Vec::new();
+        image.serialize(&mut enveloped).unwrap();
+        assert_eq!(raw, enveloped[8..]);
+
+        let deserialized = PebbleImage::deserialize(&mut raw.as_slice()).unwrap();
+        assert_eq!(deserialized.size, image.size);
+        assert_eq!(deserialized.commands.len(), 1);
+    }
+
+    #[test]
+    fn test_deserialize_rejects_bad_magic() {
+        let buffer = b"PDCS\0\0\0\0";
+        let result = PebbleImage::deserialize(&mut buffer.as_slice());
+        assert!(matches!(result, Err(Svg2PdcError::InvalidPdc(_))));
+    }
+
+    #[test]
+    fn test_serialized_size_matches_serialize() {
+        for command in [
+            DrawCommand::Path {
+                points: vec![PebblePoint { x: 1, y: 2 }; 5],
+                open: false,
+                options: DrawOptions::default(),
+            },
+            DrawCommand::Circle {
+                center: PebblePoint { x: 3, y: 4 },
+                radius: 2,
+                options: DrawOptions::default(),
+            },
+        ] {
+            let mut buffer = Vec::new();
+            let written = command.serialize(&mut buffer).unwrap();
+            assert_eq!(command.serialized_size(), buffer.len());
+            assert_eq!(written as usize, buffer.len());
+        }
+    }
+
     //     #[test]
     //     fn test_serialize_image_with_circle() {
     //         let image = PebbleImage {
@@ -1,13 +1,49 @@
+pub mod analyze;
+pub mod build;
+pub mod builder;
 pub mod color;
+pub mod compare;
+pub mod config;
+pub mod diagnostic;
+pub mod embed;
 pub mod error;
+pub mod export;
+pub mod geometry;
+pub mod glyph;
 pub mod image;
+pub mod limits;
+pub mod pack;
+pub mod paint;
+#[cfg(feature = "pdf")]
+pub mod pdf;
+pub mod platform;
 pub mod point;
+#[cfg(feature = "serde")]
+pub mod project;
+#[cfg(feature = "render")]
+pub mod render;
+pub mod smil;
+pub mod stylesheet;
 pub mod svg_converter;
+pub mod swatch;
+#[cfg(feature = "test-utils")]
+pub mod test_utils;
+pub mod timing;
+
+pub use roxmltree;
+#[cfg(feature = "render")]
+pub use tiny_skia;
 
 pub mod prelude {
+    pub use crate::builder::PebbleImageBuilder;
     pub use crate::color::{Color, PebbleColor, TruncateColor};
+    pub use crate::diagnostic::DiagnosticCode;
     pub use crate::error::{Svg2PdcError, Svg2PdcResult};
-    pub use crate::image::{DrawCommand, DrawOptions, FillColor, PebbleImage, StrokeColor};
-    pub use crate::point::{FPoint, Precision};
-    pub use crate::svg_converter::SvgConverter;
+    pub use crate::image::{
+        DrawCommand, DrawOptions, FillColor, PebbleImage, PebbleSequence, StrokeColor,
+    };
+    pub use crate::point::{FPoint, OutputMode, PixelConvention, Precision, RoundingMode};
+    pub use crate::svg_converter::{
+        ConvertOptions, InkscapePage, SvgConverter, SvgConverterBuilder,
+    };
 }
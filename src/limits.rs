@@ -0,0 +1,112 @@
+//! Limits of the PDC format and of the watch that converted images have to respect.
+//!
+//! Stroke widths and coordinates are stored in fixed-size integers, so the converter already
+//! clamps them, warning about the former with `W003`. The limits checked here are the ones a
+//! [`PebbleImage`] can exceed: counts that overflow their 16 bit fields when serialized,
+//! points outside the canvas and the size of the file.
+
+use crate::{
+    image::{DrawCommand, PebbleImage},
+    point::{FPoint, PebblePoint},
+};
+
+/// A limit exceeded by an image, see [`PebbleImage::validate`].
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum LimitViolation {
+    #[error("canvas size {}x{} is not positive", .0.x, .0.y)]
+    CanvasSize(PebblePoint),
+    #[error("{0} commands, at most {max} fit in a PDC image", max = u16::MAX)]
+    TooManyCommands(usize),
+    #[error("command {command}: {count} points, at most {max} fit in a path", max = u16::MAX)]
+    TooManyPoints { command: usize, count: usize },
+    #[error("command {command}: point ({}, {}) is outside the canvas", .point.x, .point.y)]
+    PointOffCanvas { command: usize, point: FPoint },
+    #[error("{size} bytes, over the budget of {budget} bytes")]
+    OverBudget { size: usize, budget: usize },
+}
+
+impl LimitViolation {
+    /// How to bring the image back within the limit.
+    pub fn hint(&self) -> &'static str {
+        match self {
+            Self::CanvasSize(_) => "give the SVG a positive width and height or a viewBox",
+            Self::TooManyCommands(_) => "merge shapes, or split the artwork over several images",
+            Self::TooManyPoints { .. } => {
+                "split the path, or flatten its curves with a larger tolerance"
+            }
+            Self::PointOffCanvas { .. } => "move or scale the artwork onto the canvas",
+            Self::OverBudget { .. } => {
+                "run with --optimize and --collinear-tolerance, or simplify the artwork"
+            }
+        }
+    }
+}
+
+impl PebbleImage {
+    /// Check the image against the limits of the PDC format, and its serialized size against
+    /// `budget` bytes when one is given. Empty when the image is within every limit.
+    ///
+    /// ```
+    /// use svg2pdc::{limits::LimitViolation, point::PebblePoint, prelude::*};
+    ///
+    /// let image = PebbleImage {
+    ///     size: PebblePoint { x: 10, y: 10 },
+    ///     commands: vec![DrawCommand::Circle {
+    ///         center: PebblePoint { x: 5, y: 12 },
+    ///         radius: 2,
+    ///         options: DrawOptions::default(),
+    ///     }],
+    /// };
+    /// let violations = image.validate(Some(20));
+    /// assert_eq!(violations.len(), 2);
+    /// assert!(matches!(violations[0], LimitViolation::PointOffCanvas { command: 0, .. }));
+    /// assert_eq!(violations[1], LimitViolation::OverBudget { size: 29, budget: 20 });
+    /// ```
+    pub fn validate(&self, budget: Option<usize>) -> Vec<LimitViolation> {
+        let mut violations = Vec::new();
+        if self.size.x <= 0 || self.size.y <= 0 {
+            violations.push(LimitViolation::CanvasSize(self.size));
+        }
+        if self.commands.len() > u16::MAX as usize {
+            violations.push(LimitViolation::TooManyCommands(self.commands.len()));
+        }
+        let canvas = FPoint::from(self.size);
+        for (index, command) in self.commands.iter().enumerate() {
+            let (points, scale) = match command {
+                DrawCommand::Path {
+                    points, options, ..
+                } => {
+                    if points.len() > u16::MAX as usize {
+                        violations.push(LimitViolation::TooManyPoints {
+                            command: index,
+                            count: points.len(),
+                        });
+                    }
+                    (points.as_slice(), options.precision.coordinate_scale())
+                }
+                // Circle centers are always stored with normal precision
+                DrawCommand::Circle { center, .. } => (std::slice::from_ref(center), 1.0),
+            };
+            // One violation per command is enough to find it
+            if let Some(point) = points
+                .iter()
+                .map(|&point| FPoint::from(point) / scale)
+                .find(|point| {
+                    !(0.0..=canvas.x).contains(&point.x) || !(0.0..=canvas.y).contains(&point.y)
+                })
+            {
+                violations.push(LimitViolation::PointOffCanvas {
+                    command: index,
+                    point,
+                });
+            }
+        }
+        if let Some(budget) = budget {
+            let size = self.serialized_size();
+            if size > budget {
+                violations.push(LimitViolation::OverBudget { size, budget });
+            }
+        }
+        violations
+    }
+}
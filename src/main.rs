@@ -1,57 +1,199 @@
 use anyhow::Result;
-use clap::Parser;
-use color::TruncateColor;
-use std::path::{Path, PathBuf};
-use svg_converter::SvgConverter;
+use clap::{ArgGroup, Parser, Subcommand, ValueEnum, builder::FalseyValueParser};
+use std::{
+    io::Write,
+    path::{Path, PathBuf},
+};
+use svg2pdc::{
+    analyze::{FeatureSummary, PaletteEntry, features, palette},
+    build::fnv1a,
+    color::ColorAdjustment,
+    compare::{first_byte_difference, semantic_differences},
+    config::{Config, FILE_NAME as CONFIG_FILE_NAME, Settings},
+    diagnostic::warning_count,
+    embed::{c_header, rust_module},
+    export::to_svg,
+    geometry::{Rotation, StartCorner},
+    glyph::glyph_fragment,
+    pack::{PdcPack, find_duplicates},
+    paint::{quantize, quantized_svg},
+    platform::Platform,
+    point::{Conversion, PebblePoint},
+    prelude::*,
+    smil::{animation_duration, sample_svg},
+    svg_converter::{FragmentOptions, HoleHandling},
+    swatch::swatch_sheet_svg,
+    timing::parse_timing,
+};
 
-mod color;
-mod error;
-mod image;
-mod point;
-mod svg_converter;
+#[cfg(feature = "text")]
+use svg2pdc::glyph::TextFont;
+#[cfg(feature = "render")]
+use svg2pdc::render::{annotate, render, steps, write_gif};
 
-use error::{Svg2PdcError, Svg2PdcResult};
-use point::{Conversion, Precision};
-
-#[expect(clippy::too_many_arguments)]
 fn create_pdc_from_path(
     input: &Path,
     output: &Path,
-    precision: &Precision,
-    truncate_color: &TruncateColor,
-    conversion: &Conversion,
-    verbose: bool,
-    sequence: bool,
-    #[expect(unused_variables)] duration: f32,
-    #[expect(unused_variables)] play_count: u32,
+    options: &FileOptions,
+    settings: &ConverterSettings,
+    image_options: &ImageOptions,
+    sink: &mut Sink,
 ) -> Svg2PdcResult<()> {
-    if sequence {
-        return Err(Svg2PdcError::UnsupportedOperation("sequence".to_string()));
-    }
-
-    let converter = SvgConverter::new(*precision);
-    if input.exists() {
-        if sequence {
-            unreachable!();
-        }
-
+    let FileOptions {
+        truncate_color,
+        conversion,
+        ..
+    } = options;
+    let ImageOptions {
+        page,
+        split_layers,
+        select,
+        overlays,
+        template,
+        template_above,
+        swatch_sheet,
+        emit,
+        strip_background,
+        ref geometry,
+        verbose,
+    } = *image_options;
+    let converter = settings.converter(options);
+    if input.exists() || is_stdio(input) {
         if verbose {
-            println!("Converting SVG file: {:?}", input);
+            eprintln!("Converting SVG file: {:?}", input);
         }
 
-        // let dir_name = if input.is_dir() {
-        //     input.to_path_buf()
-        // } else {
-        //     input.parent().unwrap().to_path_buf()
-        // };
+        let template = template
+            .map(|template| {
+                let content = std::fs::read_to_string(template)?;
+                converter.parse_svg_image(&content, truncate_color, conversion)
+            })
+            .transpose()?;
+
+        if input.is_file() || is_stdio(input) {
+            let content = read_input(input)?;
+            // Documents are scaled while they are converted, so that their stroke widths are
+            // rounded only once. The template keeps its size. A selected element is converted
+            // without the document's view, so it is scaled afterwards.
+            let converter = match geometry.scale {
+                Some(scale) if select.is_none() => converter.clone().with_scale(scale),
+                _ => converter.clone(),
+            };
+            let geometry = &Geometry {
+                scale: geometry.scale.filter(|_| select.is_some()),
+                ..*geometry
+            };
+
+            if emit.contains(&Emit::QuantizedSvg) {
+                let preview = if output.is_dir() {
+                    output.join(input.file_stem().unwrap())
+                } else {
+                    output.to_path_buf()
+                }
+                .with_extension("quantized.svg");
+                if verbose {
+                    eprintln!("Writing quantized preview: {:?}", preview);
+                }
+                std::fs::write(
+                    preview,
+                    quantized_svg(&content, truncate_color, &converter.color_adjustment)?,
+                )?;
+            }
 
-        // let frames = vec![];
-        // let commands = vec![];
+            if split_layers {
+                let mut layers =
+                    converter.split_svg_layers(&content, truncate_color, conversion)?;
+                if layers.is_empty() {
+                    return Err(Svg2PdcError::UnsupportedOperation(
+                        "--split-layers on a document without top-level groups".to_string(),
+                    ));
+                }
+                for (_, image) in &mut layers {
+                    if let Some(color) = strip_background {
+                        strip_image_background(
+                            image,
+                            color,
+                            truncate_color,
+                            &converter.color_adjustment,
+                        );
+                    }
+                    geometry.apply(image);
+                    apply_template(image, template.as_ref(), template_above)?;
+                }
+                return write_layers(input, output, layers, sink, verbose);
+            }
 
-        if input.is_file() {
-            let content = std::fs::read_to_string(input)?;
+            let mut pages = match select {
+                Some(_) => Vec::new(),
+                None => converter.parse_svg_pages(&content, truncate_color, conversion)?,
+            };
+            if !pages.is_empty() && !overlays.is_empty() {
+                return Err(Svg2PdcError::UnsupportedOperation(
+                    "overlays on a multi-page document".to_string(),
+                ));
+            }
+            for (_, image) in &mut pages {
+                if let Some(color) = strip_background {
+                    strip_image_background(
+                        image,
+                        color,
+                        truncate_color,
+                        &converter.color_adjustment,
+                    );
+                }
+                geometry.apply(image);
+                apply_template(image, template.as_ref(), template_above)?;
+            }
+            if !pages.is_empty() {
+                if let Some(swatch_sheet) = swatch_sheet {
+                    let mut colors: Vec<_> =
+                        pages.iter().flat_map(|(_, image)| image.colors()).collect();
+                    colors.sort_unstable_by_key(PebbleColor::inner);
+                    colors.dedup();
+                    std::fs::write(swatch_sheet, swatch_sheet_svg(&colors))?;
+                }
+                return write_pages(input, output, pages, page, sink, verbose);
+            }
+            if let Some(page) = page {
+                return Err(Svg2PdcError::UnsupportedOperation(format!(
+                    "page `{page}` requested but the document has no pages"
+                )));
+            }
 
-            let image = converter.parse_svg_image(&content, truncate_color, conversion)?;
+            let mut image = match select {
+                Some((id, size)) => {
+                    converter.parse_svg_element(&content, id, size, truncate_color, conversion)?
+                }
+                None => converter.parse_svg_image(&content, truncate_color, conversion)?,
+            };
+            if let Some(color) = strip_background {
+                strip_image_background(
+                    &mut image,
+                    color,
+                    truncate_color,
+                    &converter.color_adjustment,
+                );
+            }
+            for layer in overlays {
+                if verbose {
+                    eprintln!("Overlaying SVG file: {:?}", layer.path);
+                }
+                let content = std::fs::read_to_string(&layer.path)?;
+                let overlay = converter.parse_svg_image(&content, truncate_color, conversion)?;
+                let offset = match converter.scale {
+                    Some(scale) => {
+                        let offset = FPoint::from(layer.offset) * scale;
+                        PebblePoint::try_from(FPoint::new(offset.x.round(), offset.y.round()))?
+                    }
+                    None => layer.offset,
+                };
+                image.overlay(overlay, offset)?;
+            }
+            geometry.apply(&mut image);
+            apply_template(&mut image, template.as_ref(), template_above)?;
+            if let Some(swatch_sheet) = swatch_sheet {
+                std::fs::write(swatch_sheet, swatch_sheet_svg(&image.colors()))?;
+            }
             if verbose {
                 image.inspect();
             }
@@ -64,39 +206,918 @@ fn create_pdc_from_path(
                 output.to_path_buf()
             };
 
-            let mut file = std::fs::File::create(output)?;
-            image.serialize(&mut file)?;
+            sink.write(input.display(), &image, &output)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Whether a path of the command line stands for stdin or stdout, `-`.
+fn is_stdio(path: &Path) -> bool {
+    path == Path::new("-")
+}
+
+/// A setting of the command line, turned on by `on` and off by its `--no-` flag `off`, or left
+/// to `svg2pdc.toml` without either.
+///
+/// The `--no-` flag wins, so that it also turns off settings of the environment variables,
+/// which clap reports as given on the command line.
+fn flag(on: bool, off: bool) -> Option<bool> {
+    match (on, off) {
+        (_, true) => Some(false),
+        (true, false) => Some(true),
+        (false, false) => None,
+    }
+}
+
+/// Read an input file as SVG markup, translating PDFs when the `pdf` feature is enabled.
+/// SVG markup is read from stdin for `-`.
+fn read_input(input: &Path) -> Svg2PdcResult<String> {
+    if is_stdio(input) {
+        return Ok(std::io::read_to_string(std::io::stdin())?);
+    }
+    if !input
+        .extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("pdf"))
+    {
+        return Ok(std::fs::read_to_string(input)?);
+    }
+    #[cfg(feature = "pdf")]
+    {
+        svg2pdc::pdf::pdf_to_svg(&std::fs::read(input)?)
+    }
+    #[cfg(not(feature = "pdf"))]
+    {
+        Err(Svg2PdcError::UnsupportedOperation(
+            "PDF input, build with the `pdf` feature".to_string(),
+        ))
+    }
+}
+
+/// The image as JSON when the `serde` feature is enabled.
+fn image_to_json(image: &PebbleImage) -> Svg2PdcResult<String> {
+    #[cfg(feature = "serde")]
+    {
+        Ok(image.to_json())
+    }
+    #[cfg(not(feature = "serde"))]
+    {
+        let _ = image;
+        Err(json_unsupported())
+    }
+}
+
+/// Read an image from JSON when the `serde` feature is enabled.
+fn image_from_json(json: &str) -> Svg2PdcResult<PebbleImage> {
+    #[cfg(feature = "serde")]
+    {
+        PebbleImage::from_json(json)
+    }
+    #[cfg(not(feature = "serde"))]
+    {
+        let _ = json;
+        Err(json_unsupported())
+    }
+}
+
+#[cfg(not(feature = "serde"))]
+fn json_unsupported() -> Svg2PdcError {
+    Svg2PdcError::UnsupportedOperation("JSON images, build with the `serde` feature".to_string())
+}
+
+/// What happens to elements without a draw command equivalent.
+struct TagHandling {
+    /// Replaces the default list of silently skipped elements.
+    ignored: Option<Vec<String>>,
+    strict: bool,
+    /// Outlines `<text>` elements instead of skipping them.
+    #[cfg(feature = "text")]
+    text_font: Option<TextFont>,
+}
+
+/// How strokes are stored, `--stroke-*` and `--dashes` on the command line.
+struct StrokeWidths {
+    /// Replaces [`SvgConverter::DEFAULT_STROKE_WIDTH_TOLERANCE`].
+    tolerance: Option<f32>,
+    rounding: Option<RoundingMode>,
+    min: Option<u8>,
+    /// Tolerance of dashed strokes, which are drawn solid without it.
+    dashes: Option<f32>,
+}
+
+/// The options of `convert` that apply to every SVG, for [`ConverterSettings::converter`].
+struct ConverterSettings {
+    output_mode: OutputMode,
+    pixel_convention: Option<PixelConvention>,
+    rounding_mode: Option<RoundingMode>,
+    strokes: StrokeWidths,
+    platform: Option<Platform>,
+    clip_to_display: bool,
+    color_adjustment: ColorAdjustment,
+    current_color: Option<Color>,
+    tags: TagHandling,
+    curve_tolerance: Option<f32>,
+    holes: HoleHandling,
+    parallel: bool,
+    /// Maximum radius of the circles drawn as paths, any radius for `Some(None)`.
+    circles_as_paths: Option<Option<f32>>,
+}
+
+impl ConverterSettings {
+    /// The settings of the command line, with the --text-font read.
+    fn new(args: ConverterArgs) -> Result<Self> {
+        let output_mode = if args.improved {
+            OutputMode::Improved
+        } else if args.floor {
+            OutputMode::Floored
+        } else {
+            OutputMode::PythonCompatible
+        };
+        Ok(Self {
+            output_mode,
+            pixel_convention: args.pixel_centers.then_some(PixelConvention::PixelCenter),
+            rounding_mode: args.rounding.map(RoundingMode::from),
+            strokes: StrokeWidths {
+                tolerance: args.stroke_width_tolerance,
+                rounding: args.stroke_rounding.map(RoundingMode::from),
+                min: args.min_stroke_width,
+                dashes: args
+                    .dashes
+                    .map(|tolerance| tolerance.unwrap_or(SvgConverter::DEFAULT_DASH_TOLERANCE)),
+            },
+            platform: args.platform.map(Platform::from),
+            clip_to_display: args.clip_to_display,
+            color_adjustment: ColorAdjustment {
+                gamma: args.gamma,
+                brightness: args.brightness,
+                force_opaque: args.force_opaque,
+            },
+            current_color: args.color,
+            tags: TagHandling {
+                ignored: args.ignore_tags,
+                strict: args.strict,
+                #[cfg(feature = "text")]
+                text_font: args
+                    .text_font
+                    .map(|font| TextFont::new(std::fs::read(font)?))
+                    .transpose()?,
+            },
+            curve_tolerance: args.flatten_curves,
+            holes: HoleHandling::from(args.holes),
+            parallel: args.parallel,
+            circles_as_paths: args.circles_as_paths,
+        })
+    }
+
+    /// The converter for an SVG with these settings and its own `options`.
+    fn converter(&self, options: &FileOptions) -> SvgConverter {
+        let Self {
+            output_mode,
+            pixel_convention,
+            rounding_mode,
+            ref strokes,
+            platform,
+            clip_to_display,
+            color_adjustment,
+            current_color,
+            ref tags,
+            curve_tolerance,
+            holes,
+            parallel,
+            circles_as_paths,
+        } = *self;
+        let mut converter = SvgConverter::new(options.precision)
+            .with_output_mode(output_mode)
+            .with_color_adjustment(color_adjustment)
+            .with_strict(tags.strict)
+            .with_holes(holes);
+        if let Some(ignored) = &tags.ignored {
+            converter = converter.with_ignored_tags(ignored);
+        }
+        #[cfg(feature = "text")]
+        if let Some(text_font) = &tags.text_font {
+            converter = converter.with_text_font(text_font.clone());
+        }
+        if let Some(pixel_convention) = pixel_convention {
+            converter = converter.with_pixel_convention(pixel_convention);
+        }
+        if let Some(rounding_mode) = rounding_mode {
+            converter = converter.with_rounding_mode(rounding_mode);
+        }
+        if let Some(platform) = platform {
+            converter = converter
+                .with_platform(platform)
+                .with_clip_to_display(clip_to_display);
+        }
+        if let Some(fit) = options.fit {
+            converter = converter.with_fit(fit.into());
+        }
+        if let Some(stroke_width_tolerance) = strokes.tolerance {
+            converter = converter.with_stroke_width_tolerance(stroke_width_tolerance);
+        }
+        if let Some(rounding) = strokes.rounding {
+            converter = converter.with_stroke_width_rounding(rounding);
+        }
+        if let Some(min_stroke_width) = strokes.min {
+            converter = converter.with_min_stroke_width(min_stroke_width);
+        }
+        if let Some(dash_tolerance) = strokes.dashes {
+            converter = converter.with_dash_tolerance(dash_tolerance);
+        }
+        if let Some(curve_tolerance) = curve_tolerance {
+            converter = converter.with_curve_tolerance(curve_tolerance);
+        }
+        if let Some(current_color) = current_color {
+            converter = converter.with_current_color(current_color);
+        }
+        if !options.color_map.is_empty() {
+            converter = converter.with_color_map(options.color_map.clone());
+        }
+        converter = converter
+            .with_parallel(parallel)
+            .with_circles_as_paths(circles_as_paths.is_some());
+        if let Some(Some(max_radius)) = circles_as_paths {
+            converter = converter.with_circle_path_max_radius(max_radius);
+        }
+        converter
+    }
+}
+
+/// What `convert` does with an SVG besides converting it, for [`create_pdc_from_path`].
+struct ImageOptions<'a> {
+    page: Option<&'a str>,
+    split_layers: bool,
+    /// Id of the converted element and the size of its canvas.
+    select: Option<(&'a str, Option<PebblePoint>)>,
+    overlays: &'a [Layer],
+    template: Option<&'a Path>,
+    template_above: bool,
+    swatch_sheet: Option<&'a Path>,
+    emit: &'a [Emit],
+    strip_background: Option<Color>,
+    geometry: Geometry,
+    verbose: bool,
+}
+
+/// A bare path expression converted without an SVG file, `--path-d` on the command line.
+struct PathData<'a> {
+    d: &'a str,
+    size: PebblePoint,
+    fill: Option<Color>,
+    stroke: Option<Color>,
+    stroke_width: Option<f32>,
+}
+
+impl PathData<'_> {
+    /// The path as a `<path>` element, for [`SvgConverter::parse_svg_fragment`].
+    fn fragment(&self) -> String {
+        let mut fragment = format!(r#"<path d="{}""#, escape_attribute(self.d));
+        for (attribute, color) in [("fill", self.fill), ("stroke", self.stroke)] {
+            if let Some(Color { r, g, b, a }) = color {
+                fragment.push_str(&format!(
+                    r##" {attribute}="#{r:02x}{g:02x}{b:02x}{a:02x}""##
+                ));
+            }
+        }
+        if let Some(stroke_width) = self.stroke_width {
+            fragment.push_str(&format!(r#" stroke-width="{stroke_width}""#));
+        }
+        fragment.push_str("/>");
+        fragment
+    }
+}
+
+fn escape_attribute(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('"', "&quot;")
+        .replace('<', "&lt;")
+}
+
+/// A canvas size in pixels, `WxH` on the command line.
+#[derive(Debug, Clone, Copy)]
+struct Size(PebblePoint);
+
+impl std::str::FromStr for Size {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (width, height) = s
+            .split_once('x')
+            .ok_or_else(|| format!("expected a size like `WxH`, found `{s}`"))?;
+        let parse = |value: &str| match value.trim().parse::<i16>() {
+            Ok(size) if size >= 0 => Ok(size),
+            Ok(_) => Err(format!("invalid size `{value}`: sizes can't be negative")),
+            Err(error) => Err(format!("invalid size `{value}`: {error}")),
+        };
+        Ok(Self(PebblePoint {
+            x: parse(width)?,
+            y: parse(height)?,
+        }))
+    }
+}
+
+/// An SVG drawn on top of the input, `path[@x,y]` on the command line.
+#[derive(Debug, Clone)]
+struct Layer {
+    path: PathBuf,
+    offset: PebblePoint,
+}
+
+impl std::str::FromStr for Layer {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let Some((path, offset)) = s.rsplit_once('@') else {
+            return Ok(Self {
+                path: s.into(),
+                offset: PebblePoint::default(),
+            });
+        };
+        let (x, y) = offset
+            .split_once(',')
+            .ok_or_else(|| format!("expected an offset like `@x,y`, found `@{offset}`"))?;
+        let parse = |value: &str| {
+            value
+                .trim()
+                .parse::<i16>()
+                .map_err(|error| format!("invalid offset `{value}`: {error}"))
+        };
+        Ok(Self {
+            path: path.into(),
+            offset: PebblePoint {
+                x: parse(x)?,
+                y: parse(y)?,
+            },
+        })
+    }
+}
+
+/// Where converted images end up, and the statistics kept about them.
+struct Sink {
+    target: Target,
+    stats: Option<StatsLog>,
+}
+
+impl Sink {
+    /// Store `image`, converted from `input`, at `output`.
+    fn write(
+        &mut self,
+        input: impl std::fmt::Display,
+        image: &PebbleImage,
+        output: &Path,
+    ) -> Svg2PdcResult<()> {
+        let output = &self.target.output_path(output);
+        let bytes = self.target.write(image, output)?;
+        if let Some(stats) = &mut self.stats {
+            stats.record(
+                &input.to_string(),
+                &output.display().to_string(),
+                bytes,
+                image,
+            )?;
+        }
+        Ok(())
+    }
+
+    fn finish(self) -> Svg2PdcResult<()> {
+        self.target.finish()
+    }
+}
+
+/// Size and content statistics of every written image.
+struct StatsLog {
+    format: StatsFormat,
+    /// CSV file the rows are appended to, stdout without one.
+    file: Option<PathBuf>,
+    /// Warnings printed before the previous row, the rest belong to the next one.
+    warnings: usize,
+    rows: usize,
+}
+
+impl StatsLog {
+    const CSV_HEADER: &str = "input,output,bytes,commands,points,colors,warnings";
+
+    fn new(format: StatsFormat, file: Option<PathBuf>) -> Self {
+        Self {
+            format,
+            file,
+            warnings: warning_count(),
+            rows: 0,
+        }
+    }
+
+    fn record(
+        &mut self,
+        input: &str,
+        output: &str,
+        bytes: usize,
+        image: &PebbleImage,
+    ) -> Svg2PdcResult<()> {
+        let points: usize = image
+            .commands
+            .iter()
+            .map(|command| match command {
+                DrawCommand::Path { points, .. } => points.len(),
+                DrawCommand::Circle { .. } => 1,
+            })
+            .sum();
+        let colors = image.colors().len();
+        let warnings = warning_count() - self.warnings;
+        self.warnings += warnings;
+        let commands = image.commands.len();
+
+        match self.format {
+            StatsFormat::Text => println!(
+                "{input} -> {output}: {bytes} bytes, {commands} commands, {points} points, \
+                 {colors} colors, {warnings} warnings"
+            ),
+            StatsFormat::Csv => {
+                let row = format!(
+                    "{},{},{bytes},{commands},{points},{colors},{warnings}\n",
+                    csv_field(input),
+                    csv_field(output)
+                );
+                match &self.file {
+                    Some(file) => {
+                        use std::io::Write;
+                        let mut csv = std::fs::OpenOptions::new()
+                            .create(true)
+                            .append(true)
+                            .open(file)?;
+                        if csv.metadata()?.len() == 0 {
+                            writeln!(csv, "{}", Self::CSV_HEADER)?;
+                        }
+                        csv.write_all(row.as_bytes())?;
+                    }
+                    None => {
+                        if self.rows == 0 {
+                            println!("{}", Self::CSV_HEADER);
+                        }
+                        print!("{row}");
+                    }
+                }
+            }
+        }
+        self.rows += 1;
+        Ok(())
+    }
+}
+
+/// Quote a CSV field when it contains a separator, a quote or a line break.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Where converted images end up.
+enum Target {
+    /// Every image is written to its own PDC file.
+    Files {
+        raw: bool,
+        format: OutputFormat,
+        /// Name of the embedded array, the name of each output file without one.
+        symbol: Option<String>,
+        written: Vec<(String, PebbleImage)>,
+    },
+    /// Images are collected in a pack, keyed by the stem of the file they would be written to.
+    Pack {
+        pack: PdcPack,
+        path: PathBuf,
+        header: Option<PathBuf>,
+        module: Option<PathBuf>,
+    },
+}
+
+impl Target {
+    /// Start collecting images in the pack at `path`, adding to it if it already exists.
+    fn pack(
+        path: PathBuf,
+        header: Option<PathBuf>,
+        module: Option<PathBuf>,
+        deduplicate: bool,
+    ) -> Svg2PdcResult<Self> {
+        let mut pack = if path.exists() {
+            PdcPack::deserialize(&mut std::fs::File::open(&path)?)?
+        } else {
+            PdcPack::new()
+        };
+        pack.deduplicate = deduplicate;
+        Ok(Self::Pack {
+            pack,
+            path,
+            header,
+            module,
+        })
+    }
+
+    /// Where an image meant for `output` is written: outputs named after their input get the
+    /// extension of the output format.
+    fn output_path(&self, output: &Path) -> PathBuf {
+        match self {
+            Self::Files { format, .. }
+                if output
+                    .extension()
+                    .is_some_and(|extension| extension == "pdc") =>
+            {
+                output.with_extension(format.extension())
+            }
+            _ => output.to_path_buf(),
+        }
+    }
+
+    /// Store `image` at `output`, returning the size of its serialized form.
+    fn write(&mut self, image: &PebbleImage, output: &Path) -> Svg2PdcResult<usize> {
+        let mut bytes = Vec::new();
+        match self {
+            Self::Files {
+                raw,
+                format,
+                symbol,
+                written,
+            } => {
+                if *raw {
+                    image.serialize_raw(&mut bytes)?;
+                } else {
+                    image.serialize(&mut bytes)?;
+                }
+                let name = match is_stdio(output) {
+                    true => "image".into(),
+                    false => output.file_stem().unwrap_or_default().to_string_lossy(),
+                };
+                let symbol = symbol.as_deref().unwrap_or(&name);
+                let content = match format {
+                    OutputFormat::Pdc => bytes.clone(),
+                    OutputFormat::CHeader => c_header(symbol, &bytes).into_bytes(),
+                    OutputFormat::Rust => rust_module(symbol, &bytes).into_bytes(),
+                    OutputFormat::Json => image_to_json(image)?.into_bytes(),
+                };
+                if is_stdio(output) {
+                    std::io::stdout().lock().write_all(&content)?;
+                } else {
+                    std::fs::write(output, content)?;
+                }
+                written.push((output.display().to_string(), image.clone()));
+            }
+            Self::Pack { pack, .. } => {
+                image.serialize_raw(&mut bytes)?;
+                let key = output.file_stem().unwrap_or_default().to_string_lossy();
+                pack.insert(key, image.clone());
+            }
+        }
+        Ok(bytes.len())
+    }
+
+    /// Report identical outputs, and write the pack and its lookup tables if images were
+    /// collected in one.
+    fn finish(self) -> Svg2PdcResult<()> {
+        let (pack, path, header, module) = match self {
+            Self::Files { written, .. } => {
+                let duplicates =
+                    find_duplicates(written.iter().map(|(name, image)| (name.as_str(), image)))?;
+                report_duplicates(&duplicates, "");
+                return Ok(());
+            }
+            Self::Pack {
+                pack,
+                path,
+                header,
+                module,
+            } => (pack, path, header, module),
+        };
+        let hint = if pack.deduplicate {
+            ", stored once"
+        } else {
+            ", use --dedupe to store them once"
+        };
+        report_duplicates(&pack.duplicates()?, hint);
+        pack.serialize(&mut std::fs::File::create(&path)?)?;
+        let name = path.file_stem().unwrap_or_default().to_string_lossy();
+        if let Some(header) = header {
+            std::fs::write(header, pack.c_header(&name)?)?;
+        }
+        if let Some(module) = module {
+            std::fs::write(module, pack.rust_module(&name)?)?;
+        }
+        Ok(())
+    }
+}
+
+fn report_duplicates(duplicates: &[Vec<String>], hint: &str) {
+    for group in duplicates {
+        eprintln!("Identical output: {}{}", group.join(", "), hint);
+    }
+}
+
+fn strip_image_background(
+    image: &mut PebbleImage,
+    color: Color,
+    truncate_color: &TruncateColor,
+    adjustment: &ColorAdjustment,
+) {
+    let color = quantize(color.adjusted(adjustment), truncate_color);
+    if image.strip_background(color).is_some() {
+        eprintln!("Removed full-canvas background rectangle");
+    }
+}
+
+/// Geometry operations applied to every converted image, in the order of the fields.
+#[derive(Debug, Clone, Copy)]
+struct Geometry {
+    scale: Option<f32>,
+    rotate: Option<Rotation>,
+    flip_horizontal: bool,
+    flip_vertical: bool,
+    offset: Option<FPoint>,
+    inset: Option<f32>,
+    optimize: bool,
+    strip_invisible: bool,
+    collinear_tolerance: Option<f32>,
+    reverse: bool,
+    start_corner: Option<StartCorner>,
+}
+
+impl Geometry {
+    fn apply(&self, image: &mut PebbleImage) {
+        if let Some(scale) = self.scale {
+            image.scale(scale);
+        }
+        if let Some(rotation) = self.rotate {
+            image.rotate(rotation);
+        }
+        if self.flip_horizontal {
+            image.flip_horizontal();
+        }
+        if self.flip_vertical {
+            image.flip_vertical();
+        }
+        if let Some(offset) = self.offset {
+            image.move_by(offset);
+        }
+        if let Some(inset) = self.inset {
+            image.offset(-inset);
+        }
+        if self.optimize {
+            if self.strip_invisible {
+                let count = image.commands.len();
+                let saved = image.strip_invisible();
+                if saved > 0 {
+                    eprintln!(
+                        "Removed {} invisible commands, {saved} bytes",
+                        count - image.commands.len()
+                    );
+                }
+            }
+            image.merge_paths();
+            image.optimize(self.collinear_tolerance);
+        }
+        if self.reverse {
+            image.reverse();
+        }
+        if let Some(start_corner) = self.start_corner {
+            image.start_at(start_corner);
+        }
+    }
+}
+
+/// Wrap an image in the shared template, drawn below its commands unless `above` is set.
+fn apply_template(
+    image: &mut PebbleImage,
+    template: Option<&PebbleImage>,
+    above: bool,
+) -> Svg2PdcResult<()> {
+    let Some(template) = template else {
+        return Ok(());
+    };
+    if above {
+        image.overlay(template.clone(), PebblePoint::default())
+    } else {
+        image.underlay(template.clone(), PebblePoint::default())
+    }
+}
+
+/// Write the images of a multi-page document, either every page or only the selected one.
+///
+/// A page can be selected by its label, its id or its 1-based index.
+fn write_pages(
+    input: &Path,
+    output: &Path,
+    pages: Vec<(InkscapePage, PebbleImage)>,
+    selected: Option<&str>,
+    sink: &mut Sink,
+    verbose: bool,
+) -> Svg2PdcResult<()> {
+    if let Some(selected) = selected {
+        let (_, image) = pages
+            .into_iter()
+            .enumerate()
+            .find(|(index, (page, _))| {
+                page.label.as_deref() == Some(selected)
+                    || page.id.as_deref() == Some(selected)
+                    || (index + 1).to_string() == selected
+            })
+            .map(|(_, page)| page)
+            .ok_or_else(|| {
+                Svg2PdcError::UnsupportedOperation(format!("page `{selected}` not found"))
+            })?;
+        if verbose {
+            image.inspect();
+        }
+
+        let output = if output.is_dir() {
+            output
+                .join(input.file_stem().unwrap())
+                .with_extension("pdc")
+        } else {
+            output.to_path_buf()
+        };
+        return sink.write(input.display(), &image, &output);
+    }
+
+    if is_stdio(output) && pages.len() > 1 {
+        return Err(Svg2PdcError::UnsupportedOperation(
+            "writing every page to stdout, select one with --page".to_string(),
+        ));
+    }
+    let (directory, stem) = if output.is_dir() {
+        (output, input.file_stem().unwrap())
+    } else {
+        (
+            output.parent().unwrap_or(Path::new("")),
+            output.file_stem().unwrap(),
+        )
+    };
+    for (index, (page, image)) in pages.into_iter().enumerate() {
+        let name = page
+            .name()
+            .map(|name| name.replace(|c: char| c == '/' || c.is_whitespace(), "_"))
+            .unwrap_or_else(|| (index + 1).to_string());
+        let output = directory
+            .join(format!("{}-{}", stem.to_string_lossy(), name))
+            .with_extension("pdc");
+        if verbose {
+            eprintln!("Writing page {}: {:?}", index + 1, output);
+            image.inspect();
         }
+        sink.write(input.display(), &image, &output)?;
     }
+    Ok(())
+}
 
+/// Write every layer of a document to its own file in the directory of `output`, named after
+/// the layer.
+fn write_layers(
+    input: &Path,
+    output: &Path,
+    layers: Vec<(Option<String>, PebbleImage)>,
+    sink: &mut Sink,
+    verbose: bool,
+) -> Svg2PdcResult<()> {
+    let directory = if output.is_dir() {
+        output
+    } else {
+        output.parent().unwrap_or(Path::new(""))
+    };
+    let stem = input.file_stem().unwrap().to_string_lossy();
+    for (index, (name, image)) in layers.into_iter().enumerate() {
+        let name = name
+            .map(|name| name.replace(|c: char| c == '/' || c.is_whitespace(), "_"))
+            .unwrap_or_else(|| format!("{stem}-{}", index + 1));
+        let output = directory.join(format!("{name}.pdc"));
+        if verbose {
+            eprintln!("Writing layer {}: {:?}", index + 1, output);
+            image.inspect();
+        }
+        sink.write(input.display(), &image, &output)?;
+    }
     Ok(())
 }
 
 #[derive(Parser, Debug)]
-#[clap(version, about)]
-struct Args {
-    #[clap()]
-    /// Input file
-    input: PathBuf,
+#[clap(
+    version,
+    about,
+    args_conflicts_with_subcommands = true,
+    subcommand_negates_reqs = true
+)]
+struct Cli {
+    #[clap(subcommand)]
+    command: Option<Command>,
+
+    #[clap(long, value_name = "CODE", hide = true, exclusive = true)]
+    /// The `explain` subcommand, kept for scripts written before it
+    explain: Option<String>,
+
+    /// Without a subcommand, the arguments are those of `convert`
+    #[clap(flatten)]
+    convert: ConvertArgs,
+}
+
+/// Options of the `convert` subcommand, also accepted without a subcommand.
+#[derive(clap::Args, Debug)]
+#[clap(group(ArgGroup::new("generated").args(["path_d", "glyph"])))]
+#[clap(group(ArgGroup::new("sized").args(["path_d", "glyph", "select"]).multiple(true)))]
+struct ConvertArgs {
+    #[clap(required_unless_present_any = ["swatch_sheet", "generated"])]
+    /// Input file, `-` for stdin, or a directory whose SVGs are converted into a directory
+    /// tree mirroring it at --output [default: next to the SVGs]
+    input: Option<PathBuf>,
 
     #[clap(short, long)]
-    /// Output file
-    output: Option<PathBuf>,
+    /// Also convert the SVGs in the subdirectories of an input directory
+    recursive: bool,
+
+    #[clap(long)]
+    /// Convert every SVG of an input directory, also those that are unchanged since the
+    /// previous conversion with the same options
+    force: bool,
+
+    #[clap(long)]
+    /// Ignore the `svg2pdc.toml` of the working directory or its ancestors
+    no_config: bool,
+
+    #[clap(
+        long,
+        value_name = "D",
+        conflicts_with = "input",
+        requires = "size",
+        allow_hyphen_values = true
+    )]
+    /// Convert this SVG path expression, e.g. "M0 0 L50 0 L50 50 Z", instead of an input file
+    path_d: Option<String>,
+
+    #[clap(long, value_name = "CODEPOINT", value_parser = parse_codepoint, requires = "font", conflicts_with = "input")]
+    /// Convert the glyph of the --font for this character, `U+F0E7` or the character itself
+    glyph: Option<char>,
+
+    #[clap(long, value_name = "FILE", requires = "glyph")]
+    /// TrueType or OpenType font to take the --glyph from
+    font: Option<PathBuf>,
+
+    #[clap(long, value_name = "WxH", requires = "sized")]
+    /// Canvas size of the --path-d, --glyph or --select image, glyphs are scaled to fit it and
+    /// selected elements are centered on it
+    size: Option<Size>,
+
+    #[clap(long, value_name = "COLOR", value_parser = parse_color, requires = "generated")]
+    /// Fill color of the --path-d or --glyph image (`#RRGGBB`), glyphs default to white
+    fill: Option<Color>,
+
+    #[clap(long, value_name = "COLOR", value_parser = parse_color, requires = "path_d")]
+    /// Stroke color of the --path-d image (`#RRGGBB`)
+    stroke: Option<Color>,
+
+    #[clap(long, value_name = "PX", requires = "path_d")]
+    /// Stroke width of the --path-d image
+    stroke_width: Option<f32>,
 
     #[clap(short, long)]
+    /// Output file, `-` for stdout [default: next to the input, stdout for stdin]
+    output: Option<PathBuf>,
+
+    #[clap(long, value_enum, value_name = "FORMAT")]
+    /// Report the size, command, point, color and warning counts of every written image
+    stats_format: Option<StatsFormat>,
+
+    #[clap(long, value_name = "FILE", requires = "stats_format")]
+    /// CSV file the --stats-format csv rows are appended to, with a header when it is new
+    stats_file: Option<PathBuf>,
+
+    #[clap(long, value_name = "DIR", env = "SVG2PDC_OUTPUT_DIR")]
+    /// Directory for the output when no --output is given [default: next to the input]
+    output_dir: Option<PathBuf>,
+
+    #[clap(short, long, env = "SVG2PDC_PRECISE", value_parser = FalseyValueParser::new())]
     /// Use precise coordinates for path-like objects
     precise: bool,
 
-    #[clap(short, long)]
-    /// Create a sequence CURRENTLY UNSUPPORTED
-    sequence: bool,
+    #[clap(long)]
+    /// Don't use precise coordinates, even when --precise, SVG2PDC_PRECISE or svg2pdc.toml
+    /// asks for them
+    no_precise: bool,
 
-    #[clap(short, long)]
+    #[clap(flatten)]
+    converter: ConverterArgs,
+
+    #[clap(short, long, env = "SVG2PDC_TRUNCATE_COLOR", value_parser = FalseyValueParser::new())]
     truncate_color: bool,
 
-    #[clap(short, long)]
-    /// Duration of the animation in seconds CURRENTLY UNSUPPORTED
-    duration: Option<f32>,
+    #[clap(long)]
+    /// Don't truncate colors, even when --truncate-color, SVG2PDC_TRUNCATE_COLOR or
+    /// svg2pdc.toml asks for it
+    no_truncate_color: bool,
+
+    #[clap(long, conflicts_with = "truncate_color")]
+    /// Quantize every color to black, white or transparent, for monochrome Pebbles
+    target_bw: bool,
+
+    #[clap(long, value_name = "LUMINANCE", requires = "target_bw")]
+    /// Luminance from 0 to 1 from which --target-bw turns colors white [default: 0.5]
+    bw_threshold: Option<f32>,
 
     #[clap(short, long)]
     /// Verbose output
@@ -105,61 +1126,2108 @@ struct Args {
     #[clap(short, long)]
     /// Convert coordinates to Pebble's format
     convert: bool,
-}
 
-fn main() -> Result<()> {
-    let args = Args::parse();
+    #[clap(long)]
+    /// Require exact coordinates, even when --convert or svg2pdc.toml asks to convert them
+    no_convert: bool,
 
-    let precision = if args.precise {
-        Precision::Precise
-    } else {
-        Precision::Normal
-    };
+    #[clap(long, value_name = "FROM=TO", value_parser = parse_color_mapping)]
+    /// Replace a color of the document by another before it is quantized
+    /// (`--map-color '#ff0000=#aa0000'`), may be repeated
+    map_color: Vec<(Color, Color)>,
 
-    let sequence = args.sequence;
+    #[clap(long, value_name = "WxH", require_equals = true)]
+    /// Scale the artwork uniformly to fit a canvas of this size (`--fit=144x168`) and center it
+    /// there, together with its stroke widths. Without a size the screen of --platform is used
+    fit: Option<Option<Size>>,
 
-    if sequence && args.duration.is_none() {
-        return Err(Svg2PdcError::UnsupportedOperation("sequence".to_string()).into());
-    }
+    #[clap(long)]
+    /// Convert only this page of an Inkscape multi-page document (label, id or 1-based index).
+    /// Without it every page is written to its own file
+    page: Option<String>,
 
-    if !sequence && args.duration.is_some() {
-        return Err(Svg2PdcError::UnsupportedOperation("duration".to_string()).into());
-    }
+    #[clap(long, conflicts_with_all = ["page", "overlay"])]
+    /// Write every top-level group, or every Inkscape layer, to its own file next to the
+    /// output, named after its label or id
+    split_layers: bool,
 
-    let truncate_color = if args.truncate_color {
-        TruncateColor::Truncate
-    } else {
-        TruncateColor::Keep
-    };
+    #[clap(long, value_name = "ID", conflicts_with_all = ["page", "split_layers", "generated"])]
+    /// Convert only the element with this id (`#icon-sunny`) and its descendants, on a canvas
+    /// the size of its bounding box unless --size is given
+    select: Option<String>,
 
-    let conversion = if args.convert {
-        if args.verbose {
-            Conversion::ConvertWarn
-        } else {
-            Conversion::ConvertNoWarn
-        }
-    } else {
-        Conversion::RequireExact
-    };
+    #[clap(long, value_name = "FILE[@X,Y]", num_args = 1..)]
+    /// Draw these SVGs on top of the input, each optionally moved by X,Y pixels.
+    /// Every layer has to fit on the input's canvas
+    overlay: Vec<Layer>,
 
-    let duration = args.duration.unwrap_or(0.0);
+    #[clap(long, value_name = "FILE")]
+    /// Frame, background or badge SVG converted once and drawn below every output image
+    template: Option<PathBuf>,
 
-    let verbose = args.verbose;
-    let input = args.input;
-    let output = args.output.unwrap_or_else(|| input.with_extension("pdc"));
-    let play_count = 1;
-
-    create_pdc_from_path(
-        &input,
-        &output,
-        &precision,
-        &truncate_color,
-        &conversion,
-        verbose,
-        sequence,
-        duration,
-        play_count,
-    )?;
+    #[clap(long, requires = "template")]
+    /// Draw the template above the converted image instead of below it
+    template_above: bool,
+
+    #[clap(long, value_name = "FILE")]
+    /// Write an SVG swatch sheet of the colors used by the converted image,
+    /// or of the whole 64-color palette when no input is given
+    swatch_sheet: Option<PathBuf>,
+
+    #[clap(long, value_enum, value_delimiter = ',')]
+    /// Also write these artifacts next to the output
+    emit: Vec<Emit>,
+
+    #[clap(long, value_name = "COLOR", value_parser = parse_color)]
+    /// Remove a first rectangle covering the whole canvas when it is filled with COLOR
+    /// (`#RRGGBB`), e.g. an exported artboard background
+    strip_background: Option<Color>,
+
+    #[clap(long, value_name = "F")]
+    /// Scale the converted image, its canvas and its stroke widths by F
+    scale: Option<f32>,
+
+    #[clap(long, value_enum)]
+    /// Rotate the converted image clockwise by this many degrees
+    rotate: Option<Degrees>,
+
+    #[clap(long)]
+    /// Mirror the converted image horizontally
+    flip_h: bool,
+
+    #[clap(long)]
+    /// Mirror the converted image vertically
+    flip_v: bool,
+
+    #[clap(long, value_name = "X,Y", allow_hyphen_values = true)]
+    /// Move the converted image by X,Y pixels
+    offset: Option<Offset>,
+
+    #[clap(long, value_name = "N", allow_negative_numbers = true)]
+    /// Shrink closed shapes by N pixels, negative values grow them
+    inset: Option<f32>,
+
+    #[clap(long)]
+    /// Remove commands without a visible stroke or fill, join connected open paths with the
+    /// same stroke and no fill, and remove repeated points from paths
+    optimize: bool,
+
+    #[clap(long, requires = "optimize")]
+    /// Keep the commands that paint nothing when optimizing
+    keep_invisible: bool,
+
+    #[clap(long, value_name = "PX", requires = "optimize")]
+    /// Also remove points within PX pixels of the straight line between their neighbours
+    collinear_tolerance: Option<f32>,
+
+    #[clap(long)]
+    /// Reverse the direction of every path
+    reverse_paths: bool,
+
+    #[clap(long, value_enum)]
+    /// Start every closed path at the point closest to this corner
+    start_corner: Option<Corner>,
+
+    #[clap(long, conflicts_with = "pack")]
+    /// Write only the image payload, without the `PDCI` magic and length prefix
+    raw: bool,
+
+    #[clap(
+        long,
+        value_enum,
+        value_name = "FORMAT",
+        default_value = "pdc",
+        conflicts_with = "pack"
+    )]
+    /// Write the PDC bytes as they are, embedded in source code or as JSON, outputs ending in
+    /// `.pdc` get the extension of the format instead
+    output_format: OutputFormat,
+
+    #[clap(long, value_name = "NAME")]
+    /// Name of the array of the embedded bytes [default: the name of the output file]
+    symbol: Option<String>,
+
+    #[clap(long, value_name = "FILE")]
+    /// Add the converted images to this pack instead of writing PDC files, keyed by the name
+    /// their PDC file would have
+    pack: Option<PathBuf>,
+
+    #[clap(long, value_name = "FILE", requires = "pack")]
+    /// Write a C header with the location of every image in the pack
+    pack_header: Option<PathBuf>,
+
+    #[clap(long, value_name = "FILE", requires = "pack")]
+    /// Write a Rust module with the location of every image in the pack
+    pack_module: Option<PathBuf>,
+
+    #[clap(long, requires = "pack")]
+    /// Store identical images in the pack only once
+    dedupe: bool,
+}
+
+/// Options of the converter, shared by `convert` and `project sync`.
+#[derive(clap::Args, Debug)]
+struct ConverterArgs {
+    #[clap(long, value_name = "PX")]
+    /// Replace Bézier curves and elliptical arcs by lines staying within PX pixels of them,
+    /// instead of keeping only their end points
+    flatten_curves: Option<f32>,
+
+    #[clap(long, value_enum, value_name = "MODE", default_value = "warn")]
+    /// What happens to the holes of filled paths, like the inside of an O, which the watch
+    /// fills: keep them filled with a warning, join them to the subpath around them, or draw
+    /// only the outline of the path
+    holes: Holes,
+
+    #[clap(long, value_name = "PX", require_equals = true, requires = "precise")]
+    /// Draw circles whose center is not on a whole or half pixel as precise paths, only those
+    /// with a radius up to PX pixels when given (`--circles-as-paths=4`)
+    circles_as_paths: Option<Option<f32>>,
+
+    #[clap(long)]
+    /// Skip the quirks kept for byte-compatibility with the original tool
+    improved: bool,
+
+    #[clap(long, conflicts_with = "improved")]
+    /// Floor path points and move invalid points to the nearest valid one, like earlier
+    /// versions of svg2pdc
+    floor: bool,
+
+    #[clap(long)]
+    /// Input coordinates already address pixel centers, don't shift them by half a pixel
+    pixel_centers: bool,
+
+    #[clap(long, value_enum, value_name = "MODE")]
+    /// How points are rounded to Pebble coordinates. Defaults to legacy, the rounding of the
+    /// original tool, or to round with --improved
+    rounding: Option<Rounding>,
+
+    #[clap(long, value_name = "TAGS", value_delimiter = ',')]
+    /// Elements skipped without a warning, by name or as `prefix:name`. Replaces the default
+    /// list: metadata, title, desc, defs, script, style and sodipodi:namedview
+    ignore_tags: Option<Vec<String>>,
+
+    #[clap(long)]
+    /// Fail instead of warning on elements that are neither supported nor in --ignore-tags,
+    /// paints that are not colors and curves reduced to their end points
+    strict: bool,
+
+    #[cfg(feature = "text")]
+    #[clap(long, value_name = "FILE")]
+    /// TrueType or OpenType font to outline `<text>` elements with, instead of skipping them
+    text_font: Option<PathBuf>,
+
+    #[clap(long)]
+    /// Convert the top-level groups of the document on separate threads, for large artwork
+    parallel: bool,
+
+    #[clap(long, default_value_t = 1.0)]
+    /// Gamma correction of colors before they are mapped to the palette, above 1 lightens
+    /// the midtones
+    gamma: f32,
+
+    #[clap(long, default_value_t = 1.0)]
+    /// Multiply colors by this factor before they are mapped to the palette
+    brightness: f32,
+
+    #[clap(long, value_name = "COLOR", value_parser = parse_color)]
+    /// Color of `currentColor` paints (`#RRGGBB`), which draw nothing without it
+    color: Option<Color>,
+
+    #[clap(long)]
+    /// Make every color that isn't fully transparent opaque before it is mapped to the
+    /// palette
+    force_opaque: bool,
+
+    #[clap(long, value_name = "PX")]
+    /// Warn when a stored stroke width differs from the requested one by more than PX pixels
+    /// [default: 0.5]
+    stroke_width_tolerance: Option<f32>,
+
+    #[clap(long, value_enum, value_name = "MODE")]
+    /// How stroke widths are rounded to whole pixels. Defaults to truncating them, like the
+    /// original tool
+    stroke_rounding: Option<Rounding>,
+
+    #[clap(long, value_name = "PX")]
+    /// Raise the stroke width of stroked elements to at least PX pixels, so that hairlines
+    /// don't vanish on the watch
+    min_stroke_width: Option<u8>,
+
+    #[clap(long, value_name = "PX", require_equals = true)]
+    /// Draw dashed strokes as one open path per dash instead of solid. Dash ends within PX
+    /// pixels of a corner (`--dashes=1`, 0.5 by default) are moved onto it
+    dashes: Option<Option<f32>>,
+
+    #[clap(long, value_enum, env = "SVG2PDC_PLATFORM")]
+    /// Pebble platform the image is made for, checks that the artwork fits round displays
+    platform: Option<PlatformName>,
+
+    #[clap(long, requires = "platform")]
+    /// Pull geometry outside of a round display onto its edge
+    clip_to_display: bool,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Convert an SVG, a path expression or a font glyph to PDC, the default without a
+    /// subcommand
+    Convert(Box<ConvertArgs>),
+    /// Print the size and the commands of a PDC image, or of an SVG converted on the fly, with
+    /// the size of every command in bytes, and the colors of the image with the SVG colors
+    /// that were quantized to them
+    Inspect {
+        /// PDC image, or SVG file to convert first
+        input: PathBuf,
+
+        #[clap(short, long)]
+        /// Also print every point of every command
+        verbose: bool,
+
+        #[clap(long, value_enum, default_value = "text")]
+        format: InspectFormat,
+
+        #[clap(short, long, env = "SVG2PDC_PRECISE", value_parser = FalseyValueParser::new())]
+        /// Use precise coordinates for path-like objects of SVG inputs
+        precise: bool,
+    },
+    /// Check that SVG files convert without errors or warnings and that the images stay within
+    /// the limits of the PDC format, without writing anything
+    Validate {
+        /// SVG files, or PDC images to check against the limits only
+        #[clap(required = true)]
+        inputs: Vec<PathBuf>,
+
+        #[clap(short, long, env = "SVG2PDC_PRECISE", value_parser = FalseyValueParser::new())]
+        /// Use precise coordinates for path-like objects
+        precise: bool,
+
+        #[clap(long)]
+        /// Fail on unsupported elements, invalid colors and simplified curves instead of warning
+        strict: bool,
+
+        #[clap(long)]
+        /// Count files with warnings as failed
+        deny_warnings: bool,
+
+        #[clap(long, value_name = "BYTES")]
+        /// Fail images larger than BYTES once serialized
+        max_bytes: Option<usize>,
+    },
+    /// Convert the SVGs of a directory, then convert them again whenever they change
+    Watch {
+        /// Directory of SVG files
+        input: PathBuf,
+
+        #[clap(short, long)]
+        /// Directory for the PDC files, mirroring the input [default: next to the SVGs]
+        output: Option<PathBuf>,
+
+        #[clap(short, long)]
+        /// Also watch the subdirectories of the input
+        recursive: bool,
+
+        #[clap(short, long, env = "SVG2PDC_PRECISE", value_parser = FalseyValueParser::new())]
+        /// Use precise coordinates for path-like objects
+        precise: bool,
+
+        #[clap(short, long, env = "SVG2PDC_TRUNCATE_COLOR", value_parser = FalseyValueParser::new())]
+        truncate_color: bool,
+    },
+    /// Manage the images of a Pebble project
+    #[cfg(feature = "serde")]
+    Project {
+        #[clap(subcommand)]
+        command: ProjectCommand,
+    },
+    /// Combine SVG frames of the same size into a PDC sequence, an animation
+    Sequence {
+        /// SVG files of the frames, ordered by the numbers in their names, so `frame_2.svg`
+        /// comes before `frame_10.svg`
+        #[clap(required = true)]
+        inputs: Vec<PathBuf>,
+
+        #[clap(long)]
+        /// Take the frames from the layers of a single SVG instead, in layer order: its
+        /// Inkscape layers, or its top-level groups without layers
+        layers: bool,
+
+        #[clap(long, conflicts_with_all = ["layers", "timing"])]
+        /// Sample the SMIL animations of a single SVG instead, `<animate>` and
+        /// `<animateTransform type="translate">`, for --duration seconds or until they end
+        smil: bool,
+
+        #[clap(long, default_value_t = 10.0)]
+        /// Frames per second sampled with --smil
+        fps: f32,
+
+        #[clap(short, long)]
+        /// PDCS file to write
+        output: PathBuf,
+
+        #[clap(short, long, required_unless_present_any = ["timing", "smil"])]
+        /// Duration of the animation in seconds, shared equally by the frames
+        duration: Option<f32>,
+
+        #[clap(long, value_name = "FILE")]
+        /// JSON object or CSV rows mapping frame file names, or layer labels with --layers, to
+        /// milliseconds, frames left out share the --duration
+        timing: Option<PathBuf>,
+
+        #[clap(long, default_value_t = 1)]
+        /// How many times the animation plays, 65535 loops it forever
+        play_count: u16,
+
+        #[clap(short, long, env = "SVG2PDC_PRECISE", value_parser = FalseyValueParser::new())]
+        /// Use precise coordinates for path-like objects
+        precise: bool,
+
+        #[clap(short, long, env = "SVG2PDC_TRUNCATE_COLOR", value_parser = FalseyValueParser::new())]
+        truncate_color: bool,
+    },
+    /// Print a detailed explanation of a warning code, e.g. `W001`
+    Explain {
+        /// Warning code
+        code: String,
+    },
+    /// Render a PDC image with the index of every command drawn next to it, and print a
+    /// legend of the commands
+    #[cfg(feature = "render")]
+    Annotate {
+        /// PDC image to render
+        input: PathBuf,
+
+        /// PNG file to write
+        output: PathBuf,
+
+        #[clap(long, default_value_t = 4.0)]
+        /// Canvas pixels per watch pixel
+        scale: f32,
+    },
+    /// Write every draw command of a PDC image to its own PDC file, on the original canvas
+    Explode {
+        /// PDC image to split
+        input: PathBuf,
+
+        /// Directory for the files, named `<input>-<index>.pdc` [default: next to the input]
+        output: Option<PathBuf>,
+    },
+    /// Render a PDC image, or an SVG converted on the fly, to a PNG the way the watch draws it
+    #[cfg(feature = "render")]
+    Render {
+        /// PDC image, or SVG file to convert first
+        input: PathBuf,
+
+        #[clap(short, long)]
+        /// PNG file to write
+        output: PathBuf,
+
+        #[clap(long, default_value_t = 4.0)]
+        /// Canvas pixels per watch pixel
+        scale: f32,
+
+        #[clap(short, long)]
+        /// Use precise coordinates for path-like objects of an SVG
+        precise: bool,
+
+        #[clap(short, long)]
+        truncate_color: bool,
+    },
+    /// Write a PDC image back as an SVG, to check or edit it in an SVG editor
+    ToSvg {
+        /// PDC image to convert
+        input: PathBuf,
+
+        /// SVG file to write [default: stdout]
+        output: Option<PathBuf>,
+    },
+    /// Convert an image written with `--output-format json` back to PDC
+    FromJson {
+        /// JSON image to convert
+        input: PathBuf,
+
+        /// PDC file to write [default: the input with the `.pdc` extension]
+        output: Option<PathBuf>,
+    },
+    /// Compare two PDC images command by command, failing when they differ
+    Diff {
+        /// PDC image to compare against, e.g. a golden file
+        expected: PathBuf,
+
+        /// PDC image to compare
+        actual: PathBuf,
+    },
+    /// Preview a PDC image command by command: an animated GIF when OUTPUT ends in `.gif`,
+    /// otherwise one PNG per command named `<output>-<index>.png`
+    #[cfg(feature = "render")]
+    Steps {
+        /// PDC image to render
+        input: PathBuf,
+
+        /// GIF file or base name of the PNG frames
+        output: PathBuf,
+
+        #[clap(long, default_value_t = 4.0)]
+        /// Canvas pixels per watch pixel
+        scale: f32,
+
+        #[clap(long, value_name = "MS", default_value_t = 500)]
+        /// How long every frame of the GIF is shown
+        delay: u64,
+    },
+    /// List the SVG features that are lost or approximated in the conversion, per file or
+    /// for a whole directory of SVGs
+    Analyze {
+        /// SVG file or directory, searched recursively
+        input: PathBuf,
+
+        #[clap(long)]
+        /// Print one table for all files instead of the features of every file
+        summary: bool,
+    },
+    /// Convert an SVG and compare the result with the original Python tool's, either from a
+    /// directory of reference outputs or by running the Python script
+    CompareReference {
+        /// SVG file to convert
+        input: PathBuf,
+
+        #[clap(long, value_name = "DIR")]
+        /// Directory holding the reference output as `<input>.pdc`
+        reference: Option<PathBuf>,
+
+        #[clap(long, value_name = "FILE", conflicts_with = "reference")]
+        /// The original `svg2pdc.py`, run when no reference directory is given
+        /// [default: `svg2pdc.py` on the PATH]
+        script: Option<PathBuf>,
+
+        #[clap(long, value_name = "CMD", default_value = "python")]
+        /// Python interpreter running the script
+        interpreter: String,
+
+        #[clap(short, long)]
+        /// Use precise coordinates for path-like objects
+        precise: bool,
+
+        #[clap(short, long)]
+        /// Has no effect, colors are always truncated like the original tool does
+        truncate_color: bool,
+    },
+}
+
+/// Subcommands of `project`.
+#[cfg(feature = "serde")]
+#[derive(Subcommand, Debug)]
+enum ProjectCommand {
+    /// Convert the SVGs of the project into `resources/images/` and list them as `raw`
+    /// resources in `package.json` or `appinfo.json`
+    Sync {
+        /// Directory of the Pebble project
+        #[clap(default_value = ".")]
+        project: PathBuf,
+
+        #[clap(short, long, env = "SVG2PDC_PRECISE", value_parser = FalseyValueParser::new())]
+        /// Use precise coordinates for path-like objects
+        precise: bool,
+
+        #[clap(short, long, env = "SVG2PDC_TRUNCATE_COLOR", value_parser = FalseyValueParser::new())]
+        truncate_color: bool,
+
+        #[clap(flatten)]
+        converter: ConverterArgs,
+    },
+}
+
+/// How the statistics of the written images are reported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum StatsFormat {
+    /// One line per image on stdout
+    Text,
+    /// CSV rows, appended to --stats-file or printed to stdout
+    Csv,
+}
+
+/// How `inspect` describes an image.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum InspectFormat {
+    /// A summary line, a legend of the commands and the palette
+    Text,
+    /// A JSON object with the size, the total size in bytes, the commands and the palette
+    Json,
+}
+
+/// How the serialized images are written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum OutputFormat {
+    /// The PDC bytes
+    Pdc,
+    /// A C header with the bytes as a `static const uint8_t` array and its length
+    CHeader,
+    /// A Rust module with the bytes as a `pub const` byte slice
+    Rust,
+    /// The size and the draw commands as JSON, to edit by hand and convert back with
+    /// `from-json`
+    Json,
+}
+
+impl OutputFormat {
+    /// Extension of the files written in this format.
+    fn extension(self) -> &'static str {
+        match self {
+            Self::Pdc => "pdc",
+            Self::CHeader => "h",
+            Self::Rust => "rs",
+            Self::Json => "json",
+        }
+    }
+}
+
+/// Extra artifacts written next to the PDC output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Emit {
+    /// The input SVG with every color replaced by the one the watch displays
+    QuantizedSvg,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum Rounding {
+    Legacy,
+    Floor,
+    Round,
+    HalfEven,
+}
+
+impl From<Rounding> for RoundingMode {
+    fn from(rounding: Rounding) -> Self {
+        match rounding {
+            Rounding::Legacy => RoundingMode::Legacy,
+            Rounding::Floor => RoundingMode::Floor,
+            Rounding::Round => RoundingMode::Round,
+            Rounding::HalfEven => RoundingMode::HalfEven,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum Holes {
+    Warn,
+    Bridge,
+    Outline,
+}
+
+impl From<Holes> for HoleHandling {
+    fn from(holes: Holes) -> Self {
+        match holes {
+            Holes::Warn => HoleHandling::Warn,
+            Holes::Bridge => HoleHandling::Bridge,
+            Holes::Outline => HoleHandling::Outline,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum PlatformName {
+    Aplite,
+    Basalt,
+    Chalk,
+    Diorite,
+    Emery,
+}
+
+impl From<PlatformName> for Platform {
+    fn from(platform: PlatformName) -> Self {
+        match platform {
+            PlatformName::Aplite => Platform::Aplite,
+            PlatformName::Basalt => Platform::Basalt,
+            PlatformName::Chalk => Platform::Chalk,
+            PlatformName::Diorite => Platform::Diorite,
+            PlatformName::Emery => Platform::Emery,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum Degrees {
+    #[value(name = "90")]
+    Cw90,
+    #[value(name = "180")]
+    Cw180,
+    #[value(name = "270")]
+    Cw270,
+}
+
+impl From<Degrees> for Rotation {
+    fn from(degrees: Degrees) -> Self {
+        match degrees {
+            Degrees::Cw90 => Rotation::Cw90,
+            Degrees::Cw180 => Rotation::Cw180,
+            Degrees::Cw270 => Rotation::Cw270,
+        }
+    }
+}
+
+fn parse_codepoint(s: &str) -> Result<char, String> {
+    let mut chars = s.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        return Ok(c);
+    }
+    let hex = s
+        .strip_prefix("U+")
+        .or_else(|| s.strip_prefix("0x"))
+        .ok_or_else(|| format!("expected a character or a codepoint like `U+F0E7`, found `{s}`"))?;
+    u32::from_str_radix(hex, 16)
+        .ok()
+        .and_then(char::from_u32)
+        .ok_or_else(|| format!("invalid codepoint `{s}`"))
+}
+
+fn parse_color(s: &str) -> Result<Color, String> {
+    let hex = s.trim_start_matches('#');
+    if !matches!(hex.len(), 6 | 8) {
+        return Err(format!("expected a color like `#RRGGBB`, found `{s}`"));
+    }
+    Color::try_from_hex(hex).map_err(|error| error.to_string())
+}
+
+fn parse_color_mapping(s: &str) -> Result<(Color, Color), String> {
+    let (from, to) = s
+        .split_once('=')
+        .ok_or_else(|| format!("expected a mapping like `#RRGGBB=#RRGGBB`, found `{s}`"))?;
+    Ok((parse_color(from.trim())?, parse_color(to.trim())?))
+}
+
+/// A distance in pixels, `x,y` on the command line.
+#[derive(Debug, Clone, Copy)]
+struct Offset(FPoint);
+
+impl std::str::FromStr for Offset {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (x, y) = s
+            .split_once(',')
+            .ok_or_else(|| format!("expected an offset like `x,y`, found `{s}`"))?;
+        let parse = |value: &str| {
+            value
+                .trim()
+                .parse::<f32>()
+                .map_err(|error| format!("invalid offset `{value}`: {error}"))
+        };
+        Ok(Self(FPoint::new(parse(x)?, parse(y)?)))
+    }
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum Corner {
+    TopLeft,
+    TopRight,
+    BottomRight,
+    BottomLeft,
+}
+
+impl From<Corner> for StartCorner {
+    fn from(corner: Corner) -> Self {
+        match corner {
+            Corner::TopLeft => StartCorner::TopLeft,
+            Corner::TopRight => StartCorner::TopRight,
+            Corner::BottomRight => StartCorner::BottomRight,
+            Corner::BottomLeft => StartCorner::BottomLeft,
+        }
+    }
+}
+
+fn explain(code: &str) -> Result<()> {
+    let diagnostic = DiagnosticCode::from_code(code).ok_or_else(|| {
+        Svg2PdcError::UnsupportedOperation(format!("unknown diagnostic code `{code}`"))
+    })?;
+    println!("{}: {}\n", diagnostic, diagnostic.summary());
+    println!("{}", diagnostic.explanation());
+    Ok(())
+}
+
+fn inspect_image(input: &Path, verbose: bool, format: InspectFormat, precise: bool) -> Result<()> {
+    let (image, palette) = if input
+        .extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("pdc"))
+    {
+        let image = PebbleImage::deserialize(&mut std::fs::File::open(input)?)?;
+        // The source colors of a PDC image are unknown
+        let palette = image
+            .colors()
+            .into_iter()
+            .map(|color| PaletteEntry {
+                color,
+                sources: Vec::new(),
+            })
+            .collect();
+        (image, palette)
+    } else {
+        let precision = if precise {
+            Precision::Precise
+        } else {
+            Precision::Normal
+        };
+        let content = read_input(input)?;
+        let image = SvgConverter::new(precision).parse_svg_image(
+            &content,
+            &TruncateColor::Truncate,
+            &Conversion::ConvertWarn,
+        )?;
+        let palette = palette(
+            &svg2pdc::roxmltree::Document::parse(&content)?,
+            &TruncateColor::Truncate,
+            &ColorAdjustment::default(),
+        );
+        (image, palette)
+    };
+    let mut bytes = Vec::new();
+    image.serialize(&mut bytes)?;
+
+    match format {
+        InspectFormat::Text => {
+            println!(
+                "{}x{} canvas, {} commands, {} bytes",
+                image.size.x,
+                image.size.y,
+                image.commands.len(),
+                bytes.len()
+            );
+            let legend = image.legend();
+            let width = legend.lines().map(str::len).max().unwrap_or_default();
+            for (line, command) in legend.lines().zip(&image.commands) {
+                println!("{line:<width$} {:>5} bytes", command.serialized_size());
+            }
+            if verbose {
+                print!("{}", image.describe());
+            }
+            println!("Palette: {} colors", palette.len());
+            for entry in &palette {
+                let name = entry.color.name().unwrap_or_default();
+                let mut line = format!("  {name:<22} {}", entry.color.to_hex());
+                if !entry.sources.is_empty() {
+                    let sources: Vec<_> = entry.sources.iter().map(rgb_hex).collect();
+                    line.push_str(&format!("  from {}", sources.join(", ")));
+                }
+                println!("{line}");
+            }
+            for collision in palette.iter().flat_map(PaletteEntry::collisions) {
+                println!("warning: {collision}");
+            }
+        }
+        InspectFormat::Json => println!("{}", inspect_json(&image, &palette, bytes.len(), verbose)),
+    }
+    Ok(())
+}
+
+/// `#RRGGBB` notation of a source color.
+fn rgb_hex(color: &Color) -> String {
+    format!("#{:02X}{:02X}{:02X}", color.r, color.g, color.b)
+}
+
+/// A JSON object describing `image`, its commands and its palette, with every point when
+/// `verbose`.
+fn inspect_json(
+    image: &PebbleImage,
+    palette: &[PaletteEntry],
+    bytes: usize,
+    verbose: bool,
+) -> String {
+    let point = |point: &PebblePoint| format!("[{}, {}]", point.x, point.y);
+    let commands: Vec<_> = image
+        .commands
+        .iter()
+        .enumerate()
+        .map(|(index, command)| {
+            let options = command.options();
+            let shape = match command {
+                DrawCommand::Path { points, open, .. } => {
+                    let kind = match options.precision {
+                        Precision::Normal => "path",
+                        Precision::Precise => "precise_path",
+                    };
+                    let mut shape = format!(
+                        "\"type\": \"{kind}\", \"open\": {open}, \"point_count\": {}",
+                        points.len()
+                    );
+                    if verbose {
+                        let points: Vec<_> = points.iter().map(point).collect();
+                        shape.push_str(&format!(", \"points\": [{}]", points.join(", ")));
+                    }
+                    shape
+                }
+                DrawCommand::Circle { center, radius, .. } => format!(
+                    "\"type\": \"circle\", \"center\": {}, \"radius\": {radius}",
+                    point(center)
+                ),
+            };
+            format!(
+                "    {{\"index\": {index}, {shape}, \"fill_color\": {}, \"stroke_color\": {}, \
+                 \"stroke_width\": {}, \"bytes\": {}}}",
+                options.fill_color,
+                options.stroke_color,
+                options.stroke_width,
+                command.serialized_size()
+            )
+        })
+        .collect();
+    let commands = if commands.is_empty() {
+        "[]".to_string()
+    } else {
+        format!("[\n{}\n  ]", commands.join(",\n"))
+    };
+    let quoted = |text: String| format!("\"{text}\"");
+    let palette: Vec<_> = palette
+        .iter()
+        .map(|entry| {
+            let sources: Vec<_> = entry
+                .sources
+                .iter()
+                .map(|color| quoted(rgb_hex(color)))
+                .collect();
+            let collisions: Vec<_> = entry
+                .collisions()
+                .iter()
+                .map(|collision| {
+                    format!(
+                        "[{}, {}]",
+                        quoted(rgb_hex(&collision.first)),
+                        quoted(rgb_hex(&collision.second))
+                    )
+                })
+                .collect();
+            format!(
+                "    {{\"color\": {}, \"name\": {}, \"hex\": {}, \"sources\": [{}], \
+                 \"collisions\": [{}]}}",
+                entry.color.inner(),
+                entry
+                    .color
+                    .name()
+                    .map_or("null".to_string(), |name| quoted(name.to_string())),
+                quoted(entry.color.to_hex()),
+                sources.join(", "),
+                collisions.join(", ")
+            )
+        })
+        .collect();
+    let palette = if palette.is_empty() {
+        "[]".to_string()
+    } else {
+        format!("[\n{}\n  ]", palette.join(",\n"))
+    };
+    format!(
+        "{{\n  \"width\": {},\n  \"height\": {},\n  \"bytes\": {bytes},\n  \"commands\": {commands},\n  \"palette\": {palette}\n}}",
+        image.size.x, image.size.y
+    )
+}
+
+fn validate_svgs(
+    inputs: &[PathBuf],
+    precise: bool,
+    strict: bool,
+    deny_warnings: bool,
+    max_bytes: Option<usize>,
+) -> Result<()> {
+    let precision = if precise {
+        Precision::Precise
+    } else {
+        Precision::Normal
+    };
+    let converter = SvgConverter::new(precision).with_strict(strict);
+    let mut failed = 0;
+    for input in inputs {
+        let warnings = warning_count();
+        let result = if input
+            .extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("pdc"))
+        {
+            std::fs::File::open(input)
+                .map_err(Svg2PdcError::from)
+                .and_then(|mut file| PebbleImage::deserialize(&mut file))
+        } else {
+            read_input(input).and_then(|content| {
+                converter.parse_svg_image(&content, &TruncateColor::Keep, &Conversion::ConvertWarn)
+            })
+        };
+        let warnings = warning_count() - warnings;
+        let violations = result
+            .as_ref()
+            .map(|image| image.validate(max_bytes))
+            .unwrap_or_default();
+        match result {
+            Err(error) => {
+                failed += 1;
+                println!("{}: {error}", input.display());
+            }
+            Ok(_) if !violations.is_empty() => {
+                failed += 1;
+                for violation in violations {
+                    println!("{}: {violation}, {}", input.display(), violation.hint());
+                }
+            }
+            Ok(_) if warnings > 0 => {
+                failed += usize::from(deny_warnings);
+                println!("{}: {warnings} warnings", input.display());
+            }
+            Ok(_) => println!("{}: ok", input.display()),
+        }
+    }
+    if failed > 0 {
+        anyhow::bail!("{failed} of {} files failed validation", inputs.len());
+    }
+    Ok(())
+}
+
+/// Where the frames of a sequence come from.
+enum FrameSource {
+    /// One SVG file per frame.
+    Files,
+    /// The layers of a single SVG.
+    Layers,
+    /// The SMIL animations of a single SVG, sampled `fps` times per second.
+    Smil { fps: f32 },
+}
+
+#[expect(clippy::too_many_arguments)]
+fn create_sequence(
+    mut inputs: Vec<PathBuf>,
+    source: FrameSource,
+    output: &Path,
+    mut duration: Option<f32>,
+    timing: Option<&Path>,
+    play_count: u16,
+    precise: bool,
+    truncate_color: bool,
+) -> Result<()> {
+    if let Some(duration) = duration
+        && (!duration.is_finite() || duration < 0.0)
+    {
+        anyhow::bail!("invalid duration {duration}, expected seconds");
+    }
+    let mut timing = match timing {
+        Some(timing) => parse_timing(&std::fs::read_to_string(timing)?)?,
+        None => Vec::new(),
+    };
+    let precision = if precise {
+        Precision::Precise
+    } else {
+        Precision::Normal
+    };
+    let truncate_color = if truncate_color {
+        TruncateColor::Truncate
+    } else {
+        TruncateColor::Keep
+    };
+    let converter = SvgConverter::new(precision);
+
+    // The frames with the names the timing may refer to them by
+    let mut frames: Vec<(Vec<String>, PebbleImage)> = Vec::new();
+    let single_input = || match &inputs[..] {
+        [input] => Ok(input),
+        _ => Err(anyhow::anyhow!(
+            "--layers and --smil take the frames from a single SVG"
+        )),
+    };
+    match source {
+        FrameSource::Layers => {
+            for (name, image) in converter.parse_svg_layers(
+                &read_input(single_input()?)?,
+                &truncate_color,
+                &Conversion::ConvertWarn,
+            )? {
+                frames.push((name.into_iter().collect(), image));
+            }
+        }
+        FrameSource::Smil { fps } => {
+            if !fps.is_finite() || fps <= 0.0 {
+                anyhow::bail!("invalid frame rate {fps}");
+            }
+            let content = read_input(single_input()?)?;
+            let length = match duration {
+                Some(duration) => duration as f64,
+                None => animation_duration(&content)?
+                    .ok_or_else(|| anyhow::anyhow!("the SVG has no SMIL animations"))?,
+            };
+            let count = (length * fps as f64).round().max(1.0) as usize;
+            for index in 0..count {
+                let sampled = sample_svg(&content, index as f64 / fps as f64)?;
+                let image = converter.parse_svg_image(
+                    &sampled,
+                    &truncate_color,
+                    &Conversion::ConvertWarn,
+                )?;
+                frames.push((Vec::new(), image));
+            }
+            // Every frame lasts as long as the frame rate says
+            duration = Some(count as f32 / fps);
+        }
+        FrameSource::Files => {
+            inputs.sort_by(|a, b| natural_cmp(&a.to_string_lossy(), &b.to_string_lossy()));
+            for input in &inputs {
+                // Manifest entries name frames by their file name or by the path as given
+                let names = vec![
+                    input
+                        .file_name()
+                        .unwrap_or_default()
+                        .to_string_lossy()
+                        .into(),
+                    input.to_string_lossy().into(),
+                ];
+                let image = converter.parse_svg_image(
+                    &read_input(input)?,
+                    &truncate_color,
+                    &Conversion::ConvertWarn,
+                )?;
+                frames.push((names, image));
+            }
+        }
+    }
+    if frames.is_empty() {
+        anyhow::bail!("no frames found for the sequence");
+    }
+
+    let shared_duration = duration
+        .map(|duration| (duration * 1000.0 / frames.len() as f32).round())
+        .filter(|&duration| duration <= u16::MAX as f32);
+    if duration.is_some() && shared_duration.is_none() {
+        anyhow::bail!("frames of the sequence would last more than {}ms", u16::MAX);
+    }
+    let mut timed_frames = Vec::with_capacity(frames.len());
+    for (index, (names, image)) in frames.into_iter().enumerate() {
+        let name = names
+            .last()
+            .cloned()
+            .unwrap_or_else(|| format!("frame {index}"));
+        let timed = timing.iter().position(|(frame, _)| names.contains(frame));
+        let duration = match (timed, shared_duration) {
+            (Some(position), _) => timing.remove(position).1,
+            (None, Some(duration)) => duration as u16,
+            (None, None) => {
+                anyhow::bail!("{name} has no duration in the timing, and no --duration is given")
+            }
+        };
+        println!("Frame {index}: {name}, {duration}ms");
+        timed_frames.push((image, duration));
+    }
+    if let Some((frame, _)) = timing.first() {
+        anyhow::bail!("the timing names `{frame}`, which isn't a frame of the sequence");
+    }
+    let sequence = PebbleSequence::from_timed_images(timed_frames, play_count)?;
+    sequence.serialize(&mut std::fs::File::create(output)?)?;
+    Ok(())
+}
+
+/// Compare names with the runs of digits in them compared by their value, so `frame_2`
+/// comes before `frame_10`.
+fn natural_cmp(a: &str, b: &str) -> std::cmp::Ordering {
+    let (mut a, mut b) = (a, b);
+    loop {
+        let (Some(first_a), Some(first_b)) = (a.chars().next(), b.chars().next()) else {
+            return a.len().cmp(&b.len());
+        };
+        let ordering = if first_a.is_ascii_digit() && first_b.is_ascii_digit() {
+            let digits = |s: &str| s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+            let (number_a, number_b) = (&a[..digits(a)], &b[..digits(b)]);
+            let (trimmed_a, trimmed_b) = (
+                number_a.trim_start_matches('0'),
+                number_b.trim_start_matches('0'),
+            );
+            a = &a[number_a.len()..];
+            b = &b[number_b.len()..];
+            trimmed_a
+                .len()
+                .cmp(&trimmed_b.len())
+                .then_with(|| trimmed_a.cmp(trimmed_b))
+                .then_with(|| number_a.len().cmp(&number_b.len()))
+        } else {
+            a = &a[first_a.len_utf8()..];
+            b = &b[first_b.len_utf8()..];
+            first_a.cmp(&first_b)
+        };
+        if ordering.is_ne() {
+            return ordering;
+        }
+    }
+}
+
+#[cfg(feature = "render")]
+fn annotate_pdc(input: &Path, output: &Path, scale: f32) -> Result<()> {
+    let image = PebbleImage::deserialize(&mut std::fs::File::open(input)?)?;
+    annotate(&image, scale)?.save_png(output)?;
+    print!("{}", image.legend());
+    Ok(())
+}
+
+fn explode_pdc(input: &Path, output: Option<&Path>) -> Result<()> {
+    let image = PebbleImage::deserialize(&mut std::fs::File::open(input)?)?;
+    let directory = output.unwrap_or_else(|| input.parent().unwrap_or(Path::new("")));
+    std::fs::create_dir_all(directory)?;
+    let stem = input.file_stem().unwrap().to_string_lossy();
+    for (index, part) in image.explode().iter().enumerate() {
+        let output = directory.join(format!("{stem}-{index}.pdc"));
+        println!("Writing command {index}: {:?}", output);
+        part.serialize(&mut std::fs::File::create(output)?)?;
+    }
+    Ok(())
+}
+
+#[cfg(feature = "render")]
+fn render_preview(
+    input: &Path,
+    output: &Path,
+    scale: f32,
+    precise: bool,
+    truncate_color: bool,
+) -> Result<()> {
+    let image = if input
+        .extension()
+        .is_some_and(|extension| extension == "pdc")
+    {
+        PebbleImage::deserialize(&mut std::fs::File::open(input)?)?
+    } else {
+        let precision = if precise {
+            Precision::Precise
+        } else {
+            Precision::Normal
+        };
+        let truncate_color = if truncate_color {
+            TruncateColor::Truncate
+        } else {
+            TruncateColor::Keep
+        };
+        SvgConverter::new(precision).parse_svg_image(
+            &read_input(input)?,
+            &truncate_color,
+            &Conversion::ConvertWarn,
+        )?
+    };
+    render(&image, scale)?.save_png(output)?;
+    Ok(())
+}
+
+fn pdc_to_svg(input: &Path, output: Option<&Path>) -> Result<()> {
+    let image = PebbleImage::deserialize(&mut std::fs::File::open(input)?)?;
+    let svg = to_svg(&image);
+    match output {
+        Some(output) => std::fs::write(output, svg)?,
+        None => print!("{svg}"),
+    }
+    Ok(())
+}
+
+fn json_to_pdc(input: &Path, output: &Path) -> Result<()> {
+    let image = image_from_json(&std::fs::read_to_string(input)?)?;
+    let mut bytes = Vec::new();
+    image.serialize(&mut bytes)?;
+    std::fs::write(output, bytes)?;
+    Ok(())
+}
+
+fn diff_pdc(expected: &Path, actual: &Path) -> Result<()> {
+    let (expected_bytes, actual_bytes) = (std::fs::read(expected)?, std::fs::read(actual)?);
+    let Some(offset) = first_byte_difference(&expected_bytes, &actual_bytes) else {
+        println!("Identical ({} bytes)", actual_bytes.len());
+        return Ok(());
+    };
+    let differences = semantic_differences(
+        &PebbleImage::deserialize(&mut expected_bytes.as_slice())?,
+        &PebbleImage::deserialize(&mut actual_bytes.as_slice())?,
+    );
+    if differences.is_empty() {
+        // E.g. a raw payload against a file with the magic and length prefix
+        println!("Same image, serialized differently from byte {offset}");
+        return Ok(());
+    }
+    println!(
+        "Differs at byte {offset} ({} bytes, {} bytes)",
+        expected_bytes.len(),
+        actual_bytes.len()
+    );
+    for difference in differences {
+        println!("  {difference}");
+    }
+    Err(anyhow::anyhow!(
+        "{} differs from {}",
+        actual.display(),
+        expected.display()
+    ))
+}
+
+/// `path` moved into `output_dir` when one is set.
+fn default_output(output_dir: Option<&Path>, path: PathBuf) -> PathBuf {
+    match (output_dir, path.file_name()) {
+        (Some(output_dir), Some(name)) => output_dir.join(name),
+        _ => path,
+    }
+}
+
+fn analyze_svgs(input: &Path, summary: bool) -> Result<()> {
+    let mut totals = FeatureSummary::default();
+    for file in svg_files(input, true)? {
+        let content = std::fs::read_to_string(&file)?;
+        let usage = features(&roxmltree::Document::parse(&content)?)?;
+        if !summary {
+            let usage: Vec<_> = usage
+                .iter()
+                .map(|(feature, count)| format!("{feature} ({count})"))
+                .collect();
+            if usage.is_empty() {
+                println!("{}: converts without losses", file.display());
+            } else {
+                println!("{}: {}", file.display(), usage.join(", "));
+            }
+        }
+        totals.add(&usage);
+    }
+    if summary {
+        println!("{} files", totals.documents);
+        for (feature, documents) in totals.by_documents() {
+            println!(
+                "{feature:<32} {documents:>6} files {:>8} times",
+                totals.occurrences[feature]
+            );
+        }
+    }
+    Ok(())
+}
+
+/// `path` itself when it is a file, otherwise the SVGs in it, and in its subdirectories when
+/// `recursive`, in a stable order.
+fn svg_files(path: &Path, recursive: bool) -> Result<Vec<PathBuf>> {
+    if !path.is_dir() {
+        return Ok(vec![path.to_path_buf()]);
+    }
+    let mut files = Vec::new();
+    for entry in std::fs::read_dir(path)? {
+        let entry = entry?.path();
+        if entry.is_dir() {
+            if recursive {
+                files.extend(svg_files(&entry, recursive)?);
+            }
+        } else if entry
+            .extension()
+            .is_some_and(|extension| extension == "svg")
+        {
+            files.push(entry);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// The options of `convert` that `svg2pdc.toml` can change for single SVGs.
+struct FileOptions {
+    precision: Precision,
+    truncate_color: TruncateColor,
+    conversion: Conversion,
+    fit: Option<PebblePoint>,
+    color_map: Vec<(Color, Color)>,
+}
+
+impl FileOptions {
+    /// The options of `settings`, quantizing to black and white instead when `bw_threshold`
+    /// is given.
+    fn new(settings: Settings, bw_threshold: Option<f32>, verbose: bool) -> Self {
+        let precision = if settings.precise == Some(true) {
+            Precision::Precise
+        } else {
+            Precision::Normal
+        };
+        let truncate_color = match bw_threshold {
+            Some(threshold) => TruncateColor::BlackAndWhite { threshold },
+            None if settings.truncate_color == Some(true) => TruncateColor::Truncate,
+            None => TruncateColor::Keep,
+        };
+        let conversion = match settings.convert {
+            Some(true) if verbose => Conversion::ConvertWarn,
+            Some(true) => Conversion::ConvertNoWarn,
+            _ => Conversion::RequireExact,
+        };
+        Self {
+            precision,
+            truncate_color,
+            conversion,
+            fit: settings.fit,
+            color_map: settings.colors,
+        }
+    }
+}
+
+/// The SVGs converted into a directory and the options they were converted with, to skip the
+/// unchanged ones when the directory is converted again.
+///
+/// Stored in the output directory as a line with the hash of the options, followed by a line
+/// with the hash of the content and the path of every SVG, relative to the input.
+struct BatchCache {
+    options: u64,
+    files: std::collections::BTreeMap<PathBuf, u64>,
+}
+
+impl BatchCache {
+    const FILE_NAME: &str = ".svg2pdc-cache";
+
+    fn new(options: u64) -> Self {
+        Self {
+            options,
+            files: std::collections::BTreeMap::new(),
+        }
+    }
+
+    /// The cache of `directory`, empty if it is missing, unreadable or was written with
+    /// other options.
+    fn load(directory: &Path, options: u64) -> Self {
+        let mut cache = Self::new(options);
+        let Ok(content) = std::fs::read_to_string(directory.join(Self::FILE_NAME)) else {
+            return cache;
+        };
+        let mut lines = content.lines();
+        if lines.next() != Some(&format!("options {options:016x}")) {
+            return cache;
+        }
+        for line in lines {
+            if let Some((hash, path)) = line.split_once(' ')
+                && let Ok(hash) = u64::from_str_radix(hash, 16)
+            {
+                cache.files.insert(path.into(), hash);
+            }
+        }
+        cache
+    }
+
+    fn is_unchanged(&self, file: &Path, hash: u64) -> bool {
+        self.files.get(file) == Some(&hash)
+    }
+
+    fn insert(&mut self, file: &Path, hash: u64) {
+        self.files.insert(file.to_path_buf(), hash);
+    }
+
+    fn remove(&mut self, file: &Path) {
+        self.files.remove(file);
+    }
+
+    fn save(&self, directory: &Path) -> Result<()> {
+        let mut content = format!("options {:016x}\n", self.options);
+        for (path, hash) in &self.files {
+            content.push_str(&format!("{hash:016x} {}\n", path.display()));
+        }
+        std::fs::create_dir_all(directory)?;
+        std::fs::write(directory.join(Self::FILE_NAME), content)?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "render")]
+fn step_through_pdc(input: &Path, output: &Path, scale: f32, delay: u64) -> Result<()> {
+    let image = PebbleImage::deserialize(&mut std::fs::File::open(input)?)?;
+    let frames = steps(&image, scale)?;
+    if output
+        .extension()
+        .is_some_and(|extension| extension == "gif")
+    {
+        let file = std::fs::File::create(output)?;
+        write_gif(&frames, std::time::Duration::from_millis(delay), file)?;
+        return Ok(());
+    }
+    let stem = output.file_stem().unwrap().to_string_lossy();
+    for (index, frame) in frames.iter().enumerate() {
+        frame.save_png(output.with_file_name(format!("{stem}-{index}.png")))?;
+    }
+    Ok(())
+}
+
+/// Options of the `watch` subcommand.
+struct Watcher {
+    input: PathBuf,
+    output: Option<PathBuf>,
+    recursive: bool,
+    precise: bool,
+    truncate_color: bool,
+}
+
+impl Watcher {
+    fn run(&self) -> Result<()> {
+        let precision = if self.precise {
+            Precision::Precise
+        } else {
+            Precision::Normal
+        };
+        let converter = SvgConverter::new(precision);
+        // Editors often write a file in several steps, a file is converted once per change of
+        // its modification time
+        let mut converted = std::collections::HashMap::new();
+        for file in svg_files(&self.input, self.recursive)? {
+            self.convert(&converter, &file, &mut converted);
+        }
+
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let mut watcher = notify::recommended_watcher(sender)?;
+        let mode = if self.recursive {
+            notify::RecursiveMode::Recursive
+        } else {
+            notify::RecursiveMode::NonRecursive
+        };
+        notify::Watcher::watch(&mut watcher, &self.input, mode)?;
+        println!("Watching {} for changes", self.input.display());
+        for event in receiver {
+            let event = event?;
+            if !matches!(
+                event.kind,
+                notify::EventKind::Create(_) | notify::EventKind::Modify(_)
+            ) {
+                continue;
+            }
+            for path in &event.paths {
+                if path.is_file() && path.extension().is_some_and(|extension| extension == "svg") {
+                    self.convert(&converter, path, &mut converted);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Convert one SVG and report how long it took, errors don't stop the watch.
+    fn convert(
+        &self,
+        converter: &SvgConverter,
+        file: &Path,
+        converted: &mut std::collections::HashMap<PathBuf, std::time::SystemTime>,
+    ) {
+        let Ok(metadata) = std::fs::metadata(file) else {
+            return;
+        };
+        // A file that was just created is still empty until its content is written
+        if metadata.len() == 0 {
+            return;
+        }
+        if let Ok(modified) = metadata.modified() {
+            if converted.get(file) == Some(&modified) {
+                return;
+            }
+            converted.insert(file.to_path_buf(), modified);
+        }
+
+        let truncate_color = if self.truncate_color {
+            TruncateColor::Truncate
+        } else {
+            TruncateColor::Keep
+        };
+        let relative = file.strip_prefix(&self.input).unwrap_or(file);
+        let output = self
+            .output
+            .as_deref()
+            .unwrap_or(&self.input)
+            .join(relative)
+            .with_extension("pdc");
+        let start = std::time::Instant::now();
+        let warnings = warning_count();
+        let result = read_input(file).and_then(|content| {
+            let image =
+                converter.parse_svg_image(&content, &truncate_color, &Conversion::ConvertWarn)?;
+            if let Some(directory) = output.parent() {
+                std::fs::create_dir_all(directory)?;
+            }
+            image.serialize(&mut std::fs::File::create(&output)?)
+        });
+        let elapsed = start.elapsed().as_millis();
+        let warnings = warning_count() - warnings;
+        match result {
+            Ok(()) => println!(
+                "{}: converted in {elapsed} ms, {warnings} warnings",
+                relative.display()
+            ),
+            Err(error) => println!("{}: {error}", relative.display()),
+        }
+    }
+}
+
+/// Convert the SVGs of a Pebble project into its resources and list them in its manifest.
+#[cfg(feature = "serde")]
+fn sync_project(
+    project: &Path,
+    precise: bool,
+    truncate_color: bool,
+    settings: &ConverterSettings,
+) -> Result<()> {
+    use svg2pdc::project::{IMAGES, PebbleProject, ResourceChange, resource_name};
+
+    let mut project = PebbleProject::open(project)?;
+    let source = project.source_dir();
+    if !source.is_dir() {
+        anyhow::bail!(
+            "{} doesn't exist, put the SVGs there or name their directory in `svg2pdc.source` \
+             of the `pebble` settings",
+            source.display()
+        );
+    }
+    let options = FileOptions {
+        precision: if precise {
+            Precision::Precise
+        } else {
+            Precision::Normal
+        },
+        truncate_color: if truncate_color {
+            TruncateColor::Truncate
+        } else {
+            TruncateColor::Keep
+        },
+        conversion: Conversion::ConvertWarn,
+        fit: None,
+        color_map: Vec::new(),
+    };
+    let converter = settings.converter(&options);
+    let images = project.resources_dir().join(IMAGES);
+
+    let (mut added, mut failed) = (0, 0);
+    for file in svg_files(&source, true)? {
+        let relative = file.strip_prefix(&source).unwrap_or(&file);
+        let output = images.join(relative).with_extension("pdc");
+        let result = read_input(&file).and_then(|content| {
+            let image = converter.parse_svg_image(
+                &content,
+                &options.truncate_color,
+                &options.conversion,
+            )?;
+            if let Some(directory) = output.parent() {
+                std::fs::create_dir_all(directory)?;
+            }
+            image.serialize(&mut std::fs::File::create(&output)?)?;
+            // Resource files use forward slashes on every platform
+            let resource = Path::new(IMAGES).join(relative.with_extension("pdc"));
+            let resource = resource
+                .components()
+                .map(|component| component.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+            project.set_raw_resource(&resource_name(relative), &resource)
+        });
+        match result {
+            Ok(change) => {
+                let status = match change {
+                    ResourceChange::Added => "converted, added to the resources",
+                    ResourceChange::Updated => "converted, resource type changed to raw",
+                    ResourceChange::Unchanged => "converted",
+                };
+                added += usize::from(change == ResourceChange::Added);
+                println!("{}: {status}", relative.display());
+            }
+            Err(error) => {
+                failed += 1;
+                println!("{}: {error}", relative.display());
+            }
+        }
+    }
+    project.save()?;
+    println!(
+        "{added} resources added to {}, {failed} SVGs failed",
+        project.manifest.display()
+    );
+    if failed > 0 {
+        anyhow::bail!("{failed} SVGs could not be converted");
+    }
+    Ok(())
+}
+
+/// Options of the `compare-reference` subcommand.
+struct ReferenceComparison<'a> {
+    input: &'a Path,
+    reference: Option<&'a Path>,
+    script: Option<&'a Path>,
+    interpreter: &'a str,
+    precise: bool,
+}
+
+impl ReferenceComparison<'_> {
+    fn run(&self) -> Result<()> {
+        let precision = if self.precise {
+            Precision::Precise
+        } else {
+            Precision::Normal
+        };
+        let content = std::fs::read_to_string(self.input)?;
+        // The original tool always truncates colors and converts invalid points without
+        // complaining
+        let image = SvgConverter::new(precision).parse_svg_image(
+            &content,
+            &TruncateColor::Truncate,
+            &Conversion::ConvertNoWarn,
+        )?;
+        let mut actual = Vec::new();
+        image.serialize(&mut actual)?;
+
+        let expected = self.reference_output()?;
+        let Some(offset) = first_byte_difference(&expected, &actual) else {
+            println!("Identical to the reference ({} bytes)", actual.len());
+            return Ok(());
+        };
+        println!(
+            "Differs from the reference at byte {offset} (reference {} bytes, converted {} bytes)",
+            expected.len(),
+            actual.len()
+        );
+        let expected = PebbleImage::deserialize(&mut expected.as_slice())?;
+        for difference in semantic_differences(&expected, &image) {
+            println!("  {difference}");
+        }
+        Err(anyhow::anyhow!("output differs from the reference"))
+    }
+
+    fn reference_output(&self) -> Result<Vec<u8>> {
+        let stem = self.input.file_stem().unwrap();
+        if let Some(reference) = self.reference {
+            let path = reference.join(stem).with_extension("pdc");
+            return std::fs::read(&path)
+                .map_err(|error| anyhow::anyhow!("cannot read reference {path:?}: {error}"));
+        }
+
+        let script = match self.script {
+            Some(script) => script.to_path_buf(),
+            None => find_on_path("svg2pdc.py").ok_or_else(|| {
+                anyhow::anyhow!("`svg2pdc.py` not found on the PATH, pass --script or --reference")
+            })?,
+        };
+        let output = std::env::temp_dir()
+            .join(format!("{}-{}", stem.to_string_lossy(), std::process::id()))
+            .with_extension("pdc");
+        let mut command = std::process::Command::new(self.interpreter);
+        command.arg(&script).arg(self.input).arg("-o").arg(&output);
+        if self.precise {
+            command.arg("-p");
+        }
+        let status = command.status()?;
+        if !status.success() {
+            return Err(anyhow::anyhow!("{script:?} failed with {status}"));
+        }
+        let expected = std::fs::read(&output)?;
+        std::fs::remove_file(&output)?;
+        Ok(expected)
+    }
+}
+
+fn find_on_path(name: &str) -> Option<PathBuf> {
+    std::env::split_paths(&std::env::var_os("PATH")?)
+        .map(|directory| directory.join(name))
+        .find(|path| path.is_file())
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+
+    match cli.command {
+        Some(Command::Convert(args)) => convert(*args),
+        Some(Command::Inspect {
+            input,
+            verbose,
+            format,
+            precise,
+        }) => inspect_image(&input, verbose, format, precise),
+        Some(Command::Validate {
+            inputs,
+            precise,
+            strict,
+            deny_warnings,
+            max_bytes,
+        }) => validate_svgs(&inputs, precise, strict, deny_warnings, max_bytes),
+        Some(Command::Watch {
+            input,
+            output,
+            recursive,
+            precise,
+            truncate_color,
+        }) => Watcher {
+            input: input.canonicalize()?,
+            output,
+            recursive,
+            precise,
+            truncate_color,
+        }
+        .run(),
+        #[cfg(feature = "serde")]
+        Some(Command::Project {
+            command:
+                ProjectCommand::Sync {
+                    project,
+                    precise,
+                    truncate_color,
+                    converter,
+                },
+        }) => sync_project(
+            &project,
+            precise,
+            truncate_color,
+            &ConverterSettings::new(converter)?,
+        ),
+        Some(Command::Sequence {
+            inputs,
+            layers,
+            smil,
+            fps,
+            output,
+            duration,
+            timing,
+            play_count,
+            precise,
+            truncate_color,
+        }) => create_sequence(
+            inputs,
+            match (layers, smil) {
+                (true, _) => FrameSource::Layers,
+                (_, true) => FrameSource::Smil { fps },
+                _ => FrameSource::Files,
+            },
+            &output,
+            duration,
+            timing.as_deref(),
+            play_count,
+            precise,
+            truncate_color,
+        ),
+        Some(Command::Explain { code }) => explain(&code),
+        #[cfg(feature = "render")]
+        Some(Command::Annotate {
+            input,
+            output,
+            scale,
+        }) => annotate_pdc(&input, &output, scale),
+        Some(Command::Explode { input, output }) => explode_pdc(&input, output.as_deref()),
+        #[cfg(feature = "render")]
+        Some(Command::Render {
+            input,
+            output,
+            scale,
+            precise,
+            truncate_color,
+        }) => render_preview(&input, &output, scale, precise, truncate_color),
+        Some(Command::ToSvg { input, output }) => pdc_to_svg(&input, output.as_deref()),
+        Some(Command::FromJson { input, output }) => {
+            let output = output.unwrap_or_else(|| input.with_extension("pdc"));
+            json_to_pdc(&input, &output)
+        }
+        Some(Command::Diff { expected, actual }) => diff_pdc(&expected, &actual),
+        #[cfg(feature = "render")]
+        Some(Command::Steps {
+            input,
+            output,
+            scale,
+            delay,
+        }) => step_through_pdc(&input, &output, scale, delay),
+        Some(Command::Analyze { input, summary }) => analyze_svgs(&input, summary),
+        Some(Command::CompareReference {
+            input,
+            reference,
+            script,
+            interpreter,
+            precise,
+            truncate_color: _,
+        }) => ReferenceComparison {
+            input: &input,
+            reference: reference.as_deref(),
+            script: script.as_deref(),
+            interpreter: &interpreter,
+            precise,
+        }
+        .run(),
+        None => match cli.explain {
+            Some(code) => explain(&code),
+            None => convert(cli.convert),
+        },
+    }
+}
+
+fn convert(mut args: ConvertArgs) -> Result<()> {
+    let config = if args.no_config {
+        None
+    } else {
+        Config::discover(&std::env::current_dir()?)?
+    };
+    // Every option but --force may change the images, and so does every file read besides
+    // the SVGs
+    let force = std::mem::take(&mut args.force);
+    let mut options = format!("{} {args:?} {config:?}", env!("CARGO_PKG_VERSION"));
+    let mut read_files: Vec<PathBuf> = args
+        .template
+        .iter()
+        .chain(args.overlay.iter().map(|layer| &layer.path))
+        .chain(&args.font)
+        .cloned()
+        .collect();
+    #[cfg(feature = "text")]
+    read_files.extend(args.converter.text_font.clone());
+    read_files.extend(
+        config
+            .as_ref()
+            .map(|config| config.root.join(CONFIG_FILE_NAME)),
+    );
+    for file in read_files {
+        let content = std::fs::read(&file).unwrap_or_default();
+        options.push_str(&format!(" {}={:016x}", file.display(), fnv1a(&content)));
+    }
+    let options_hash = fnv1a(options.as_bytes());
+    let config = config.unwrap_or_default();
+    if args.output_dir.is_none() {
+        args.output_dir = config.output_dir.clone();
+    }
+
+    let bw_threshold = if args.target_bw {
+        let threshold = args
+            .bw_threshold
+            .unwrap_or(TruncateColor::DEFAULT_BW_THRESHOLD);
+        if !(0.0..=1.0).contains(&threshold) {
+            anyhow::bail!("invalid luminance threshold {threshold}, expected 0 to 1");
+        }
+        Some(threshold)
+    } else {
+        None
+    };
+
+    let fit = match (args.fit, args.converter.platform) {
+        (Some(Some(Size(size))), _) => Some(size),
+        (Some(None), Some(platform)) => Some(Platform::from(platform).screen_size()),
+        (Some(None), None) => anyhow::bail!("--fit without a size needs --platform"),
+        (None, _) => None,
+    };
+
+    let verbose = args.verbose;
+    // The command line wins over the config file
+    let command_line = Settings {
+        precise: flag(args.precise, args.no_precise),
+        truncate_color: flag(args.truncate_color, args.no_truncate_color),
+        convert: flag(args.convert, args.no_convert),
+        fit,
+        colors: std::mem::take(&mut args.map_color),
+    };
+    let file_options = |input: Option<&Path>| {
+        let settings = input.map_or_else(
+            || config.settings.clone(),
+            |input| config.settings_for(input),
+        );
+        FileOptions::new(command_line.clone().or(&settings), bw_threshold, verbose)
+    };
+    let options = file_options(args.input.as_deref());
+    let settings = ConverterSettings::new(args.converter)?;
+    let geometry = Geometry {
+        scale: args.scale,
+        rotate: args.rotate.map(Rotation::from),
+        flip_horizontal: args.flip_h,
+        flip_vertical: args.flip_v,
+        offset: args.offset.map(|Offset(offset)| offset),
+        inset: args.inset,
+        optimize: args.optimize,
+        strip_invisible: !args.keep_invisible,
+        collinear_tolerance: args.collinear_tolerance,
+        reverse: args.reverse_paths,
+        start_corner: args.start_corner.map(StartCorner::from),
+    };
+    let mut sink = Sink {
+        target: match args.pack {
+            Some(pack) => Target::pack(pack, args.pack_header, args.pack_module, args.dedupe)?,
+            None => Target::Files {
+                raw: args.raw,
+                format: args.output_format,
+                symbol: args.symbol,
+                written: Vec::new(),
+            },
+        },
+        stats: args
+            .stats_format
+            .map(|format| StatsLog::new(format, args.stats_file)),
+    };
+
+    let to_stdout = match &args.output {
+        Some(output) => is_stdio(output),
+        None => args.input.as_deref().is_some_and(is_stdio),
+    };
+    if to_stdout {
+        if sink
+            .stats
+            .as_ref()
+            .is_some_and(|stats| stats.file.is_none())
+        {
+            anyhow::bail!("the statistics go to stdout with the image, write them to --stats-file");
+        }
+        if args.split_layers {
+            anyhow::bail!("--split-layers writes several images, stdout takes only one");
+        }
+        if args.input.as_deref().is_some_and(Path::is_dir) {
+            anyhow::bail!("a directory is converted into a directory, not stdout");
+        }
+    }
+
+    if let Some(output_dir) = &args.output_dir {
+        std::fs::create_dir_all(output_dir)?;
+    }
+
+    if let Some(d) = &args.path_d {
+        let path = PathData {
+            d,
+            size: args.size.map(|Size(size)| size).unwrap_or_default(),
+            fill: args.fill,
+            stroke: args.stroke,
+            stroke_width: args.stroke_width,
+        };
+        let converter = settings.converter(&options);
+        let mut image = converter.parse_svg_fragment(
+            &path.fragment(),
+            &FragmentOptions {
+                size: path.size,
+                translate: FPoint::default(),
+                truncate_color: options.truncate_color,
+                conversion: options.conversion,
+            },
+        )?;
+        geometry.apply(&mut image);
+        if verbose {
+            image.inspect();
+        }
+        let output = args
+            .output
+            .unwrap_or_else(|| default_output(args.output_dir.as_deref(), "path.pdc".into()));
+        sink.write("--path-d", &image, &output)?;
+        sink.finish()?;
+        return Ok(());
+    }
+
+    if let (Some(glyph), Some(font)) = (args.glyph, &args.font) {
+        let size = args
+            .size
+            .map(|Size(size)| size)
+            .unwrap_or(PebblePoint { x: 32, y: 32 });
+        let white = Color {
+            r: 255,
+            g: 255,
+            b: 255,
+            a: 255,
+        };
+        let fragment = glyph_fragment(
+            &std::fs::read(font)?,
+            glyph,
+            size,
+            args.fill.unwrap_or(white),
+        )?;
+        let converter = settings.converter(&options);
+        let mut image = converter.parse_svg_fragment(
+            &fragment,
+            &FragmentOptions {
+                size,
+                translate: FPoint::default(),
+                truncate_color: options.truncate_color,
+                // Flattened curves never land exactly on the coordinate grid
+                conversion: Conversion::ConvertNoWarn,
+            },
+        )?;
+        geometry.apply(&mut image);
+        if verbose {
+            image.inspect();
+        }
+        let output = args.output.unwrap_or_else(|| {
+            default_output(
+                args.output_dir.as_deref(),
+                format!("U+{:04X}.pdc", glyph as u32).into(),
+            )
+        });
+        sink.write(
+            format_args!("{} U+{:04X}", font.display(), glyph as u32),
+            &image,
+            &output,
+        )?;
+        sink.finish()?;
+        return Ok(());
+    }
+
+    let Some(input) = args.input else {
+        let swatch_sheet = args
+            .swatch_sheet
+            .expect("input is required without --swatch-sheet");
+        let palette: Vec<_> = PebbleColor::palette().collect();
+        std::fs::write(swatch_sheet, swatch_sheet_svg(&palette))?;
+        return Ok(());
+    };
+    let image_options = ImageOptions {
+        page: args.page.as_deref(),
+        split_layers: args.split_layers,
+        select: args
+            .select
+            .as_deref()
+            .map(|id| (id, args.size.map(|Size(size)| size))),
+        overlays: &args.overlay,
+        template: args.template.as_deref(),
+        template_above: args.template_above,
+        swatch_sheet: args.swatch_sheet.as_deref(),
+        emit: &args.emit,
+        strip_background: args.strip_background,
+        geometry,
+        verbose,
+    };
+    let convert_file = |input: &Path, output: &Path, options: &FileOptions, sink: &mut Sink| {
+        create_pdc_from_path(input, output, options, &settings, &image_options, sink)
+    };
+
+    if input.is_dir() {
+        let output = args
+            .output
+            .or(args.output_dir)
+            .unwrap_or_else(|| input.clone());
+        let files = svg_files(&input, args.recursive)?;
+        // A pack is written as a whole, so all of its images are converted
+        let mut cache = match sink.target {
+            Target::Files { .. } if !force => BatchCache::load(&output, options_hash),
+            _ => BatchCache::new(options_hash),
+        };
+        let (mut failed, mut unchanged) = (0, 0);
+        for file in &files {
+            // The output mirrors the directories below the input
+            let relative = file.strip_prefix(&input).unwrap_or(file);
+            let file_output = output.join(relative).with_extension("pdc");
+            let content = match std::fs::read(file) {
+                Ok(content) => content,
+                Err(error) => {
+                    eprintln!("Failed to convert {}: {error}", file.display());
+                    failed += 1;
+                    continue;
+                }
+            };
+            let hash = fnv1a(&content);
+            if cache.is_unchanged(relative, hash) && sink.target.output_path(&file_output).exists()
+            {
+                unchanged += 1;
+                continue;
+            }
+            let result = file_output
+                .parent()
+                .map_or(Ok(()), std::fs::create_dir_all)
+                .map_err(Svg2PdcError::from)
+                .and_then(|()| {
+                    convert_file(file, &file_output, &file_options(Some(file)), &mut sink)
+                });
+            match result {
+                Ok(()) => cache.insert(relative, hash),
+                Err(error) => {
+                    eprintln!("Failed to convert {}: {error}", file.display());
+                    cache.remove(relative);
+                    failed += 1;
+                }
+            }
+        }
+        if matches!(sink.target, Target::Files { .. }) {
+            cache.save(&output)?;
+        }
+        sink.finish()?;
+        eprintln!(
+            "Converted {} of {} files, {unchanged} unchanged, {failed} failed",
+            files.len() - failed - unchanged,
+            files.len()
+        );
+        if failed > 0 {
+            anyhow::bail!("{failed} of {} files failed to convert", files.len());
+        }
+        return Ok(());
+    }
+
+    let output = match args.output {
+        Some(output) => output,
+        None if is_stdio(&input) => input.clone(),
+        None => default_output(args.output_dir.as_deref(), input.with_extension("pdc")),
+    };
+    convert_file(&input, &output, &options, &mut sink)?;
+    sink.finish()?;
 
     Ok(())
 }
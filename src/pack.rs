@@ -0,0 +1,298 @@
+//! Packs bundling many PDC images under string keys.
+//!
+//! A pack is a single resource holding e.g. every weather icon of a watchface, so the app
+//! only needs one resource id and looks images up through the generated index.
+//!
+//! ```text
+//! "PDCP"                magic
+//! u8                    version (1)
+//! u8                    reserved
+//! u16                   number of entries
+//! per entry:
+//!   u16                 key length in bytes
+//!   [u8]                key, UTF-8
+//!   u32                 offset of the image from the start of the pack
+//!   u32                 length of the image
+//! [u8]                  the images, each a complete `PDCI` file
+//! ```
+//!
+//! All integers are little endian, like in PDC images.
+
+use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
+use std::{
+    collections::HashMap,
+    io::{Read, Write},
+};
+
+use crate::{
+    error::{Svg2PdcError, Svg2PdcResult},
+    image::PebbleImage,
+};
+
+#[derive(Debug, Clone, Default)]
+pub struct PdcPack {
+    /// The images in the pack, in index order.
+    pub entries: Vec<(String, PebbleImage)>,
+    /// Store byte-identical images once, with every key of the group pointing at it.
+    pub deduplicate: bool,
+}
+
+/// Location of an image inside a serialized pack.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackIndexEntry {
+    pub key: String,
+    pub offset: u32,
+    pub length: u32,
+}
+
+impl PdcPack {
+    const PACK_MAGIC: &[u8; 4] = b"PDCP";
+    const PACK_VERSION: u8 = 1;
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an image, replacing any image already stored under `key`.
+    pub fn insert(&mut self, key: impl Into<String>, image: PebbleImage) {
+        let key = key.into();
+        match self
+            .entries
+            .iter_mut()
+            .find(|(existing, _)| *existing == key)
+        {
+            Some((_, existing)) => *existing = image,
+            None => self.entries.push((key, image)),
+        }
+    }
+
+    pub fn get(&self, key: &str) -> Option<&PebbleImage> {
+        self.entries
+            .iter()
+            .find(|(existing, _)| existing == key)
+            .map(|(_, image)| image)
+    }
+
+    /// Serialize the images and compute where each of them ends up in the pack.
+    fn layout(&self) -> Svg2PdcResult<(Vec<PackIndexEntry>, Vec<u8>)> {
+        let header_size = 8 + self
+            .entries
+            .iter()
+            .map(|(key, _)| 2 + key.len() + 8)
+            .sum::<usize>();
+
+        let mut data = Vec::new();
+        let mut index = Vec::with_capacity(self.entries.len());
+        let mut stored: HashMap<Vec<u8>, (u32, u32)> = HashMap::new();
+        for (key, image) in &self.entries {
+            let mut bytes = Vec::new();
+            image.serialize(&mut bytes)?;
+            let location = match stored.get(&bytes) {
+                Some(&location) if self.deduplicate => location,
+                _ => {
+                    let location = ((header_size + data.len()) as u32, bytes.len() as u32);
+                    data.extend_from_slice(&bytes);
+                    stored.insert(bytes, location);
+                    location
+                }
+            };
+            index.push(PackIndexEntry {
+                key: key.clone(),
+                offset: location.0,
+                length: location.1,
+            });
+        }
+        Ok((index, data))
+    }
+
+    /// Where each image is stored in the serialized pack.
+    pub fn index(&self) -> Svg2PdcResult<Vec<PackIndexEntry>> {
+        Ok(self.layout()?.0)
+    }
+
+    pub fn serialize<W: Write>(&self, writer: &mut W) -> Svg2PdcResult<()> {
+        let (index, data) = self.layout()?;
+
+        writer.write_all(Self::PACK_MAGIC)?;
+        writer.write_u8(Self::PACK_VERSION)?;
+        writer.write_u8(0)?; // reserved byte
+        writer.write_u16::<LittleEndian>(index.len() as u16)?;
+        for entry in &index {
+            writer.write_u16::<LittleEndian>(entry.key.len() as u16)?;
+            writer.write_all(entry.key.as_bytes())?;
+            writer.write_u32::<LittleEndian>(entry.offset)?;
+            writer.write_u32::<LittleEndian>(entry.length)?;
+        }
+        writer.write_all(&data)?;
+        Ok(())
+    }
+
+    pub fn deserialize<R: Read>(reader: &mut R) -> Svg2PdcResult<Self> {
+        let mut content = Vec::new();
+        reader.read_to_end(&mut content)?;
+        let mut header = content.as_slice();
+
+        let mut magic = [0; 4];
+        header.read_exact(&mut magic)?;
+        if &magic != Self::PACK_MAGIC {
+            return Err(Svg2PdcError::InvalidPdc(format!(
+                "expected pack magic {:?}, found {:?}",
+                Self::PACK_MAGIC,
+                magic
+            )));
+        }
+        let version = header.read_u8()?;
+        if version != Self::PACK_VERSION {
+            return Err(Svg2PdcError::InvalidPdc(format!(
+                "unsupported pack version {version}"
+            )));
+        }
+        header.read_u8()?; // reserved byte
+        let count = header.read_u16::<LittleEndian>()?;
+
+        let mut entries = Vec::with_capacity(count as usize);
+        for _ in 0..count {
+            let mut key = vec![0; header.read_u16::<LittleEndian>()? as usize];
+            header.read_exact(&mut key)?;
+            let key = String::from_utf8(key)
+                .map_err(|_| Svg2PdcError::InvalidPdc("pack key is not UTF-8".to_string()))?;
+            let offset = header.read_u32::<LittleEndian>()? as usize;
+            let length = header.read_u32::<LittleEndian>()? as usize;
+            let mut image = content.get(offset..offset + length).ok_or_else(|| {
+                Svg2PdcError::InvalidPdc(format!("image `{key}` lies outside of the pack"))
+            })?;
+            entries.push((key, PebbleImage::deserialize(&mut image)?));
+        }
+        Ok(Self {
+            entries,
+            deduplicate: false,
+        })
+    }
+
+    /// Groups of keys whose images serialize to the same bytes, see [`find_duplicates`].
+    pub fn duplicates(&self) -> Svg2PdcResult<Vec<Vec<String>>> {
+        find_duplicates(
+            self.entries
+                .iter()
+                .map(|(key, image)| (key.as_str(), image)),
+        )
+    }
+
+    /// C source declaring an enum of the keys and a table with the location of each image,
+    /// for use with `resource_load_byte_range()`.
+    ///
+    /// ```
+    /// use svg2pdc::{pack::PdcPack, point::PebblePoint, prelude::*};
+    ///
+    /// let mut pack = PdcPack::new();
+    /// let image = PebbleImage { size: PebblePoint { x: 10, y: 10 }, commands: vec![] };
+    /// pack.insert("light-rain", image);
+    ///
+    /// let header = pack.c_header("weather").unwrap();
+    /// assert!(header.contains("WEATHER_LIGHT_RAIN = 0,"));
+    /// assert!(header.contains("{ \"light-rain\", 28, 16 },"));
+    /// ```
+    pub fn c_header(&self, name: &str) -> Svg2PdcResult<String> {
+        let prefix = identifier(name).to_uppercase();
+        let lower = prefix.to_lowercase();
+        let index = self.index()?;
+
+        let mut header = String::new();
+        header.push_str("#pragma once\n\n#include <stdint.h>\n\n");
+        header.push_str("typedef enum {\n");
+        for (position, entry) in index.iter().enumerate() {
+            header.push_str(&format!(
+                "  {prefix}_{} = {position},\n",
+                identifier(&entry.key).to_uppercase()
+            ));
+        }
+        header.push_str(&format!("  {prefix}_COUNT = {},\n", index.len()));
+        header.push_str(&format!("}} {lower}_key_t;\n\n"));
+        header.push_str(&format!(
+            "static const struct {{\n  const char *key;\n  uint32_t offset;\n  uint32_t length;\n}} {lower}_index[] = {{\n"
+        ));
+        for entry in &index {
+            header.push_str(&format!(
+                "  {{ \"{}\", {}, {} }},\n",
+                entry.key.escape_default(),
+                entry.offset,
+                entry.length
+            ));
+        }
+        header.push_str("};\n");
+        Ok(header)
+    }
+
+    /// Rust source with a constant table of the location of each image.
+    ///
+    /// ```
+    /// use svg2pdc::{pack::PdcPack, point::PebblePoint, prelude::*};
+    ///
+    /// let mut pack = PdcPack::new();
+    /// pack.insert("sunny", PebbleImage { size: PebblePoint { x: 10, y: 10 }, commands: vec![] });
+    ///
+    /// let module = pack.rust_module("weather").unwrap();
+    /// assert!(module.contains("pub const WEATHER: &[(&str, u32, u32)] = &["));
+    /// assert!(module.contains("(\"sunny\", 23, 16),"));
+    /// ```
+    pub fn rust_module(&self, name: &str) -> Svg2PdcResult<String> {
+        let mut module = format!(
+            "/// Key, offset and length of every image in the pack.\npub const {}: &[(&str, u32, u32)] = &[\n",
+            identifier(name).to_uppercase()
+        );
+        for entry in self.index()? {
+            module.push_str(&format!(
+                "    ({:?}, {}, {}),\n",
+                entry.key, entry.offset, entry.length
+            ));
+        }
+        module.push_str("];\n");
+        Ok(module)
+    }
+}
+
+/// Find named images that serialize to the same bytes, e.g. the same artwork exported under
+/// different names.
+///
+/// Every returned group holds at least two names, in the order they were given.
+///
+/// ```
+/// use svg2pdc::{pack::find_duplicates, point::PebblePoint, prelude::*};
+///
+/// let empty = PebbleImage { size: PebblePoint { x: 10, y: 10 }, commands: vec![] };
+/// let larger = PebbleImage { size: PebblePoint { x: 20, y: 20 }, commands: vec![] };
+/// let duplicates = find_duplicates([("a", &empty), ("b", &larger), ("c", &empty)]).unwrap();
+/// assert_eq!(duplicates, vec![vec!["a".to_string(), "c".to_string()]]);
+/// ```
+pub fn find_duplicates<'a>(
+    images: impl IntoIterator<Item = (&'a str, &'a PebbleImage)>,
+) -> Svg2PdcResult<Vec<Vec<String>>> {
+    let mut groups: Vec<Vec<String>> = Vec::new();
+    let mut group_of: HashMap<Vec<u8>, usize> = HashMap::new();
+    for (name, image) in images {
+        let mut bytes = Vec::new();
+        image.serialize(&mut bytes)?;
+        match group_of.get(&bytes) {
+            Some(&group) => groups[group].push(name.to_string()),
+            None => {
+                group_of.insert(bytes, groups.len());
+                groups.push(vec![name.to_string()]);
+            }
+        }
+    }
+    groups.retain(|group| group.len() > 1);
+    Ok(groups)
+}
+
+/// Turn a key into a C and Rust identifier by replacing everything but letters and digits.
+pub(crate) fn identifier(key: &str) -> String {
+    let identifier: String = key
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    if identifier.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("_{identifier}")
+    } else {
+        identifier
+    }
+}
@@ -0,0 +1,261 @@
+//! Paint values of SVG attributes and the quantized preview export.
+//!
+//! Pebble images only have flat colors, so gradients and patterns referenced with `url(#id)`
+//! are approximated by a single color. The same approximation is used by the converter and
+//! the quantized SVG preview, so the preview shows what the watch will draw.
+
+use roxmltree::{Document, Node};
+
+use crate::{
+    color::{Color, ColorAdjustment, PebbleColor, TruncateColor},
+    error::Svg2PdcResult,
+};
+
+/// A resolved `fill` or `stroke` value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Paint {
+    /// `none`, or a value without a color: nothing is drawn, unlike a black paint.
+    None,
+    Color(Color),
+}
+
+impl Paint {
+    /// Quantize the paint, [`Paint::None`] becomes [`PebbleColor::nothing`] whatever the
+    /// opacity and adjustment.
+    pub fn quantize(
+        self,
+        opacity: u8,
+        adjustment: &ColorAdjustment,
+        truncate_color: &TruncateColor,
+    ) -> PebbleColor {
+        match self {
+            Self::None => PebbleColor::nothing(),
+            Self::Color(color) => quantize(
+                color.with_opacity(opacity).adjusted(adjustment),
+                truncate_color,
+            ),
+        }
+    }
+}
+
+/// Resolve an SVG paint value, `none` and values without a color are [`Paint::None`].
+///
+/// `url(#id)` references to gradients are approximated by the average of their stops,
+/// references to patterns by the average fill of the shapes inside them.
+pub(crate) fn resolve_paint(document: &Document<'_>, paint: &str) -> Paint {
+    match resolve_color(document, paint) {
+        Some(color) => Paint::Color(color),
+        None => Paint::None,
+    }
+}
+
+/// Whether a paint value is `none`, a color or a `url()` reference, the values
+/// [`resolve_paint`] understands even when they draw nothing.
+pub(crate) fn is_paint(paint: &str) -> bool {
+    let paint = paint.trim();
+    paint.eq_ignore_ascii_case("none") || paint.starts_with("url(") || Color::parse(paint).is_ok()
+}
+
+fn resolve_color(document: &Document<'_>, paint: &str) -> Option<Color> {
+    let Some(id) = paint
+        .trim()
+        .strip_prefix("url(")
+        .and_then(|reference| reference.split(')').next())
+    else {
+        return Color::parse(paint).ok();
+    };
+    let server = find_by_id(document, id.trim().trim_matches(['\'', '"']))?;
+    match server.tag_name().name() {
+        "linearGradient" | "radialGradient" => gradient_color(document, server),
+        "pattern" => average(
+            server
+                .descendants()
+                .filter_map(|node| property(node, "fill"))
+                .filter_map(|fill| resolve_color(document, fill)),
+        ),
+        _ => None,
+    }
+}
+
+/// Quantize a color to the Pebble palette the way the converter does.
+pub fn quantize(color: Color, truncate_color: &TruncateColor) -> PebbleColor {
+    match truncate_color {
+        TruncateColor::Truncate => PebbleColor::from_color_with_truncate(color),
+        TruncateColor::Keep => PebbleColor::from_color_with_convert(color),
+        TruncateColor::BlackAndWhite { threshold } => {
+            PebbleColor::from_color_black_and_white(color, *threshold)
+        }
+    }
+}
+
+/// Rewrite an SVG document with every fill and stroke color replaced by the color the watch
+/// will display.
+///
+/// Gradients and patterns are replaced by their flat approximation, and fills that Pebble
+/// treats as transparent become `none`. Everything else in the document is kept verbatim.
+/// Colors are adjusted before quantization, like [`crate::svg_converter::SvgConverter`] does.
+///
+/// ```
+/// use svg2pdc::{color::{ColorAdjustment, TruncateColor}, paint::quantized_svg};
+///
+/// let svg = r##"<svg xmlns="http://www.w3.org/2000/svg"><rect fill="#ee1111" style="stroke:#3344ee"/></svg>"##;
+/// let quantized = quantized_svg(svg, &TruncateColor::Keep, &ColorAdjustment::default()).unwrap();
+/// assert!(quantized.contains(r##"fill="#FF0000""##));
+/// assert!(quantized.contains("stroke:#5555FF"));
+/// ```
+pub fn quantized_svg(
+    content: &str,
+    truncate_color: &TruncateColor,
+    adjustment: &ColorAdjustment,
+) -> Svg2PdcResult<String> {
+    let document = Document::parse(content)?;
+
+    let mut replacements = Vec::new();
+    for node in document.descendants().filter(Node::is_element) {
+        for attribute in node.attributes() {
+            let value = match attribute.name() {
+                "fill" | "stroke" => quantized_paint(
+                    &document,
+                    attribute.name(),
+                    attribute.value(),
+                    truncate_color,
+                    adjustment,
+                ),
+                "style" => attribute
+                    .value()
+                    .split(';')
+                    .map(|declaration| match declaration.split_once(':') {
+                        Some((key, value)) if matches!(key.trim(), "fill" | "stroke") => format!(
+                            "{}:{}",
+                            key,
+                            quantized_paint(
+                                &document,
+                                key.trim(),
+                                value,
+                                truncate_color,
+                                adjustment
+                            )
+                        ),
+                        _ => declaration.to_string(),
+                    })
+                    .collect::<Vec<_>>()
+                    .join(";"),
+                _ => continue,
+            };
+            replacements.push((attribute.range_value(), value));
+        }
+    }
+
+    let mut quantized = content.to_string();
+    // Replace from the end so the earlier ranges stay valid
+    replacements.sort_by_key(|(range, _)| std::cmp::Reverse(range.start));
+    for (range, value) in replacements {
+        quantized.replace_range(range, &value);
+    }
+    Ok(quantized)
+}
+
+fn quantized_paint(
+    document: &Document<'_>,
+    property: &str,
+    paint: &str,
+    truncate_color: &TruncateColor,
+    adjustment: &ColorAdjustment,
+) -> String {
+    let paint = resolve_paint(document, paint);
+    let color = paint.quantize(255, adjustment, truncate_color);
+    // This is a pebble caveat, a black fill is treated as transparent
+    if paint == Paint::None || property == "fill" && color.is_black() {
+        "none".to_string()
+    } else {
+        color.rgb_hex()
+    }
+}
+
+fn find_by_id<'a, 'input>(document: &'a Document<'input>, id: &str) -> Option<Node<'a, 'input>> {
+    let id = id.strip_prefix('#')?;
+    document.descendants().find(|node| {
+        node.attribute("id")
+            .is_some_and(|node_id| node_id.eq_ignore_ascii_case(id))
+    })
+}
+
+/// Average color of the stops of a gradient, following `href` to inherited stops.
+fn gradient_color(document: &Document<'_>, gradient: Node<'_, '_>) -> Option<Color> {
+    let stops: Vec<_> = gradient
+        .children()
+        .filter(|node| node.has_tag_name((gradient.tag_name().namespace().unwrap_or(""), "stop")))
+        .collect();
+    if stops.is_empty() {
+        let href = gradient
+            .attribute(("http://www.w3.org/1999/xlink", "href"))
+            .or(gradient.attribute("href"))?;
+        let inherited = find_by_id(document, href)?;
+        // Don't follow references that loop back to this gradient
+        if inherited == gradient {
+            return None;
+        }
+        return gradient_color(document, inherited);
+    }
+
+    average(stops.into_iter().filter_map(|stop| {
+        let color = Color::parse(property(stop, "stop-color")?).ok()?;
+        let opacity = property(stop, "stop-opacity")
+            .and_then(|opacity| opacity.trim().parse::<f32>().ok())
+            .unwrap_or(1.0);
+        Some(color.with_opacity((opacity.clamp(0.0, 1.0) * 255.0) as u8))
+    }))
+}
+
+/// Value of a presentation property, set either as an attribute or in the `style` attribute.
+pub(crate) fn property<'a>(node: Node<'a, '_>, name: &str) -> Option<&'a str> {
+    node.attribute("style")
+        .and_then(|style| {
+            style.split(';').find_map(|declaration| {
+                let (key, value) = declaration.split_once(':')?;
+                key.trim()
+                    .eq_ignore_ascii_case(name)
+                    .then_some(value.trim())
+            })
+        })
+        .or(node.attribute(name))
+}
+
+fn average(colors: impl Iterator<Item = Color>) -> Option<Color> {
+    let (count, [r, g, b, a]) = colors.fold((0_u32, [0_u32; 4]), |(count, sum), color| {
+        (
+            count + 1,
+            [
+                sum[0] + color.r as u32,
+                sum[1] + color.g as u32,
+                sum[2] + color.b as u32,
+                sum[3] + color.a as u32,
+            ],
+        )
+    });
+    (count > 0).then(|| Color {
+        r: (r / count) as u8,
+        g: (g / count) as u8,
+        b: (b / count) as u8,
+        a: (a / count) as u8,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gradient_is_replaced_by_average_of_stops() {
+        let svg = r##"<svg xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink">
+            <linearGradient id="Base"><stop offset="0" stop-color="#ff0000"/><stop offset="1" style="stop-color:#0000ff"/></linearGradient>
+            <linearGradient id="Derived" xlink:href="#Base"/>
+            <rect fill="url(#Derived)" stroke="url('#Base')"/>
+        </svg>"##;
+
+        let quantized =
+            quantized_svg(svg, &TruncateColor::Keep, &ColorAdjustment::default()).unwrap();
+        assert!(quantized.contains(r##"fill="#550055""##));
+        assert!(quantized.contains(r##"stroke="#550055""##));
+    }
+}
@@ -0,0 +1,218 @@
+//! Import of single-page vector PDFs.
+//!
+//! The path painting operations of the first page are translated into an SVG document, which
+//! then goes through the regular conversion, so a PDF behaves exactly like the same artwork
+//! exported to SVG. Text, images and shadings are skipped.
+
+use lopdf::{Document, Object, content::Content};
+
+use crate::error::{Svg2PdcError, Svg2PdcResult};
+
+/// Translate the first page of a PDF into an SVG document of the size of its media box.
+pub fn pdf_to_svg(data: &[u8]) -> Svg2PdcResult<String> {
+    let pdf_error = |error: lopdf::Error| Svg2PdcError::InvalidPdf(error.to_string());
+    let document = Document::load_mem(data).map_err(pdf_error)?;
+    let (_, &page) = document
+        .get_pages()
+        .first_key_value()
+        .ok_or_else(|| Svg2PdcError::InvalidPdf("the document has no pages".to_string()))?;
+    let media_box = media_box(&document, page)?;
+    let content =
+        Content::decode(&document.get_page_content(page).map_err(pdf_error)?).map_err(pdf_error)?;
+
+    let mut painter = Painter {
+        media_box,
+        state: GraphicsState::default(),
+        saved: Vec::new(),
+        path: String::new(),
+        current: (0.0, 0.0),
+        elements: String::new(),
+    };
+    for operation in &content.operations {
+        let operands: Vec<f32> = operation
+            .operands
+            .iter()
+            .filter_map(|operand| operand.as_float().ok())
+            .collect();
+        painter.apply(&operation.operator, &operands);
+    }
+
+    let [x0, y0, x1, y1] = media_box;
+    let (width, height) = (x1 - x0, y1 - y0);
+    Ok(format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}">{}</svg>"#,
+        painter.elements
+    ))
+}
+
+/// The media box of a page, which may be inherited from the page tree.
+fn media_box(document: &Document, page: lopdf::ObjectId) -> Svg2PdcResult<[f32; 4]> {
+    let mut node = document
+        .get_dictionary(page)
+        .map_err(|error| Svg2PdcError::InvalidPdf(error.to_string()))?;
+    loop {
+        if let Ok(Object::Array(values)) = node.get_deref(b"MediaBox", document)
+            && let [x0, y0, x1, y1] = values.as_slice()
+            && let (Ok(x0), Ok(y0), Ok(x1), Ok(y1)) =
+                (x0.as_float(), y0.as_float(), x1.as_float(), y1.as_float())
+        {
+            return Ok([x0.min(x1), y0.min(y1), x0.max(x1), y0.max(y1)]);
+        }
+        node = node
+            .get_deref(b"Parent", document)
+            .and_then(Object::as_dict)
+            .map_err(|_| Svg2PdcError::InvalidPdf("the page has no media box".to_string()))?;
+    }
+}
+
+#[derive(Debug, Clone)]
+struct GraphicsState {
+    /// Current transformation matrix `[a b c d e f]`.
+    ctm: [f32; 6],
+    fill: [u8; 3],
+    stroke: [u8; 3],
+    line_width: f32,
+}
+
+impl Default for GraphicsState {
+    fn default() -> Self {
+        Self {
+            ctm: [1.0, 0.0, 0.0, 1.0, 0.0, 0.0],
+            fill: [0, 0, 0],
+            stroke: [0, 0, 0],
+            line_width: 1.0,
+        }
+    }
+}
+
+struct Painter {
+    media_box: [f32; 4],
+    state: GraphicsState,
+    saved: Vec<GraphicsState>,
+    /// Path data of the path under construction, already in SVG coordinates.
+    path: String,
+    /// Current point in user space, needed by the `v` operator.
+    current: (f32, f32),
+    elements: String,
+}
+
+impl Painter {
+    fn apply(&mut self, operator: &str, operands: &[f32]) {
+        match (operator, operands) {
+            ("q", _) => self.saved.push(self.state.clone()),
+            ("Q", _) => self.state = self.saved.pop().unwrap_or_default(),
+            ("cm", &[a, b, c, d, e, f]) => {
+                let [a2, b2, c2, d2, e2, f2] = self.state.ctm;
+                self.state.ctm = [
+                    a * a2 + b * c2,
+                    a * b2 + b * d2,
+                    c * a2 + d * c2,
+                    c * b2 + d * d2,
+                    e * a2 + f * c2 + e2,
+                    e * b2 + f * d2 + f2,
+                ];
+            }
+            ("w", &[width]) => self.state.line_width = width,
+
+            ("g" | "sc" | "scn", &[gray]) => self.state.fill = gray_color(gray),
+            ("G" | "SC" | "SCN", &[gray]) => self.state.stroke = gray_color(gray),
+            ("rg" | "sc" | "scn", &[r, g, b]) => self.state.fill = rgb_color(r, g, b),
+            ("RG" | "SC" | "SCN", &[r, g, b]) => self.state.stroke = rgb_color(r, g, b),
+            ("k" | "sc" | "scn", &[c, m, y, k]) => self.state.fill = cmyk_color(c, m, y, k),
+            ("K" | "SC" | "SCN", &[c, m, y, k]) => self.state.stroke = cmyk_color(c, m, y, k),
+
+            ("m", &[x, y]) => self.segment('M', &[(x, y)]),
+            ("l", &[x, y]) => self.segment('L', &[(x, y)]),
+            ("c", &[x1, y1, x2, y2, x3, y3]) => self.segment('C', &[(x1, y1), (x2, y2), (x3, y3)]),
+            ("v", &[x2, y2, x3, y3]) => self.segment('C', &[self.current, (x2, y2), (x3, y3)]),
+            ("y", &[x1, y1, x3, y3]) => self.segment('C', &[(x1, y1), (x3, y3), (x3, y3)]),
+            ("h", _) => self.path.push('Z'),
+            ("re", &[x, y, width, height]) => {
+                self.segment('M', &[(x, y)]);
+                self.segment('L', &[(x + width, y)]);
+                self.segment('L', &[(x + width, y + height)]);
+                self.segment('L', &[(x, y + height)]);
+                self.path.push('Z');
+            }
+
+            ("S", _) => self.paint(false, true),
+            ("s", _) => {
+                self.path.push('Z');
+                self.paint(false, true);
+            }
+            ("f" | "F" | "f*", _) => self.paint(true, false),
+            ("B" | "B*", _) => self.paint(true, true),
+            ("b" | "b*", _) => {
+                self.path.push('Z');
+                self.paint(true, true);
+            }
+            ("n", _) => self.path.clear(),
+            _ => {}
+        }
+    }
+
+    /// Append a path segment, transforming its points to SVG coordinates.
+    fn segment(&mut self, command: char, points: &[(f32, f32)]) {
+        self.path.push(command);
+        let transformed: Vec<_> = points
+            .iter()
+            .map(|&point| {
+                let (x, y) = self.transform(point);
+                format!("{x} {y}")
+            })
+            .collect();
+        self.path.push_str(&transformed.join(" "));
+        if let Some(&last) = points.last() {
+            self.current = last;
+        }
+    }
+
+    fn transform(&self, (x, y): (f32, f32)) -> (f32, f32) {
+        let [a, b, c, d, e, f] = self.state.ctm;
+        let [x0, _, _, y1] = self.media_box;
+        (a * x + c * y + e - x0, y1 - (b * x + d * y + f))
+    }
+
+    fn paint(&mut self, fill: bool, stroke: bool) {
+        let path = std::mem::take(&mut self.path);
+        if path.is_empty() {
+            return;
+        }
+        let fill = if fill {
+            hex(self.state.fill)
+        } else {
+            "none".to_string()
+        };
+        // Strokes are turned off through their width, a missing stroke would be black
+        let [a, b, c, d, ..] = self.state.ctm;
+        let stroke_width = if stroke {
+            self.state.line_width * (a * d - b * c).abs().sqrt()
+        } else {
+            0.0
+        };
+        self.elements.push_str(&format!(
+            r#"<path d="{path}" fill="{fill}" stroke="{}" stroke-width="{stroke_width}"/>"#,
+            hex(self.state.stroke)
+        ));
+    }
+}
+
+fn gray_color(gray: f32) -> [u8; 3] {
+    rgb_color(gray, gray, gray)
+}
+
+fn rgb_color(r: f32, g: f32, b: f32) -> [u8; 3] {
+    [r, g, b].map(|channel| (channel.clamp(0.0, 1.0) * 255.0).round() as u8)
+}
+
+fn cmyk_color(c: f32, m: f32, y: f32, k: f32) -> [u8; 3] {
+    rgb_color(
+        (1.0 - c) * (1.0 - k),
+        (1.0 - m) * (1.0 - k),
+        (1.0 - y) * (1.0 - k),
+    )
+}
+
+fn hex([r, g, b]: [u8; 3]) -> String {
+    format!("#{r:02x}{g:02x}{b:02x}")
+}
@@ -0,0 +1,33 @@
+//! Pebble hardware platforms and their displays.
+
+use crate::point::PebblePoint;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Platform {
+    /// Pebble and Pebble Steel, black and white.
+    Aplite,
+    /// Pebble Time and Pebble Time Steel.
+    Basalt,
+    /// Pebble Time Round.
+    Chalk,
+    /// Pebble 2, black and white.
+    Diorite,
+    /// Pebble Time 2.
+    Emery,
+}
+
+impl Platform {
+    /// Size of the display in pixels.
+    pub const fn screen_size(&self) -> PebblePoint {
+        match self {
+            Self::Aplite | Self::Basalt | Self::Diorite => PebblePoint { x: 144, y: 168 },
+            Self::Chalk => PebblePoint { x: 180, y: 180 },
+            Self::Emery => PebblePoint { x: 200, y: 228 },
+        }
+    }
+
+    /// Whether the display is round, only the circle inscribed in the screen size is visible.
+    pub const fn is_round(&self) -> bool {
+        matches!(self, Self::Chalk)
+    }
+}
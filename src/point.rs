@@ -1,15 +1,38 @@
 use std::ops::{Add, Div, Mul, Sub};
 
-use crate::error::{Svg2PdcError, Svg2PdcResult};
+use crate::{
+    diagnostic::DiagnosticCode,
+    error::{Svg2PdcError, Svg2PdcResult},
+};
 
-#[derive(Debug, Clone, Copy, Default)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
 pub enum Precision {
     #[default]
     Normal,
     Precise,
 }
 
+impl Precision {
+    /// Number of Pebble coordinate units per pixel.
+    pub const fn coordinate_scale(&self) -> f32 {
+        match self {
+            Precision::Normal => 1.0,
+            Precision::Precise => 8.0,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
 pub enum Conversion {
     ConvertNoWarn,
     ConvertWarn,
@@ -17,7 +40,121 @@ pub enum Conversion {
     RequireExact,
 }
 
+/// How closely the generated coordinates follow the original Python `svg2pdc` tool.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum OutputMode {
+    /// Reproduce the output of the original tool byte for byte.
+    ///
+    /// Points are used as they are, not floored or moved to the nearest valid point like in
+    /// [`Self::Floored`], which the reference output of the original tool for fractional path
+    /// coordinates rules out. They are nudged by `f32::EPSILON` before rounding and default to
+    /// the [`PixelConvention::PixelCorner`] half pixel shift.
+    #[default]
+    PythonCompatible,
+    /// Round points to the nearest Pebble coordinate without the compatibility quirks,
+    /// defaulting to [`PixelConvention::PixelCenter`].
+    Improved,
+    /// The output of earlier versions of this crate: like [`Self::PythonCompatible`], but the
+    /// points of paths are floored and invalid points are moved to the nearest valid one
+    /// before they are rounded.
+    Floored,
+}
+
+impl OutputMode {
+    /// The pixel convention used unless one is chosen explicitly.
+    pub const fn default_pixel_convention(&self) -> PixelConvention {
+        match self {
+            OutputMode::PythonCompatible | OutputMode::Floored => PixelConvention::PixelCorner,
+            OutputMode::Improved => PixelConvention::PixelCenter,
+        }
+    }
+
+    /// The rounding mode used unless one is chosen explicitly.
+    pub const fn default_rounding_mode(&self) -> RoundingMode {
+        match self {
+            OutputMode::PythonCompatible | OutputMode::Floored => RoundingMode::Legacy,
+            OutputMode::Improved => RoundingMode::Round,
+        }
+    }
+}
+
+/// How points are moved onto the integer grid of Pebble coordinates.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum RoundingMode {
+    /// Nudge by `f32::EPSILON` and round half away from zero, like the original tool.
+    #[default]
+    Legacy,
+    /// Round down, the largest coordinate that isn't past the point.
+    Floor,
+    /// Round to the nearest coordinate, halfway cases away from zero.
+    Round,
+    /// Round to the nearest coordinate, halfway cases to the even one, so that halfway
+    /// points don't all drift in the same direction.
+    HalfEven,
+}
+
+impl RoundingMode {
+    /// Round a coordinate that is already scaled to Pebble units.
+    ///
+    /// ```rust
+    /// use svg2pdc::point::RoundingMode;
+    ///
+    /// assert_eq!(RoundingMode::Floor.round(2.5), 2.0);
+    /// assert_eq!(RoundingMode::Round.round(2.5), 3.0);
+    /// assert_eq!(RoundingMode::HalfEven.round(2.5), 2.0);
+    /// assert_eq!(RoundingMode::HalfEven.round(3.5), 4.0);
+    /// ```
+    pub fn round(&self, value: f32) -> f32 {
+        match self {
+            RoundingMode::Legacy => (value + f32::EPSILON).round(),
+            RoundingMode::Floor => value.floor(),
+            RoundingMode::Round => value.round(),
+            RoundingMode::HalfEven => value.round_ties_even(),
+        }
+    }
+}
+
+/// What integer coordinates of the input refer to.
+///
+/// Pebble draws a coordinate at the center of a pixel, so input that addresses pixel
+/// corners has to be shifted by half a pixel to render the same on the watch.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum PixelConvention {
+    /// Integer coordinates are pixel corners, like in SVG editors. Points are shifted by
+    /// `(-0.5, -0.5)` before rounding, which is what the original tool does.
+    #[default]
+    PixelCorner,
+    /// Integer coordinates already are pixel centers and are used as they are.
+    PixelCenter,
+}
+
+impl PixelConvention {
+    /// Offset added to both coordinates of a point before it is rounded.
+    pub const fn offset(&self) -> f32 {
+        match self {
+            PixelConvention::PixelCorner => -0.5,
+            PixelConvention::PixelCenter => 0.0,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FPoint {
     pub x: f32,
     pub y: f32,
@@ -48,17 +185,22 @@ impl FPoint {
         &self,
         precision: &Precision,
         conversion: &Conversion,
+        rounding_mode: &RoundingMode,
+        pixel_convention: &PixelConvention,
+        output_mode: &OutputMode,
     ) -> Svg2PdcResult<PebblePoint> {
         let nearest_valid = (*self).find_nearest_valid(precision);
-        let point = if self != &nearest_valid {
+        // Like the original tool, an invalid point is only reported, the rounding below takes
+        // care of moving it onto the grid. Floored output moves it to the nearest valid point
+        // first.
+        if self != &nearest_valid {
             match conversion {
-                Conversion::ConvertNoWarn => nearest_valid,
+                Conversion::ConvertNoWarn => {}
                 Conversion::ConvertWarn => {
-                    eprintln!(
-                        "Warning: Point {:?} is not a valid pebble coordinate. Using nearest valid point {:?}",
+                    DiagnosticCode::InvalidPoint.warn(format_args!(
+                        "Point {:?} is not a valid pebble coordinate. Nearest valid point is {:?}",
                         self, nearest_valid
-                    );
-                    nearest_valid
+                    ));
                 }
                 Conversion::RequireExact => {
                     return Err(Svg2PdcError::InvalidPoint {
@@ -67,20 +209,19 @@ impl FPoint {
                     });
                 }
             }
-        } else {
-            *self
+        }
+        let point = match output_mode {
+            OutputMode::Floored => nearest_valid,
+            OutputMode::PythonCompatible | OutputMode::Improved => *self,
         };
-        let translated = point + FPoint::new(-0.5, -0.5);
+        let offset = pixel_convention.offset();
+        let translated = point + FPoint::new(offset, offset);
 
-        let translated = translated.round();
-        let translated = match precision {
-            Precision::Normal => translated,
-            Precision::Precise => translated * 8.0,
-        };
-        Ok(PebblePoint {
-            x: translated.x as u16,
-            y: translated.y as u16,
-        })
+        let translated = translated * precision.coordinate_scale();
+        PebblePoint::try_from(FPoint::new(
+            rounding_mode.round(translated.x),
+            rounding_mode.round(translated.y),
+        ))
     }
 }
 
@@ -127,10 +268,60 @@ impl Div<f32> for FPoint {
         }
     }
 }
-#[derive(Debug, Clone, Copy, Default)]
+/// A point in Pebble coordinates, signed like the `GPoint` of the PDC format, so shapes may
+/// extend left of or above the canvas.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PebblePoint {
-    pub x: u16,
-    pub y: u16,
+    pub x: i16,
+    pub y: i16,
+}
+
+impl PebblePoint {
+    /// Position of a stored coordinate on the canvas in watch pixels, where the pixel at
+    /// `(x, y)` covers `x..x + 1`, so whole stored coordinates address pixel centers.
+    ///
+    /// ```rust
+    /// use svg2pdc::point::{FPoint, PebblePoint, Precision};
+    ///
+    /// let point = PebblePoint { x: 12, y: 4 };
+    /// assert_eq!(point.pixel_position(&Precision::Normal), FPoint::new(12.5, 4.5));
+    /// assert_eq!(point.pixel_position(&Precision::Precise), FPoint::new(2.0, 1.0));
+    /// ```
+    pub fn pixel_position(self, precision: &Precision) -> FPoint {
+        FPoint::from(self) / precision.coordinate_scale() + FPoint::new(0.5, 0.5)
+    }
+}
+
+impl TryFrom<FPoint> for PebblePoint {
+    type Error = Svg2PdcError;
+
+    /// Convert a point that is already rounded to whole Pebble coordinates, failing when
+    /// it doesn't fit the 16 bit range instead of saturating.
+    ///
+    /// ```rust
+    /// use svg2pdc::point::{FPoint, PebblePoint};
+    ///
+    /// let point = PebblePoint::try_from(FPoint::new(-4.0, 12.0)).unwrap();
+    /// assert_eq!(point, PebblePoint { x: -4, y: 12 });
+    /// assert!(PebblePoint::try_from(FPoint::new(40_000.0, 0.0)).is_err());
+    /// ```
+    fn try_from(point: FPoint) -> Svg2PdcResult<Self> {
+        let range = i16::MIN as f32..=i16::MAX as f32;
+        if !(range.contains(&point.x) && range.contains(&point.y)) {
+            return Err(Svg2PdcError::CoordinateOutOfRange(point));
+        }
+        Ok(Self {
+            x: point.x as i16,
+            y: point.y as i16,
+        })
+    }
+}
+
+impl From<(i16, i16)> for PebblePoint {
+    fn from((x, y): (i16, i16)) -> Self {
+        Self { x, y }
+    }
 }
 
 impl From<PebblePoint> for FPoint {
@@ -0,0 +1,179 @@
+//! Pebble projects whose image resources are converted from SVGs.
+//!
+//! SDK 3 projects list their resources in the `pebble` object of `package.json`, older
+//! projects at the top level of `appinfo.json`. Converted images are stored in
+//! `resources/images/` and listed as `raw` resources, which apps load with
+//! `gdraw_command_image_create_with_resource`.
+//!
+//! The SVGs are taken from `resources/svg/` unless the project names another directory,
+//! relative to the project:
+//!
+//! ```json
+//! "pebble": {
+//!     "svg2pdc": { "source": "design/icons" }
+//! }
+//! ```
+
+use std::path::{Path, PathBuf};
+
+use serde_json::{Value, json};
+
+use crate::error::{Svg2PdcError, Svg2PdcResult};
+
+/// Directory of the SVGs when the project doesn't name one, relative to the project.
+pub const DEFAULT_SOURCE: &str = "resources/svg";
+
+/// Directory of the converted images, relative to the `resources` directory of the project.
+pub const IMAGES: &str = "images";
+
+/// A Pebble project and the resources listed in its manifest.
+#[derive(Debug, Clone)]
+pub struct PebbleProject {
+    pub root: PathBuf,
+    /// `package.json`, or `appinfo.json` of older projects.
+    pub manifest: PathBuf,
+    document: Value,
+}
+
+/// What [`PebbleProject::set_raw_resource`] did to the resources of a project.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResourceChange {
+    Added,
+    /// An entry for the file had another type.
+    Updated,
+    Unchanged,
+}
+
+impl PebbleProject {
+    /// Read the manifest of the project in `root`.
+    pub fn open(root: impl Into<PathBuf>) -> Svg2PdcResult<Self> {
+        let root = root.into();
+        let manifest = ["package.json", "appinfo.json"]
+            .into_iter()
+            .map(|name| root.join(name))
+            .find(|manifest| manifest.is_file())
+            .ok_or_else(|| {
+                Svg2PdcError::UnsupportedOperation(format!(
+                    "{} has neither a package.json nor an appinfo.json",
+                    root.display()
+                ))
+            })?;
+        let document =
+            serde_json::from_str(&std::fs::read_to_string(&manifest)?).map_err(|error| {
+                Svg2PdcError::ParseError(format!("{}: {error}", manifest.display()))
+            })?;
+        let project = Self {
+            root,
+            manifest,
+            document,
+        };
+        if project.settings().is_none() {
+            return Err(Svg2PdcError::ParseError(format!(
+                "{}: no `pebble` object, not a Pebble project",
+                project.manifest.display()
+            )));
+        }
+        Ok(project)
+    }
+
+    /// The Pebble settings: the `pebble` object of `package.json`, all of `appinfo.json`.
+    fn settings(&self) -> Option<&Value> {
+        match self.manifest.ends_with("package.json") {
+            true => self.document.get("pebble"),
+            false => Some(&self.document),
+        }
+        .filter(|settings| settings.is_object())
+    }
+
+    fn settings_mut(&mut self) -> &mut Value {
+        match self.manifest.ends_with("package.json") {
+            true => &mut self.document["pebble"],
+            false => &mut self.document,
+        }
+    }
+
+    /// The directory holding the SVGs, `svg2pdc.source` of the settings or
+    /// [`DEFAULT_SOURCE`].
+    pub fn source_dir(&self) -> PathBuf {
+        let source = self
+            .settings()
+            .and_then(|settings| settings.pointer("/svg2pdc/source"))
+            .and_then(Value::as_str)
+            .unwrap_or(DEFAULT_SOURCE);
+        self.root.join(source)
+    }
+
+    /// The directory the resource files are relative to.
+    pub fn resources_dir(&self) -> PathBuf {
+        self.root.join("resources")
+    }
+
+    /// The resource entries of the project.
+    pub fn media(&self) -> &[Value] {
+        self.settings()
+            .and_then(|settings| settings.pointer("/resources/media"))
+            .and_then(Value::as_array)
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// List `file`, a path relative to the resources directory, as a `raw` resource named
+    /// `name`. An entry that already lists the file keeps its name, the app refers to it.
+    pub fn set_raw_resource(&mut self, name: &str, file: &str) -> Svg2PdcResult<ResourceChange> {
+        let resources = &mut self.settings_mut()["resources"];
+        if resources.is_null() {
+            *resources = json!({});
+        }
+        let media = resources
+            .as_object_mut()
+            .ok_or_else(|| {
+                Svg2PdcError::ParseError("`resources` of the project is not an object".to_string())
+            })?
+            .entry("media")
+            .or_insert_with(|| json!([]))
+            .as_array_mut()
+            .ok_or_else(|| {
+                Svg2PdcError::ParseError(
+                    "`resources.media` of the project is not a list".to_string(),
+                )
+            })?;
+        Ok(match media.iter_mut().find(|entry| entry["file"] == file) {
+            Some(entry) if entry["type"] == "raw" => ResourceChange::Unchanged,
+            Some(entry) => {
+                entry["type"] = json!("raw");
+                ResourceChange::Updated
+            }
+            None => {
+                media.push(json!({ "type": "raw", "name": name, "file": file }));
+                ResourceChange::Added
+            }
+        })
+    }
+
+    /// Write the manifest back, keeping the order of its keys.
+    pub fn save(&self) -> Svg2PdcResult<()> {
+        let mut json = serde_json::to_string_pretty(&self.document).expect("JSON values serialize");
+        json.push('\n');
+        std::fs::write(&self.manifest, json)?;
+        Ok(())
+    }
+}
+
+/// The resource name of the image converted from `path`, relative to the source directory.
+///
+/// ```rust
+/// use std::path::Path;
+/// use svg2pdc::project::resource_name;
+///
+/// assert_eq!(resource_name(Path::new("weather/partly-cloudy.svg")), "IMAGE_WEATHER_PARTLY_CLOUDY");
+/// ```
+pub fn resource_name(path: &Path) -> String {
+    let mut name = String::from("IMAGE_");
+    for character in path.with_extension("").to_string_lossy().chars() {
+        if character.is_ascii_alphanumeric() {
+            name.push(character.to_ascii_uppercase());
+        } else if !name.ends_with('_') {
+            name.push('_');
+        }
+    }
+    name.trim_end_matches('_').to_string()
+}
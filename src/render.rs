@@ -0,0 +1,328 @@
+//! Rasterization of PDC images, for previews and debugging.
+//!
+//! This approximates the watch renderer: fills are drawn before strokes, strokes are centered
+//! on the path, black fills are left out, and a stored coordinate addresses the center of a
+//! pixel. Edges are anti-aliased, so a preview is close to the watch but not pixel-exact.
+
+use std::{io::Write, time::Duration};
+use tiny_skia::{
+    FillRule, LineCap, LineJoin, Paint, Path, PathBuilder, Pixmap, Rect, Stroke, Transform,
+};
+
+use crate::{
+    color::PebbleColor,
+    error::{Svg2PdcError, Svg2PdcResult},
+    image::{DrawCommand, PebbleImage},
+    point::{FPoint, Precision},
+};
+
+/// Render an image onto a transparent canvas, `scale` pixels per watch pixel.
+pub fn render(image: &PebbleImage, scale: f32) -> Svg2PdcResult<Pixmap> {
+    let mut pixmap = canvas(image, scale)?;
+    for command in &image.commands {
+        draw_command(&mut pixmap, command, scale);
+    }
+    Ok(pixmap)
+}
+
+/// Render the image once per command, each frame showing the image after one more command
+/// is drawn, so paint order and occlusion can be stepped through.
+///
+/// ```
+/// use svg2pdc::{point::PebblePoint, prelude::*, render::steps};
+///
+/// let circle = DrawCommand::Circle {
+///     center: PebblePoint { x: 5, y: 5 },
+///     radius: 3,
+///     options: DrawOptions::default(),
+/// };
+/// let image = PebbleImage {
+///     size: PebblePoint { x: 10, y: 10 },
+///     commands: vec![circle.clone(), circle],
+/// };
+/// assert_eq!(steps(&image, 1.0).unwrap().len(), 2);
+/// ```
+pub fn steps(image: &PebbleImage, scale: f32) -> Svg2PdcResult<Vec<Pixmap>> {
+    let mut pixmap = canvas(image, scale)?;
+    let mut frames = Vec::with_capacity(image.commands.len());
+    for command in &image.commands {
+        draw_command(&mut pixmap, command, scale);
+        frames.push(pixmap.clone());
+    }
+    Ok(frames)
+}
+
+/// Write frames of the same size as a looping animated GIF, showing each for `delay`.
+pub fn write_gif<W: Write>(frames: &[Pixmap], delay: Duration, writer: W) -> Svg2PdcResult<()> {
+    let Some(first) = frames.first() else {
+        return Err(Svg2PdcError::Render("no frames to animate".to_string()));
+    };
+    let encoding_error = |error: gif::EncodingError| Svg2PdcError::Render(error.to_string());
+    let (width, height) = (first.width() as u16, first.height() as u16);
+    let mut encoder = gif::Encoder::new(writer, width, height, &[]).map_err(encoding_error)?;
+    encoder
+        .set_repeat(gif::Repeat::Infinite)
+        .map_err(encoding_error)?;
+    // GIF delays are in hundredths of a second
+    let delay = (delay.as_millis() / 10).min(u16::MAX as u128) as u16;
+    for frame in frames {
+        let mut rgba: Vec<u8> = frame
+            .pixels()
+            .iter()
+            .flat_map(|pixel| {
+                let pixel = pixel.demultiply();
+                [pixel.red(), pixel.green(), pixel.blue(), pixel.alpha()]
+            })
+            .collect();
+        let mut frame = gif::Frame::from_rgba_speed(width, height, &mut rgba, 10);
+        frame.delay = delay;
+        encoder.write_frame(&frame).map_err(encoding_error)?;
+    }
+    Ok(())
+}
+
+/// Render an image with the index of every command drawn next to its first point, on a label
+/// in the color of the command.
+///
+/// ```
+/// use svg2pdc::{point::PebblePoint, prelude::*, render::annotate};
+///
+/// let image = PebbleImage { size: PebblePoint { x: 10, y: 20 }, commands: vec![] };
+/// let annotated = annotate(&image, 4.0).unwrap();
+/// assert_eq!((annotated.width(), annotated.height()), (40, 80));
+/// ```
+pub fn annotate(image: &PebbleImage, scale: f32) -> Svg2PdcResult<Pixmap> {
+    let mut pixmap = render(image, scale)?;
+    for (index, command) in image.commands.iter().enumerate() {
+        let anchor = command.anchor() * scale;
+        draw_label(
+            &mut pixmap,
+            &index.to_string(),
+            anchor,
+            label_color(command),
+        );
+    }
+    Ok(pixmap)
+}
+
+fn canvas(image: &PebbleImage, scale: f32) -> Svg2PdcResult<Pixmap> {
+    let width = (image.size.x as f32 * scale).ceil() as u32;
+    let height = (image.size.y as f32 * scale).ceil() as u32;
+    Pixmap::new(width, height).ok_or_else(|| {
+        Svg2PdcError::Render(format!(
+            "cannot render a {}x{} image at scale {scale}",
+            image.size.x, image.size.y
+        ))
+    })
+}
+
+/// Draw a single command the way the watch does, fill first and stroke on top.
+pub fn draw_command(pixmap: &mut Pixmap, command: &DrawCommand, scale: f32) {
+    let options = command.options();
+    let Some(path) = command_path(command, scale) else {
+        return;
+    };
+    // The watch leaves shapes with a black fill unfilled
+    if let Some(paint) = paint(options.fill_color)
+        && !PebbleColor::from_inner(options.fill_color).is_black()
+    {
+        pixmap.fill_path(
+            &path,
+            &paint,
+            FillRule::EvenOdd,
+            Transform::identity(),
+            None,
+        );
+    }
+    if let Some(paint) = paint(options.stroke_color)
+        && options.stroke_width > 0
+    {
+        let stroke = Stroke {
+            width: options.stroke_width as f32 * scale,
+            line_cap: LineCap::Round,
+            line_join: LineJoin::Round,
+            ..Default::default()
+        };
+        pixmap.stroke_path(&path, &paint, &stroke, Transform::identity(), None);
+    }
+}
+
+fn command_path(command: &DrawCommand, scale: f32) -> Option<Path> {
+    match command {
+        DrawCommand::Path {
+            points,
+            open,
+            options,
+        } => {
+            let mut builder = PathBuilder::new();
+            for (index, point) in points.iter().enumerate() {
+                let point = point.pixel_position(&options.precision) * scale;
+                if index == 0 {
+                    builder.move_to(point.x, point.y);
+                } else {
+                    builder.line_to(point.x, point.y);
+                }
+            }
+            if !open {
+                builder.close();
+            }
+            builder.finish()
+        }
+        // Circle centers are always stored with normal precision
+        DrawCommand::Circle { center, radius, .. } => {
+            let center = center.pixel_position(&Precision::Normal) * scale;
+            PathBuilder::from_circle(center.x, center.y, *radius as f32 * scale)
+        }
+    }
+}
+
+/// Where the label of a command goes: the first point of a path or the center of a circle.
+fn paint(color: u8) -> Option<Paint<'static>> {
+    let color = PebbleColor::from_inner(color);
+    if color.get_a() == 0 {
+        return None;
+    }
+    let mut paint = Paint::default();
+    paint.set_color_rgba8(
+        color.get_r() * 85,
+        color.get_g() * 85,
+        color.get_b() * 85,
+        color.get_a() * 85,
+    );
+    paint.anti_alias = true;
+    Some(paint)
+}
+
+/// The fill color of a command, or its stroke color for commands without a fill.
+fn label_color(command: &DrawCommand) -> PebbleColor {
+    let options = command.options();
+    [options.fill_color, options.stroke_color]
+        .into_iter()
+        .map(PebbleColor::from_inner)
+        .find(|color| color.get_a() != 0)
+        .unwrap_or(PebbleColor::from_argb(3, 2, 2, 2))
+}
+
+/// Size of a dot of the label font, in canvas pixels.
+const DOT: f32 = 2.0;
+
+/// 3x5 bitmaps of the digits, one bit per dot, row by row from the top left.
+const DIGITS: [u16; 10] = [
+    0b111_101_101_101_111,
+    0b010_110_010_010_111,
+    0b111_001_111_100_111,
+    0b111_001_111_001_111,
+    0b101_101_111_001_001,
+    0b111_100_111_001_111,
+    0b111_100_111_101_111,
+    0b111_001_001_001_001,
+    0b111_101_111_101_111,
+    0b111_101_111_001_111,
+];
+
+/// Draw `text` (digits only) on a label filled with `color`, with its top left corner at
+/// `anchor` but kept inside the canvas.
+fn draw_label(pixmap: &mut Pixmap, text: &str, anchor: FPoint, color: PebbleColor) {
+    let width = (text.len() as f32 * 4.0 + 1.0) * DOT;
+    let height = 7.0 * DOT;
+    let x = anchor.x.min(pixmap.width() as f32 - width).max(0.0);
+    let y = anchor.y.min(pixmap.height() as f32 - height).max(0.0);
+
+    let mut outline = Paint::default();
+    outline.set_color_rgba8(0, 0, 0, 255);
+    let mut background = Paint::default();
+    background.set_color_rgba8(
+        color.get_r() * 85,
+        color.get_g() * 85,
+        color.get_b() * 85,
+        255,
+    );
+    // Dark digits on light labels and the other way round
+    let light = color.get_r() as u16 * 3 + color.get_g() as u16 * 6 + color.get_b() as u16 > 15;
+    let mut ink = Paint::default();
+    if light {
+        ink.set_color_rgba8(0, 0, 0, 255);
+    } else {
+        ink.set_color_rgba8(255, 255, 255, 255);
+    }
+
+    let mut fill_rect = |x: f32, y: f32, width: f32, height: f32, paint: &Paint| {
+        if let Some(rect) = Rect::from_xywh(x, y, width, height) {
+            pixmap.fill_rect(rect, paint, Transform::identity(), None);
+        }
+    };
+    fill_rect(x - 1.0, y - 1.0, width + 2.0, height + 2.0, &outline);
+    fill_rect(x, y, width, height, &background);
+    for (position, digit) in text.chars().filter_map(|c| c.to_digit(10)).enumerate() {
+        let bitmap = DIGITS[digit as usize];
+        for dot in 0..15 {
+            if bitmap & (1 << (14 - dot)) != 0 {
+                fill_rect(
+                    x + (1.0 + position as f32 * 4.0 + (dot % 3) as f32) * DOT,
+                    y + (1.0 + (dot / 3) as f32) * DOT,
+                    DOT,
+                    DOT,
+                    &ink,
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{image::DrawOptions, point::PebblePoint};
+
+    #[test]
+    fn test_stored_coordinates_address_pixel_centers() {
+        let red = PebbleColor::from_argb(3, 3, 0, 0).inner();
+        let options = DrawOptions {
+            fill_color: red,
+            precision: Precision::Precise,
+            ..Default::default()
+        };
+        // From the center of pixel (1, 1) to the center of pixel (3, 3) on a 6x6 canvas
+        let square = [(1, 1), (3, 1), (3, 3), (1, 3)]
+            .into_iter()
+            .map(|(x, y)| PebblePoint { x: x * 8, y: y * 8 })
+            .collect();
+        let image = PebbleImage {
+            size: PebblePoint { x: 6, y: 6 },
+            commands: vec![DrawCommand::Path {
+                points: square,
+                open: false,
+                options,
+            }],
+        };
+
+        let pixmap = render(&image, 1.0).unwrap();
+        assert_eq!(pixmap.pixel(2, 2).unwrap().red(), 255);
+        assert_eq!(pixmap.pixel(2, 2).unwrap().alpha(), 255);
+        assert_eq!(pixmap.pixel(0, 0).unwrap().alpha(), 0);
+        assert_eq!(pixmap.pixel(5, 5).unwrap().alpha(), 0);
+        assert_eq!(image.legend().lines().count(), 1);
+    }
+
+    #[test]
+    fn test_black_fills_stay_transparent() {
+        let black = PebbleColor::from_argb(3, 0, 0, 0).inner();
+        let image = PebbleImage {
+            size: PebblePoint { x: 10, y: 10 },
+            commands: vec![DrawCommand::Circle {
+                center: PebblePoint { x: 4, y: 4 },
+                radius: 4,
+                options: DrawOptions {
+                    fill_color: black,
+                    stroke_color: black,
+                    stroke_width: 1,
+                    ..Default::default()
+                },
+            }],
+        };
+
+        let pixmap = render(&image, 1.0).unwrap();
+        assert_eq!(pixmap.pixel(4, 4).unwrap().alpha(), 0);
+        assert!(pixmap.pixel(4, 0).unwrap().alpha() > 0);
+    }
+}
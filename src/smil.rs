@@ -0,0 +1,422 @@
+//! Sampling of SMIL animations, to turn animated SVGs into the frames of a
+//! [`crate::image::PebbleSequence`].
+//!
+//! `<animate>` interpolates numeric attributes like `x`, `y`, `cx` or `cy`, and
+//! `<animateTransform type="translate">` moves its target. An animation is sampled by
+//! rewriting the document with the animated values at that time and removing the animation
+//! elements, the result converts like any other SVG.
+//!
+//! Animations begin at a clock value (`2s`, `500ms`), others like `begin="click"` never do.
+//! `values` with `keyTimes`, `from`/`to`/`by`, `repeatCount`, `fill="freeze"`,
+//! `additive="sum"` and `calcMode="discrete"` are supported, other timing features are not.
+
+use roxmltree::{Document, Node};
+
+use crate::{
+    diagnostic::ElementLocation,
+    error::{Svg2PdcError, Svg2PdcResult},
+};
+
+/// An `<animate>` or `<animateTransform>` element.
+struct Animation<'a, 'input> {
+    node: Node<'a, 'input>,
+    target: Node<'a, 'input>,
+    attribute: String,
+    /// Begin of the animation in seconds, `None` when it waits for an event.
+    begin: Option<f64>,
+    /// Simple duration in seconds, `None` when it is `indefinite`.
+    duration: Option<f64>,
+    /// Number of repetitions, infinite with `indefinite`.
+    repeat_count: f64,
+    freeze: bool,
+    additive: bool,
+    discrete: bool,
+    /// Values and the times they are reached at, as fractions of the simple duration.
+    key_frames: Vec<(f64, Vec<f64>)>,
+}
+
+impl<'a, 'input> Animation<'a, 'input> {
+    fn from_node(node: Node<'a, 'input>) -> Svg2PdcResult<Option<Self>> {
+        let invalid = |attribute: &str, reason: String| Svg2PdcError::InvalidAttribute {
+            attribute: attribute.to_string(),
+            element: ElementLocation::of(node),
+            reason,
+        };
+        let is_transform = node.has_tag_name("animateTransform");
+        if is_transform && node.attribute("type").unwrap_or("translate") != "translate" {
+            return Err(invalid(
+                "type",
+                "only `translate` transforms can be sampled".to_string(),
+            ));
+        }
+
+        let target = match node
+            .attribute(("http://www.w3.org/1999/xlink", "href"))
+            .or(node.attribute("href"))
+        {
+            Some(href) => {
+                let id = href.trim().trim_start_matches('#');
+                node.document()
+                    .descendants()
+                    .find(|candidate| candidate.attribute("id") == Some(id))
+                    .ok_or_else(|| invalid("href", format!("no element with the id `{id}`")))?
+            }
+            None => match node.parent_element() {
+                Some(parent) => parent,
+                None => return Ok(None),
+            },
+        };
+        let attribute = match (is_transform, node.attribute("attributeName")) {
+            (_, Some(attribute)) => attribute.to_string(),
+            (true, None) => "transform".to_string(),
+            (false, None) => {
+                return Err(Svg2PdcError::MissingAttribute {
+                    attribute: "attributeName".to_string(),
+                    element: ElementLocation::of(node),
+                });
+            }
+        };
+
+        let clock = |name: &str| -> Svg2PdcResult<Option<f64>> {
+            match node.attribute(name).map(str::trim) {
+                None | Some("indefinite") => Ok(None),
+                Some(value) => clock_value(value)
+                    .map(Some)
+                    .ok_or_else(|| invalid(name, format!("`{value}` is not a clock value"))),
+            }
+        };
+        // Only the first begin counts, and begins waiting for events never happen
+        let begin = match node.attribute("begin") {
+            None => Some(0.0),
+            Some(begin) => clock_value(begin.split(';').next().unwrap_or("")),
+        };
+        let duration = clock("dur")?.filter(|&duration| duration > 0.0);
+        let repeat_count = match node.attribute("repeatCount").map(str::trim) {
+            None => 1.0,
+            Some("indefinite") => f64::INFINITY,
+            Some(count) => count
+                .parse::<f64>()
+                .ok()
+                .filter(|count| *count > 0.0)
+                .ok_or_else(|| invalid("repeatCount", format!("`{count}` is not a count")))?,
+        };
+
+        let numbers = |name: &str, value: &str| {
+            parse_numbers(value).ok_or_else(|| invalid(name, format!("`{value}` is not numeric")))
+        };
+        let values = match node.attribute("values") {
+            Some(values) => values
+                .split(';')
+                .filter(|value| !value.trim().is_empty())
+                .map(|value| numbers("values", value))
+                .collect::<Svg2PdcResult<Vec<_>>>()?,
+            None => {
+                let base = || -> Svg2PdcResult<Vec<f64>> {
+                    match (is_transform, target.attribute(attribute.as_str())) {
+                        (false, Some(value)) => numbers(&attribute, value),
+                        _ => Ok(vec![0.0; 2]),
+                    }
+                };
+                let from = match node.attribute("from") {
+                    Some(from) => numbers("from", from)?,
+                    None => base()?,
+                };
+                let to = match (node.attribute("to"), node.attribute("by")) {
+                    (Some(to), _) => numbers("to", to)?,
+                    (None, Some(by)) => add(&from, &numbers("by", by)?),
+                    (None, None) => return Ok(None),
+                };
+                vec![from, to]
+            }
+        };
+        let discrete = node.attribute("calcMode") == Some("discrete");
+        let key_times = match node.attribute("keyTimes") {
+            Some(key_times) => parse_numbers(&key_times.replace(';', " "))
+                .filter(|key_times| key_times.len() == values.len())
+                .ok_or_else(|| invalid("keyTimes", "expected one time per value".to_string()))?,
+            // Discrete values split the duration into equal intervals, interpolated values
+            // are reached at equal intervals
+            None if discrete => (0..values.len())
+                .map(|index| index as f64 / values.len() as f64)
+                .collect(),
+            None => (0..values.len())
+                .map(|index| index as f64 / (values.len().max(2) - 1) as f64)
+                .collect(),
+        };
+
+        Ok(Some(Self {
+            node,
+            target,
+            attribute,
+            begin,
+            duration,
+            repeat_count,
+            freeze: node.attribute("fill") == Some("freeze"),
+            additive: node.attribute("additive") == Some("sum"),
+            discrete,
+            key_frames: key_times.into_iter().zip(values).collect(),
+        }))
+    }
+
+    /// End of the animation in seconds, infinite when it repeats forever.
+    fn end(&self) -> Option<f64> {
+        Some(self.begin? + self.duration? * self.repeat_count)
+    }
+
+    /// The animated value at `time`, `None` when the animation doesn't apply.
+    fn value_at(&self, time: f64) -> Option<Vec<f64>> {
+        let (begin, duration) = (self.begin?, self.duration?);
+        if time < begin || self.key_frames.is_empty() {
+            return None;
+        }
+        let progress = match self.end() {
+            Some(end) if time >= end => {
+                if !self.freeze {
+                    return None;
+                }
+                let repeats = self.repeat_count;
+                match repeats.fract() {
+                    0.0 => 1.0,
+                    fraction => fraction,
+                }
+            }
+            _ => ((time - begin) % duration) / duration,
+        };
+
+        let next = self
+            .key_frames
+            .iter()
+            .position(|(key_time, _)| *key_time > progress);
+        Some(match next {
+            None => self.key_frames[self.key_frames.len() - 1].1.clone(),
+            Some(0) => self.key_frames[0].1.clone(),
+            Some(next) => {
+                let (start_time, start) = &self.key_frames[next - 1];
+                let (end_time, end) = &self.key_frames[next];
+                if self.discrete {
+                    return Some(start.clone());
+                }
+                let t = (progress - start_time) / (end_time - start_time);
+                start
+                    .iter()
+                    .zip(end)
+                    .map(|(start, end)| start + (end - start) * t)
+                    .collect()
+            }
+        })
+    }
+}
+
+/// Parse a clock value like `2s`, `500ms`, `1.5min` or `3` (seconds) into seconds.
+fn clock_value(value: &str) -> Option<f64> {
+    let value = value.trim();
+    let (number, scale) = [("ms", 0.001), ("min", 60.0), ("h", 3600.0), ("s", 1.0)]
+        .into_iter()
+        .find_map(|(unit, scale)| Some((value.strip_suffix(unit)?, scale)))
+        .unwrap_or((value, 1.0));
+    number
+        .trim()
+        .parse::<f64>()
+        .ok()
+        .filter(|number| number.is_finite())
+        .map(|number| number * scale)
+}
+
+/// Numbers separated by whitespace or commas, in user units.
+fn parse_numbers(value: &str) -> Option<Vec<f64>> {
+    let numbers = value
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|number| !number.is_empty())
+        .map(|number| number.trim_end_matches("px").parse::<f64>().ok())
+        .collect::<Option<Vec<_>>>()?;
+    (!numbers.is_empty()).then_some(numbers)
+}
+
+fn add(a: &[f64], b: &[f64]) -> Vec<f64> {
+    (0..a.len().max(b.len()))
+        .map(|index| a.get(index).unwrap_or(&0.0) + b.get(index).unwrap_or(&0.0))
+        .collect()
+}
+
+fn format_numbers(numbers: &[f64]) -> String {
+    numbers
+        .iter()
+        .map(|number| format!("{}", (number * 1000.0).round() / 1000.0))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn animations<'a, 'input>(
+    document: &'a Document<'input>,
+) -> Svg2PdcResult<Vec<Animation<'a, 'input>>> {
+    let mut animations = Vec::new();
+    for node in document
+        .descendants()
+        .filter(|node| node.has_tag_name("animate") || node.has_tag_name("animateTransform"))
+    {
+        animations.extend(Animation::from_node(node)?);
+    }
+    Ok(animations)
+}
+
+/// Length of the animations of a document in seconds: until the last one ends, or one cycle
+/// of the animations that repeat forever. `None` when nothing in the document is animated.
+///
+/// ```rust
+/// use svg2pdc::smil::animation_duration;
+///
+/// let svg = r#"<svg xmlns="http://www.w3.org/2000/svg">
+///     <rect x="0" y="0" width="2" height="2">
+///         <animate attributeName="x" from="0" to="10" begin="1s" dur="500ms" repeatCount="2"/>
+///     </rect>
+/// </svg>"#;
+/// assert_eq!(animation_duration(svg).unwrap(), Some(2.0));
+/// ```
+pub fn animation_duration(content: &str) -> Svg2PdcResult<Option<f64>> {
+    let document = Document::parse(content)?;
+    Ok(animations(&document)?
+        .iter()
+        .filter_map(|animation| {
+            let end = animation.end()?;
+            match end.is_finite() {
+                true => Some(end),
+                false => Some(animation.begin? + animation.duration?),
+            }
+        })
+        .reduce(f64::max))
+}
+
+/// The document as it is drawn `time` seconds into its animations, without the animation
+/// elements.
+///
+/// ```rust
+/// use svg2pdc::smil::sample_svg;
+///
+/// let svg = r#"<svg xmlns="http://www.w3.org/2000/svg"><circle cx="0" cy="5" r="2"><animate attributeName="cx" values="0;10;0" dur="2s"/></circle></svg>"#;
+/// assert_eq!(
+///     sample_svg(svg, 0.5).unwrap(),
+///     r#"<svg xmlns="http://www.w3.org/2000/svg"><circle cx="5" cy="5" r="2"></circle></svg>"#
+/// );
+/// ```
+pub fn sample_svg(content: &str, time: f64) -> Svg2PdcResult<String> {
+    let document = Document::parse(content)?;
+    let animations = animations(&document)?;
+
+    let mut edits = Vec::new();
+    // Later animations of the same attribute override or add to the earlier ones
+    let mut targets: Vec<(Node<'_, '_>, &str)> = Vec::new();
+    for animation in &animations {
+        let key = (animation.target, animation.attribute.as_str());
+        if !targets.contains(&key) {
+            targets.push(key);
+        }
+    }
+    for (target, attribute) in targets {
+        let is_transform = attribute == "transform";
+        let base = target.attribute(attribute);
+        let mut value: Option<String> = None;
+        let mut numbers = match (is_transform, base) {
+            (false, Some(base)) => parse_numbers(base),
+            _ => None,
+        };
+        for animation in animations
+            .iter()
+            .filter(|animation| animation.target == target && animation.attribute == attribute)
+        {
+            let Some(sampled) = animation.value_at(time) else {
+                continue;
+            };
+            if is_transform {
+                let translate = format!("translate({})", format_numbers(&sampled));
+                value = Some(
+                    match (animation.additive, value.or(base.map(str::to_string))) {
+                        (true, Some(previous)) => format!("{previous} {translate}"),
+                        _ => translate,
+                    },
+                );
+            } else {
+                let sampled = match (animation.additive, &numbers) {
+                    (true, Some(previous)) => add(previous, &sampled),
+                    _ => sampled,
+                };
+                value = Some(format_numbers(&sampled));
+                numbers = Some(sampled);
+            }
+        }
+        let Some(value) = value else {
+            continue;
+        };
+        match target
+            .attributes()
+            .find(|candidate| candidate.name() == attribute && candidate.namespace().is_none())
+        {
+            Some(existing) => edits.push((existing.range_value(), value)),
+            None => {
+                // A new attribute goes right after the tag name
+                let start = target.range().start + 1;
+                let name_length = content[start..]
+                    .find(|c: char| c.is_whitespace() || c == '/' || c == '>')
+                    .unwrap_or(0);
+                let position = start + name_length;
+                edits.push((position..position, format!(" {attribute}=\"{value}\"")));
+            }
+        }
+    }
+    for animation in &animations {
+        edits.push((animation.node.range(), String::new()));
+    }
+
+    let mut sampled = content.to_string();
+    // Edit from the end so the earlier ranges stay valid
+    edits.sort_by_key(|(range, _)| std::cmp::Reverse(range.start));
+    for (range, value) in edits {
+        sampled.replace_range(range, &value);
+    }
+    Ok(sampled)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clock_values() {
+        assert_eq!(clock_value("2s"), Some(2.0));
+        assert_eq!(clock_value(" 500ms "), Some(0.5));
+        assert_eq!(clock_value("1.5min"), Some(90.0));
+        assert_eq!(clock_value("3"), Some(3.0));
+        assert_eq!(clock_value("click"), None);
+    }
+
+    #[test]
+    fn test_translate_freeze_and_events() {
+        let svg = r##"<svg xmlns="http://www.w3.org/2000/svg">
+            <g transform="translate(1, 1)">
+                <animateTransform attributeName="transform" type="translate" from="0 0" to="10 20" dur="1s" additive="sum" fill="freeze"/>
+                <rect x="0" y="0" width="2" height="2"/>
+            </g>
+            <rect id="button" x="0" y="0" width="2" height="2"/>
+            <animate href="#button" attributeName="y" to="5" dur="1s" begin="button.click"/>
+        </svg>"##;
+        let at_half = sample_svg(svg, 0.5).unwrap();
+        assert!(at_half.contains(r#"transform="translate(1, 1) translate(5 10)""#));
+        assert!(!at_half.contains("animate"));
+        assert!(at_half.contains(r#"<rect id="button" x="0" y="0""#));
+        let frozen = sample_svg(svg, 3.0).unwrap();
+        assert!(frozen.contains(r#"transform="translate(1, 1) translate(10 20)""#));
+    }
+
+    #[test]
+    fn test_key_times_and_discrete_values() {
+        let svg = r#"<svg xmlns="http://www.w3.org/2000/svg"><rect width="2" height="2"><animate attributeName="x" values="0;10;20" keyTimes="0;0.8;1" dur="1s" repeatCount="indefinite"/><animate attributeName="y" values="1;2;3" calcMode="discrete" dur="3s"/></rect></svg>"#;
+        let sampled = sample_svg(svg, 1.4).unwrap();
+        assert!(sampled.contains(r#"<rect y="2" x="5" width"#), "{sampled}");
+        // After its end, an animation without fill="freeze" leaves the attribute as it was
+        assert!(
+            sample_svg(svg, 3.4)
+                .unwrap()
+                .contains(r#"<rect x="5" width"#)
+        );
+        assert_eq!(animation_duration(svg).unwrap(), Some(3.0));
+    }
+}
@@ -0,0 +1,254 @@
+//! The `<style>` elements of a document, as written by Illustrator and other editors that put
+//! the presentation of shapes in classes, e.g. `<style>.cls-1{fill:#aabbcc}</style>`.
+//!
+//! Only simple selectors are supported: `*`, element names, classes and ids, optionally
+//! combined like `path.cls-1`. Rules with combinators, attribute selectors or pseudo-classes
+//! are ignored, and so are at-rules like `@media`.
+
+use std::collections::HashMap;
+
+use roxmltree::{Document, Node};
+
+/// A simple selector of a rule, e.g. `path.cls-1`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct Selector {
+    tag: Option<String>,
+    id: Option<String>,
+    classes: Vec<String>,
+}
+
+impl Selector {
+    /// Parse a compound selector, `None` for anything more complex.
+    fn parse(selector: &str) -> Option<Self> {
+        let selector = selector.trim();
+        if selector.is_empty()
+            || selector.contains(|c: char| c.is_whitespace() || "[]:>+~()".contains(c))
+        {
+            return None;
+        }
+        // Every part but the first one starts with a `.` or a `#`
+        let mut parts = Vec::new();
+        let mut start = 0;
+        for (offset, c) in selector.char_indices() {
+            if matches!(c, '.' | '#') && offset > 0 {
+                parts.push(&selector[start..offset]);
+                start = offset;
+            }
+        }
+        parts.push(&selector[start..]);
+
+        let mut parsed = Self::default();
+        for (index, part) in parts.into_iter().enumerate() {
+            if let Some(class) = part.strip_prefix('.') {
+                if class.is_empty() {
+                    return None;
+                }
+                parsed.classes.push(class.to_string());
+            } else if let Some(id) = part.strip_prefix('#') {
+                if id.is_empty() || parsed.id.is_some() {
+                    return None;
+                }
+                parsed.id = Some(id.to_string());
+            } else if index == 0 && part != "*" {
+                parsed.tag = Some(part.to_string());
+            }
+        }
+        Some(parsed)
+    }
+
+    fn matches(&self, node: Node<'_, '_>) -> bool {
+        let classes = node.attribute("class").unwrap_or("");
+        self.tag
+            .as_ref()
+            .is_none_or(|tag| node.tag_name().name() == tag)
+            && self
+                .id
+                .as_ref()
+                .is_none_or(|id| node.attribute("id") == Some(id))
+            && self
+                .classes
+                .iter()
+                .all(|class| classes.split_whitespace().any(|name| name == class))
+    }
+
+    /// CSS specificity, rules with a higher one win over earlier rules.
+    fn specificity(&self) -> (usize, usize, usize) {
+        (
+            usize::from(self.id.is_some()),
+            self.classes.len(),
+            usize::from(self.tag.is_some()),
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Rule {
+    selector: Selector,
+    declarations: Vec<(String, String)>,
+}
+
+/// The rules of all `<style>` elements of a document, in document order.
+#[derive(Debug, Clone, Default)]
+pub struct Stylesheet {
+    rules: Vec<Rule>,
+}
+
+impl Stylesheet {
+    /// Collect the CSS rules of every `<style>` element of a document.
+    ///
+    /// ```rust
+    /// use svg2pdc::{roxmltree::Document, stylesheet::Stylesheet};
+    ///
+    /// let document = Document::parse(
+    ///     r#"<svg xmlns="http://www.w3.org/2000/svg">
+    ///         <style>.cls-1 { fill: #AABBCC; stroke: red } path.cls-1 { fill: blue }</style>
+    ///         <path id="shape" class="cls-1" d="M0 0 L1 1"/>
+    ///     </svg>"#,
+    /// ).unwrap();
+    /// let stylesheet = Stylesheet::from_document(&document);
+    /// let shape = document.descendants().find(|node| node.has_attribute("id")).unwrap();
+    /// let properties = stylesheet.properties(shape);
+    /// assert_eq!(properties["fill"], "blue");
+    /// assert_eq!(properties["stroke"], "red");
+    /// ```
+    pub fn from_document(document: &Document<'_>) -> Self {
+        let mut rules = Vec::new();
+        for style in document.descendants().filter(|node| {
+            node.has_tag_name("style")
+                && node
+                    .attribute("type")
+                    .is_none_or(|kind| kind.eq_ignore_ascii_case("text/css"))
+        }) {
+            let css: String = style.children().filter_map(|child| child.text()).collect();
+            parse_rules(&css, &mut rules);
+        }
+        Self { rules }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// The properties the rules matching `node` set, with names in lowercase and values as
+    /// written.
+    pub fn properties(&self, node: Node<'_, '_>) -> HashMap<String, String> {
+        let mut matching: Vec<_> = self
+            .rules
+            .iter()
+            .filter(|rule| rule.selector.matches(node))
+            .collect();
+        // A stable sort keeps the document order of rules with the same specificity
+        matching.sort_by_key(|rule| rule.selector.specificity());
+        matching
+            .into_iter()
+            .flat_map(|rule| rule.declarations.iter().cloned())
+            .collect()
+    }
+}
+
+/// Parse the rules of a style sheet, skipping comments and at-rules.
+fn parse_rules(css: &str, rules: &mut Vec<Rule>) {
+    let css = strip_comments(css);
+    let mut rest = css.as_str();
+    while let Some(open) = rest.find('{') {
+        let prelude = rest[..open].trim();
+        let block_end = block_end(&rest[open..]).map_or(rest.len(), |end| open + end);
+        let block = &rest[(open + 1).min(block_end)..block_end];
+        rest = rest.get(block_end + 1..).unwrap_or("");
+        if prelude.starts_with('@') {
+            continue;
+        }
+        let declarations: Vec<_> = block
+            .split(';')
+            .filter_map(|declaration| {
+                let (name, value) = declaration.split_once(':')?;
+                let value = value.trim();
+                let value = value.strip_suffix("!important").unwrap_or(value).trim();
+                Some((name.trim().to_lowercase(), value.to_string()))
+            })
+            .collect();
+        for selector in prelude.split(',').filter_map(Selector::parse) {
+            rules.push(Rule {
+                selector,
+                declarations: declarations.clone(),
+            });
+        }
+    }
+}
+
+/// Offset of the `}` closing the block that starts at the beginning of `css`.
+fn block_end(css: &str) -> Option<usize> {
+    let mut depth = 0;
+    for (offset, c) in css.char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(offset);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+fn strip_comments(css: &str) -> String {
+    let mut stripped = String::with_capacity(css.len());
+    let mut rest = css;
+    while let Some(start) = rest.find("/*") {
+        stripped.push_str(&rest[..start]);
+        rest = rest[start + 2..]
+            .find("*/")
+            .map_or("", |end| &rest[start + 2 + end + 2..]);
+    }
+    stripped.push_str(rest);
+    stripped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_selectors() {
+        let selector = |value| Selector::parse(value);
+        assert_eq!(
+            selector("path.cls-1.cls-2"),
+            Some(Selector {
+                tag: Some("path".to_string()),
+                id: None,
+                classes: vec!["cls-1".to_string(), "cls-2".to_string()],
+            })
+        );
+        assert_eq!(
+            selector("#shape"),
+            Some(Selector {
+                id: Some("shape".to_string()),
+                ..Selector::default()
+            })
+        );
+        assert_eq!(selector("*"), Some(Selector::default()));
+        for unsupported in ["g path", "g > path", "a:hover", "[fill]", ".", "path.", ""] {
+            assert_eq!(selector(unsupported), None, "{unsupported}");
+        }
+    }
+
+    #[test]
+    fn test_comments_and_at_rules_are_skipped() {
+        let mut rules = Vec::new();
+        parse_rules(
+            "/* colors */ @media print { .a { fill: red } } .b { fill: blue; /* x */ stroke: #FFF !important }",
+            &mut rules,
+        );
+        assert_eq!(rules.len(), 1);
+        assert_eq!(
+            rules[0].declarations,
+            vec![
+                ("fill".to_string(), "blue".to_string()),
+                ("stroke".to_string(), "#FFF".to_string()),
+            ]
+        );
+    }
+}
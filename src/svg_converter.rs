@@ -1,15 +1,25 @@
-use std::{collections::HashMap, num::ParseFloatError, str::FromStr};
+use std::{collections::HashMap, str::FromStr};
 
-use roxmltree::{Document, Node};
-use svgtypes::{PathSegment, TransformListToken, ViewBox};
+use roxmltree::{Document, Node, NodeId};
+use svgtypes::{Align, AspectRatio, PathSegment, TransformListToken, ViewBox};
 
 use crate::{
-    color::{Color, PebbleColor, TruncateColor},
+    color::{Color, ColorAdjustment, PebbleColor, TruncateColor},
+    diagnostic::{self, ConversionReport, DiagnosticCode, ElementLocation, ElementScope},
     error::{Svg2PdcError, Svg2PdcResult},
+    glyph::{bridge_holes, find_holes},
     image::{DrawCommand, DrawOptions, PebbleImage},
-    point::{Conversion, FPoint, Precision},
+    paint::{Paint, is_paint, resolve_paint},
+    platform::Platform,
+    point::{
+        Conversion, FPoint, OutputMode, PebblePoint, PixelConvention, Precision, RoundingMode,
+    },
+    stylesheet::Stylesheet,
 };
 
+#[cfg(feature = "text")]
+use crate::glyph::TextFont;
+
 #[derive(Debug, Clone, Default)]
 struct GroupOptions {
     pub opacity: Option<f64>,
@@ -17,181 +27,1442 @@ struct GroupOptions {
     pub fill_opacity: Option<f64>,
     pub stroke_color: Option<String>,
     pub stroke_opacity: Option<f64>,
-    pub stroke_width: Option<u8>,
+    pub stroke_width: Option<f32>,
+    /// `visible`, `hidden` or `collapse` in lowercase, inherited unlike `display`.
+    pub visibility: Option<String>,
 }
 
-pub struct SvgConverter {
+impl GroupOptions {
+    /// The options an element sets itself, see [`properties`].
+    fn from_node(node: Node<'_, '_>, stylesheet: &Stylesheet) -> Svg2PdcResult<Self> {
+        let properties = properties(node, stylesheet);
+        Self::from_properties(node, |name| properties.get(name).map(String::as_str))
+    }
+
+    /// The options set by the presentation properties of `node` that `property` looks up.
+    /// `inherit` leaves a property unset, so it falls back to the parent like a missing one.
+    fn from_properties<'a>(
+        node: Node<'_, '_>,
+        property: impl Fn(&str) -> Option<&'a str>,
+    ) -> Svg2PdcResult<Self> {
+        let property = |name: &str| {
+            property(name).filter(|value| !value.trim().eq_ignore_ascii_case("inherit"))
+        };
+        let opacity = |name| {
+            property(name)
+                .map(|value| parse_opacity(node, name, value))
+                .transpose()
+        };
+        Ok(Self {
+            opacity: opacity("opacity")?,
+            fill_color: property("fill").map(|fill| fill.to_string()),
+            fill_opacity: opacity("fill-opacity")?,
+            stroke_color: property("stroke").map(|stroke| stroke.to_string()),
+            stroke_opacity: opacity("stroke-opacity")?,
+            stroke_width: property("stroke-width")
+                .map(|stroke_width| parse_number(node, "stroke-width", stroke_width))
+                .transpose()?,
+            visibility: property("visibility")
+                .map(|visibility| visibility.trim().to_ascii_lowercase()),
+        })
+    }
+
+    /// Inherit from the options of the parent, `fallback`. Opacities multiply down the tree,
+    /// so a 50% group in a 50% group is drawn at 25%, everything else is taken from `fallback`
+    /// where `self` leaves it unset.
+    fn or(self, fallback: &Self) -> Self {
+        let multiply = |own: Option<f64>, inherited: Option<f64>| match (own, inherited) {
+            (Some(own), Some(inherited)) => Some(own * inherited),
+            (own, inherited) => own.or(inherited),
+        };
+        Self {
+            opacity: multiply(self.opacity, fallback.opacity),
+            fill_color: self.fill_color.or_else(|| fallback.fill_color.clone()),
+            fill_opacity: multiply(self.fill_opacity, fallback.fill_opacity),
+            stroke_color: self.stroke_color.or_else(|| fallback.stroke_color.clone()),
+            stroke_opacity: multiply(self.stroke_opacity, fallback.stroke_opacity),
+            stroke_width: self.stroke_width.or(fallback.stroke_width),
+            visibility: self.visibility.or_else(|| fallback.visibility.clone()),
+        }
+    }
+}
+
+/// The presentation properties `node` sets itself, by name in lowercase.
+///
+/// The `style` attribute takes precedence over the rules of `stylesheet`, which take
+/// precedence over presentation attributes like `fill="red"`. Properties set by none of
+/// them are inherited from the parent group by [`GroupOptions::or`].
+fn properties(node: Node<'_, '_>, stylesheet: &Stylesheet) -> HashMap<String, String> {
+    let mut properties: HashMap<String, String> = node
+        .attributes()
+        .map(|attr| (attr.name().to_ascii_lowercase(), attr.value().to_string()))
+        .collect();
+    properties.extend(stylesheet.properties(node));
+    // Property names are case-insensitive, values keep their case for `url(#Id)`
+    properties.extend(
+        node.attribute("style")
+            .unwrap_or("")
+            .split(';')
+            .filter_map(|declaration| {
+                let (name, value) = declaration.split_once(':')?;
+                Some((name.trim().to_ascii_lowercase(), value.trim().to_string()))
+            }),
+    );
+    properties
+}
+
+/// The value of one presentation property of `node`, with the precedence of [`properties`].
+fn property(node: Node<'_, '_>, stylesheet: &Stylesheet, name: &str) -> Option<String> {
+    // The last declaration wins, like in `properties`
+    let inline = node.attribute("style").and_then(|style| {
+        style.split(';').rev().find_map(|declaration| {
+            let (key, value) = declaration.split_once(':')?;
+            key.trim()
+                .eq_ignore_ascii_case(name)
+                .then(|| value.trim().to_string())
+        })
+    });
+    inline
+        .or_else(|| stylesheet.properties(node).remove(name))
+        .or_else(|| node.attribute(name).map(str::to_string))
+}
+
+/// The `font-size` of text without one, `medium` in browsers.
+#[cfg(feature = "text")]
+const DEFAULT_FONT_SIZE: f32 = 16.0;
+
+/// Replace every run of whitespace with a single space.
+#[cfg(feature = "text")]
+fn collapse_whitespace(text: &str) -> String {
+    let mut collapsed = String::with_capacity(text.len());
+    for character in text.chars() {
+        if !character.is_whitespace() {
+            collapsed.push(character);
+        } else if !collapsed.ends_with(' ') {
+            collapsed.push(' ');
+        }
+    }
+    collapsed
+}
+
+/// The value of an inherited property on `node` or the closest ancestor setting it, with the
+/// element it is set on.
+fn inherited_property<'a, 'input>(
+    node: Node<'a, 'input>,
+    stylesheet: &Stylesheet,
+    name: &str,
+) -> Option<(Node<'a, 'input>, String)> {
+    node.ancestors()
+        .find_map(|ancestor| property(ancestor, stylesheet, name).map(|value| (ancestor, value)))
+}
+
+/// The `stroke-dasharray` of `node` with an odd number of lengths repeated, and its
+/// `stroke-dashoffset`, or `None` for a solid stroke.
+fn dash_pattern(
+    node: Node<'_, '_>,
+    stylesheet: &Stylesheet,
+) -> Svg2PdcResult<Option<(Vec<f32>, f32)>> {
+    let Some((element, value)) = inherited_property(node, stylesheet, "stroke-dasharray") else {
+        return Ok(None);
+    };
+    if value.trim().eq_ignore_ascii_case("none") {
+        return Ok(None);
+    }
+    let mut pattern = value
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|length| !length.is_empty())
+        .map(|length| parse_number(element, "stroke-dasharray", length))
+        .collect::<Svg2PdcResult<Vec<_>>>()?;
+    // Negative lengths make the pattern invalid and a pattern without length is solid
+    if pattern.iter().any(|length| *length < 0.0) || pattern.iter().sum::<f32>() <= 0.0 {
+        return Ok(None);
+    }
+    if pattern.len() % 2 == 1 {
+        pattern.extend_from_within(..);
+    }
+    let offset = match inherited_property(node, stylesheet, "stroke-dashoffset") {
+        Some((element, value)) => parse_number(element, "stroke-dashoffset", &value)?,
+        None => 0.0,
+    };
+    Ok(Some((pattern, offset)))
+}
+
+/// Properties that draw content of other elements, which is left out of the image.
+const OMITTED_REFERENCES: &[&str] = &[
+    "clip-path",
+    "mask",
+    "marker",
+    "marker-start",
+    "marker-mid",
+    "marker-end",
+];
+
+/// Warn once when `node` or its descendants are clipped, masked or have markers, instead of
+/// for every element.
+fn warn_omitted_references(node: Node<'_, '_>, stylesheet: &Stylesheet) {
+    let elements = node
+        .descendants()
+        .filter(|node| node.is_element())
+        .filter(|node| {
+            OMITTED_REFERENCES.iter().any(|name| {
+                property(*node, stylesheet, name)
+                    .is_some_and(|value| !value.trim().eq_ignore_ascii_case("none"))
+            })
+        })
+        .count();
+    if elements > 0 {
+        DiagnosticCode::ClippingOmitted.warn(format_args!(
+            "{elements} elements are clipped, masked or have markers, clipping and masking are approximated by omission: the elements are drawn in full and their markers are left out"
+        ));
+    }
+}
+
+/// Parse a numeric attribute value using the SVG number grammar.
+///
+/// Exponents (`5e-3`), leading dots (`.5`) and absolute units (`1.0px`, `2mm`) are accepted,
+/// units are converted to user units (CSS pixels).
+fn parse_number(node: Node<'_, '_>, attribute: &str, value: &str) -> Svg2PdcResult<f32> {
+    use svgtypes::LengthUnit;
+
+    let invalid = || Svg2PdcError::InvalidNumber {
+        attribute: attribute.to_string(),
+        value: value.to_string(),
+        element: ElementLocation::of(node),
+    };
+    // Units are case-insensitive, like the exponent
+    let length =
+        svgtypes::Length::from_str(&value.trim().to_ascii_lowercase()).map_err(|_| invalid())?;
+    let scale = match length.unit {
+        LengthUnit::None | LengthUnit::Px => 1.0,
+        LengthUnit::In => 96.0,
+        LengthUnit::Cm => 96.0 / 2.54,
+        LengthUnit::Mm => 96.0 / 25.4,
+        LengthUnit::Pt => 96.0 / 72.0,
+        LengthUnit::Pc => 16.0,
+        LengthUnit::Em | LengthUnit::Ex | LengthUnit::Percent => return Err(invalid()),
+    };
+    Ok((length.number * scale) as f32)
+}
+
+/// Parse an opacity, either as a number or a percentage.
+fn parse_opacity(node: Node<'_, '_>, attribute: &str, value: &str) -> Svg2PdcResult<f64> {
+    match value.trim().strip_suffix('%') {
+        Some(percentage) => Ok(parse_number(node, attribute, percentage)? as f64 / 100.0),
+        None => Ok(parse_number(node, attribute, value)? as f64),
+    }
+}
+
+/// Parse a required numeric attribute of a shape.
+fn number_attribute(node: Node<'_, '_>, attribute: &str) -> Svg2PdcResult<f32> {
+    let value = node
+        .attribute(attribute)
+        .ok_or_else(|| Svg2PdcError::MissingAttribute {
+            attribute: attribute.to_string(),
+            element: ElementLocation::of(node),
+        })?;
+    parse_number(node, attribute, value)
+}
+
+/// The precision an element and its descendants ask for instead of the one of the converter,
+/// with a `data-pdc-precision="precise"` attribute or a `pdc-precise` class, or `normal`.
+fn element_precision(node: Node<'_, '_>) -> Svg2PdcResult<Option<Precision>> {
+    if let Some(value) = node.attribute("data-pdc-precision") {
+        return match value.trim().to_ascii_lowercase().as_str() {
+            "precise" => Ok(Some(Precision::Precise)),
+            "normal" => Ok(Some(Precision::Normal)),
+            _ => Err(invalid_attribute(
+                node,
+                "data-pdc-precision",
+                format!("`{value}` is neither `precise` nor `normal`"),
+            )),
+        };
+    }
+    let classes = node.attribute("class").unwrap_or("");
+    Ok(classes.split_whitespace().find_map(|class| match class {
+        "pdc-precise" => Some(Precision::Precise),
+        "pdc-normal" => Some(Precision::Normal),
+        _ => None,
+    }))
+}
+
+/// The error for an attribute svgtypes failed to parse, naming the element it belongs to.
+fn invalid_attribute(node: Node<'_, '_>, attribute: &str, error: impl ToString) -> Svg2PdcError {
+    Svg2PdcError::InvalidAttribute {
+        attribute: attribute.to_string(),
+        element: ElementLocation::of(node),
+        reason: error.to_string(),
+    }
+}
+
+/// Short reference to an element for warnings, e.g. `<rect id="frame"> at 12:5`.
+fn element_reference(node: Node<'_, '_>) -> String {
+    let position = node.document().text_pos_at(node.range().start);
+    match node.attribute("id") {
+        Some(id) => format!("<{} id=\"{}\"> at {}", node.tag_name().name(), id, position),
+        None => format!("<{}> at {}", node.tag_name().name(), position),
+    }
+}
+
+/// A subpath is closed when it ends where it started, the repeated point is dropped.
+fn close_subpath(mut points: Vec<FPoint>) -> Shape {
+    let first = *points.first().unwrap_or(&FPoint::default());
+    let last = *points.last().unwrap_or(&FPoint::default());
+
+    let open = first != last;
+
+    if !open {
+        points.pop();
+    }
+
+    Shape::Path { points, open }
+}
+
+/// Largest distance in pixels between a circle and the polygon replacing it.
+const CIRCLE_TOLERANCE: f32 = 0.125;
+
+/// A closed polygon inscribed in a circle, with enough corners to stay within
+/// [`CIRCLE_TOLERANCE`] of it.
+fn circle_polygon(center: FPoint, radius: f32) -> Shape {
+    let segments = if radius > CIRCLE_TOLERANCE {
+        (std::f32::consts::PI / (1.0 - CIRCLE_TOLERANCE / radius).acos()).ceil()
+    } else {
+        0.0
+    }
+    .clamp(8.0, 128.0) as usize;
+    let points = (0..segments)
+        .map(|segment| {
+            let angle = std::f32::consts::TAU * segment as f32 / segments as f32;
+            center + FPoint::new(angle.cos(), angle.sin()) * radius
+        })
+        .collect();
+    Shape::Path {
+        points,
+        open: false,
+    }
+}
+
+/// Deepest subdivision of a curve, a curve is split into at most 2^depth lines.
+const MAX_FLATTENING_DEPTH: u32 = 10;
+
+/// Add the points of a cubic Bézier after its start point as lines that stay within
+/// `tolerance` of the curve, subdividing it where it isn't flat enough yet.
+fn flatten_cubic(points: &mut Vec<FPoint>, curve: [FPoint; 4], tolerance: f32, depth: u32) {
+    let [start, first, second, end] = curve;
+    let flat = line_distance(first, start, end).max(line_distance(second, start, end)) <= tolerance;
+    if flat || depth >= MAX_FLATTENING_DEPTH {
+        points.push(end);
+        return;
+    }
+    // De Casteljau split at the middle of the curve
+    let mid = |a: FPoint, b: FPoint| (a + b) * 0.5;
+    let (a, b, c) = (mid(start, first), mid(first, second), mid(second, end));
+    let (d, e) = (mid(a, b), mid(b, c));
+    let middle = mid(d, e);
+    flatten_cubic(points, [start, a, d, middle], tolerance, depth + 1);
+    flatten_cubic(points, [middle, e, c, end], tolerance, depth + 1);
+}
+
+/// Distance from `point` to the line through `a` and `b`, or to `a` when they coincide.
+fn line_distance(point: FPoint, a: FPoint, b: FPoint) -> f32 {
+    let (direction, offset) = (b - a, point - a);
+    let length = (direction.x * direction.x + direction.y * direction.y).sqrt();
+    if length == 0.0 {
+        (offset.x * offset.x + offset.y * offset.y).sqrt()
+    } else {
+        (direction.x * offset.y - direction.y * offset.x).abs() / length
+    }
+}
+
+/// Split a polyline into the dashes of `pattern`, alternating dash and gap lengths, starting
+/// `offset` into the pattern. Dash ends within `tolerance` of a corner are moved onto it, so
+/// corners stay sharp and on the grid.
+fn dash_polyline(
+    points: &[FPoint],
+    pattern: &[f32],
+    offset: f32,
+    tolerance: f32,
+) -> Vec<Vec<FPoint>> {
+    if points.len() < 2 {
+        return Vec::new();
+    }
+    let total: f32 = pattern.iter().sum();
+    let mut index = 0;
+    let mut left = pattern[0];
+    let mut offset = offset.rem_euclid(total);
+    while offset >= left {
+        offset -= left;
+        index = (index + 1) % pattern.len();
+        left = pattern[index];
+    }
+    left -= offset;
+
+    let mut dashes = Vec::new();
+    let mut dash = (index % 2 == 0).then(|| points[..1].to_vec());
+    for segment in points.windows(2) {
+        let (start, end) = (segment[0], segment[1]);
+        let delta = end - start;
+        let length = (delta.x * delta.x + delta.y * delta.y).sqrt();
+        let mut position = 0.0;
+        // Every dash or gap ending on this segment toggles the dash
+        while length - position > left {
+            position += left;
+            let point = start + (end - start) * (position / length);
+            let point = if position <= tolerance {
+                start
+            } else if length - position <= tolerance {
+                end
+            } else {
+                point
+            };
+            match dash.take() {
+                Some(mut points) => {
+                    points.push(point);
+                    dashes.push(points);
+                }
+                None => dash = Some(vec![point]),
+            }
+            index = (index + 1) % pattern.len();
+            left = pattern[index];
+        }
+        left -= length - position;
+        if let Some(points) = &mut dash {
+            points.push(end);
+        }
+    }
+    dashes.extend(dash);
+    for dash in &mut dashes {
+        dash.dedup();
+    }
+    // Dashes of zero length, like the dots of `0 4`, can't be drawn without round caps
+    dashes.retain(|dash| dash.len() > 1);
+    dashes
+}
+
+/// Geometry of an element in document space, before it is converted to Pebble coordinates.
+#[derive(Debug, Clone)]
+enum Shape {
+    Path { points: Vec<FPoint>, open: bool },
+    Circle { center: FPoint, radius: f32 },
+}
+
+impl Shape {
+    /// Scale the shape along both axes, a circle scaled unevenly becomes a polygon.
+    fn scaled(self, scale: FPoint) -> Self {
+        match self {
+            Self::Path { points, open } => Self::Path {
+                points: points
+                    .into_iter()
+                    .map(|point| FPoint::new(point.x * scale.x, point.y * scale.y))
+                    .collect(),
+                open,
+            },
+            Self::Circle { center, radius } if scale.x == scale.y => Self::Circle {
+                center: center * scale.x,
+                radius: radius * scale.x,
+            },
+            Self::Circle { center, radius } => circle_polygon(center, radius).scaled(scale),
+        }
+    }
+
+    /// Check whether the translated shape overlaps the rectangle from the origin to `size`.
+    fn intersects(&self, translation: &FPoint, size: &FPoint) -> bool {
+        let (min, max) = match self {
+            Self::Path { points, .. } => points.iter().fold(
+                (
+                    FPoint::new(f32::INFINITY, f32::INFINITY),
+                    FPoint::new(f32::NEG_INFINITY, f32::NEG_INFINITY),
+                ),
+                |(min, max), point| {
+                    (
+                        FPoint::new(min.x.min(point.x), min.y.min(point.y)),
+                        FPoint::new(max.x.max(point.x), max.y.max(point.y)),
+                    )
+                },
+            ),
+            Self::Circle { center, radius } => (
+                *center - FPoint::new(*radius, *radius),
+                *center + FPoint::new(*radius, *radius),
+            ),
+        };
+        let (min, max) = (min + *translation, max + *translation);
+        min.x <= size.x && min.y <= size.y && max.x >= 0.0 && max.y >= 0.0
+    }
+
+    /// Check whether any part of the translated shape lies outside of `circle`.
+    fn exceeds(&self, translation: &FPoint, circle: &DisplayCircle) -> bool {
+        match self {
+            Self::Path { points, .. } => points
+                .iter()
+                .any(|point| circle.distance(*point + *translation) > circle.radius),
+            Self::Circle { center, radius } => {
+                circle.distance(*center + *translation) + radius > circle.radius
+            }
+        }
+    }
+
+    /// Pull the parts of the translated shape outside of `circle` onto its edge.
+    ///
+    /// Returns `None` when nothing of the shape is left on the display.
+    fn clip(self, translation: &FPoint, circle: &DisplayCircle) -> Option<Self> {
+        match self {
+            Self::Path { ref points, open } => {
+                let mut segments: Vec<_> =
+                    points.windows(2).map(|pair| (pair[0], pair[1])).collect();
+                if let (Some(&first), Some(&last)) = (points.first(), points.last())
+                    && (!open || segments.is_empty())
+                {
+                    segments.push((last, first));
+                }
+                let off_screen = segments.into_iter().all(|(a, b)| {
+                    circle.segment_distance(a + *translation, b + *translation) >= circle.radius
+                }) && !self.contains(circle.center - *translation);
+                if off_screen {
+                    return None;
+                }
+                let points = points
+                    .iter()
+                    .map(|&point| {
+                        let point = point + *translation;
+                        let distance = circle.distance(point);
+                        let point = if distance > circle.radius {
+                            circle.center + (point - circle.center) * (circle.radius / distance)
+                        } else {
+                            point
+                        };
+                        point - *translation
+                    })
+                    .collect();
+                Some(Self::Path { points, open })
+            }
+            Self::Circle { center, radius } => {
+                let distance = circle.distance(center + *translation);
+                let radius = radius.min(circle.radius - distance);
+                (radius > 0.0).then_some(Self::Circle { center, radius })
+            }
+        }
+    }
+
+    /// Check whether a point lies inside a closed path, using the even-odd rule.
+    fn contains(&self, point: FPoint) -> bool {
+        let Self::Path {
+            points,
+            open: false,
+        } = self
+        else {
+            return false;
+        };
+        points
+            .iter()
+            .zip(points.iter().cycle().skip(1))
+            .filter(|(a, b)| {
+                (a.y > point.y) != (b.y > point.y)
+                    && point.x < a.x + (point.y - a.y) / (b.y - a.y) * (b.x - a.x)
+            })
+            .count()
+            % 2
+            == 1
+    }
+}
+
+/// Scale and offset from the user space of a document onto its canvas, applied to shapes
+/// after the translations of the document.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct ViewTransform {
+    scale: FPoint,
+    offset: FPoint,
+}
+
+impl ViewTransform {
+    const IDENTITY: Self = Self {
+        scale: FPoint::new(1.0, 1.0),
+        offset: FPoint::new(0.0, 0.0),
+    };
+
+    /// Map a view box of `size` onto a viewport following its `preserveAspectRatio`.
+    fn view_box(size: FPoint, viewport: FPoint, aspect: AspectRatio) -> Self {
+        let scale = FPoint::new(viewport.x / size.x, viewport.y / size.y);
+        if !(scale.x.is_finite() && scale.y.is_finite() && scale.x > 0.0 && scale.y > 0.0) {
+            return Self::IDENTITY;
+        }
+        let (align_x, align_y) = match aspect.align {
+            Align::None => {
+                return Self {
+                    scale,
+                    offset: FPoint::default(),
+                };
+            }
+            Align::XMinYMin => (0.0, 0.0),
+            Align::XMidYMin => (0.5, 0.0),
+            Align::XMaxYMin => (1.0, 0.0),
+            Align::XMinYMid => (0.0, 0.5),
+            Align::XMidYMid => (0.5, 0.5),
+            Align::XMaxYMid => (1.0, 0.5),
+            Align::XMinYMax => (0.0, 1.0),
+            Align::XMidYMax => (0.5, 1.0),
+            Align::XMaxYMax => (1.0, 1.0),
+        };
+        let uniform = match aspect.slice {
+            true => scale.x.max(scale.y),
+            false => scale.x.min(scale.y),
+        };
+        Self {
+            scale: FPoint::new(uniform, uniform),
+            offset: FPoint::new(
+                (viewport.x - size.x * uniform) * align_x,
+                (viewport.y - size.y * uniform) * align_y,
+            ),
+        }
+    }
+
+    /// Scale both axes by `factor`.
+    fn scale(factor: f32) -> Self {
+        Self {
+            scale: FPoint::new(factor, factor),
+            offset: FPoint::default(),
+        }
+    }
+
+    /// Scale `size` uniformly to fit into `target` and center it there.
+    fn fit(size: FPoint, target: FPoint) -> Self {
+        Self::view_box(size, target, AspectRatio::default())
+    }
+
+    /// The transform applying `self` first and `next` after it.
+    fn then(self, next: Self) -> Self {
+        Self {
+            scale: FPoint::new(self.scale.x * next.scale.x, self.scale.y * next.scale.y),
+            offset: next.apply(self.offset),
+        }
+    }
+
+    fn apply(&self, point: FPoint) -> FPoint {
+        FPoint::new(
+            point.x * self.scale.x + self.offset.x,
+            point.y * self.scale.y + self.offset.y,
+        )
+    }
+
+    /// Factor stroke widths are scaled by, the geometric mean of the scales of both axes.
+    fn stroke_scale(&self) -> f32 {
+        (self.scale.x * self.scale.y).sqrt()
+    }
+}
+
+/// The visible area of a round display, in the coordinates of the converted image.
+#[derive(Debug, Clone, Copy)]
+struct DisplayCircle {
+    center: FPoint,
+    radius: f32,
+}
+
+impl DisplayCircle {
+    /// The circle inscribed in the screen of `platform`, with the image centered on the screen.
+    fn new(platform: Platform, size: FPoint) -> Option<Self> {
+        platform.is_round().then(|| Self {
+            center: size / 2.0,
+            radius: platform.screen_size().x as f32 / 2.0,
+        })
+    }
+
+    fn distance(&self, point: FPoint) -> f32 {
+        let delta = point - self.center;
+        (delta.x * delta.x + delta.y * delta.y).sqrt()
+    }
+
+    /// Distance from the center to the closest point of the segment from `a` to `b`.
+    fn segment_distance(&self, a: FPoint, b: FPoint) -> f32 {
+        let direction = b - a;
+        let length = direction.x * direction.x + direction.y * direction.y;
+        if length == 0.0 {
+            return self.distance(a);
+        }
+        let to_center = self.center - a;
+        let t = ((to_center.x * direction.x + to_center.y * direction.y) / length).clamp(0.0, 1.0);
+        self.distance(a + direction * t)
+    }
+}
+
+/// A page of an Inkscape 1.2+ multi-page document.
+///
+/// Pages are stored as `<inkscape:page>` elements inside `<sodipodi:namedview>`, with their
+/// position and size given in user units.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InkscapePage {
+    pub id: Option<String>,
+    pub label: Option<String>,
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+impl InkscapePage {
+    const INKSCAPE_NAMESPACE: &str = "http://www.inkscape.org/namespaces/inkscape";
+
+    /// The label of the page, falling back to its id.
+    pub fn name(&self) -> Option<&str> {
+        self.label.as_deref().or(self.id.as_deref())
+    }
+
+    fn from_node(node: Node<'_, '_>) -> Svg2PdcResult<Self> {
+        let dimension = |name: &str| parse_number(node, name, node.attribute(name).unwrap_or("0"));
+        Ok(Self {
+            id: node.attribute("id").map(str::to_string),
+            label: node
+                .attribute((Self::INKSCAPE_NAMESPACE, "label"))
+                .map(str::to_string),
+            x: dimension("x")?,
+            y: dimension("y")?,
+            width: dimension("width")?,
+            height: dimension("height")?,
+        })
+    }
+}
+
+/// Options for converting part of a document with [`SvgConverter::convert_node`].
+#[derive(Debug, Clone, Copy)]
+pub struct FragmentOptions {
+    /// Size of the canvas the fragment is drawn on.
+    pub size: PebblePoint,
+    /// Translation applied on top of the transforms of the fragment and its ancestors.
+    pub translate: FPoint,
+    pub truncate_color: TruncateColor,
+    pub conversion: Conversion,
+}
+
+/// How the colors, the points and the stroke widths of a document are converted.
+///
+/// New options get a default, so build the options with `..Default::default()` to keep
+/// compiling when they are added.
+///
+/// ```rust
+/// use svg2pdc::prelude::*;
+///
+/// let converter = SvgConverter::new(Precision::Normal).with_options(ConvertOptions {
+///     precision: Precision::Precise,
+///     min_stroke_width: Some(2),
+///     ..Default::default()
+/// });
+/// let image = converter
+///     .convert(r#"<svg xmlns="http://www.w3.org/2000/svg" width="10" height="10"><path d="M1 1 L9 1" stroke="red" stroke-width="0.5"/></svg>"#)
+///     .unwrap();
+/// assert_eq!(image.commands[0].options().precision, Precision::Precise);
+/// assert_eq!(image.commands[0].options().stroke_width, 2);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct ConvertOptions {
+    /// Used by [`SvgConverter::convert`], the other entry points take it as an argument.
+    pub truncate_color: TruncateColor,
+    /// Used by [`SvgConverter::convert`], the other entry points take it as an argument.
+    pub conversion: Conversion,
+    /// Elements and groups can override it with a `data-pdc-precision` attribute or a
+    /// `pdc-precise` or `pdc-normal` class.
     pub precision: Precision,
+    pub output_mode: OutputMode,
+    /// Overrides the pixel convention implied by the output mode.
+    pub pixel_convention: Option<PixelConvention>,
+    /// Rounding of points, [`OutputMode::default_rounding_mode`] when unset.
+    pub rounding_mode: Option<RoundingMode>,
+    /// Largest distance in pixels between a Bézier curve or an elliptical arc and the lines
+    /// replacing it. Without it curves and arcs are reduced to their end points, like the
+    /// original tool does.
+    pub curve_tolerance: Option<f32>,
+    /// How far the stored stroke width may be from the requested one before a warning is printed.
+    pub stroke_width_tolerance: f32,
+    /// Rounding of stroke widths to whole pixels, truncated like the original tool when unset.
+    pub stroke_width_rounding: Option<RoundingMode>,
+    /// Smallest stroke width of stroked elements, so that hairlines don't vanish on the watch.
+    pub min_stroke_width: Option<u8>,
+    /// Draw dashed strokes as one open path per dash instead of solid. Dash ends within this
+    /// distance in pixels of a corner of the path are moved onto it.
+    pub dash_tolerance: Option<f32>,
+}
+
+impl Default for ConvertOptions {
+    fn default() -> Self {
+        Self {
+            truncate_color: TruncateColor::default(),
+            conversion: Conversion::default(),
+            precision: Precision::default(),
+            output_mode: OutputMode::default(),
+            pixel_convention: None,
+            rounding_mode: None,
+            curve_tolerance: None,
+            stroke_width_tolerance: SvgConverter::DEFAULT_STROKE_WIDTH_TOLERANCE,
+            stroke_width_rounding: None,
+            min_stroke_width: None,
+            dash_tolerance: None,
+        }
+    }
+}
+
+/// What happens to the holes of a filled path, like the inside of a donut, which the watch
+/// would fill, because it fills every subpath of a path on its own.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum HoleHandling {
+    /// Keep the subpaths as they are and warn that the holes are filled.
+    #[default]
+    Warn,
+    /// Join every hole to the subpath around it with a zero-width cut, so that the region
+    /// around it is filled without it. The stroke is drawn separately, without the cuts.
+    Bridge,
+    /// Draw only the outline of the path, in its stroke or else in its fill color.
+    Outline,
+}
+
+#[derive(Debug, Clone)]
+pub struct SvgConverter {
+    /// How colors, points and stroke widths are converted.
+    pub options: ConvertOptions,
+    /// Platform the image is converted for, round displays check what ends up off-screen.
+    pub platform: Option<Platform>,
+    /// Pull geometry outside of a round display onto its edge instead of only warning.
+    pub clip_to_display: bool,
+    /// Size of the page being converted, elements entirely outside of it are skipped.
+    page_clip: Option<FPoint>,
+    /// Visible area of a round display for the canvas being converted.
+    display_circle: Option<DisplayCircle>,
+    /// Applied to every color before it is quantized.
+    pub color_adjustment: ColorAdjustment,
+    /// Canvas size whole documents are scaled into uniformly, centered, together with their
+    /// stroke widths.
+    pub fit: Option<FPoint>,
+    /// Factor documents and pages are scaled by after the view box and [`Self::fit`], together
+    /// with their canvas and stroke widths.
+    pub scale: Option<f32>,
+    /// Scale and offset onto the canvas of the document being converted.
+    canvas_transform: Option<ViewTransform>,
+    /// Elements skipped without a warning, by local name or as `prefix:name`.
+    pub ignored_tags: Vec<String>,
+    /// Fail instead of warning on elements that are neither supported nor ignored, paints
+    /// that are not colors and curves reduced to their end points without a
+    /// [`ConvertOptions::curve_tolerance`].
+    pub strict: bool,
+    /// In precise images, draw circles with a sub-pixel center as sampled precise paths.
+    pub circles_as_paths: bool,
+    /// Largest radius in pixels of the circles drawn as paths with [`Self::circles_as_paths`].
+    /// The paths of large circles have many points, a circle command is much smaller.
+    pub circle_path_max_radius: Option<f32>,
+    /// Convert the top-level groups of a document on separate threads. Warnings of different
+    /// groups may then be printed out of document order.
+    pub parallel: bool,
+    /// Color of `currentColor` paints, which draw nothing without one.
+    pub current_color: Option<Color>,
+    /// Colors replaced by others before they are quantized, matched by their red, green and
+    /// blue. The paint keeps its opacity, and the first mapping of a color wins.
+    pub color_map: Vec<(Color, Color)>,
+    /// What happens to the holes of filled paths, see [`HoleHandling`].
+    pub holes: HoleHandling,
+    /// Font `<text>` elements are outlined with, they are skipped as unsupported without one.
+    #[cfg(feature = "text")]
+    pub text_font: Option<TextFont>,
+    /// The frame layer drawn when converting a frame of an animation, the other frame
+    /// layers are skipped.
+    frame: Option<FrameLayers>,
+}
+
+/// Builds an [`SvgConverter`] one setting at a time, every setting left out keeps the
+/// default of [`SvgConverter::new`].
+///
+/// ```rust
+/// use svg2pdc::{point::Conversion, prelude::*};
+///
+/// let converter = SvgConverter::builder()
+///     .precision(Precision::Precise)
+///     .truncate_color(TruncateColor::Truncate)
+///     .conversion(Conversion::ConvertNoWarn)
+///     .rounding_mode(RoundingMode::Round)
+///     .curve_tolerance(0.25)
+///     .build();
+/// let image = converter
+///     .convert(r#"<svg xmlns="http://www.w3.org/2000/svg" width="10" height="10"><path d="M1 1 Q5 9 9 1" stroke="red"/></svg>"#)
+///     .unwrap();
+/// assert!(matches!(&image.commands[0], DrawCommand::Path { points, .. } if points.len() > 2));
+/// ```
+#[derive(Debug, Clone)]
+pub struct SvgConverterBuilder {
+    converter: SvgConverter,
+}
+
+impl Default for SvgConverterBuilder {
+    fn default() -> Self {
+        Self {
+            converter: SvgConverter::new(Precision::default()),
+        }
+    }
+}
+
+impl SvgConverterBuilder {
+    pub fn precision(mut self, precision: Precision) -> Self {
+        self.converter.options.precision = precision;
+        self
+    }
+
+    pub fn options(mut self, options: ConvertOptions) -> Self {
+        self.converter.options = options;
+        self
+    }
+
+    pub fn truncate_color(mut self, truncate_color: TruncateColor) -> Self {
+        self.converter.options.truncate_color = truncate_color;
+        self
+    }
+
+    pub fn conversion(mut self, conversion: Conversion) -> Self {
+        self.converter.options.conversion = conversion;
+        self
+    }
+
+    pub fn output_mode(self, output_mode: OutputMode) -> Self {
+        self.map(|converter| converter.with_output_mode(output_mode))
+    }
+
+    pub fn pixel_convention(self, pixel_convention: PixelConvention) -> Self {
+        self.map(|converter| converter.with_pixel_convention(pixel_convention))
+    }
+
+    pub fn rounding_mode(self, rounding_mode: RoundingMode) -> Self {
+        self.map(|converter| converter.with_rounding_mode(rounding_mode))
+    }
+
+    pub fn curve_tolerance(self, curve_tolerance: f32) -> Self {
+        self.map(|converter| converter.with_curve_tolerance(curve_tolerance))
+    }
+
+    pub fn stroke_width_tolerance(self, stroke_width_tolerance: f32) -> Self {
+        self.map(|converter| converter.with_stroke_width_tolerance(stroke_width_tolerance))
+    }
+
+    pub fn stroke_width_rounding(self, stroke_width_rounding: RoundingMode) -> Self {
+        self.map(|converter| converter.with_stroke_width_rounding(stroke_width_rounding))
+    }
+
+    pub fn min_stroke_width(self, min_stroke_width: u8) -> Self {
+        self.map(|converter| converter.with_min_stroke_width(min_stroke_width))
+    }
+
+    pub fn platform(self, platform: Platform) -> Self {
+        self.map(|converter| converter.with_platform(platform))
+    }
+
+    pub fn clip_to_display(self, clip_to_display: bool) -> Self {
+        self.map(|converter| converter.with_clip_to_display(clip_to_display))
+    }
+
+    pub fn fit(self, size: FPoint) -> Self {
+        self.map(|converter| converter.with_fit(size))
+    }
+
+    pub fn scale(self, factor: f32) -> Self {
+        self.map(|converter| converter.with_scale(factor))
+    }
+
+    pub fn color_adjustment(self, color_adjustment: ColorAdjustment) -> Self {
+        self.map(|converter| converter.with_color_adjustment(color_adjustment))
+    }
+
+    pub fn current_color(self, current_color: Color) -> Self {
+        self.map(|converter| converter.with_current_color(current_color))
+    }
+
+    pub fn color_map(self, color_map: Vec<(Color, Color)>) -> Self {
+        self.map(|converter| converter.with_color_map(color_map))
+    }
+
+    pub fn dash_tolerance(self, dash_tolerance: f32) -> Self {
+        self.map(|converter| converter.with_dash_tolerance(dash_tolerance))
+    }
+
+    pub fn holes(self, holes: HoleHandling) -> Self {
+        self.map(|converter| converter.with_holes(holes))
+    }
+
+    #[cfg(feature = "text")]
+    pub fn text_font(self, text_font: TextFont) -> Self {
+        self.map(|converter| converter.with_text_font(text_font))
+    }
+
+    pub fn ignored_tags(self, ignored_tags: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.map(|converter| converter.with_ignored_tags(ignored_tags))
+    }
+
+    pub fn strict(self, strict: bool) -> Self {
+        self.map(|converter| converter.with_strict(strict))
+    }
+
+    pub fn circles_as_paths(self, circles_as_paths: bool) -> Self {
+        self.map(|converter| converter.with_circles_as_paths(circles_as_paths))
+    }
+
+    pub fn circle_path_max_radius(self, circle_path_max_radius: f32) -> Self {
+        self.map(|converter| converter.with_circle_path_max_radius(circle_path_max_radius))
+    }
+
+    pub fn parallel(self, parallel: bool) -> Self {
+        self.map(|converter| converter.with_parallel(parallel))
+    }
+
+    pub fn build(self) -> SvgConverter {
+        self.converter
+    }
+
+    fn map(self, configure: impl FnOnce(SvgConverter) -> SvgConverter) -> Self {
+        Self {
+            converter: configure(self.converter),
+        }
+    }
+}
+
+/// The top-level groups of a document holding the frames of an animation, and the one of
+/// them being converted.
+#[derive(Debug, Clone)]
+struct FrameLayers {
+    layers: Vec<NodeId>,
+    current: NodeId,
+    /// Whether the content outside of the frame layers is drawn as well.
+    shared: bool,
 }
 
 impl SvgConverter {
+    /// Stroke widths are truncated to whole pixels like the original tool does, so this keeps
+    /// quiet about `1.5px` strokes becoming `1`.
+    pub const DEFAULT_STROKE_WIDTH_TOLERANCE: f32 = 0.5;
+
+    /// Dash ends closer than half a pixel to a corner would only cut off a sliver of it.
+    pub const DEFAULT_DASH_TOLERANCE: f32 = 0.5;
+
+    /// Containers whose content is only drawn through a reference, like a `clip-path`, and is
+    /// always skipped with its descendants. See [`DiagnosticCode::ClippingOmitted`].
+    pub const NON_RENDERED_TAGS: &[&str] = &["clipPath", "mask", "marker", "metadata"];
+
+    /// Elements that never hold artwork, like editor metadata, and are skipped silently.
+    pub const DEFAULT_IGNORED_TAGS: &[&str] = &[
+        "metadata",
+        "title",
+        "desc",
+        "defs",
+        "script",
+        "style",
+        "sodipodi:namedview",
+    ];
+
     pub fn new(precision: Precision) -> Self {
-        Self { precision }
+        Self {
+            options: ConvertOptions {
+                precision,
+                ..ConvertOptions::default()
+            },
+            platform: None,
+            clip_to_display: false,
+            page_clip: None,
+            display_circle: None,
+            color_adjustment: ColorAdjustment::default(),
+            fit: None,
+            scale: None,
+            canvas_transform: None,
+            ignored_tags: Self::DEFAULT_IGNORED_TAGS
+                .iter()
+                .map(|tag| tag.to_string())
+                .collect(),
+            strict: false,
+            circles_as_paths: false,
+            circle_path_max_radius: None,
+            parallel: false,
+            current_color: None,
+            color_map: Vec::new(),
+            holes: HoleHandling::default(),
+            #[cfg(feature = "text")]
+            text_font: None,
+            frame: None,
+        }
+    }
+
+    /// Configure every setting of a converter by name, see [`SvgConverterBuilder`].
+    pub fn builder() -> SvgConverterBuilder {
+        SvgConverterBuilder::default()
+    }
+
+    pub fn with_options(self, options: ConvertOptions) -> Self {
+        Self { options, ..self }
+    }
+
+    pub fn with_output_mode(mut self, output_mode: OutputMode) -> Self {
+        self.options.output_mode = output_mode;
+        self
+    }
+
+    pub fn with_pixel_convention(mut self, pixel_convention: PixelConvention) -> Self {
+        self.options.pixel_convention = Some(pixel_convention);
+        self
+    }
+
+    pub fn with_rounding_mode(mut self, rounding_mode: RoundingMode) -> Self {
+        self.options.rounding_mode = Some(rounding_mode);
+        self
+    }
+
+    pub fn with_platform(self, platform: Platform) -> Self {
+        Self {
+            platform: Some(platform),
+            ..self
+        }
+    }
+
+    /// Scale documents uniformly to fit into `size` and center them there, like fitting an
+    /// icon to the screen of a platform.
+    pub fn with_fit(self, size: FPoint) -> Self {
+        Self {
+            fit: Some(size),
+            ..self
+        }
+    }
+
+    /// Scale documents by `factor` while they are converted. Unlike [`PebbleImage::scale`]
+    /// after the conversion, stroke widths are only rounded once, after scaling.
+    pub fn with_scale(self, factor: f32) -> Self {
+        Self {
+            scale: Some(factor),
+            ..self
+        }
+    }
+
+    pub fn with_curve_tolerance(mut self, curve_tolerance: f32) -> Self {
+        self.options.curve_tolerance = Some(curve_tolerance);
+        self
+    }
+
+    pub fn with_circles_as_paths(self, circles_as_paths: bool) -> Self {
+        Self {
+            circles_as_paths,
+            ..self
+        }
+    }
+
+    pub fn with_circle_path_max_radius(self, circle_path_max_radius: f32) -> Self {
+        Self {
+            circle_path_max_radius: Some(circle_path_max_radius),
+            ..self
+        }
+    }
+
+    pub fn with_current_color(self, current_color: Color) -> Self {
+        Self {
+            current_color: Some(current_color),
+            ..self
+        }
+    }
+
+    pub fn with_color_map(self, color_map: Vec<(Color, Color)>) -> Self {
+        Self { color_map, ..self }
     }
+
+    pub fn with_dash_tolerance(mut self, dash_tolerance: f32) -> Self {
+        self.options.dash_tolerance = Some(dash_tolerance);
+        self
+    }
+
+    pub fn with_holes(self, holes: HoleHandling) -> Self {
+        Self { holes, ..self }
+    }
+
+    #[cfg(feature = "text")]
+    pub fn with_text_font(self, text_font: TextFont) -> Self {
+        Self {
+            text_font: Some(text_font),
+            ..self
+        }
+    }
+
+    pub fn with_parallel(self, parallel: bool) -> Self {
+        Self { parallel, ..self }
+    }
+
+    pub fn with_clip_to_display(self, clip_to_display: bool) -> Self {
+        Self {
+            clip_to_display,
+            ..self
+        }
+    }
+
+    /// A copy of the converter checking against the display for a canvas of `size`.
+    fn for_canvas(&self, size: FPoint) -> Self {
+        Self {
+            display_circle: self
+                .platform
+                .and_then(|platform| DisplayCircle::new(platform, size)),
+            ..self.clone()
+        }
+    }
+
+    pub fn with_stroke_width_tolerance(mut self, stroke_width_tolerance: f32) -> Self {
+        self.options.stroke_width_tolerance = stroke_width_tolerance;
+        self
+    }
+
+    pub fn with_stroke_width_rounding(mut self, stroke_width_rounding: RoundingMode) -> Self {
+        self.options.stroke_width_rounding = Some(stroke_width_rounding);
+        self
+    }
+
+    /// Raise the stroke width of stroked elements to at least `min_stroke_width` pixels,
+    /// elements with a zero stroke width still have no stroke.
+    pub fn with_min_stroke_width(mut self, min_stroke_width: u8) -> Self {
+        self.options.min_stroke_width = Some(min_stroke_width);
+        self
+    }
+
+    pub fn with_color_adjustment(self, color_adjustment: ColorAdjustment) -> Self {
+        Self {
+            color_adjustment,
+            ..self
+        }
+    }
+
+    /// Replace the [`Self::DEFAULT_IGNORED_TAGS`].
+    pub fn with_ignored_tags(
+        self,
+        ignored_tags: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        Self {
+            ignored_tags: ignored_tags.into_iter().map(Into::into).collect(),
+            ..self
+        }
+    }
+
+    pub fn with_strict(self, strict: bool) -> Self {
+        Self { strict, ..self }
+    }
+
+    /// Whether `node` is in the ignored tags, by its local name or its `prefix:name`.
+    fn is_ignored(&self, node: Node<'_, '_>) -> bool {
+        let name = node.tag_name().name();
+        let qualified = node
+            .tag_name()
+            .namespace()
+            .and_then(|namespace| node.lookup_prefix(namespace))
+            .map(|prefix| format!("{prefix}:{name}"));
+        self.ignored_tags
+            .iter()
+            .any(|tag| tag == name || Some(tag) == qualified.as_ref())
+    }
+
     fn get_viewbox(document: &Document<'_>) -> Svg2PdcResult<svgtypes::ViewBox> {
         let root = document.root_element();
         let view_box = match root.attribute("viewBox") {
-            Some(view_box) => ViewBox::from_str(view_box)?,
+            Some(view_box) => ViewBox::from_str(view_box)
+                .map_err(|error| invalid_attribute(root, "viewBox", error))?,
             None => ViewBox {
                 x: 0.0,
                 y: 0.0,
-                w: root.attribute("width").unwrap_or("0").parse().unwrap(),
-                h: root.attribute("height").unwrap_or("0").parse().unwrap(),
+                w: parse_number(root, "width", root.attribute("width").unwrap_or("0"))? as f64,
+                h: parse_number(root, "height", root.attribute("height").unwrap_or("0"))? as f64,
             },
         };
         Ok(view_box)
     }
 
+    /// The size the document is drawn at, its `width` and `height`. A missing or relative
+    /// one follows the aspect ratio of the view box, or is the size of the view box.
+    fn get_viewport(document: &Document<'_>, view_box_size: FPoint) -> Svg2PdcResult<FPoint> {
+        let root = document.root_element();
+        if !root.has_attribute("viewBox") {
+            return Ok(view_box_size);
+        }
+        let length = |attribute| match root.attribute(attribute) {
+            Some(value) if !value.trim().ends_with('%') => {
+                parse_number(root, attribute, value).map(Some)
+            }
+            _ => Ok(None),
+        };
+        let aspect = view_box_size.x / view_box_size.y;
+        Ok(match (length("width")?, length("height")?) {
+            (Some(width), Some(height)) => FPoint::new(width, height),
+            (Some(width), None) => FPoint::new(width, width / aspect),
+            (None, Some(height)) => FPoint::new(height * aspect, height),
+            (None, None) => view_box_size,
+        })
+    }
+
     fn get_commands(
         &self,
         translation: &FPoint,
         truncate_color: &TruncateColor,
         group_options: &GroupOptions,
         conversion: &Conversion,
+        stylesheet: &Stylesheet,
         node: Node<'_, '_>,
     ) -> Svg2PdcResult<Vec<DrawCommand>> {
-        let mut commands = Vec::new();
-
-        for child in node.children() {
-            let display = child.attribute("display");
-            if let Some("none") = display {
-                continue;
-            }
-            let tag = child.tag_name().name();
-
-            match tag {
-                "layer" | "g" => {
-                    if tag == "g" {
-                        let subgroup_options = GroupOptions {
-                            opacity: child
-                                .attribute("opacity")
-                                .map(|opacity| opacity.parse().unwrap()),
-                            fill_color: child.attribute("fill").map(|fill| fill.to_string()),
-                            fill_opacity: child
-                                .attribute("fill-opacity")
-                                .map(|fill_opacity| fill_opacity.parse().unwrap()),
-                            stroke_color: child
-                                .attribute("stroke")
-                                .map(|stroke| stroke.to_string()),
-                            stroke_opacity: child
-                                .attribute("stroke-opacity")
-                                .map(|stroke_opacity| stroke_opacity.parse().unwrap()),
-                            stroke_width: child.attribute("stroke-width").map(|stroke_width| {
-                                stroke_width
-                                    .chars()
-                                    .filter(|c| "1234567890.".contains(*c))
-                                    .collect::<String>()
-                                    .parse()
-                                    .unwrap()
-                            }),
-                        };
+        let children: Vec<_> = node
+            .children()
+            .filter(|child| match &self.frame {
+                // Animators usually hide all but the frame they are working on
+                Some(frame) if frame.layers.contains(&child.id()) => child.id() == frame.current,
+                Some(frame) if !frame.shared && node.parent_element().is_none() => false,
+                _ => !property(*child, stylesheet, "display")
+                    .is_some_and(|display| display.trim().eq_ignore_ascii_case("none")),
+            })
+            .collect();
+        let child_commands = |child| {
+            self.get_child_commands(
+                translation,
+                truncate_color,
+                group_options,
+                conversion,
+                stylesheet,
+                child,
+            )
+        };
 
-                        let translate = self.get_child_translation(child)?;
+        // Top-level groups are independent of each other, so they can be converted at the
+        // same time and joined in document order, and so are their warnings
+        let command_lists: Vec<_> = if self.parallel && node.parent_element().is_none() {
+            let collecting = diagnostic::is_collecting();
+            std::thread::scope(|scope| {
+                let handles: Vec<_> = children
+                    .iter()
+                    .map(|&child| {
+                        child.has_tag_name("g").then(|| {
+                            scope.spawn(move || match collecting {
+                                true => diagnostic::collect(|| child_commands(child)),
+                                false => (child_commands(child), ConversionReport::default()),
+                            })
+                        })
+                    })
+                    .collect();
+                children
+                    .iter()
+                    .zip(handles)
+                    .map(|(&child, handle)| match handle {
+                        Some(handle) => {
+                            let (commands, report) = handle
+                                .join()
+                                .unwrap_or_else(|panic| std::panic::resume_unwind(panic));
+                            diagnostic::forward(report);
+                            commands
+                        }
+                        None => child_commands(child),
+                    })
+                    .collect()
+            })
+        } else {
+            children.into_iter().map(child_commands).collect()
+        };
 
-                        commands.extend(self.get_commands(
-                            &(translate + *translation),
-                            truncate_color,
-                            &subgroup_options,
-                            conversion,
-                            child,
-                        )?);
-                    }
-                }
-                _ => {
-                    let translate = self.get_child_translation(child)? + *translation;
-                    let command = self.create_command(
-                        &translate,
-                        truncate_color,
-                        group_options,
-                        conversion,
-                        child,
-                    )?;
-                    if let Some(command) = command {
-                        commands.push(command);
-                    }
-                }
-            }
+        let mut commands = Vec::new();
+        for command_list in command_lists {
+            commands.extend(command_list?);
         }
         Ok(commands)
     }
 
-    fn create_command(
+    /// The commands for one child of a group, all of its descendants for a subgroup.
+    fn get_child_commands(
         &self,
         translation: &FPoint,
         truncate_color: &TruncateColor,
         group_options: &GroupOptions,
         conversion: &Conversion,
-        node: Node<'_, '_>,
-    ) -> Svg2PdcResult<Option<DrawCommand>> {
-        let mut style: HashMap<String, String> = node
-            .attribute("style")
-            .unwrap_or("")
-            .split(';')
-            .map(|style| {
-                let mut parts = style.split(':');
-                let key = parts.next().unwrap_or("").trim();
-                let value = parts.next().unwrap_or("").trim();
-                (key.to_string(), value.to_string())
-            })
-            .collect();
-        let attributes: HashMap<String, String> = node
-            .attributes()
-            .map(|attr| {
-                (
-                    attr.name().to_string().to_lowercase(),
-                    attr.value().to_string().to_lowercase(),
+        stylesheet: &Stylesheet,
+        child: Node<'_, '_>,
+    ) -> Svg2PdcResult<Vec<DrawCommand>> {
+        if let Some(precision) = element_precision(child)?
+            && precision != self.options.precision
+        {
+            let mut converter = self.clone();
+            converter.options.precision = precision;
+            return converter.get_child_commands(
+                translation,
+                truncate_color,
+                group_options,
+                conversion,
+                stylesheet,
+                child,
+            );
+        }
+        match child.tag_name().name() {
+            "g" => {
+                let subgroup_options =
+                    GroupOptions::from_node(child, stylesheet)?.or(group_options);
+                let translate = self.get_child_translation(child)?;
+                self.get_commands(
+                    &(translate + *translation),
+                    truncate_color,
+                    &subgroup_options,
+                    conversion,
+                    stylesheet,
+                    child,
                 )
-            })
-            .collect();
-
-        style.extend(attributes);
-
-        let stroke = style.get("stroke").or(group_options.stroke_color.as_ref());
-        let stroke_width = style
-            .get("stroke-width")
-            .map_or(group_options.stroke_width, |width| {
-                width.parse::<f32>().map(|n| n as u8).ok()
-            });
-
-        let fill = style.get("fill").or(group_options.fill_color.as_ref());
-
-        let opacity = style
-            .get("opacity")
-            .map_or(group_options.opacity, |opacity| {
-                Some(opacity.parse().unwrap())
-            })
-            .unwrap_or(1.0) as f32;
-        let stroke_opacity = style
-            .get("stroke-opacity")
-            .map_or(group_options.stroke_opacity, |opacity| {
-                Some(opacity.parse().unwrap())
-            })
-            .unwrap_or(1.0) as f32;
+            }
+            "layer" => Ok(Vec::new()),
+            _ => {
+                let translate = self.get_child_translation(child)? + *translation;
+                self.create_commands(
+                    &translate,
+                    truncate_color,
+                    group_options,
+                    conversion,
+                    stylesheet,
+                    child,
+                )
+            }
+        }
+    }
 
-        let fill_opacity = style
-            .get("fill-opacity")
-            .map_or(group_options.fill_opacity, |opacity| {
-                Some(opacity.parse().unwrap())
-            })
-            .unwrap_or(1.0) as f32;
+    /// The commands drawing a shape element, one for every subpath of a `<path>`.
+    fn create_commands(
+        &self,
+        translation: &FPoint,
+        truncate_color: &TruncateColor,
+        group_options: &GroupOptions,
+        conversion: &Conversion,
+        stylesheet: &Stylesheet,
+        node: Node<'_, '_>,
+    ) -> Svg2PdcResult<Vec<DrawCommand>> {
+        let _element = ElementScope::enter(node);
+        let own_options = GroupOptions::from_node(node, stylesheet)?;
+        let GroupOptions {
+            opacity,
+            fill_color: fill,
+            fill_opacity,
+            stroke_color: stroke,
+            stroke_opacity,
+            stroke_width,
+            visibility,
+        } = own_options.or(group_options);
+        if matches!(visibility.as_deref(), Some("hidden" | "collapse")) {
+            return Ok(Vec::new());
+        }
 
-        let stroke_color = stroke
-            .map(|color| Color::try_from_hex(color).unwrap_or_default())
-            .unwrap_or_default()
-            .with_opacity((opacity * stroke_opacity * 255.0) as u8);
-        let stroke_color = match truncate_color {
-            TruncateColor::Truncate => PebbleColor::from_color_with_truncate(stroke_color),
-            TruncateColor::Keep => PebbleColor::from_color_with_convert(stroke_color),
-        };
+        let opacity = opacity.unwrap_or(1.0) as f32;
+        let stroke_opacity = stroke_opacity.unwrap_or(1.0) as f32;
+        let fill_opacity = fill_opacity.unwrap_or(1.0) as f32;
 
-        let fill_color = fill
-            .map(|color| Color::try_from_hex(color).unwrap_or_default())
-            .unwrap_or_default()
-            .with_opacity((opacity * fill_opacity * 255.0) as u8);
-        let fill_color = match truncate_color {
-            TruncateColor::Truncate => PebbleColor::from_color_with_truncate(fill_color),
-            TruncateColor::Keep => PebbleColor::from_color_with_convert(fill_color),
+        let document = node.document();
+        // A missing paint draws nothing, like `none`. Inherited paints keep their case.
+        let paint = |property, value: Option<&String>| -> Svg2PdcResult<Paint> {
+            let paint = match value.map(|value| value.trim()) {
+                None => Paint::None,
+                Some(value) if value.eq_ignore_ascii_case("currentcolor") => {
+                    self.current_color.map_or(Paint::None, Paint::Color)
+                }
+                Some(value) if !is_paint(value) => {
+                    self.invalid_color(node, property, value)?;
+                    Paint::None
+                }
+                Some(value) => resolve_paint(document, value),
+            };
+            Ok(self.map_color(paint))
         };
+        let stroke_color = paint("stroke", stroke.as_ref())?.quantize(
+            (opacity * stroke_opacity * 255.0) as u8,
+            &self.color_adjustment,
+            truncate_color,
+        );
+        let fill_color = paint("fill", fill.as_ref())?.quantize(
+            (opacity * fill_opacity * 255.0) as u8,
+            &self.color_adjustment,
+            truncate_color,
+        );
 
         // This is a pebble caveat, if the fill color is black, it will be treated as transparent
         let fill_color = if fill_color.is_black() {
@@ -204,12 +1475,13 @@ impl SvgConverter {
         //     return Ok(None);
         // }
 
-        let stroke_width = stroke_width.unwrap_or(1);
-
         let stroke_width = if stroke_color == PebbleColor::nothing() {
             0
         } else {
-            stroke_width
+            let scale = self
+                .canvas_transform
+                .map_or(1.0, |transform| transform.stroke_scale());
+            self.convert_stroke_width(stroke_width.unwrap_or(1.0) * scale, node)
         };
 
         let stroke_color = if stroke_width == 0 {
@@ -225,52 +1497,274 @@ impl SvgConverter {
             stroke_width,
             stroke_color: stroke_color.inner(),
             fill_color: fill_color.inner(),
-            precision: self.precision,
+            precision: self.options.precision,
             conversion: *conversion,
+            output_mode: self.options.output_mode,
+            pixel_convention: self
+                .options
+                .pixel_convention
+                .unwrap_or(self.options.output_mode.default_pixel_convention()),
+            rounding_mode: self
+                .options
+                .rounding_mode
+                .unwrap_or(self.options.output_mode.default_rounding_mode()),
         };
 
-        match tag {
-            "path" => Ok(Some(self.parse_path(node, options)?)),
-            "circle" => Ok(Some(self.parse_circle(node, options)?)),
-            "polyline" => Ok(Some(self.parse_polyline(node, options)?)),
-            "polygon" => Ok(Some(self.parse_polygon(node, options)?)),
-            "line" => Ok(Some(self.parse_line(node, options)?)),
-            "rect" => Ok(Some(self.parse_rect(node, options)?)),
+        let shapes = match tag {
+            "path" => self.parse_path(node)?,
+            "circle" => vec![self.parse_circle(node)?],
+            "polyline" => vec![self.parse_polyline(node)?],
+            "polygon" => vec![self.parse_polygon(node)?],
+            "line" => vec![self.parse_line(node)?],
+            "rect" => vec![self.parse_rect(node)?],
+            #[cfg(feature = "text")]
+            "text" if self.text_font.is_some() => self.parse_text(node, stylesheet)?,
             "g" | "layer" => unreachable!(),
-            "" => Ok(None), // skip empty nodes
-            // tag => Err(Svg2PdcError::UnsupportedTag(tag.to_string())),
+            "" => return Ok(Vec::new()), // skip empty nodes
+            tag if Self::NON_RENDERED_TAGS.contains(&tag) => return Ok(Vec::new()),
+            _ if self.is_ignored(node) => return Ok(Vec::new()),
+            "image" => {
+                self.skipped_image(node)?;
+                return Ok(Vec::new());
+            }
+            _ if self.strict => {
+                return Err(Svg2PdcError::UnsupportedTag(ElementLocation::of(node)));
+            }
             tag => {
-                eprintln!("Skipping unsupported tag: {}", tag);
-                Ok(None)
+                DiagnosticCode::UnsupportedTag
+                    .warn(format_args!("Skipping unsupported tag: {}", tag));
+                return Ok(Vec::new());
+            }
+        };
+
+        let shapes = match tag {
+            "path" => self.fill_holes(shapes, options, node, stylesheet)?,
+            _ => shapes
+                .into_iter()
+                .map(|shape| (shape, options.clone()))
+                .collect(),
+        };
+        let dashes = match self.options.dash_tolerance {
+            Some(tolerance) if stroke_width > 0 => {
+                dash_pattern(node, stylesheet)?.map(|pattern| (pattern, tolerance))
+            }
+            _ => None,
+        };
+        let mut commands = Vec::with_capacity(shapes.len());
+        for (shape, options) in shapes {
+            let Some(((pattern, offset), tolerance)) = &dashes else {
+                commands.extend(self.finish_shape(shape, options, node)?);
+                continue;
+            };
+            let shape = match shape {
+                Shape::Circle { center, radius } => circle_polygon(center, radius),
+                shape => shape,
+            };
+            let Shape::Path { mut points, open } = shape else {
+                unreachable!("circles are dashed as polygons");
+            };
+            // The fill stays whole below the dashes of the outline
+            if options.fill_color != PebbleColor::nothing().inner() {
+                let fill = DrawOptions {
+                    stroke_width: 0,
+                    stroke_color: PebbleColor::nothing().inner(),
+                    ..options.clone()
+                };
+                let shape = Shape::Path {
+                    points: points.clone(),
+                    open,
+                };
+                commands.extend(self.finish_shape(shape, fill, node)?);
+            }
+            if !open && let Some(&first) = points.first() {
+                points.push(first);
+            }
+            let stroke = DrawOptions {
+                fill_color: PebbleColor::nothing().inner(),
+                ..options.clone()
+            };
+            for points in dash_polyline(&points, pattern, *offset, *tolerance) {
+                let shape = Shape::Path { points, open: true };
+                commands.extend(self.finish_shape(shape, stroke.clone(), node)?);
             }
         }
+        Ok(commands)
+    }
+
+    /// Clip a shape to the page and the display and convert it into a draw command, if
+    /// anything of it is left.
+    fn finish_shape(
+        &self,
+        shape: Shape,
+        options: DrawOptions,
+        node: Node<'_, '_>,
+    ) -> Svg2PdcResult<Option<DrawCommand>> {
+        let (shape, options) = match self.canvas_transform {
+            Some(transform) => (
+                shape.scaled(transform.scale),
+                DrawOptions {
+                    translate: transform.apply(options.translate),
+                    ..options
+                },
+            ),
+            None => (shape, options),
+        };
+        if let Some(page_size) = self.page_clip
+            && !shape.intersects(&options.translate, &page_size)
+        {
+            return Ok(None);
+        }
+
+        let shape = match &self.display_circle {
+            Some(circle) if shape.exceeds(&options.translate, circle) => {
+                DiagnosticCode::OutsideDisplay.warn(format_args!(
+                    "{} extends past the edge of the round display",
+                    element_reference(node)
+                ));
+                if !self.clip_to_display {
+                    shape
+                } else if let Some(shape) = shape.clip(&options.translate, circle) {
+                    shape
+                } else {
+                    return Ok(None);
+                }
+            }
+            _ => shape,
+        };
+
+        let (shape, options) = match shape {
+            Shape::Circle { center, radius }
+                if self.circles_as_paths
+                    && self.options.precision == Precision::Precise
+                    && self
+                        .circle_path_max_radius
+                        .is_none_or(|max_radius| radius <= max_radius)
+                    && (center + options.translate).find_nearest_valid(&Precision::Normal)
+                        != center + options.translate =>
+            {
+                DiagnosticCode::CircleApproximated.warn(format_args!(
+                    "{} has a sub-pixel center and is drawn as a precise path",
+                    element_reference(node)
+                ));
+                let options = DrawOptions {
+                    // The sampled outline never lies exactly on the coordinate grid
+                    conversion: Conversion::ConvertNoWarn,
+                    ..options
+                };
+                (circle_polygon(center, radius), options)
+            }
+            shape => (shape, options),
+        };
+
+        Ok(Some(Self::convert_shape(shape, options)?))
     }
 
-    fn parse_path(&self, node: Node<'_, '_>, options: DrawOptions) -> Svg2PdcResult<DrawCommand> {
+    /// Parse the `d` of a path into its subpaths, every `M` after the first and every segment
+    /// after a `Z` starts a new one.
+    fn parse_path(&self, node: Node<'_, '_>) -> Svg2PdcResult<Vec<Shape>> {
         let d = node.attribute("d").unwrap_or("");
-        let path = svgtypes::PathParser::from(d);
-        let path_segments: Result<Vec<_>, svgtypes::Error> = path.collect();
-        let path_segments = path_segments?;
+        let path_segments = svgtypes::PathParser::from(d)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|error| invalid_attribute(node, "d", error))?;
 
+        let mut subpaths = Vec::new();
         let mut points = Vec::new();
         let mut current_point = FPoint::default();
+        // Second control point of the previous cubic or quadratic segment, reflected by the
+        // smooth variants
+        let mut cubic_control = None;
+        let mut quadratic_control = None;
+        // Curves and arcs that only keep their end point without a curve tolerance
+        let mut simplified = 0;
 
         for segment in path_segments {
+            let start = current_point;
+            let point = |abs: bool, x: f64, y: f64| match abs {
+                true => FPoint::new(x as f32, y as f32),
+                false => FPoint::new(x as f32, y as f32) + start,
+            };
+            let (previous_cubic, previous_quadratic) =
+                (cubic_control.take(), quadratic_control.take());
             match segment {
-                PathSegment::MoveTo { abs, x, y }
-                | PathSegment::LineTo { abs, x, y }
-                | PathSegment::SmoothCurveTo { abs, x, y, .. }
-                | PathSegment::CurveTo { abs, x, y, .. }
-                | PathSegment::Quadratic { abs, x, y, .. }
-                | PathSegment::SmoothQuadratic { abs, x, y }
-                | PathSegment::EllipticalArc { abs, x, y, .. } => {
-                    let point = match abs {
-                        true => FPoint::new(x as f32, y as f32),
-                        false => FPoint::new(x as f32, y as f32) + current_point,
-                    };
+                PathSegment::MoveTo { abs, x, y } => {
+                    // A lone move draws nothing
+                    if points.len() > 1 {
+                        subpaths.push(self.close_path(std::mem::take(&mut points)));
+                    }
+                    points.clear();
+                    let point = point(abs, x, y);
                     points.push(point);
                     current_point = point;
                 }
+                PathSegment::LineTo { abs, x, y } => {
+                    let point = point(abs, x, y);
+                    points.push(point);
+                    current_point = point;
+                }
+                PathSegment::EllipticalArc {
+                    abs,
+                    rx,
+                    ry,
+                    x_axis_rotation,
+                    large_arc,
+                    sweep,
+                    x,
+                    y,
+                } => {
+                    let end = point(abs, x, y);
+                    if start != end && rx != 0.0 && ry != 0.0 {
+                        simplified += 1;
+                    }
+                    self.push_arc(
+                        &mut points,
+                        start,
+                        end,
+                        FPoint::new(rx as f32, ry as f32),
+                        x_axis_rotation as f32,
+                        large_arc,
+                        sweep,
+                    );
+                    current_point = end;
+                }
+                PathSegment::CurveTo {
+                    abs,
+                    x1,
+                    y1,
+                    x2,
+                    y2,
+                    x,
+                    y,
+                } => {
+                    let (first, second, end) =
+                        (point(abs, x1, y1), point(abs, x2, y2), point(abs, x, y));
+                    self.push_cubic(&mut points, [start, first, second, end]);
+                    simplified += 1;
+                    cubic_control = Some(second);
+                    current_point = end;
+                }
+                PathSegment::SmoothCurveTo { abs, x2, y2, x, y } => {
+                    let first = previous_cubic.map_or(start, |control| start * 2.0 - control);
+                    let (second, end) = (point(abs, x2, y2), point(abs, x, y));
+                    self.push_cubic(&mut points, [start, first, second, end]);
+                    simplified += 1;
+                    cubic_control = Some(second);
+                    current_point = end;
+                }
+                PathSegment::Quadratic { abs, x1, y1, x, y } => {
+                    let (control, end) = (point(abs, x1, y1), point(abs, x, y));
+                    self.push_quadratic(&mut points, [start, control, end]);
+                    simplified += 1;
+                    quadratic_control = Some(control);
+                    current_point = end;
+                }
+                PathSegment::SmoothQuadratic { abs, x, y } => {
+                    let control = previous_quadratic.map_or(start, |control| start * 2.0 - control);
+                    let end = point(abs, x, y);
+                    self.push_quadratic(&mut points, [start, control, end]);
+                    simplified += 1;
+                    quadratic_control = Some(control);
+                    current_point = end;
+                }
 
                 PathSegment::HorizontalLineTo { abs, x } => {
                     let point = match abs {
@@ -289,222 +1783,583 @@ impl SvgConverter {
                     current_point = point;
                 }
                 PathSegment::ClosePath { .. } => {
-                    if current_point != *points.first().unwrap_or(&FPoint::default()) {
-                        points.push(points[0]);
+                    let first = *points.first().unwrap_or(&FPoint::default());
+                    if current_point != first {
+                        points.push(first);
+                    }
+                    if points.len() > 1 {
+                        subpaths.push(self.close_path(std::mem::take(&mut points)));
                     }
+                    // Segments after it start a new subpath where the closed one did
+                    points = vec![first];
+                    current_point = first;
                 }
             }
         }
 
-        // Chopping decicmal points as instead of rounding them to maintain binary compatibility with the original implementation
-        // TODO: introduce a new option to allow rounding
-        let mut points = points
-            .iter()
-            .map(|point| FPoint::new(point.x.floor(), point.y.floor()))
-            .collect::<Vec<_>>();
+        if simplified > 0 && self.options.curve_tolerance.is_none() {
+            self.simplified_curves(node, simplified)?;
+        }
+        if points.len() > 1 || subpaths.is_empty() {
+            subpaths.push(self.close_path(points));
+        }
+        Ok(subpaths)
+    }
+
+    /// A subpath of a `<path>`, with the decimals of its points chopped instead of rounded in
+    /// [`OutputMode::Floored`].
+    fn close_path(&self, points: Vec<FPoint>) -> Shape {
+        close_subpath(match self.options.output_mode {
+            OutputMode::Floored => points
+                .into_iter()
+                .map(|point| FPoint::new(point.x.floor(), point.y.floor()))
+                .collect(),
+            OutputMode::PythonCompatible | OutputMode::Improved => points,
+        })
+    }
 
-        let first = *points.first().unwrap_or(&FPoint::default());
-        let last = *points.last().unwrap_or(&FPoint::default());
+    /// Add the points of a cubic Bézier after its start point: the end point only, or the
+    /// flattened curve with a [`ConvertOptions::curve_tolerance`].
+    fn push_cubic(&self, points: &mut Vec<FPoint>, curve: [FPoint; 4]) {
+        match self.options.curve_tolerance {
+            Some(tolerance) => flatten_cubic(points, curve, tolerance, 0),
+            None => points.push(curve[3]),
+        }
+    }
 
-        let open = first != last;
+    fn push_quadratic(&self, points: &mut Vec<FPoint>, [start, control, end]: [FPoint; 3]) {
+        // A quadratic Bézier is a cubic one with both control points on the way to its control
+        let first = start + (control - start) * (2.0 / 3.0);
+        let second = end + (control - end) * (2.0 / 3.0);
+        self.push_cubic(points, [start, first, second, end]);
+    }
 
-        if !open {
-            points.pop();
+    /// Add the points of an elliptical arc after its start point: the end point only, or the
+    /// sampled arc with a [`ConvertOptions::curve_tolerance`].
+    ///
+    /// The endpoint parameterization of the path data is converted to the center of the
+    /// ellipse and the angles of the ends, as described in the SVG specification (F.6.5).
+    #[expect(clippy::too_many_arguments)]
+    fn push_arc(
+        &self,
+        points: &mut Vec<FPoint>,
+        start: FPoint,
+        end: FPoint,
+        radii: FPoint,
+        x_axis_rotation: f32,
+        large_arc: bool,
+        sweep: bool,
+    ) {
+        let (mut rx, mut ry) = (radii.x.abs(), radii.y.abs());
+        let Some(tolerance) = self.options.curve_tolerance else {
+            points.push(end);
+            return;
+        };
+        if start == end {
+            return;
+        }
+        if rx == 0.0 || ry == 0.0 {
+            points.push(end);
+            return;
         }
 
-        let points = points
-            .iter()
-            .map(|point| point.pebble_coordinates(&options.precision, &options.conversion))
-            .collect::<Result<Vec<_>, _>>()?;
+        let (sin, cos) = x_axis_rotation.to_radians().sin_cos();
+        let half = (start - end) * 0.5;
+        let x1 = cos * half.x + sin * half.y;
+        let y1 = -sin * half.x + cos * half.y;
+        // Radii too small to reach from one end to the other are scaled up until they do
+        let lambda = (x1 * x1) / (rx * rx) + (y1 * y1) / (ry * ry);
+        if lambda > 1.0 {
+            rx *= lambda.sqrt();
+            ry *= lambda.sqrt();
+        }
+        let numerator = rx * rx * ry * ry - rx * rx * y1 * y1 - ry * ry * x1 * x1;
+        let denominator = rx * rx * y1 * y1 + ry * ry * x1 * x1;
+        let sign = if large_arc == sweep { -1.0 } else { 1.0 };
+        let coefficient = sign * (numerator.max(0.0) / denominator).sqrt();
+        let (cx, cy) = (coefficient * rx * y1 / ry, -coefficient * ry * x1 / rx);
+        let center = FPoint::new(cos * cx - sin * cy, sin * cx + cos * cy) + (start + end) * 0.5;
 
-        Ok(DrawCommand::Path {
-            points,
-            open,
-            options,
-        })
+        let start_angle = ((y1 - cy) / ry).atan2((x1 - cx) / rx);
+        let end_angle = ((-y1 - cy) / ry).atan2((-x1 - cx) / rx);
+        let mut sweep_angle = end_angle - start_angle;
+        if sweep && sweep_angle < 0.0 {
+            sweep_angle += std::f32::consts::TAU;
+        } else if !sweep && sweep_angle > 0.0 {
+            sweep_angle -= std::f32::consts::TAU;
+        }
+
+        let radius = rx.max(ry);
+        let step = if radius > tolerance {
+            2.0 * (1.0 - tolerance / radius).acos()
+        } else {
+            std::f32::consts::PI
+        };
+        let segments = (sweep_angle.abs() / step).ceil().clamp(1.0, 256.0) as usize;
+        for segment in 1..segments {
+            let angle = start_angle + sweep_angle * segment as f32 / segments as f32;
+            let (x, y) = (rx * angle.cos(), ry * angle.sin());
+            points.push(center + FPoint::new(cos * x - sin * y, sin * x + cos * y));
+        }
+        points.push(end);
     }
 
-    fn parse_circle(&self, node: Node<'_, '_>, options: DrawOptions) -> Svg2PdcResult<DrawCommand> {
-        let cx = node
-            .attribute("cx")
-            .ok_or(Svg2PdcError::UnsupportedCircle)?
-            .parse::<f32>()
-            .map_err(|_| Svg2PdcError::UnsupportedCircle)?;
-
-        let cy = node
-            .attribute("cy")
-            .ok_or(Svg2PdcError::UnsupportedCircle)?
-            .parse::<f32>()
-            .map_err(|_| Svg2PdcError::UnsupportedCircle)?;
-
-        let radius = match node.attribute("r") {
-            Some(r) => Some(r),
-            None => node.attribute("z"),
-        }
-        .ok_or(Svg2PdcError::UnsupportedCircle)?
-        .parse::<f32>()
-        .map_err(|_| Svg2PdcError::UnsupportedCircle)?;
-        // Circle does not support precise coordinates
-        let center =
-            FPoint::new(cx, cy).pebble_coordinates(&Precision::Normal, &options.conversion)?;
-
-        Ok(DrawCommand::Circle {
-            center,
-            radius: radius as u16,
-            options,
+    fn parse_circle(&self, node: Node<'_, '_>) -> Svg2PdcResult<Shape> {
+        let cx = number_attribute(node, "cx")?;
+        let cy = number_attribute(node, "cy")?;
+        let radius = match (node.attribute("r"), node.attribute("z")) {
+            (Some(_), _) => number_attribute(node, "r")?,
+            (None, Some(_)) => number_attribute(node, "z")?,
+            (None, None) => return Err(Svg2PdcError::UnsupportedCircle(ElementLocation::of(node))),
+        };
+        Ok(Shape::Circle {
+            center: FPoint::new(cx, cy),
+            radius,
         })
     }
 
-    fn parse_polyline(
-        &self,
-        node: Node<'_, '_>,
-        options: DrawOptions,
-    ) -> Svg2PdcResult<DrawCommand> {
-        let points = node
-            .attribute("points")
-            .ok_or(Svg2PdcError::InvalidPolyline(format!("{node:?}")))?;
-        let points = self.get_points_from_str(points)?;
+    fn parse_polyline(&self, node: Node<'_, '_>) -> Svg2PdcResult<Shape> {
+        let points = self.get_points(node)?;
 
-        let points = points
-            .iter()
-            .map(|point| point.pebble_coordinates(&options.precision, &options.conversion))
-            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Shape::Path { points, open: true })
+    }
 
-        Ok(DrawCommand::Path {
+    fn parse_polygon(&self, node: Node<'_, '_>) -> Svg2PdcResult<Shape> {
+        let points = self.get_points(node)?;
+
+        Ok(Shape::Path {
             points,
+            open: false,
+        })
+    }
+
+    fn parse_line(&self, node: Node<'_, '_>) -> Svg2PdcResult<Shape> {
+        let x1 = number_attribute(node, "x1")?;
+        let y1 = number_attribute(node, "y1")?;
+        let x2 = number_attribute(node, "x2")?;
+        let y2 = number_attribute(node, "y2")?;
+
+        Ok(Shape::Path {
+            points: vec![FPoint::new(x1, y1), FPoint::new(x2, y2)],
             open: true,
-            options,
         })
     }
 
-    fn parse_polygon(
+    /// The subpaths of a filled path with the options to draw each of them. Holes, which the
+    /// watch would fill, are handled according to [`Self::holes`].
+    fn fill_holes(
         &self,
-        node: Node<'_, '_>,
+        shapes: Vec<Shape>,
         options: DrawOptions,
-    ) -> Svg2PdcResult<DrawCommand> {
-        let points = node
-            .attribute("points")
-            .ok_or(Svg2PdcError::InvalidPolyline(format!("{node:?}")))?;
-        let points = self.get_points_from_str(points)?;
-
-        let points = points
+        node: Node<'_, '_>,
+        stylesheet: &Stylesheet,
+    ) -> Svg2PdcResult<Vec<(Shape, DrawOptions)>> {
+        let is_contour = |shape: &Shape| matches!(shape, Shape::Path { points, open: false } if points.len() > 2);
+        let contours: Vec<_> = shapes
             .iter()
-            .map(|point| point.pebble_coordinates(&options.precision, &options.conversion))
-            .collect::<Result<Vec<_>, _>>()?;
+            .filter_map(|shape| match shape {
+                Shape::Path { points, .. } if is_contour(shape) => Some(points.clone()),
+                _ => None,
+            })
+            .collect();
+        let even_odd = inherited_property(node, stylesheet, "fill-rule")
+            .is_some_and(|(_, value)| value.trim().eq_ignore_ascii_case("evenodd"));
+        let holes = match options.fill_color == PebbleColor::nothing().inner() {
+            true => Vec::new(),
+            false => find_holes(&contours, even_odd),
+        };
+        let count = holes.iter().filter(|hole| **hole).count();
+        if count == 0 {
+            return Ok(shapes
+                .into_iter()
+                .map(|shape| (shape, options.clone()))
+                .collect());
+        }
 
-        Ok(DrawCommand::Path {
-            points,
-            open: false,
-            options,
-        })
+        match self.holes {
+            HoleHandling::Warn => {
+                DiagnosticCode::FilledHoles.warn(format_args!(
+                    "{} holes of {} are filled, the watch fills every subpath on its own",
+                    count,
+                    element_reference(node)
+                ));
+                Ok(shapes
+                    .into_iter()
+                    .map(|shape| (shape, options.clone()))
+                    .collect())
+            }
+            HoleHandling::Bridge => {
+                let fill = DrawOptions {
+                    stroke_width: 0,
+                    stroke_color: PebbleColor::nothing().inner(),
+                    ..options.clone()
+                };
+                let stroke = DrawOptions {
+                    fill_color: PebbleColor::nothing().inner(),
+                    ..options.clone()
+                };
+                let mut paths: Vec<_> = bridge_holes(contours, &holes)
+                    .into_iter()
+                    .map(|points| {
+                        let shape = Shape::Path {
+                            points,
+                            open: false,
+                        };
+                        (shape, fill.clone())
+                    })
+                    .collect();
+                // Stroked along the original subpaths, the cuts would show
+                for shape in shapes {
+                    if !is_contour(&shape) {
+                        paths.push((shape, options.clone()));
+                    } else if options.stroke_width > 0 {
+                        paths.push((shape, stroke.clone()));
+                    }
+                }
+                Ok(paths)
+            }
+            HoleHandling::Outline => {
+                let outline = match options.stroke_width {
+                    0 => DrawOptions {
+                        stroke_width: 1,
+                        stroke_color: options.fill_color,
+                        fill_color: PebbleColor::nothing().inner(),
+                        ..options
+                    },
+                    _ => DrawOptions {
+                        fill_color: PebbleColor::nothing().inner(),
+                        ..options
+                    },
+                };
+                Ok(shapes
+                    .into_iter()
+                    .map(|shape| (shape, outline.clone()))
+                    .collect())
+            }
+        }
     }
 
-    fn parse_line(&self, node: Node<'_, '_>, options: DrawOptions) -> Svg2PdcResult<DrawCommand> {
-        let x1 = node
-            .attribute("x1")
-            .ok_or(Svg2PdcError::InvalidPolyline(format!("{node:?}")))?;
-        let x1 = x1
-            .parse::<f32>()
-            .map_err(|_| Svg2PdcError::InvalidPolyline(format!("{node:?}")))?;
-
-        let y1 = node
-            .attribute("y1")
-            .ok_or(Svg2PdcError::InvalidPolyline(format!("{node:?}")))?;
-        let y1 = y1
-            .parse::<f32>()
-            .map_err(|_| Svg2PdcError::InvalidPolyline(format!("{node:?}")))?;
-
-        let x2 = node
-            .attribute("x2")
-            .ok_or(Svg2PdcError::InvalidPolyline(format!("{node:?}")))?;
-        let x2 = x2
-            .parse::<f32>()
-            .map_err(|_| Svg2PdcError::InvalidPolyline(format!("{node:?}")))?;
-
-        let y2 = node
-            .attribute("y2")
-            .ok_or(Svg2PdcError::InvalidPolyline(format!("{node:?}")))?;
-        let y2 = y2
-            .parse::<f32>()
-            .map_err(|_| Svg2PdcError::InvalidPolyline(format!("{node:?}")))?;
-
-        let points = vec![
-            FPoint::new(x1, y1).pebble_coordinates(&options.precision, &options.conversion)?,
-            FPoint::new(x2, y2).pebble_coordinates(&options.precision, &options.conversion)?,
-        ];
+    /// Outline a `<text>` element with [`Self::text_font`], one closed path per filled region.
+    ///
+    /// A `<tspan>` with an `x` or a `y` starts a new line, the others continue the current
+    /// one, and every line is aligned by the `text-anchor` of the element. Whitespace is
+    /// collapsed like without `xml:space`, and the paint of the `<text>` element is used for
+    /// all of its text.
+    #[cfg(feature = "text")]
+    fn parse_text(&self, node: Node<'_, '_>, stylesheet: &Stylesheet) -> Svg2PdcResult<Vec<Shape>> {
+        let Some(font) = &self.text_font else {
+            return Ok(Vec::new());
+        };
+        // Only the first of a list of positions, the others would place single characters
+        let position = |element: Node<'_, '_>, attribute| {
+            element
+                .attribute(attribute)
+                .and_then(|value| {
+                    value
+                        .split(|c: char| c == ',' || c.is_whitespace())
+                        .find(|value| !value.is_empty())
+                })
+                .map(|value| parse_number(element, attribute, value))
+                .transpose()
+        };
+        let font_size = |element: Node<'_, '_>| {
+            inherited_property(element, stylesheet, "font-size")
+                .map_or(Ok(DEFAULT_FONT_SIZE), |(ancestor, value)| {
+                    parse_number(ancestor, "font-size", &value)
+                })
+        };
 
-        Ok(DrawCommand::Path {
-            points,
-            open: true,
-            options,
-        })
+        // Runs of text with the position they move to and their font size
+        let mut runs = Vec::new();
+        for child in node.children() {
+            if child.is_text() {
+                let text = collapse_whitespace(child.text().unwrap_or_default());
+                runs.push(((None, None), text, font_size(node)?));
+            } else if child.has_tag_name("tspan") {
+                let text: String = child
+                    .descendants()
+                    .filter(|descendant| descendant.is_text())
+                    .filter_map(|descendant| descendant.text())
+                    .collect();
+                let moved_to = (position(child, "x")?, position(child, "y")?);
+                runs.push((moved_to, collapse_whitespace(&text), font_size(child)?));
+            }
+        }
+        if let Some((_, text, _)) = runs.first_mut() {
+            *text = text.trim_start().to_string();
+        }
+        if let Some((_, text, _)) = runs.last_mut() {
+            *text = text.trim_end().to_string();
+        }
+
+        let shift = match property(node, stylesheet, "text-anchor")
+            .as_deref()
+            .map(str::trim)
+        {
+            Some("middle") => 0.5,
+            Some("end") => 1.0,
+            _ => 0.0,
+        };
+        let mut shapes = Vec::new();
+        // Draws a line of runs starting at `start` and returns where it ends
+        let mut draw_line = |start: FPoint, line: &[(String, f32)]| {
+            let width: f32 = line
+                .iter()
+                .map(|(text, size)| font.advance(text, *size))
+                .sum();
+            let mut pen = start.x - width * shift;
+            for (text, size) in line {
+                let polygons = font.text_polygons(text, *size, FPoint::new(pen, start.y));
+                shapes.extend(polygons.into_iter().map(|points| Shape::Path {
+                    points,
+                    open: false,
+                }));
+                pen += font.advance(text, *size);
+            }
+            pen
+        };
+        let mut start = FPoint::new(
+            position(node, "x")?.unwrap_or(0.0),
+            position(node, "y")?.unwrap_or(0.0),
+        );
+        let mut line = Vec::new();
+        for ((x, y), text, size) in runs {
+            if x.is_some() || y.is_some() {
+                let end = draw_line(start, &line);
+                start = FPoint::new(x.unwrap_or(end), y.unwrap_or(start.y));
+                line.clear();
+            }
+            line.push((text, size));
+        }
+        draw_line(start, &line);
+        Ok(shapes)
     }
 
-    fn parse_rect(&self, node: Node<'_, '_>, options: DrawOptions) -> Svg2PdcResult<DrawCommand> {
-        let x = node
-            .attribute("x")
-            .ok_or(Svg2PdcError::InvalidPolyline(format!("{node:?}")))?;
-        let x = x
-            .parse::<f32>()
-            .map_err(|_| Svg2PdcError::InvalidPolyline(format!("{node:?}")))?;
-
-        let y = node
-            .attribute("y")
-            .ok_or(Svg2PdcError::InvalidPolyline(format!("{node:?}")))?;
-        let y = y
-            .parse::<f32>()
-            .map_err(|_| Svg2PdcError::InvalidPolyline(format!("{node:?}")))?;
-
-        let width = node
-            .attribute("width")
-            .ok_or(Svg2PdcError::InvalidPolyline(format!("{node:?}")))?;
-        let width = width
-            .parse::<f32>()
-            .map_err(|_| Svg2PdcError::InvalidPolyline(format!("{node:?}")))?;
-
-        let height = node
-            .attribute("height")
-            .ok_or(Svg2PdcError::InvalidPolyline(format!("{node:?}")))?;
-        let height = height
-            .parse::<f32>()
-            .map_err(|_| Svg2PdcError::InvalidPolyline(format!("{node:?}")))?;
-
-        let points = vec![
-            FPoint::new(x, y).pebble_coordinates(&options.precision, &options.conversion)?,
-            FPoint::new(x + width, y)
-                .pebble_coordinates(&options.precision, &options.conversion)?,
-            FPoint::new(x + width, y + height)
-                .pebble_coordinates(&options.precision, &options.conversion)?,
-            FPoint::new(x, y + height)
-                .pebble_coordinates(&options.precision, &options.conversion)?,
-        ];
+    fn parse_rect(&self, node: Node<'_, '_>) -> Svg2PdcResult<Shape> {
+        // A missing position is 0, unlike a missing size
+        let position = |attribute| {
+            node.attribute(attribute)
+                .map_or(Ok(0.0), |value| parse_number(node, attribute, value))
+        };
+        let (x, y) = (position("x")?, position("y")?);
+        let width = number_attribute(node, "width")?;
+        let height = number_attribute(node, "height")?;
+        // A missing or `auto` radius takes the value of the other one
+        let radius = |attribute| match node.attribute(attribute) {
+            None | Some("auto") => Ok(None),
+            Some(value) => parse_number(node, attribute, value).map(Some),
+        };
+        let (rx, ry) = (radius("rx")?, radius("ry")?);
+        let (rx, ry) = (rx.or(ry).unwrap_or(0.0), ry.or(rx).unwrap_or(0.0));
+        let (rx, ry) = (rx.min(width / 2.0), ry.min(height / 2.0));
+        if rx <= 0.0 || ry <= 0.0 {
+            return Ok(Shape::Path {
+                points: vec![
+                    FPoint::new(x, y),
+                    FPoint::new(x + width, y),
+                    FPoint::new(x + width, y + height),
+                    FPoint::new(x, y + height),
+                ],
+                open: false,
+            });
+        }
 
-        Ok(DrawCommand::Path {
+        let tolerance = self.options.curve_tolerance.unwrap_or(CIRCLE_TOLERANCE);
+        let largest = rx.max(ry);
+        let segments = if largest > tolerance {
+            (std::f32::consts::FRAC_PI_4 / (1.0 - tolerance / largest).acos()).ceil()
+        } else {
+            1.0
+        }
+        .clamp(1.0, 32.0) as usize;
+        // Corner centers clockwise from the top left, with the angle their arc starts at
+        let corners = [
+            (FPoint::new(x + rx, y + ry), std::f32::consts::PI),
+            (
+                FPoint::new(x + width - rx, y + ry),
+                -std::f32::consts::FRAC_PI_2,
+            ),
+            (FPoint::new(x + width - rx, y + height - ry), 0.0),
+            (
+                FPoint::new(x + rx, y + height - ry),
+                std::f32::consts::FRAC_PI_2,
+            ),
+        ];
+        let mut points: Vec<FPoint> = Vec::new();
+        for (center, start_angle) in corners {
+            for segment in 0..=segments {
+                let angle =
+                    start_angle + std::f32::consts::FRAC_PI_2 * segment as f32 / segments as f32;
+                let point = (center + FPoint::new(rx * angle.cos(), ry * angle.sin()))
+                    .find_nearest_valid(&self.options.precision);
+                if points.last() != Some(&point) && points.first() != Some(&point) {
+                    points.push(point);
+                }
+            }
+        }
+        Ok(Shape::Path {
             points,
             open: false,
-            options,
         })
     }
 
-    fn get_points_from_str(&self, points: &str) -> Svg2PdcResult<Vec<FPoint>> {
-        let points_list: Result<Vec<FPoint>, ParseFloatError> = points
-            .split_whitespace()
-            .map(|chunk| {
-                let mut parts = chunk.split(',');
-                let x = parts.next().unwrap_or("").parse()?;
-                let y = parts.next().unwrap_or("").parse()?;
-                Ok(FPoint::new(x, y))
-            })
-            .collect();
-        let points = points_list.map_err(|_| Svg2PdcError::ParseError(points.to_string()))?;
-        Ok(points)
+    /// Convert the geometry of an element into a draw command.
+    fn convert_shape(shape: Shape, options: DrawOptions) -> Svg2PdcResult<DrawCommand> {
+        match shape {
+            Shape::Path { points, open } => {
+                let points = points
+                    .iter()
+                    .map(|point| Self::convert_point(*point, &options))
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(DrawCommand::Path {
+                    points,
+                    open,
+                    options,
+                })
+            }
+            Shape::Circle { center, radius } => {
+                // Circle does not support precise coordinates, its center is in pixels even in
+                // precise images
+                let center = Self::convert_point(
+                    center,
+                    &DrawOptions {
+                        precision: Precision::Normal,
+                        ..options.clone()
+                    },
+                )?;
+                Ok(DrawCommand::Circle {
+                    center,
+                    radius: radius as u16,
+                    options,
+                })
+            }
+        }
+    }
+
+    /// Convert a stroke width to the whole pixels stored in a draw command.
+    ///
+    /// Like the original tool the width is truncated, values outside of the `u8` range are clamped.
+    fn convert_stroke_width(&self, width: f32, node: Node<'_, '_>) -> u8 {
+        let rounded = self
+            .options
+            .stroke_width_rounding
+            .map_or(width, |rounding| rounding.round(width)) as u8;
+        let converted = match self.options.min_stroke_width {
+            Some(min_stroke_width) if width > 0.0 => rounded.max(min_stroke_width),
+            _ => rounded,
+        };
+        if (converted as f32 - width).abs() > self.options.stroke_width_tolerance {
+            let change = if !(0.0..=u8::MAX as f32).contains(&width) {
+                "clamped"
+            } else if converted > rounded {
+                "raised"
+            } else if self.options.stroke_width_rounding.is_some() {
+                "rounded"
+            } else {
+                "truncated"
+            };
+            DiagnosticCode::StrokeWidthChanged.warn(format_args!(
+                "Stroke width {} of {} {} to {}",
+                width,
+                element_reference(node),
+                change,
+                converted
+            ));
+        }
+        converted
+    }
+
+    /// A paint that is not a color draws nothing, or fails the conversion in strict mode.
+    fn invalid_color(&self, node: Node<'_, '_>, property: &str, value: &str) -> Svg2PdcResult<()> {
+        if self.strict {
+            return Err(Svg2PdcError::InvalidAttribute {
+                attribute: property.to_string(),
+                element: ElementLocation::of(node),
+                reason: format!("`{value}` is not a color"),
+            });
+        }
+        DiagnosticCode::InvalidColor.warn(format_args!(
+            "{} of {} is not a color: `{}`, nothing is drawn",
+            property,
+            element_reference(node),
+            value
+        ));
+        Ok(())
+    }
+
+    /// Raster images are reported with their position, or fail the conversion in strict mode.
+    fn skipped_image(&self, node: Node<'_, '_>) -> Svg2PdcResult<()> {
+        let element = ElementLocation::of(node);
+        if self.strict {
+            return Err(Svg2PdcError::RasterImage(element));
+        }
+        DiagnosticCode::RasterImage.warn(format_args!(
+            "{element} is a raster image, which PDC images can't hold, skipping it"
+        ));
+        Ok(())
+    }
+
+    /// Curves reduced to their end points are reported once per path, or fail the conversion
+    /// in strict mode.
+    fn simplified_curves(&self, node: Node<'_, '_>, curves: usize) -> Svg2PdcResult<()> {
+        if self.strict {
+            return Err(Svg2PdcError::InvalidAttribute {
+                attribute: "d".to_string(),
+                element: ElementLocation::of(node),
+                reason: format!(
+                    "{curves} curves would be reduced to their end points, flatten them with a curve tolerance"
+                ),
+            });
+        }
+        DiagnosticCode::CurveSimplified.warn(format_args!(
+            "{} curves of {} reduced to their end points",
+            curves,
+            element_reference(node)
+        ));
+        Ok(())
+    }
+
+    /// Convert a point in document space into the final Pebble coordinates of a command.
+    fn convert_point(point: FPoint, options: &DrawOptions) -> Svg2PdcResult<PebblePoint> {
+        (point + options.translate).pebble_coordinates(
+            &options.precision,
+            &options.conversion,
+            &options.rounding_mode,
+            &options.pixel_convention,
+            &options.output_mode,
+        )
+    }
+
+    /// The `points` of a polyline or polygon.
+    fn get_points(&self, node: Node<'_, '_>) -> Svg2PdcResult<Vec<FPoint>> {
+        let points = node
+            .attribute("points")
+            .ok_or_else(|| Svg2PdcError::MissingAttribute {
+                attribute: "points".to_string(),
+                element: ElementLocation::of(node),
+            })?;
+        let numbers = svgtypes::NumberListParser::from(points)
+            .map(|number| number.map(|number| number as f32))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|error| invalid_attribute(node, "points", error))?;
+        if numbers.len() % 2 != 0 {
+            return Err(invalid_attribute(
+                node,
+                "points",
+                "odd number of coordinates",
+            ));
+        }
+        Ok(numbers
+            .chunks_exact(2)
+            .map(|pair| FPoint::new(pair[0], pair[1]))
+            .collect())
     }
 
     fn get_child_translation(&self, child: Node<'_, '_>) -> Result<FPoint, Svg2PdcError> {
-        let transform_list: Result<Vec<TransformListToken>, svgtypes::Error> =
+        let transform_list =
             svgtypes::TransformListParser::from(child.attribute("transform").unwrap_or(""))
-                .collect();
-        let transform_list = transform_list?;
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|error| invalid_attribute(child, "transform", error))?;
         let translate = transform_list
             .into_iter()
             .find(|token| matches!(token, TransformListToken::Translate { .. }))
@@ -516,6 +2371,30 @@ impl SvgConverter {
         Ok(translate)
     }
 
+    /// Convert a whole document with the [`Self::options`] of the converter.
+    pub fn convert(&self, content: &str) -> Svg2PdcResult<PebbleImage> {
+        self.parse_svg_image(
+            content,
+            &self.options.truncate_color,
+            &self.options.conversion,
+        )
+    }
+
+    /// The paint with its color replaced by the [`Self::color_map`].
+    fn map_color(&self, paint: Paint) -> Paint {
+        let Paint::Color(color) = paint else {
+            return paint;
+        };
+        let replacement = self
+            .color_map
+            .iter()
+            .find(|(from, _)| (from.r, from.g, from.b) == (color.r, color.g, color.b));
+        match replacement {
+            Some((_, to)) => Paint::Color(Color { a: color.a, ..*to }),
+            None => paint,
+        }
+    }
+
     pub fn parse_svg_image(
         &self,
         content: &str,
@@ -523,24 +2402,460 @@ impl SvgConverter {
         conversion: &Conversion,
     ) -> Svg2PdcResult<PebbleImage> {
         let root = roxmltree::Document::parse(content)?;
-        let view_box = Self::get_viewbox(&root)?;
+        self.convert_root(&root, truncate_color, conversion)
+    }
+
+    /// [`Self::parse_svg_image`], collecting the warnings of the conversion in a report instead
+    /// of printing them.
+    ///
+    /// ```rust
+    /// use svg2pdc::{diagnostic::DiagnosticCode, point::Conversion, prelude::*};
+    ///
+    /// let svg = r#"<svg xmlns="http://www.w3.org/2000/svg" width="10" height="10">
+    ///     <path id="edge" d="M0.3 0 L5 5" stroke="white"/>
+    /// </svg>"#;
+    /// let (_, report) = SvgConverter::new(Precision::Normal)
+    ///     .parse_svg_image_with_report(svg, &TruncateColor::Keep, &Conversion::ConvertWarn)
+    ///     .unwrap();
+    /// assert_eq!(report.count(DiagnosticCode::InvalidPoint), 1);
+    /// let element = report.warnings[0].element.as_ref().unwrap();
+    /// assert_eq!((element.id.as_deref(), element.line), (Some("edge"), 2));
+    /// ```
+    pub fn parse_svg_image_with_report(
+        &self,
+        content: &str,
+        truncate_color: &TruncateColor,
+        conversion: &Conversion,
+    ) -> Svg2PdcResult<(PebbleImage, ConversionReport)> {
+        let (image, mut report) =
+            diagnostic::collect(|| self.parse_svg_image(content, truncate_color, conversion));
+        report.locate(content);
+        Ok((image?, report))
+    }
+
+    fn convert_root(
+        &self,
+        root: &Document<'_>,
+        truncate_color: &TruncateColor,
+        conversion: &Conversion,
+    ) -> Svg2PdcResult<PebbleImage> {
+        let view_box = Self::get_viewbox(root)?;
         let translation = FPoint {
             x: -view_box.x as f32,
             y: -view_box.y as f32,
-        };
-        let size = FPoint {
-            x: view_box.w as f32,
-            y: view_box.h as f32,
+        } + self.get_child_translation(root.root_element())?;
+        let view_box_size = FPoint::new(view_box.w as f32, view_box.h as f32);
+        let viewport = Self::get_viewport(root, view_box_size)?;
+        let aspect = root
+            .root_element()
+            .attribute("preserveAspectRatio")
+            .map(|aspect| {
+                AspectRatio::from_str(aspect).map_err(|error| {
+                    invalid_attribute(root.root_element(), "preserveAspectRatio", error)
+                })
+            })
+            .transpose()?
+            .unwrap_or_default();
+        let mut transform = ViewTransform::view_box(view_box_size, viewport, aspect);
+        let mut size = viewport;
+        if let Some(target) = self.fit {
+            transform = transform.then(ViewTransform::fit(viewport, target));
+            size = target;
+        }
+        if let Some(factor) = self.scale {
+            transform = transform.then(ViewTransform::scale(factor));
+            size = size * factor;
         }
-        .pebble_coordinates(&self.precision, conversion)?;
+        // The canvas size is always expressed in whole pixels, precise images only have their
+        // points in eighths of a pixel
+        let size = PebblePoint::try_from(FPoint::new(size.x.round(), size.y.round()))?;
 
-        let commands = self.get_commands(
+        let mut converter = self.for_canvas(size.into());
+        if transform != ViewTransform::IDENTITY {
+            converter.canvas_transform = Some(transform);
+        }
+        let stylesheet = Stylesheet::from_document(root);
+        warn_omitted_references(root.root_element(), &stylesheet);
+        let commands = converter.get_commands(
             &translation,
             truncate_color,
-            &GroupOptions::default(),
+            &GroupOptions::from_node(root.root_element(), &stylesheet)?,
             conversion,
+            &stylesheet,
             root.root_element(),
         )?;
         Ok(PebbleImage { size, commands })
     }
+
+    /// Convert an element and its descendants onto a canvas of an explicit size.
+    ///
+    /// Translations of the ancestors of `node` are applied, so the fragment ends up where it
+    /// is drawn in the full document, and styles are inherited from all of its ancestors,
+    /// including the root `<svg>` element.
+    pub fn convert_node(
+        &self,
+        node: Node<'_, '_>,
+        options: &FragmentOptions,
+    ) -> Svg2PdcResult<PebbleImage> {
+        let root = node.document().root_element();
+        let mut translation = options.translate + self.get_child_translation(root)?;
+        for ancestor in node
+            .ancestors()
+            .skip(1)
+            .filter(|ancestor| ancestor.is_element() && ancestor.parent_element().is_some())
+        {
+            translation = translation + self.get_child_translation(ancestor)?;
+        }
+
+        let mut converter = self.for_canvas(options.size.into());
+        // The closest override of the precision applies, like it would in the whole document
+        for ancestor in node.ancestors().filter(|ancestor| ancestor.is_element()) {
+            if let Some(precision) = element_precision(ancestor)? {
+                converter.options.precision = precision;
+                break;
+            }
+        }
+        let stylesheet = Stylesheet::from_document(node.document());
+        warn_omitted_references(node, &stylesheet);
+        let mut group_options = GroupOptions::default();
+        for ancestor in node
+            .ancestors()
+            .skip(1)
+            .filter(|ancestor| ancestor.is_element())
+            .collect::<Vec<_>>()
+            .into_iter()
+            .rev()
+        {
+            group_options = GroupOptions::from_node(ancestor, &stylesheet)?.or(&group_options);
+        }
+        let commands = match node.tag_name().name() {
+            "svg" => converter.get_commands(
+                &translation,
+                &options.truncate_color,
+                &GroupOptions::from_node(node, &stylesheet)?.or(&group_options),
+                &options.conversion,
+                &stylesheet,
+                node,
+            )?,
+            "g" => converter.get_commands(
+                &(self.get_child_translation(node)? + translation),
+                &options.truncate_color,
+                &GroupOptions::from_node(node, &stylesheet)?.or(&group_options),
+                &options.conversion,
+                &stylesheet,
+                node,
+            )?,
+            _ => converter.create_commands(
+                &(self.get_child_translation(node)? + translation),
+                &options.truncate_color,
+                &group_options,
+                &options.conversion,
+                &stylesheet,
+                node,
+            )?,
+        };
+
+        Ok(PebbleImage {
+            size: options.size,
+            commands,
+        })
+    }
+
+    /// Convert only the element of a document with the id `id`, a leading `#` is allowed,
+    /// and its descendants, like one icon of a sheet.
+    ///
+    /// The canvas is the bounding box of the element, or `size` with the element centered on
+    /// it when given.
+    ///
+    /// ```rust
+    /// use svg2pdc::{point::{Conversion, PebblePoint}, prelude::*};
+    ///
+    /// let svg = r##"<svg xmlns="http://www.w3.org/2000/svg" width="100" height="100">
+    ///     <g id="icon-sunny" transform="translate(40, 40)">
+    ///         <rect x="0" y="0" width="8" height="6" fill="#ffff00"/>
+    ///     </g>
+    ///     <circle id="moon" cx="80" cy="80" r="5" fill="#ffffff"/>
+    /// </svg>"##;
+    /// let converter = SvgConverter::new(Precision::Normal);
+    /// let icon = converter
+    ///     .parse_svg_element(svg, "#icon-sunny", None, &TruncateColor::Keep, &Conversion::RequireExact)
+    ///     .unwrap();
+    /// assert_eq!(icon.size, PebblePoint { x: 8, y: 6 });
+    /// assert_eq!(icon.commands.len(), 1);
+    ///
+    /// let size = Some(PebblePoint { x: 12, y: 12 });
+    /// let icon = converter
+    ///     .parse_svg_element(svg, "icon-sunny", size, &TruncateColor::Keep, &Conversion::RequireExact)
+    ///     .unwrap();
+    /// assert_eq!(icon.size, PebblePoint { x: 12, y: 12 });
+    /// ```
+    pub fn parse_svg_element(
+        &self,
+        content: &str,
+        id: &str,
+        size: Option<PebblePoint>,
+        truncate_color: &TruncateColor,
+        conversion: &Conversion,
+    ) -> Svg2PdcResult<PebbleImage> {
+        let root = roxmltree::Document::parse(content)?;
+        let id = id.strip_prefix('#').unwrap_or(id);
+        let node = root
+            .descendants()
+            .find(|node| node.attribute("id") == Some(id))
+            .ok_or_else(|| {
+                Svg2PdcError::UnsupportedOperation(format!("no element with the id `{id}`"))
+            })?;
+
+        // Find where the element is drawn first, without its warnings or the display of a
+        // platform, which would clip it
+        let (placed, _) = diagnostic::collect(|| {
+            Self {
+                platform: None,
+                ..self.clone()
+            }
+            .convert_node(
+                node,
+                &FragmentOptions {
+                    size: size.unwrap_or_default(),
+                    translate: FPoint::default(),
+                    truncate_color: *truncate_color,
+                    conversion: Conversion::ConvertNoWarn,
+                },
+            )
+        });
+        let (min, max) = placed?
+            .commands
+            .iter()
+            .filter_map(DrawCommand::bounding_box)
+            .map(|bounds| (bounds.min, bounds.max))
+            .reduce(|(min, max), (other_min, other_max)| {
+                (
+                    FPoint::new(min.x.min(other_min.x), min.y.min(other_min.y)),
+                    FPoint::new(max.x.max(other_max.x), max.y.max(other_max.y)),
+                )
+            })
+            .ok_or_else(|| {
+                Svg2PdcError::UnsupportedOperation(format!("element `{id}` draws nothing"))
+            })?;
+        // The bounds are measured from pixel centers, the canvas starts at pixel edges
+        let (min, max) = (min - FPoint::new(0.5, 0.5), max - FPoint::new(0.5, 0.5));
+        let min = FPoint::new(min.x.floor(), min.y.floor());
+        let extent = FPoint::new(max.x.ceil(), max.y.ceil()) - min;
+        let (size, margin) = match size {
+            Some(size) => {
+                let margin = (FPoint::from(size) - extent) / 2.0;
+                (size, FPoint::new(margin.x.round(), margin.y.round()))
+            }
+            None => (PebblePoint::try_from(extent)?, FPoint::default()),
+        };
+        self.convert_node(
+            node,
+            &FragmentOptions {
+                size,
+                translate: margin - min,
+                truncate_color: *truncate_color,
+                conversion: *conversion,
+            },
+        )
+    }
+
+    /// Convert a snippet of SVG markup, such as `<path d="M0 0 L10 10"/>`, without a
+    /// surrounding document.
+    ///
+    /// The snippet may contain several top-level elements; they are converted in order.
+    ///
+    /// ```rust
+    /// use svg2pdc::{point::{Conversion, PebblePoint}, prelude::*, svg_converter::FragmentOptions};
+    ///
+    /// let converter = SvgConverter::new(Precision::Normal);
+    /// let image = converter
+    ///     .parse_svg_fragment(
+    ///         r##"<line x1="0" y1="0" x2="10" y2="10" stroke="#ffffff"/><circle cx="5" cy="5" r="2" fill="#ff0000"/>"##,
+    ///         &FragmentOptions {
+    ///             size: PebblePoint { x: 20, y: 20 },
+    ///             translate: FPoint::default(),
+    ///             truncate_color: TruncateColor::Truncate,
+    ///             conversion: Conversion::RequireExact,
+    ///         },
+    ///     )
+    ///     .unwrap();
+    /// assert_eq!(image.commands.len(), 2);
+    /// ```
+    pub fn parse_svg_fragment(
+        &self,
+        content: &str,
+        options: &FragmentOptions,
+    ) -> Svg2PdcResult<PebbleImage> {
+        let wrapped = format!(r#"<svg xmlns="http://www.w3.org/2000/svg">{content}</svg>"#);
+        let document = roxmltree::Document::parse(&wrapped)?;
+        self.convert_node(document.root_element(), options)
+    }
+
+    /// List the Inkscape pages of a document, in document order.
+    ///
+    /// Documents without pages, including single-page Inkscape documents that never had
+    /// pages added, return an empty list.
+    pub fn get_pages(document: &Document<'_>) -> Svg2PdcResult<Vec<InkscapePage>> {
+        document
+            .descendants()
+            .filter(|node| node.has_tag_name((InkscapePage::INKSCAPE_NAMESPACE, "page")))
+            .map(InkscapePage::from_node)
+            .collect()
+    }
+
+    /// Convert every page of an Inkscape multi-page document into its own image.
+    ///
+    /// The content is translated into the coordinate space of each page, and elements that
+    /// lie entirely outside of a page are left out of its image. Returns an empty list when
+    /// the document has no pages.
+    pub fn parse_svg_pages(
+        &self,
+        content: &str,
+        truncate_color: &TruncateColor,
+        conversion: &Conversion,
+    ) -> Svg2PdcResult<Vec<(InkscapePage, PebbleImage)>> {
+        let root = roxmltree::Document::parse(content)?;
+        let stylesheet = Stylesheet::from_document(&root);
+        let pages = Self::get_pages(&root)?;
+        if !pages.is_empty() {
+            warn_omitted_references(root.root_element(), &stylesheet);
+        }
+        pages
+            .into_iter()
+            .map(|page| {
+                let size = FPoint::new(page.width, page.height) * self.scale.unwrap_or(1.0);
+                let converter = Self {
+                    page_clip: Some(size),
+                    canvas_transform: self.scale.map(ViewTransform::scale),
+                    ..self.for_canvas(size)
+                };
+                let commands = converter.get_commands(
+                    &(FPoint::new(-page.x, -page.y)
+                        + self.get_child_translation(root.root_element())?),
+                    truncate_color,
+                    &GroupOptions::from_node(root.root_element(), &stylesheet)?,
+                    conversion,
+                    &stylesheet,
+                    root.root_element(),
+                )?;
+                let size = PebblePoint::try_from(FPoint::new(size.x.round(), size.y.round()))?;
+                Ok((page, PebbleImage { size, commands }))
+            })
+            .collect()
+    }
+
+    /// The top-level groups of a document holding the frames of an animation: its Inkscape
+    /// layers, or every top-level `<g>` when it has no layers.
+    fn frame_layers<'a, 'input>(document: &'a Document<'input>) -> Vec<Node<'a, 'input>> {
+        let groups: Vec<_> = document
+            .root_element()
+            .children()
+            .filter(|node| node.has_tag_name("g"))
+            .collect();
+        let layers: Vec<_> = groups
+            .iter()
+            .copied()
+            .filter(|group| {
+                group.attribute((InkscapePage::INKSCAPE_NAMESPACE, "groupmode")) == Some("layer")
+            })
+            .collect();
+        if layers.is_empty() { groups } else { layers }
+    }
+
+    /// Convert every layer of a document into an animation frame, in layer order, with the
+    /// label of the layer falling back to its id.
+    ///
+    /// Frames are the Inkscape layers at the top of the document, or its top-level groups
+    /// when it has no layers. Hidden layers are drawn as well, and everything outside of
+    /// the frame layers is drawn on every frame, e.g. a background.
+    ///
+    /// ```rust
+    /// use svg2pdc::{point::Conversion, prelude::*};
+    ///
+    /// let svg = r##"<svg xmlns="http://www.w3.org/2000/svg"
+    ///     xmlns:inkscape="http://www.inkscape.org/namespaces/inkscape" width="10" height="10">
+    ///     <rect x="0" y="0" width="10" height="10" fill="#0000ff"/>
+    ///     <g inkscape:groupmode="layer" inkscape:label="open">
+    ///         <circle cx="5" cy="5" r="3" fill="#ffffff"/>
+    ///     </g>
+    ///     <g inkscape:groupmode="layer" inkscape:label="closed" display="none">
+    ///         <path d="M2 5 L8 5" stroke="#ffffff"/>
+    ///     </g>
+    /// </svg>"##;
+    /// let frames = SvgConverter::new(Precision::Normal)
+    ///     .parse_svg_layers(svg, &TruncateColor::Keep, &Conversion::RequireExact)
+    ///     .unwrap();
+    /// assert_eq!(frames.len(), 2);
+    /// assert_eq!(frames[1].0.as_deref(), Some("closed"));
+    /// assert!(frames.iter().all(|(_, image)| image.commands.len() == 2));
+    /// ```
+    pub fn parse_svg_layers(
+        &self,
+        content: &str,
+        truncate_color: &TruncateColor,
+        conversion: &Conversion,
+    ) -> Svg2PdcResult<Vec<(Option<String>, PebbleImage)>> {
+        self.convert_layers(content, truncate_color, conversion, true)
+    }
+
+    /// Convert every layer of a document into its own image, like the icons of a sheet, on
+    /// the canvas of the document. Unlike [`Self::parse_svg_layers`] the content outside of
+    /// the layers is left out.
+    ///
+    /// ```rust
+    /// use svg2pdc::{point::Conversion, prelude::*};
+    ///
+    /// let svg = r##"<svg xmlns="http://www.w3.org/2000/svg" width="10" height="10">
+    ///     <rect x="0" y="0" width="10" height="10" fill="#0000ff"/>
+    ///     <g id="sunny"><circle cx="5" cy="5" r="3" fill="#ffff00"/></g>
+    ///     <g id="windy"><path d="M2 5 L8 5" stroke="#ffffff"/></g>
+    /// </svg>"##;
+    /// let icons = SvgConverter::new(Precision::Normal)
+    ///     .split_svg_layers(svg, &TruncateColor::Keep, &Conversion::RequireExact)
+    ///     .unwrap();
+    /// assert_eq!(icons.len(), 2);
+    /// assert_eq!(icons[0].0.as_deref(), Some("sunny"));
+    /// assert!(icons.iter().all(|(_, image)| image.commands.len() == 1));
+    /// ```
+    pub fn split_svg_layers(
+        &self,
+        content: &str,
+        truncate_color: &TruncateColor,
+        conversion: &Conversion,
+    ) -> Svg2PdcResult<Vec<(Option<String>, PebbleImage)>> {
+        self.convert_layers(content, truncate_color, conversion, false)
+    }
+
+    /// Convert every layer into an image, with the content outside of the layers when
+    /// `shared`.
+    fn convert_layers(
+        &self,
+        content: &str,
+        truncate_color: &TruncateColor,
+        conversion: &Conversion,
+        shared: bool,
+    ) -> Svg2PdcResult<Vec<(Option<String>, PebbleImage)>> {
+        let root = roxmltree::Document::parse(content)?;
+        let layers = Self::frame_layers(&root);
+        let ids: Vec<_> = layers.iter().map(Node::id).collect();
+        layers
+            .into_iter()
+            .map(|layer| {
+                let converter = Self {
+                    frame: Some(FrameLayers {
+                        layers: ids.clone(),
+                        current: layer.id(),
+                        shared,
+                    }),
+                    ..self.clone()
+                };
+                let image = converter.convert_root(&root, truncate_color, conversion)?;
+                let name = layer
+                    .attribute((InkscapePage::INKSCAPE_NAMESPACE, "label"))
+                    .or(layer.attribute("id"))
+                    .map(str::to_string);
+                Ok((name, image))
+            })
+            .collect()
+    }
 }
@@ -0,0 +1,65 @@
+//! Swatch sheets showing colors of the Pebble palette.
+//!
+//! A sheet lets designers pick colors that survive quantization before drawing, or check
+//! which colors a converted image ended up with.
+
+use std::fmt::Write;
+
+use crate::color::PebbleColor;
+
+const COLUMNS: usize = 8;
+const SWATCH_WIDTH: usize = 80;
+const SWATCH_HEIGHT: usize = 40;
+const LABEL_HEIGHT: usize = 28;
+
+/// Render `colors` as an SVG document, one labeled swatch per color.
+///
+/// Every swatch is labeled with the hex color the watch displays and its Pebble color byte.
+///
+/// ```
+/// use svg2pdc::{color::PebbleColor, swatch::swatch_sheet_svg};
+///
+/// let sheet = swatch_sheet_svg(&PebbleColor::palette().collect::<Vec<_>>());
+/// assert!(sheet.contains("#55AAFF"));
+/// assert!(sheet.contains("0xDA"));
+/// ```
+pub fn swatch_sheet_svg(colors: &[PebbleColor]) -> String {
+    let rows = colors.len().div_ceil(COLUMNS).max(1);
+    let width = COLUMNS * SWATCH_WIDTH;
+    let height = rows * (SWATCH_HEIGHT + LABEL_HEIGHT);
+
+    let mut svg = String::new();
+    // Writing to a String can't fail
+    let _ = writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}">"#
+    );
+    let _ = writeln!(
+        svg,
+        r##"  <rect width="{width}" height="{height}" fill="#FFFFFF"/>"##
+    );
+    for (index, color) in colors.iter().enumerate() {
+        let x = index % COLUMNS * SWATCH_WIDTH;
+        let y = index / COLUMNS * (SWATCH_HEIGHT + LABEL_HEIGHT);
+        let hex = color.rgb_hex();
+        let _ = writeln!(
+            svg,
+            r##"  <rect x="{x}" y="{y}" width="{SWATCH_WIDTH}" height="{SWATCH_HEIGHT}" fill="{hex}" stroke="#000000"/>"##
+        );
+        let _ = writeln!(
+            svg,
+            r##"  <text x="{}" y="{}" font-family="monospace" font-size="11" fill="#000000">{hex}</text>"##,
+            x + 4,
+            y + SWATCH_HEIGHT + 12
+        );
+        let _ = writeln!(
+            svg,
+            r##"  <text x="{}" y="{}" font-family="monospace" font-size="11" fill="#000000">0x{:02X}</text>"##,
+            x + 4,
+            y + SWATCH_HEIGHT + 24,
+            color.inner()
+        );
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
@@ -0,0 +1,212 @@
+//! Helpers for testing code built on top of the PDC model.
+//!
+//! Available behind the `test-utils` feature so downstream crates can reuse the
+//! same generators and assertions the crate uses for its own tests.
+
+use rand::Rng;
+
+use crate::{
+    image::{DrawCommand, DrawOptions, PebbleImage},
+    point::{PebblePoint, Precision},
+};
+
+/// Generate a random image that is valid to serialize.
+///
+/// ```rust
+/// use svg2pdc::test_utils::{assert_round_trip, random_image};
+///
+/// let mut rng = rand::rng();
+/// for _ in 0..10 {
+///     assert_round_trip(&random_image(&mut rng));
+/// }
+/// ```
+pub fn random_image<R: Rng + ?Sized>(rng: &mut R) -> PebbleImage {
+    let size = PebblePoint {
+        x: rng.random_range(1..=200),
+        y: rng.random_range(1..=200),
+    };
+    let commands = (0..rng.random_range(0..16))
+        .map(|_| random_command(rng, size))
+        .collect();
+    PebbleImage { size, commands }
+}
+
+/// Generate a random draw command with its points inside a canvas of the given size.
+pub fn random_command<R: Rng + ?Sized>(rng: &mut R, size: PebblePoint) -> DrawCommand {
+    let precision = if rng.random_bool(0.5) {
+        Precision::Normal
+    } else {
+        Precision::Precise
+    };
+    let options = DrawOptions {
+        stroke_width: rng.random(),
+        stroke_color: rng.random(),
+        fill_color: rng.random(),
+        precision,
+        ..Default::default()
+    };
+
+    if rng.random_bool(0.25) {
+        return DrawCommand::Circle {
+            center: random_point(rng, size, Precision::Normal),
+            radius: rng.random_range(0..=size.x.max(size.y) as u16),
+            options: DrawOptions {
+                precision: Precision::Normal,
+                ..options
+            },
+        };
+    }
+
+    DrawCommand::Path {
+        points: (0..rng.random_range(1..32))
+            .map(|_| random_point(rng, size, precision))
+            .collect(),
+        open: rng.random_bool(0.5),
+        options,
+    }
+}
+
+fn random_point<R: Rng + ?Sized>(
+    rng: &mut R,
+    size: PebblePoint,
+    precision: Precision,
+) -> PebblePoint {
+    let scale = match precision {
+        Precision::Normal => 1,
+        Precision::Precise => 8,
+    };
+    PebblePoint {
+        x: rng.random_range(0..size.x * scale),
+        y: rng.random_range(0..size.y * scale),
+    }
+}
+
+/// Serialize and deserialize an image, asserting that both the bytes and the structure survive.
+///
+/// Returns the deserialized image.
+pub fn assert_round_trip(image: &PebbleImage) -> PebbleImage {
+    let mut serialized = Vec::new();
+    image
+        .serialize(&mut serialized)
+        .expect("failed to serialize image");
+
+    let deserialized =
+        PebbleImage::deserialize(&mut serialized.as_slice()).expect("failed to deserialize image");
+    assert_images_eq(image, &deserialized);
+
+    let mut reserialized = Vec::new();
+    deserialized
+        .serialize(&mut reserialized)
+        .expect("failed to serialize deserialized image");
+    assert_eq!(
+        serialized, reserialized,
+        "round trip changed the serialized bytes"
+    );
+
+    deserialized
+}
+
+/// Assert that two images are structurally equal.
+///
+/// Only the fields that end up in the serialized PDC are compared, so the translation
+/// and conversion mode recorded in [`DrawOptions`] are ignored.
+pub fn assert_images_eq(left: &PebbleImage, right: &PebbleImage) {
+    if let Some(difference) = image_difference(left, right) {
+        panic!("images differ: {difference}");
+    }
+}
+
+/// Describe the first structural difference between two images, if any.
+pub fn image_difference(left: &PebbleImage, right: &PebbleImage) -> Option<String> {
+    if left.size != right.size {
+        return Some(format!("size {:?} != {:?}", left.size, right.size));
+    }
+    if left.commands.len() != right.commands.len() {
+        return Some(format!(
+            "command count {} != {}",
+            left.commands.len(),
+            right.commands.len()
+        ));
+    }
+    left.commands
+        .iter()
+        .zip(&right.commands)
+        .enumerate()
+        .find_map(|(index, (left, right))| {
+            command_difference(left, right)
+                .map(|difference| format!("command {index}: {difference}"))
+        })
+}
+
+fn command_difference(left: &DrawCommand, right: &DrawCommand) -> Option<String> {
+    let (left_options, right_options) = match (left, right) {
+        (
+            DrawCommand::Path {
+                points: left_points,
+                open: left_open,
+                options: left_options,
+            },
+            DrawCommand::Path {
+                points: right_points,
+                open: right_open,
+                options: right_options,
+            },
+        ) => {
+            if left_open != right_open {
+                return Some(format!("open {left_open} != {right_open}"));
+            }
+            if left_points != right_points {
+                return Some(format!("points {left_points:?} != {right_points:?}"));
+            }
+            (left_options, right_options)
+        }
+        (
+            DrawCommand::Circle {
+                center: left_center,
+                radius: left_radius,
+                options: left_options,
+            },
+            DrawCommand::Circle {
+                center: right_center,
+                radius: right_radius,
+                options: right_options,
+            },
+        ) => {
+            if left_center != right_center {
+                return Some(format!("center {left_center:?} != {right_center:?}"));
+            }
+            if left_radius != right_radius {
+                return Some(format!("radius {left_radius} != {right_radius}"));
+            }
+            (left_options, right_options)
+        }
+        _ => return Some("command types differ".to_string()),
+    };
+
+    if left_options.stroke_width != right_options.stroke_width {
+        return Some(format!(
+            "stroke width {} != {}",
+            left_options.stroke_width, right_options.stroke_width
+        ));
+    }
+    if left_options.stroke_color != right_options.stroke_color {
+        return Some(format!(
+            "stroke color {} != {}",
+            left_options.stroke_color, right_options.stroke_color
+        ));
+    }
+    if left_options.fill_color != right_options.fill_color {
+        return Some(format!(
+            "fill color {} != {}",
+            left_options.fill_color, right_options.fill_color
+        ));
+    }
+    if matches!(left, DrawCommand::Path { .. }) && left_options.precision != right_options.precision
+    {
+        return Some(format!(
+            "precision {:?} != {:?}",
+            left_options.precision, right_options.precision
+        ));
+    }
+    None
+}
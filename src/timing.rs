@@ -0,0 +1,207 @@
+//! Frame durations of an animation, read from a manifest mapping frame file names to
+//! milliseconds.
+//!
+//! A manifest is either a JSON object, `{"frame_1.svg": 100, "frame_2.svg": 400}`, or CSV
+//! rows of a name and a duration, optionally under a header:
+//!
+//! ```text
+//! frame,duration
+//! frame_1.svg,100
+//! frame_2.svg,400
+//! ```
+
+use crate::error::{Svg2PdcError, Svg2PdcResult};
+
+/// Parse a JSON or CSV manifest into frame names and durations in milliseconds, in the
+/// order of the manifest.
+///
+/// ```rust
+/// use svg2pdc::timing::parse_timing;
+///
+/// let json = parse_timing(r#"{"open.svg": 100, "closed.svg": 2000}"#).unwrap();
+/// let csv = parse_timing("frame,duration\nopen.svg,100\nclosed.svg,2000\n").unwrap();
+/// assert_eq!(json, csv);
+/// assert_eq!(json[1], ("closed.svg".to_string(), 2000));
+/// ```
+pub fn parse_timing(manifest: &str) -> Svg2PdcResult<Vec<(String, u16)>> {
+    if manifest.trim_start().starts_with('{') {
+        parse_json(manifest)
+    } else {
+        parse_csv(manifest)
+    }
+}
+
+fn parse_duration(name: &str, duration: &str) -> Svg2PdcResult<u16> {
+    duration.trim().parse().map_err(|_| {
+        Svg2PdcError::ParseError(format!(
+            "duration of `{name}` is not a whole number of milliseconds up to {}: `{}`",
+            u16::MAX,
+            duration.trim()
+        ))
+    })
+}
+
+fn parse_csv(manifest: &str) -> Svg2PdcResult<Vec<(String, u16)>> {
+    let mut timing = Vec::new();
+    for (index, line) in manifest.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        // Names may contain commas, durations don't
+        let Some((name, duration)) = line.rsplit_once(',') else {
+            return Err(Svg2PdcError::ParseError(format!(
+                "line {} of the timing isn't `name,duration`: `{line}`",
+                index + 1
+            )));
+        };
+        let name = name.trim();
+        let name = match name
+            .strip_prefix('"')
+            .and_then(|name| name.strip_suffix('"'))
+        {
+            Some(quoted) => quoted.replace("\"\"", "\""),
+            None => name.to_string(),
+        };
+        // A header row names the columns instead
+        if timing.is_empty() && duration.trim().parse::<f64>().is_err() {
+            continue;
+        }
+        timing.push((name.clone(), parse_duration(&name, duration)?));
+    }
+    Ok(timing)
+}
+
+/// Parse a flat JSON object of numbers.
+fn parse_json(manifest: &str) -> Svg2PdcResult<Vec<(String, u16)>> {
+    let mut parser = JsonParser {
+        rest: manifest.trim(),
+    };
+    parser.expect('{')?;
+    let mut timing = Vec::new();
+    if !parser.consume('}') {
+        loop {
+            let name = parser.string()?;
+            parser.expect(':')?;
+            let duration = parser.number();
+            timing.push((name.clone(), parse_duration(&name, duration)?));
+            if parser.consume('}') {
+                break;
+            }
+            parser.expect(',')?;
+        }
+    }
+    if !parser.rest.is_empty() {
+        return Err(parser.error("the end of the timing"));
+    }
+    Ok(timing)
+}
+
+struct JsonParser<'a> {
+    rest: &'a str,
+}
+
+impl JsonParser<'_> {
+    fn error(&self, expected: &str) -> Svg2PdcError {
+        let found: String = self.rest.chars().take(16).collect();
+        Svg2PdcError::ParseError(format!(
+            "expected {expected} in the timing, found `{found}`"
+        ))
+    }
+
+    /// Skip whitespace and `c` if it comes next.
+    fn consume(&mut self, c: char) -> bool {
+        self.rest = self.rest.trim_start();
+        match self.rest.strip_prefix(c) {
+            Some(rest) => {
+                self.rest = rest;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn expect(&mut self, c: char) -> Svg2PdcResult<()> {
+        match self.consume(c) {
+            true => Ok(()),
+            false => Err(self.error(&format!("`{c}`"))),
+        }
+    }
+
+    fn string(&mut self) -> Svg2PdcResult<String> {
+        self.expect('"')?;
+        let mut string = String::new();
+        let mut chars = self.rest.char_indices();
+        while let Some((offset, c)) = chars.next() {
+            match c {
+                '"' => {
+                    self.rest = &self.rest[offset + 1..];
+                    return Ok(string);
+                }
+                '\\' => match chars.next().map(|(_, escaped)| escaped) {
+                    Some('n') => string.push('\n'),
+                    Some('t') => string.push('\t'),
+                    Some('u') => {
+                        let code: String = chars.by_ref().take(4).map(|(_, c)| c).collect();
+                        let escaped = u32::from_str_radix(&code, 16)
+                            .ok()
+                            .and_then(char::from_u32)
+                            .ok_or_else(|| self.error("a unicode escape"))?;
+                        string.push(escaped);
+                    }
+                    Some(escaped @ ('"' | '\\' | '/')) => string.push(escaped),
+                    _ => return Err(self.error("an escape sequence")),
+                },
+                c => string.push(c),
+            }
+        }
+        Err(self.error("the end of a string"))
+    }
+
+    /// The text of a number, checked when it is parsed as a duration.
+    fn number(&mut self) -> &str {
+        self.rest = self.rest.trim_start();
+        let end = self
+            .rest
+            .find(|c: char| c.is_whitespace() || matches!(c, ',' | '}'))
+            .unwrap_or(self.rest.len());
+        let (number, rest) = self.rest.split_at(end);
+        self.rest = rest;
+        number
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_json_strings_and_errors() {
+        assert_eq!(
+            parse_timing(r#" { "a \"b\".svg" : 1 , "é.svg":2 } "#).unwrap(),
+            vec![("a \"b\".svg".to_string(), 1), ("é.svg".to_string(), 2)]
+        );
+        assert_eq!(parse_timing("{}").unwrap(), Vec::new());
+        for invalid in [
+            r#"{"a.svg": 1"#,
+            r#"{"a.svg": 1.5}"#,
+            r#"{"a.svg": 70000}"#,
+            r#"{"a.svg" 1}"#,
+            r#"{"a.svg": 1} x"#,
+        ] {
+            assert!(parse_timing(invalid).is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn test_csv_quoted_names() {
+        assert_eq!(
+            parse_timing("\"blink, \"\"closed\"\".svg\",300\nopen.svg, 100").unwrap(),
+            vec![
+                ("blink, \"closed\".svg".to_string(), 300),
+                ("open.svg".to_string(), 100)
+            ]
+        );
+        assert!(parse_timing("open.svg,100\nclosed.svg,soon").is_err());
+    }
+}
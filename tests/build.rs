@@ -0,0 +1,48 @@
+use svg2pdc::{build::convert_dir, prelude::*};
+
+const SVG: &str = r##"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 10 10">
+    <rect x="1" y="1" width="4" height="4" fill="#ffffff"/>
+</svg>"##;
+
+#[test]
+fn test_only_outdated_images_are_converted() -> anyhow::Result<()> {
+    let root = std::env::temp_dir().join(format!("svg2pdc-build-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&root);
+    let (source, output) = (root.join("icons"), root.join("out"));
+    std::fs::create_dir_all(source.join("weather"))?;
+    std::fs::write(source.join("weather/sun.svg"), SVG)?;
+    std::fs::write(source.join("moon.svg"), SVG)?;
+    std::fs::write(source.join("notes.txt"), "not an image")?;
+    let converter = SvgConverter::new(Precision::Normal);
+
+    let manifest = convert_dir(&source, &output, &converter)?;
+    let names: Vec<_> = manifest.images.iter().map(|image| &image.name).collect();
+    assert_eq!(names, ["moon", "weather/sun"]);
+    assert!(manifest.images.iter().all(|image| image.converted));
+    let sun = manifest.get("weather/sun").unwrap();
+    assert_eq!(sun.output, output.join("weather/sun.pdc"));
+    let image = PebbleImage::deserialize(&mut std::fs::File::open(&sun.output)?)?;
+    assert_eq!(image.commands.len(), 1);
+    assert!(
+        manifest
+            .rust_module()
+            .contains("pub const WEATHER_SUN: &[u8]")
+    );
+
+    // Nothing changed since, the images are kept
+    let manifest = convert_dir(&source, &output, &converter)?;
+    assert!(manifest.images.iter().all(|image| !image.converted));
+
+    // Other options make the images outdated
+    let precise = SvgConverter::new(Precision::Precise);
+    let manifest = convert_dir(&source, &output, &precise)?;
+    assert!(manifest.images.iter().all(|image| image.converted));
+    let manifest = convert_dir(&source, &output, &precise)?;
+    assert!(manifest.images.iter().all(|image| !image.converted));
+
+    std::fs::write(source.join("broken.svg"), "<svg")?;
+    let error = convert_dir(&source, &output, &converter).unwrap_err();
+    assert!(error.to_string().contains("broken.svg"));
+    std::fs::remove_dir_all(root)?;
+    Ok(())
+}
@@ -122,3 +122,131 @@ fn test_pencil_inkscape() -> anyhow::Result<()> {
     assert_eq!(converted_pdc, original_pdc_content);
     Ok(())
 }
+
+#[test]
+fn test_circles() -> anyhow::Result<()> {
+    test_svg_conversion("tests/resources/svg/circles.svg")
+}
+
+#[test]
+fn test_precise_circles() -> anyhow::Result<()> {
+    // Only the path is precise, the canvas size and the circle centers stay in pixels
+    let svg_content = std::fs::read_to_string("tests/resources/svg/circles.svg")?;
+    let image = SvgConverter::new(Precision::Precise).parse_svg_image(
+        &svg_content,
+        &TruncateColor::Truncate,
+        &Conversion::RequireExact,
+    )?;
+    let mut converted_pdc = Vec::new();
+    image.serialize(&mut converted_pdc)?;
+
+    assert_eq!(
+        converted_pdc,
+        std::fs::read("tests/resources/golden_pdc/circles-precise.pdc")?
+    );
+    Ok(())
+}
+
+#[test]
+fn test_fractional_path() -> anyhow::Result<()> {
+    // Cut from pencil-illustrator.svg, the commands are those of the original tool's output:
+    // the points of the paths are neither floored nor moved to the nearest valid point
+    let svg_content = std::fs::read_to_string("tests/resources/svg/fractional-path.svg")?;
+    let image = SvgConverter::new(Precision::Normal).parse_svg_image(
+        &svg_content,
+        &TruncateColor::Truncate,
+        &Conversion::ConvertNoWarn,
+    )?;
+    let mut converted_pdc = Vec::new();
+    image.serialize(&mut converted_pdc)?;
+
+    assert_eq!(
+        converted_pdc,
+        std::fs::read("tests/resources/golden_pdc/fractional-path.pdc")?
+    );
+    Ok(())
+}
+
+#[test]
+fn test_improved_output_mode() -> anyhow::Result<()> {
+    let svg_content = r##"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 20 20">
+        <polyline points="2,3 10.5,12.5" stroke="#ffffff" />
+    </svg>"##;
+
+    let compatible = SvgConverter::new(Precision::Normal).parse_svg_image(
+        svg_content,
+        &TruncateColor::Truncate,
+        &Conversion::RequireExact,
+    )?;
+    let improved = SvgConverter::new(Precision::Normal)
+        .with_output_mode(OutputMode::Improved)
+        .parse_svg_image(
+            svg_content,
+            &TruncateColor::Truncate,
+            &Conversion::RequireExact,
+        )?;
+
+    let points = |image: &PebbleImage| match &image.commands[0] {
+        DrawCommand::Path { points, .. } => points
+            .iter()
+            .map(|point| (point.x, point.y))
+            .collect::<Vec<_>>(),
+        command => panic!("unexpected command {command:?}"),
+    };
+    assert_eq!(points(&compatible), vec![(2, 3), (10, 12)]);
+    assert_eq!(points(&improved), vec![(2, 3), (11, 13)]);
+    Ok(())
+}
+
+#[test]
+fn test_pixel_center_convention() -> anyhow::Result<()> {
+    let svg_content = r##"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 20 20">
+        <polyline points="2,3 10.5,12.5" stroke="#ffffff" />
+    </svg>"##;
+
+    let image = SvgConverter::new(Precision::Normal)
+        .with_pixel_convention(PixelConvention::PixelCenter)
+        .parse_svg_image(
+            svg_content,
+            &TruncateColor::Truncate,
+            &Conversion::RequireExact,
+        )?;
+
+    match &image.commands[0] {
+        DrawCommand::Path { points, .. } => {
+            assert_eq!((points[1].x, points[1].y), (11, 13));
+        }
+        command => panic!("unexpected command {command:?}"),
+    }
+    Ok(())
+}
+
+#[test]
+fn test_floored_output_mode() -> anyhow::Result<()> {
+    let svg_content = r##"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 20 20">
+        <path d="M1.3 1.3 L5.7 2.2 L3 8" stroke="#ffffff" />
+        <polyline points="1.8,1.8 4,4" stroke="#ffffff" />
+    </svg>"##;
+    let convert = |output_mode: OutputMode, conversion: Conversion| {
+        SvgConverter::new(Precision::Normal)
+            .with_output_mode(output_mode)
+            .parse_svg_image(svg_content, &TruncateColor::Truncate, &conversion)
+    };
+    let points = |image: &PebbleImage, index: usize| match &image.commands[index] {
+        DrawCommand::Path { points, .. } => points
+            .iter()
+            .map(|point| (point.x, point.y))
+            .collect::<Vec<_>>(),
+        command => panic!("unexpected command {command:?}"),
+    };
+
+    // The points of paths are floored instead of rounded
+    let floored = convert(OutputMode::Floored, Conversion::ConvertNoWarn)?;
+    assert_eq!(points(&floored, 0), vec![(1, 1), (5, 2), (3, 8)]);
+
+    // Invalid points are moved to the nearest valid one before they are rounded
+    let compatible = convert(OutputMode::PythonCompatible, Conversion::ConvertNoWarn)?;
+    assert_eq!(points(&compatible, 1), vec![(1, 1), (4, 4)]);
+    assert_eq!(points(&floored, 1), vec![(2, 2), (4, 4)]);
+    Ok(())
+}
@@ -0,0 +1,60 @@
+use svg2pdc::{point::Conversion, prelude::*};
+
+const SVG: &str = r##"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 20 20">
+    <circle cx="5.25" cy="5" r="3" fill="#ffffff" stroke-width="0"/>
+    <circle cx="14.5" cy="5" r="3" fill="#ffffff" stroke-width="0"/>
+</svg>"##;
+
+#[test]
+fn test_sub_pixel_circles_become_precise_paths() -> anyhow::Result<()> {
+    let converter = SvgConverter::new(Precision::Precise);
+    assert!(
+        converter
+            .parse_svg_image(SVG, &TruncateColor::Keep, &Conversion::RequireExact)
+            .is_err()
+    );
+
+    let image = converter.with_circles_as_paths(true).parse_svg_image(
+        SVG,
+        &TruncateColor::Keep,
+        &Conversion::RequireExact,
+    )?;
+    let [
+        DrawCommand::Path {
+            points,
+            open: false,
+            ..
+        },
+        DrawCommand::Circle { .. },
+    ] = image.commands.as_slice()
+    else {
+        panic!("expected a path and a circle, got {:?}", image.commands);
+    };
+
+    // The rightmost point of the outline, in eighths of a pixel
+    let right = points.iter().map(|point| point.x).max().unwrap();
+    assert_eq!(right, ((5.25 + 3.0 - 0.5) * 8.0) as i16);
+    Ok(())
+}
+
+#[test]
+fn test_large_circles_stay_circles() -> anyhow::Result<()> {
+    let converter = SvgConverter::new(Precision::Precise)
+        .with_circles_as_paths(true)
+        .with_circle_path_max_radius(2.0);
+    let image = converter.parse_svg_image(SVG, &TruncateColor::Keep, &Conversion::ConvertNoWarn)?;
+    assert!(
+        image
+            .commands
+            .iter()
+            .all(|command| matches!(command, DrawCommand::Circle { .. }))
+    );
+
+    let image = converter.with_circle_path_max_radius(3.0).parse_svg_image(
+        SVG,
+        &TruncateColor::Keep,
+        &Conversion::ConvertNoWarn,
+    )?;
+    assert!(matches!(image.commands[0], DrawCommand::Path { .. }));
+    Ok(())
+}
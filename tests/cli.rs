@@ -0,0 +1,252 @@
+use std::{
+    io::Write,
+    path::Path,
+    process::{Command, Output, Stdio},
+};
+
+const SVG: &str = r##"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 10 10">
+    <rect x="0" y="0" width="10" height="10" fill="#ffffff"/>
+    <rect x="1" y="1" width="4" height="4" fill="#ff0000"/>
+</svg>"##;
+
+/// A fresh directory for the files of a test.
+fn temp_dir(name: &str) -> anyhow::Result<std::path::PathBuf> {
+    let directory = std::env::temp_dir().join(format!("svg2pdc-cli-{name}-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&directory);
+    std::fs::create_dir_all(&directory)?;
+    Ok(directory)
+}
+
+/// Run the command line tool with `stdin` piped into it.
+fn svg2pdc(args: &[&str], stdin: &str) -> anyhow::Result<Output> {
+    svg2pdc_in(Path::new("."), &[], args, stdin)
+}
+
+/// [`svg2pdc`] with `directory` as the working directory and the environment variables `env`.
+fn svg2pdc_in(
+    directory: &Path,
+    env: &[(&str, &str)],
+    args: &[&str],
+    stdin: &str,
+) -> anyhow::Result<Output> {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_svg2pdc"))
+        .current_dir(directory)
+        .envs(env.iter().copied())
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    child
+        .stdin
+        .take()
+        .expect("stdin is piped")
+        .write_all(stdin.as_bytes())?;
+    Ok(child.wait_with_output()?)
+}
+
+#[test]
+fn test_status_messages_stay_out_of_stdout() -> anyhow::Result<()> {
+    let output = svg2pdc(
+        &[
+            "-",
+            "-o",
+            "-",
+            "--strip-background",
+            "#ffffff",
+            "--optimize",
+            "--verbose",
+        ],
+        SVG,
+    )?;
+    assert!(output.status.success());
+    assert!(output.stdout.starts_with(b"PDCI"), "{:?}", output.stdout);
+    let stderr = String::from_utf8(output.stderr)?;
+    assert!(stderr.contains("Removed full-canvas background rectangle"));
+    Ok(())
+}
+
+#[test]
+fn test_changed_template_converts_the_directory_again() -> anyhow::Result<()> {
+    let root = temp_dir("template")?;
+    let (input, output, template) = (root.join("icons"), root.join("out"), root.join("frame.svg"));
+    std::fs::create_dir_all(&input)?;
+    std::fs::write(input.join("icon.svg"), SVG)?;
+    let frame = |color: &str| {
+        format!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 10 10"><rect x="0" y="0" width="10" height="1" fill="{color}"/></svg>"#
+        )
+    };
+    std::fs::write(&template, frame("#0000ff"))?;
+    let args = [
+        input.to_str().unwrap(),
+        "-o",
+        output.to_str().unwrap(),
+        "--template",
+        template.to_str().unwrap(),
+        "--no-config",
+    ];
+    let summary = |output: &Output| String::from_utf8_lossy(&output.stderr).into_owned();
+
+    assert!(summary(&svg2pdc(&args, "")?).contains("Converted 1 of 1 files, 0 unchanged"));
+    assert!(summary(&svg2pdc(&args, "")?).contains("Converted 0 of 1 files, 1 unchanged"));
+    let before = std::fs::read(output.join("icon.pdc"))?;
+    std::fs::write(&template, frame("#00ff00"))?;
+    assert!(summary(&svg2pdc(&args, "")?).contains("Converted 1 of 1 files, 0 unchanged"));
+    assert_ne!(std::fs::read(output.join("icon.pdc"))?, before);
+    std::fs::remove_dir_all(root)?;
+    Ok(())
+}
+
+#[test]
+fn test_command_line_turns_config_settings_off() -> anyhow::Result<()> {
+    let root = temp_dir("no-precise")?;
+    std::fs::write(root.join("svg2pdc.toml"), "precise = true")?;
+    // The type of the first command, a precise path or a path
+    let command_type = |args: &[&str]| -> anyhow::Result<u8> {
+        let output = svg2pdc_in(&root, &[], &[&["-", "-o", "-"], args].concat(), SVG)?;
+        assert!(output.status.success(), "{output:?}");
+        Ok(output.stdout[16])
+    };
+
+    assert_eq!(command_type(&[])?, 3);
+    assert_eq!(command_type(&["--no-precise"])?, 1);
+    assert_eq!(command_type(&["--precise", "--no-precise"])?, 1);
+    std::fs::remove_dir_all(root)?;
+    Ok(())
+}
+
+#[test]
+fn test_command_line_turns_environment_settings_off() -> anyhow::Result<()> {
+    let svg = r##"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 10 10">
+        <rect x="1" y="1" width="4" height="4" fill="#a00000"/>
+    </svg>"##;
+    // The type and the fill color of the first command
+    let command = |env: (&str, &str), args: &[&str]| -> anyhow::Result<(u8, u8)> {
+        let output = svg2pdc_in(
+            Path::new("."),
+            &[env],
+            &[&["-", "-o", "-", "--no-config"], args].concat(),
+            svg,
+        )?;
+        assert!(output.status.success(), "{output:?}");
+        Ok((output.stdout[16], output.stdout[20]))
+    };
+
+    assert_eq!(command(("SVG2PDC_PRECISE", "1"), &[])?.0, 3);
+    assert_eq!(command(("SVG2PDC_PRECISE", "1"), &["--no-precise"])?.0, 1);
+    // Truncated, red drops to 0b01 instead of being rounded to 0b10
+    assert_eq!(
+        command(("SVG2PDC_TRUNCATE_COLOR", "1"), &[])?.1,
+        0b1101_0000
+    );
+    assert_eq!(
+        command(("SVG2PDC_TRUNCATE_COLOR", "1"), &["--no-truncate-color"])?.1,
+        0b1110_0000
+    );
+    Ok(())
+}
+
+#[test]
+fn test_compare_reference_truncates_colors_like_the_original_tool() -> anyhow::Result<()> {
+    let root = temp_dir("compare-reference")?;
+    let input = root.join("icon.svg");
+    std::fs::write(
+        &input,
+        r##"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 10 10">
+            <rect x="1" y="1" width="4" height="4" fill="#a00000"/>
+        </svg>"##,
+    )?;
+    let reference = root.join("icon.pdc");
+    let output = svg2pdc(
+        &[
+            input.to_str().unwrap(),
+            "-o",
+            reference.to_str().unwrap(),
+            "--truncate-color",
+            "--no-config",
+        ],
+        "",
+    )?;
+    assert!(output.status.success(), "{output:?}");
+    // Stands in for svg2pdc.py, which rejects the options it doesn't know
+    let script = root.join("svg2pdc.sh");
+    std::fs::write(
+        &script,
+        r#"while [ $# -gt 0 ]; do
+    case "$1" in
+        -o) output="$2"; shift ;;
+        -t) exit 2 ;;
+    esac
+    shift
+done
+cp "$(dirname "$0")/icon.pdc" "$output"
+"#,
+    )?;
+
+    for truncate_color in [&[][..], &["--truncate-color"]] {
+        for source in [
+            ["--reference", root.to_str().unwrap(), "--interpreter", "sh"],
+            ["--script", script.to_str().unwrap(), "--interpreter", "sh"],
+        ] {
+            let args = [
+                &["compare-reference", input.to_str().unwrap()][..],
+                &source,
+                truncate_color,
+            ]
+            .concat();
+            let output = svg2pdc(&args, "")?;
+            assert!(output.status.success(), "{args:?}: {output:?}");
+            assert!(String::from_utf8(output.stdout)?.contains("Identical to the reference"));
+        }
+    }
+    std::fs::remove_dir_all(root)?;
+    Ok(())
+}
+
+#[test]
+fn test_explain_flag_matches_the_subcommand() -> anyhow::Result<()> {
+    let subcommand = svg2pdc(&["explain", "W001"], "")?;
+    let flag = svg2pdc(&["--explain", "W001"], "")?;
+    assert!(subcommand.status.success() && flag.status.success());
+    assert!(String::from_utf8(flag.stdout.clone())?.starts_with("W001: "));
+    assert_eq!(flag.stdout, subcommand.stdout);
+    Ok(())
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_project_sync_converts_like_convert() -> anyhow::Result<()> {
+    let root = temp_dir("project-sync")?;
+    std::fs::write(
+        root.join("package.json"),
+        r#"{"pebble": {"resources": {"media": []}}}"#,
+    )?;
+    std::fs::create_dir_all(root.join("resources/svg"))?;
+    let svg = r##"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 10 10">
+        <line x1="1" y1="1" x2="9" y2="9" stroke="#ff0000" stroke-width="1"/>
+    </svg>"##;
+    std::fs::write(root.join("resources/svg/line.svg"), svg)?;
+    let options = ["--min-stroke-width", "3", "--improved"];
+
+    let output = svg2pdc_in(
+        &root,
+        &[],
+        &[&["project", "sync"], &options[..]].concat(),
+        "",
+    )?;
+    assert!(output.status.success(), "{output:?}");
+    let synced = std::fs::read(root.join("resources/images/line.pdc"))?;
+    let output = svg2pdc_in(
+        &root,
+        &[],
+        &[&["-", "-o", "-", "--convert"], &options[..]].concat(),
+        svg,
+    )?;
+    assert!(output.status.success(), "{output:?}");
+    assert_eq!(synced, output.stdout);
+    // The stroke width, raised from 1
+    assert_eq!(synced[19], 3);
+    std::fs::remove_dir_all(root)?;
+    Ok(())
+}
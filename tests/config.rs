@@ -0,0 +1,104 @@
+use svg2pdc::{
+    color::PebbleColor,
+    config::{Config, Settings},
+    point::{Conversion, PebblePoint},
+    prelude::*,
+};
+
+#[test]
+fn test_file_overrides_fall_back_to_the_settings() -> anyhow::Result<()> {
+    let config = Config::parse(
+        r##"
+precise = true
+convert = true
+fit = "144x168"
+output-dir = "build/images"
+
+[colors]
+"#ff0000" = "#aa0000"
+
+[files."icons/logo.svg"]
+precise = false
+fit = "50x50"
+
+[files."icons/logo.svg".colors]
+"#00ff00" = "#005500"
+"##,
+        "project",
+    )?;
+    assert_eq!(
+        config.output_dir.as_deref(),
+        Some("project/build/images".as_ref())
+    );
+
+    let red = (
+        Color::try_from_hex("ff0000")?,
+        Color::try_from_hex("aa0000")?,
+    );
+    let green = (
+        Color::try_from_hex("00ff00")?,
+        Color::try_from_hex("005500")?,
+    );
+    assert_eq!(
+        config.settings_for("project/icons/logo.svg".as_ref()),
+        Settings {
+            precise: Some(false),
+            truncate_color: None,
+            convert: Some(true),
+            fit: Some(PebblePoint { x: 50, y: 50 }),
+            colors: vec![green, red],
+        }
+    );
+    assert_eq!(
+        config.settings_for("project/icons/sun.svg".as_ref()),
+        config.settings
+    );
+
+    // Settings given elsewhere, like on the command line, win
+    let command_line = Settings {
+        precise: Some(true),
+        ..Default::default()
+    };
+    let settings = command_line.or(&config.settings_for("project/icons/logo.svg".as_ref()));
+    assert_eq!(settings.precise, Some(true));
+    assert_eq!(settings.fit, Some(PebblePoint { x: 50, y: 50 }));
+    Ok(())
+}
+
+#[test]
+fn test_invalid_config_is_rejected() {
+    for content in [
+        "precize = true",
+        "precise = \"yes\"",
+        "fit = \"144\"",
+        "[colors]\n\"red\" = \"#aa0000\"",
+        "[files.\"logo.svg\"]\noutput-dir = \"out\"",
+    ] {
+        let error = Config::parse(content, ".").unwrap_err();
+        assert!(matches!(error, Svg2PdcError::ParseError(_)), "{content}");
+    }
+}
+
+#[test]
+fn test_color_map_keeps_the_opacity() -> anyhow::Result<()> {
+    let svg = r##"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 10 10">
+        <rect x="1" y="1" width="4" height="4" fill="#ff0000"/>
+        <rect x="5" y="5" width="4" height="4" fill="#ff0000" fill-opacity="0"/>
+        <rect x="1" y="5" width="4" height="4" fill="#0000ff"/>
+    </svg>"##;
+    let converter = SvgConverter::new(Precision::Normal).with_color_map(vec![(
+        Color::try_from_hex("ff0000")?,
+        Color::try_from_hex("00ff00")?,
+    )]);
+    let image = converter.parse_svg_image(svg, &TruncateColor::Keep, &Conversion::RequireExact)?;
+    let fills: Vec<_> = image
+        .commands
+        .iter()
+        .map(|command| PebbleColor::from_inner(command.options().fill_color))
+        .collect();
+    assert_eq!(
+        fills,
+        [PebbleColor::GREEN, PebbleColor::CLEAR, PebbleColor::BLUE]
+    );
+    Ok(())
+}
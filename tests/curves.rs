@@ -0,0 +1,93 @@
+use svg2pdc::{
+    point::{Conversion, PebblePoint},
+    prelude::*,
+    svg_converter::FragmentOptions,
+};
+
+const OPTIONS: FragmentOptions = FragmentOptions {
+    size: PebblePoint { x: 40, y: 40 },
+    translate: FPoint::new(0.0, 0.0),
+    truncate_color: TruncateColor::Truncate,
+    conversion: Conversion::ConvertNoWarn,
+};
+
+fn path_points(converter: &SvgConverter, d: &str) -> anyhow::Result<Vec<PebblePoint>> {
+    let image = converter.parse_svg_fragment(
+        &format!(r##"<path d="{d}" stroke="#ffffff" fill="none"/>"##),
+        &OPTIONS,
+    )?;
+    match image.commands.as_slice() {
+        [DrawCommand::Path { points, .. }] => Ok(points.clone()),
+        commands => panic!("expected a single path, got {commands:?}"),
+    }
+}
+
+#[test]
+fn test_curves_keep_only_end_points_by_default() -> anyhow::Result<()> {
+    let converter = SvgConverter::new(Precision::Normal);
+    let points = path_points(&converter, "M0 0 C0 20 20 20 20 0")?;
+    assert_eq!(points.len(), 2);
+    Ok(())
+}
+
+#[test]
+fn test_flattened_curves_follow_the_curve() -> anyhow::Result<()> {
+    let converter = SvgConverter::new(Precision::Precise).with_curve_tolerance(0.1);
+    for d in [
+        "M0 0 C0 20 20 20 20 0",
+        "M0 0 Q10 30 20 0",
+        // Smooth segments mirror the previous control point
+        "M0 0 C0 10 5 15 10 15 S20 10 20 0",
+        "m0 0 q0 15 10 15 t10 -15",
+    ] {
+        let points = path_points(&converter, d)?;
+        assert!(points.len() > 8, "{d} has only {} points", points.len());
+        // The curves are symmetric and bulge to at most y = 15, x = 10 is their lowest point
+        let lowest = points.iter().max_by_key(|point| point.y).unwrap();
+        let to_pixels = |coordinate: i16| coordinate as f32 / 8.0 + 0.5;
+        assert!(
+            (to_pixels(lowest.x) - 10.0).abs() <= 1.0,
+            "{d}: lowest point {lowest:?}"
+        );
+        assert!(
+            (13.0..=15.5).contains(&to_pixels(lowest.y)),
+            "{d}: lowest point {lowest:?}"
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn test_flattened_arcs_honor_their_flags() -> anyhow::Result<()> {
+    let to_pixels = |coordinate: i16| coordinate as f32 / 8.0 + 0.5;
+    let default = SvgConverter::new(Precision::Precise);
+    assert_eq!(path_points(&default, "M0 10 A10 10 0 0 0 20 10")?.len(), 2);
+
+    let converter = SvgConverter::new(Precision::Precise).with_curve_tolerance(0.1);
+    // The sweep flag picks the side of the half circle, y grows downwards
+    for (d, expected_y) in [
+        ("M0 10 A10 10 0 0 0 20 10", 20.0),
+        ("M0 10 A10 10 0 0 1 20 10", 0.0),
+        ("M0 10 A10 5 0 0 0 20 10", 15.0),
+    ] {
+        let points = path_points(&converter, d)?;
+        assert!(points.len() > 8, "{d} has only {} points", points.len());
+        let middle = points
+            .iter()
+            .min_by_key(|point| point.x.abs_diff(10 * 8))
+            .unwrap();
+        assert!(
+            (to_pixels(middle.y) - expected_y).abs() <= 0.5,
+            "{d}: middle point {middle:?}"
+        );
+    }
+
+    // Both arcs join the same points on circles around (10, 10) and (20, 20)
+    let large = path_points(&converter, "M10 20 A10 10 0 1 1 20 10")?;
+    let small = path_points(&converter, "M10 20 A10 10 0 0 1 20 10")?;
+    let min_x = |points: &[PebblePoint]| to_pixels(points.iter().map(|p| p.x).min().unwrap());
+    assert!(min_x(&large) <= 0.5, "large arc {large:?}");
+    assert!((min_x(&small) - 10.0).abs() <= 0.5, "small arc {small:?}");
+    assert_eq!(large.last(), small.last());
+    Ok(())
+}
@@ -0,0 +1,77 @@
+use svg2pdc::{point::Conversion, prelude::*};
+
+fn paths(image: &PebbleImage) -> Vec<(Vec<(i16, i16)>, bool)> {
+    image
+        .commands
+        .iter()
+        .map(|command| match command {
+            DrawCommand::Path { points, open, .. } => (
+                points.iter().map(|point| (point.x, point.y)).collect(),
+                *open,
+            ),
+            command => panic!("unexpected command {command:?}"),
+        })
+        .collect()
+}
+
+#[test]
+fn test_dashed_strokes_become_open_paths() -> anyhow::Result<()> {
+    let svg = r##"<svg xmlns="http://www.w3.org/2000/svg" width="40" height="40">
+        <g stroke-dasharray="6" stroke-dashoffset="3">
+            <line x1="2" y1="30" x2="38" y2="30" stroke="#ffffff"/>
+        </g>
+    </svg>"##;
+    let convert = |converter: SvgConverter| {
+        converter.parse_svg_image(svg, &TruncateColor::Keep, &Conversion::RequireExact)
+    };
+
+    // Without the option dashes are drawn solid, like the original tool does
+    assert_eq!(
+        convert(SvgConverter::new(Precision::Normal))?
+            .commands
+            .len(),
+        1
+    );
+
+    let image = convert(SvgConverter::new(Precision::Normal).with_dash_tolerance(0.5))?;
+    assert_eq!(
+        paths(&image),
+        vec![
+            (vec![(2, 30), (5, 30)], true),
+            (vec![(11, 30), (17, 30)], true),
+            (vec![(23, 30), (29, 30)], true),
+            (vec![(35, 30), (38, 30)], true),
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_dashed_shapes_keep_their_fill_and_corners() -> anyhow::Result<()> {
+    let svg = r##"<svg xmlns="http://www.w3.org/2000/svg" width="40" height="40">
+        <rect x="0" y="0" width="10" height="4" fill="#ff0000" stroke="#ffffff" stroke-dasharray="9 1"/>
+    </svg>"##;
+    let convert = |tolerance| {
+        SvgConverter::new(Precision::Normal)
+            .with_dash_tolerance(tolerance)
+            .parse_svg_image(svg, &TruncateColor::Keep, &Conversion::RequireExact)
+    };
+
+    let image = convert(0.5)?;
+    let drawn = paths(&image);
+    // The fill without a stroke, then the dashes without a fill
+    assert_eq!(drawn[0], (vec![(0, 0), (10, 0), (10, 4), (0, 4)], false));
+    let fill = image.commands[0].options();
+    assert_eq!((fill.stroke_width, fill.fill_color != 0), (0, true));
+    assert!(image.commands[1..].iter().all(|command| {
+        let options = command.options();
+        options.fill_color == PebbleColor::nothing().inner() && options.stroke_width == 1
+    }));
+    assert_eq!(drawn[1], (vec![(0, 0), (9, 0)], true));
+    assert_eq!(drawn[2].0[..2], [(10, 0), (10, 4)]);
+
+    // A dash ending close to a corner reaches it
+    let image = convert(1.5)?;
+    assert_eq!(paths(&image)[1], (vec![(0, 0), (10, 0)], true));
+    Ok(())
+}
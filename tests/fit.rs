@@ -0,0 +1,119 @@
+use svg2pdc::{
+    platform::Platform,
+    point::{Conversion, PebblePoint},
+    prelude::*,
+};
+
+#[test]
+fn test_fit_scales_and_centers_the_view_box() -> anyhow::Result<()> {
+    let svg_content = r##"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 20 10">
+        <path d="M0 0 L20 10" stroke="#ffffff" stroke-width="2"/>
+        <circle cx="10" cy="5" r="4" fill="#ff0000"/>
+    </svg>"##;
+    let image = SvgConverter::new(Precision::Normal)
+        .with_pixel_convention(PixelConvention::PixelCenter)
+        .with_fit(Platform::Chalk.screen_size().into())
+        .parse_svg_image(
+            svg_content,
+            &TruncateColor::Truncate,
+            &Conversion::RequireExact,
+        )?;
+
+    assert_eq!(image.size, PebblePoint { x: 180, y: 180 });
+    match &image.commands[..] {
+        [
+            DrawCommand::Path {
+                points, options, ..
+            },
+            DrawCommand::Circle { center, radius, .. },
+        ] => {
+            // Scaled by 9 and moved down by (180 - 90) / 2
+            assert_eq!(
+                points,
+                &[PebblePoint { x: 0, y: 45 }, PebblePoint { x: 180, y: 135 }]
+            );
+            assert_eq!(options.stroke_width, 18);
+            assert_eq!(*center, PebblePoint { x: 90, y: 90 });
+            assert_eq!(*radius, 36);
+        }
+        commands => panic!("unexpected commands {commands:?}"),
+    }
+    Ok(())
+}
+
+fn convert(svg_content: &str) -> anyhow::Result<PebbleImage> {
+    Ok(SvgConverter::new(Precision::Normal)
+        .with_pixel_convention(PixelConvention::PixelCenter)
+        .parse_svg_image(
+            svg_content,
+            &TruncateColor::Truncate,
+            &Conversion::RequireExact,
+        )?)
+}
+
+fn path_points(image: &PebbleImage) -> Vec<PebblePoint> {
+    match &image.commands[..] {
+        [DrawCommand::Path { points, .. }] => points.clone(),
+        commands => panic!("unexpected commands {commands:?}"),
+    }
+}
+
+#[test]
+fn test_view_box_is_scaled_to_width_and_height() -> anyhow::Result<()> {
+    let image = convert(
+        r##"<svg xmlns="http://www.w3.org/2000/svg" width="24" height="24" viewBox="0 0 512 512">
+            <path d="M0 0 L512 256" stroke="#ffffff"/>
+        </svg>"##,
+    )?;
+    assert_eq!(image.size, PebblePoint { x: 24, y: 24 });
+    assert_eq!(
+        path_points(&image),
+        [PebblePoint { x: 0, y: 0 }, PebblePoint { x: 24, y: 12 }]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_preserve_aspect_ratio() -> anyhow::Result<()> {
+    let svg = |aspect: &str| {
+        format!(
+            r##"<svg xmlns="http://www.w3.org/2000/svg" width="40" height="20" viewBox="0 0 10 10"
+                preserveAspectRatio="{aspect}">
+                <path d="M0 0 L10 10" stroke="#ffffff"/>
+            </svg>"##
+        )
+    };
+    for (aspect, expected) in [
+        ("xMidYMid meet", [(10, 0), (30, 20)]),
+        ("xMinYMin meet", [(0, 0), (20, 20)]),
+        ("xMaxYMax", [(20, 0), (40, 20)]),
+        ("xMidYMin slice", [(0, 0), (40, 40)]),
+        ("none", [(0, 0), (40, 20)]),
+    ] {
+        let points: Vec<_> = path_points(&convert(&svg(aspect))?)
+            .iter()
+            .map(|point| (point.x, point.y))
+            .collect();
+        assert_eq!(points, expected, "{aspect}");
+    }
+    Ok(())
+}
+
+#[test]
+fn test_scale_rounds_stroke_widths_once() -> anyhow::Result<()> {
+    let svg_content = r##"<svg xmlns="http://www.w3.org/2000/svg" width="512" height="512">
+        <path d="M0 0 L512 512" stroke="#ffffff" stroke-width="25"/>
+    </svg>"##;
+    let image = SvgConverter::new(Precision::Normal)
+        .with_pixel_convention(PixelConvention::PixelCenter)
+        .with_scale(0.1)
+        .parse_svg_image(
+            svg_content,
+            &TruncateColor::Truncate,
+            &Conversion::ConvertNoWarn,
+        )?;
+    assert_eq!(image.size, PebblePoint { x: 51, y: 51 });
+    // 2.5 pixels, scaling the stored width of 25 would give 3
+    assert_eq!(image.commands[0].options().stroke_width, 2);
+    Ok(())
+}
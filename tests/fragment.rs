@@ -0,0 +1,254 @@
+use svg2pdc::{
+    point::{Conversion, PebblePoint},
+    prelude::*,
+    roxmltree,
+    svg_converter::FragmentOptions,
+};
+
+const OPTIONS: FragmentOptions = FragmentOptions {
+    size: PebblePoint { x: 20, y: 20 },
+    translate: FPoint::new(0.0, 0.0),
+    truncate_color: TruncateColor::Truncate,
+    conversion: Conversion::RequireExact,
+};
+
+#[test]
+fn test_convert_node_applies_ancestor_translation() -> anyhow::Result<()> {
+    let svg_content = r##"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 100 100">
+        <g transform="translate(10, 20)" stroke="#ffffff">
+            <line id="selected" x1="1" y1="2" x2="3" y2="4" />
+            <line x1="50" y1="50" x2="60" y2="60" />
+        </g>
+    </svg>"##;
+    let document = roxmltree::Document::parse(svg_content)?;
+    let node = document
+        .descendants()
+        .find(|node| node.attribute("id") == Some("selected"))
+        .unwrap();
+
+    let image = SvgConverter::new(Precision::Normal).convert_node(node, &OPTIONS)?;
+
+    assert_eq!(image.size, OPTIONS.size);
+    match &image.commands[..] {
+        [
+            DrawCommand::Path {
+                points, options, ..
+            },
+        ] => {
+            assert_eq!(
+                points,
+                &[PebblePoint { x: 11, y: 22 }, PebblePoint { x: 13, y: 24 }]
+            );
+            assert_eq!(options.stroke_color, 0b1111_1111);
+        }
+        commands => panic!("unexpected commands {commands:?}"),
+    }
+    Ok(())
+}
+
+#[test]
+fn test_parse_svg_fragment_without_document() -> anyhow::Result<()> {
+    let image = SvgConverter::new(Precision::Normal).parse_svg_fragment(
+        r##"<g fill="#ff0000"><rect x="1" y="1" width="4" height="4"/></g><path d="M0 0 L5 5" stroke="#ffffff"/>"##,
+        &OPTIONS,
+    )?;
+    assert_eq!(image.commands.len(), 2);
+    Ok(())
+}
+
+#[test]
+fn test_css_colors_are_resolved() -> anyhow::Result<()> {
+    let image = SvgConverter::new(Precision::Normal).parse_svg_fragment(
+        r#"<rect x="1" y="1" width="4" height="4" fill="Red" stroke="rgb(0, 0, 255)"/>
+           <rect x="1" y="1" width="4" height="4" style="fill: hsl(120, 100%, 50%); stroke: #0ff"/>"#,
+        &OPTIONS,
+    )?;
+    let colors: Vec<_> = image
+        .commands
+        .iter()
+        .map(|command| (command.options().fill_color, command.options().stroke_color))
+        .collect();
+    assert_eq!(
+        colors,
+        vec![
+            (
+                PebbleColor::from_argb(3, 3, 0, 0).inner(),
+                PebbleColor::from_argb(3, 0, 0, 3).inner()
+            ),
+            (
+                PebbleColor::from_argb(3, 0, 3, 0).inner(),
+                PebbleColor::from_argb(3, 0, 3, 3).inner()
+            ),
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_missing_and_none_paints_draw_nothing() -> anyhow::Result<()> {
+    let image = SvgConverter::new(Precision::Normal).parse_svg_fragment(
+        r##"<rect x="1" y="1" width="4" height="4" fill="#ffffff"/>
+            <rect x="1" y="1" width="4" height="4" fill="#ffffff" stroke="none"/>
+            <rect x="1" y="1" width="4" height="4" fill="none" stroke="#000000"/>"##,
+        &OPTIONS,
+    )?;
+    let white = PebbleColor::from_argb(3, 3, 3, 3).inner();
+    let black = PebbleColor::from_argb(3, 0, 0, 0).inner();
+    let nothing = PebbleColor::nothing().inner();
+    let paints: Vec<_> = image
+        .commands
+        .iter()
+        .map(|command| {
+            let options = command.options();
+            (
+                options.fill_color,
+                options.stroke_color,
+                options.stroke_width,
+            )
+        })
+        .collect();
+    assert_eq!(
+        paints,
+        vec![
+            (white, nothing, 0),
+            (white, nothing, 0),
+            (nothing, black, 1)
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_current_color_uses_the_converter_color() -> anyhow::Result<()> {
+    let fragment = r#"<rect x="1" y="1" width="4" height="4" fill="currentColor"/>"#;
+    let untinted = SvgConverter::new(Precision::Normal).parse_svg_fragment(fragment, &OPTIONS)?;
+    assert_eq!(
+        untinted.commands[0].options().fill_color,
+        PebbleColor::nothing().inner()
+    );
+
+    let tinted = SvgConverter::new(Precision::Normal)
+        .with_current_color(Color::try_from_hex("#00ff00")?)
+        .parse_svg_fragment(fragment, &OPTIONS)?;
+    assert_eq!(
+        tinted.commands[0].options().fill_color,
+        PebbleColor::from_argb(3, 0, 3, 0).inner()
+    );
+    Ok(())
+}
+
+#[test]
+fn test_style_element_rules_apply_to_classes() -> anyhow::Result<()> {
+    let image = SvgConverter::new(Precision::Normal).parse_svg_fragment(
+        r#"<style>
+               .cls-1 { fill: #ff0000 }
+               rect.cls-2 { stroke: #0000ff }
+               .cls-3 { fill: #00ff00; stroke: #ffffff }
+           </style>
+           <rect class="cls-1 cls-2" x="1" y="1" width="4" height="4"/>
+           <rect class="cls-1" x="1" y="1" width="4" height="4" style="fill: #ffffff"/>
+           <g class="cls-3"><rect x="1" y="1" width="4" height="4" stroke="none"/></g>"#,
+        &OPTIONS,
+    )?;
+    let colors: Vec<_> = image
+        .commands
+        .iter()
+        .map(|command| (command.options().fill_color, command.options().stroke_color))
+        .collect();
+    let nothing = PebbleColor::nothing().inner();
+    assert_eq!(
+        colors,
+        vec![
+            (
+                PebbleColor::from_argb(3, 3, 0, 0).inner(),
+                PebbleColor::from_argb(3, 0, 0, 3).inner()
+            ),
+            (PebbleColor::from_argb(3, 3, 3, 3).inner(), nothing),
+            (PebbleColor::from_argb(3, 0, 3, 0).inner(), nothing),
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_nested_group_opacities_multiply() -> anyhow::Result<()> {
+    let converter = SvgConverter::new(Precision::Normal);
+    let options = FragmentOptions {
+        conversion: Conversion::ConvertNoWarn,
+        ..OPTIONS
+    };
+    let fill_color = |fragment: &str| -> anyhow::Result<u8> {
+        let image = converter.parse_svg_fragment(fragment, &options)?;
+        Ok(image.commands[0].options().fill_color)
+    };
+    let rect = r##"<rect x="1" y="1" width="4" height="4" fill="#ffffff"/>"##;
+
+    let nested = fill_color(&format!(
+        r#"<g opacity="0.7"><g fill-opacity="0.7">{rect}</g></g>"#
+    ))?;
+    let flat = fill_color(&format!(r#"<g opacity="0.49">{rect}</g>"#))?;
+    assert_ne!(flat, PebbleColor::nothing().inner());
+    assert_eq!(nested, flat);
+    assert_ne!(
+        nested,
+        fill_color(&format!(r#"<g opacity="0.7">{rect}</g>"#))?
+    );
+
+    let nested = fill_color(&format!(
+        r#"<g opacity="0.7"><g opacity="0.7">{rect}</g></g>"#
+    ))?;
+    assert_eq!(nested, flat);
+    Ok(())
+}
+
+#[test]
+fn test_selected_element_is_cropped_to_its_bounds() -> anyhow::Result<()> {
+    let svg_content = r##"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 100 100">
+        <g id="icon" transform="translate(10, 20)" stroke="#ffffff" stroke-width="2">
+            <line x1="5" y1="5" x2="15" y2="9" />
+        </g>
+        <line x1="50" y1="50" x2="60" y2="60" stroke="#ffffff" />
+    </svg>"##;
+    let converter = SvgConverter::new(Precision::Normal);
+    let select = |size| {
+        converter.parse_svg_element(
+            svg_content,
+            "#icon",
+            size,
+            &OPTIONS.truncate_color,
+            &OPTIONS.conversion,
+        )
+    };
+    let points = |image: &PebbleImage| match &image.commands[..] {
+        [DrawCommand::Path { points, .. }] => points.clone(),
+        commands => panic!("unexpected commands {commands:?}"),
+    };
+
+    // The stroke reaches one pixel past the ends of the line
+    let image = select(None)?;
+    assert_eq!(image.size, PebblePoint { x: 12, y: 6 });
+    assert_eq!(
+        points(&image),
+        vec![PebblePoint { x: 1, y: 1 }, PebblePoint { x: 11, y: 5 }]
+    );
+
+    let image = select(Some(PebblePoint { x: 20, y: 10 }))?;
+    assert_eq!(image.size, PebblePoint { x: 20, y: 10 });
+    assert_eq!(
+        points(&image),
+        vec![PebblePoint { x: 5, y: 3 }, PebblePoint { x: 15, y: 7 }]
+    );
+
+    assert!(
+        converter
+            .parse_svg_element(
+                svg_content,
+                "missing",
+                None,
+                &OPTIONS.truncate_color,
+                &OPTIONS.conversion
+            )
+            .is_err()
+    );
+    Ok(())
+}
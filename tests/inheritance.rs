@@ -0,0 +1,146 @@
+use svg2pdc::{
+    color::PebbleColor,
+    point::{Conversion, PebblePoint},
+    prelude::*,
+    roxmltree,
+    svg_converter::FragmentOptions,
+};
+
+const SVG: &str = r##"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 20 20">
+    <g fill="#ff0000" stroke-width="0">
+        <g transform="translate(1, 1)">
+            <g fill="#0000ff"><rect x="1" y="1" width="4" height="4"/></g>
+            <rect id="nested" x="10" y="10" width="4" height="4"/>
+        </g>
+    </g>
+</svg>"##;
+
+fn fills(image: &PebbleImage) -> Vec<(u8, u8)> {
+    image
+        .commands
+        .iter()
+        .map(|command| (command.options().fill_color, command.options().stroke_width))
+        .collect()
+}
+
+#[test]
+fn test_styles_are_inherited_across_group_levels() -> anyhow::Result<()> {
+    let red = PebbleColor::from_argb(3, 3, 0, 0).inner();
+    let blue = PebbleColor::from_argb(3, 0, 0, 3).inner();
+
+    let converter = SvgConverter::new(Precision::Normal);
+    let image = converter.parse_svg_image(SVG, &TruncateColor::Keep, &Conversion::RequireExact)?;
+    assert_eq!(fills(&image), vec![(blue, 0), (red, 0)]);
+
+    let document = roxmltree::Document::parse(SVG)?;
+    let node = document
+        .descendants()
+        .find(|node| node.attribute("id") == Some("nested"))
+        .unwrap();
+    let fragment = converter.convert_node(
+        node,
+        &FragmentOptions {
+            size: PebblePoint { x: 20, y: 20 },
+            translate: FPoint::default(),
+            truncate_color: TruncateColor::Keep,
+            conversion: Conversion::RequireExact,
+        },
+    )?;
+    assert_eq!(fills(&fragment), vec![(red, 0)]);
+    Ok(())
+}
+
+#[test]
+fn test_values_keep_their_case() -> anyhow::Result<()> {
+    let svg = r##"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 20 20">
+        <style>.Outlined { STROKE: url(#RedGradient); stroke-width: 2PX }</style>
+        <linearGradient id="RedGradient"><stop offset="0" stop-color="#FF0000"/></linearGradient>
+        <rect x="1" y="1" width="4" height="4" fill="url(#RedGradient)" stroke="none"/>
+        <rect x="1" y="1" width="4" height="4" style="FILL: url(#RedGradient)" stroke="none"/>
+        <rect class="Outlined" x="1" y="1" width="4" height="4" fill="Blue"/>
+    </svg>"##;
+    let red = PebbleColor::from_argb(3, 3, 0, 0).inner();
+    let blue = PebbleColor::from_argb(3, 0, 0, 3).inner();
+
+    let image = SvgConverter::new(Precision::Normal).parse_svg_image(
+        svg,
+        &TruncateColor::Keep,
+        &Conversion::RequireExact,
+    )?;
+    let options: Vec<_> = image
+        .commands
+        .iter()
+        .map(|command| {
+            let options = command.options();
+            (
+                options.fill_color,
+                options.stroke_color,
+                options.stroke_width,
+            )
+        })
+        .collect();
+    assert_eq!(options, vec![(red, 0, 0), (red, 0, 0), (blue, red, 2)]);
+    Ok(())
+}
+
+#[test]
+fn test_style_attribute_beats_stylesheet_beats_presentation_attributes() -> anyhow::Result<()> {
+    let svg = r##"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 20 20">
+        <style>.styled { fill: #00ff00 }</style>
+        <g style="fill: #0000ff" fill="#ff0000" stroke-width="0">
+            <rect x="1" y="1" width="4" height="4"/>
+            <rect x="1" y="1" width="4" height="4" fill="#ff0000"/>
+            <rect x="1" y="1" width="4" height="4" fill="#ff0000" style="fill: #ffffff"/>
+            <rect class="styled" x="1" y="1" width="4" height="4" fill="#ff0000"/>
+            <rect class="styled" x="1" y="1" width="4" height="4" style="fill: #ffffff"/>
+        </g>
+    </svg>"##;
+    let color = |r, g, b| PebbleColor::from_argb(3, r, g, b).inner();
+
+    let image = SvgConverter::new(Precision::Normal).parse_svg_image(
+        svg,
+        &TruncateColor::Keep,
+        &Conversion::RequireExact,
+    )?;
+    assert_eq!(
+        fills(&image),
+        vec![
+            (color(0, 0, 3), 0),
+            (color(3, 0, 0), 0),
+            (color(3, 3, 3), 0),
+            (color(0, 3, 0), 0),
+            (color(3, 3, 3), 0),
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_hidden_elements_are_skipped() -> anyhow::Result<()> {
+    let svg = r##"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 20 20">
+        <style>.gone { display: none }</style>
+        <g stroke-width="0">
+            <rect x="1" y="1" width="1" height="1" fill="#ff0000" style="display:none"/>
+            <rect x="1" y="1" width="1" height="1" fill="#ff0000" class="gone"/>
+            <g style="display: none"><rect x="1" y="1" width="1" height="1" fill="#ff0000"/></g>
+            <rect x="1" y="1" width="1" height="1" fill="#ff0000" style="visibility:hidden"/>
+            <g visibility="hidden">
+                <rect x="1" y="1" width="1" height="1" fill="#ff0000"/>
+                <rect x="1" y="1" width="1" height="1" fill="#0000ff" visibility="visible"/>
+            </g>
+            <rect x="1" y="1" width="1" height="1" fill="#ffffff" display="inline"/>
+        </g>
+    </svg>"##;
+    let color = |r, g, b| PebbleColor::from_argb(3, r, g, b).inner();
+
+    let image = SvgConverter::new(Precision::Normal).parse_svg_image(
+        svg,
+        &TruncateColor::Keep,
+        &Conversion::RequireExact,
+    )?;
+    assert_eq!(
+        fills(&image),
+        vec![(color(0, 0, 3), 0), (color(3, 3, 3), 0)]
+    );
+    Ok(())
+}
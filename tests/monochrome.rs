@@ -0,0 +1,50 @@
+use svg2pdc::{color::PebbleColor, point::Conversion, prelude::*};
+
+const SVG: &str = r##"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 20 20">
+    <rect x="1" y="1" width="4" height="4" fill="#ffcc00" stroke="#0000ff"/>
+    <rect x="6" y="1" width="4" height="4" fill="#ffffff" stroke="#ff0000" stroke-opacity="0.3"/>
+    <circle cx="10" cy="10" r="3" fill="#777777" stroke="#aaaaaa"/>
+</svg>"##;
+
+fn colors(image: &PebbleImage) -> Vec<(PebbleColor, PebbleColor)> {
+    image
+        .commands
+        .iter()
+        .map(|command| {
+            let options = command.options();
+            (
+                PebbleColor::from_inner(options.fill_color),
+                PebbleColor::from_inner(options.stroke_color),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn test_black_and_white_follows_the_threshold() -> anyhow::Result<()> {
+    let converter = SvgConverter::new(Precision::Normal);
+    let convert = |threshold| {
+        converter.parse_svg_image(
+            SVG,
+            &TruncateColor::BlackAndWhite { threshold },
+            &Conversion::RequireExact,
+        )
+    };
+
+    // Black fills are drawn as nothing, faint strokes become transparent
+    let image = convert(TruncateColor::DEFAULT_BW_THRESHOLD)?;
+    assert_eq!(
+        colors(&image),
+        vec![
+            (PebbleColor::WHITE, PebbleColor::BLACK),
+            (PebbleColor::WHITE, PebbleColor::CLEAR),
+            (PebbleColor::CLEAR, PebbleColor::WHITE),
+        ]
+    );
+
+    let image = convert(0.3)?;
+    assert_eq!(colors(&image)[2], (PebbleColor::WHITE, PebbleColor::WHITE));
+    let image = convert(0.9)?;
+    assert_eq!(colors(&image)[0], (PebbleColor::CLEAR, PebbleColor::BLACK));
+    Ok(())
+}
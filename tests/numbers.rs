@@ -0,0 +1,176 @@
+use svg2pdc::{
+    point::{Conversion, PebblePoint},
+    prelude::*,
+    svg_converter::FragmentOptions,
+};
+
+const OPTIONS: FragmentOptions = FragmentOptions {
+    size: PebblePoint { x: 20, y: 20 },
+    translate: FPoint::new(0.0, 0.0),
+    truncate_color: TruncateColor::Truncate,
+    conversion: Conversion::RequireExact,
+};
+
+#[test]
+fn test_lenient_numbers() -> anyhow::Result<()> {
+    let image = SvgConverter::new(Precision::Normal).parse_svg_fragment(
+        r##"<g opacity="100%" stroke-width="2px">
+            <rect x=".5" y="5e-1" width="4px" height="4.0" stroke="#ffffff" stroke-opacity="1e0"/>
+            <polyline points="1.5-1.5 2.5,2.5" stroke="#ffffff"/>
+        </g>"##,
+        &OPTIONS,
+    )?;
+
+    let [
+        DrawCommand::Path {
+            points: rect,
+            options,
+            ..
+        },
+        DrawCommand::Path { points: line, .. },
+    ] = &image.commands[..]
+    else {
+        panic!("unexpected commands {:?}", image.commands);
+    };
+    assert_eq!(rect[2], PebblePoint { x: 4, y: 4 });
+    assert_eq!(options.stroke_width, 2);
+    assert_eq!(options.stroke_color, 0b1111_1111);
+    assert_eq!(line[0], PebblePoint { x: 1, y: -2 });
+    Ok(())
+}
+
+#[test]
+fn test_fractional_stroke_widths_follow_the_rounding_and_floor() -> anyhow::Result<()> {
+    let fragment = r##"<g stroke="#ffffff" stroke-width="0.75">
+        <line x1="1" y1="1" x2="5" y2="5"/>
+        <line x1="1" y1="1" x2="5" y2="5" stroke-width="1.5"/>
+        <line x1="1" y1="1" x2="5" y2="5" stroke-width="2.9pt"/>
+        <line x1="1" y1="1" x2="5" y2="5" stroke-width="0"/>
+    </g>"##;
+    let widths = |converter: SvgConverter| -> anyhow::Result<Vec<u8>> {
+        let image = converter.parse_svg_fragment(fragment, &OPTIONS)?;
+        Ok(image
+            .commands
+            .iter()
+            .map(|command| command.options().stroke_width)
+            .collect())
+    };
+
+    let converter = SvgConverter::new(Precision::Normal);
+    assert_eq!(widths(converter.clone())?, vec![0, 1, 3, 0]);
+    assert_eq!(
+        widths(
+            converter
+                .clone()
+                .with_stroke_width_rounding(RoundingMode::Round)
+        )?,
+        vec![1, 2, 4, 0]
+    );
+    assert_eq!(
+        widths(converter.with_min_stroke_width(2))?,
+        vec![2, 2, 3, 0]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_invalid_number_names_the_attribute() {
+    let error = SvgConverter::new(Precision::Normal)
+        .parse_svg_fragment(
+            r##"<rect x="0" y="0" width="4" height="4" opacity="half"/>"##,
+            &OPTIONS,
+        )
+        .unwrap_err();
+
+    match error {
+        Svg2PdcError::InvalidNumber {
+            attribute,
+            value,
+            element,
+        } => {
+            assert_eq!(attribute, "opacity");
+            assert_eq!(value, "half");
+            assert_eq!((element.tag.as_str(), element.line), ("rect", 1));
+            assert_eq!(element.column, 41);
+        }
+        error => panic!("unexpected error {error}"),
+    }
+}
+
+#[test]
+fn test_coordinates_outside_the_range_are_rejected() -> anyhow::Result<()> {
+    let converter = SvgConverter::new(Precision::Precise);
+    let image = converter.parse_svg_fragment(
+        r##"<line x1="-10" y1="2" x2="4000" y2="2" stroke="#ffffff"/>"##,
+        &OPTIONS,
+    )?;
+    match &image.commands[..] {
+        [DrawCommand::Path { points, .. }] => {
+            assert_eq!(points[0], PebblePoint { x: -84, y: 12 });
+            assert_eq!(points[1], PebblePoint { x: 31_996, y: 12 });
+        }
+        commands => panic!("unexpected commands {commands:?}"),
+    }
+
+    let error = converter
+        .parse_svg_fragment(
+            r##"<line x1="0" y1="0" x2="5000" y2="0" stroke="#ffffff"/>"##,
+            &OPTIONS,
+        )
+        .unwrap_err();
+    assert!(
+        matches!(error, Svg2PdcError::CoordinateOutOfRange(_)),
+        "unexpected error {error}"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_malformed_attributes_are_errors() {
+    for (fragment, expected) in [
+        (
+            r#"<polygon points="1 2 3"/>"#,
+            "line 1: <polygon> invalid `points`: odd number of coordinates",
+        ),
+        (
+            r#"<polyline id="zigzag"/>"#,
+            r#"line 1: <polyline id="zigzag"> missing `points`"#,
+        ),
+        (
+            r#"<line x1="0" y1="0" x2="1"/>"#,
+            "line 1: <line> missing `y2`",
+        ),
+        (
+            r#"<path d="M 0 0 L" stroke="red"/>"#,
+            "line 1: <path> invalid `d`",
+        ),
+        (
+            r#"<g transform="translate(1,"><rect/></g>"#,
+            "line 1: <g> invalid `transform`",
+        ),
+    ] {
+        let error = SvgConverter::new(Precision::Normal)
+            .parse_svg_fragment(fragment, &OPTIONS)
+            .unwrap_err();
+        assert!(
+            error.to_string().starts_with(expected),
+            "{fragment}: unexpected error {error}"
+        );
+    }
+}
+
+#[test]
+fn test_errors_start_with_the_line_of_the_element() {
+    let error = SvgConverter::new(Precision::Normal)
+        .parse_svg_image(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"0 0 20 20\">\n\
+             <g>\n\
+             <rect x=\"1\" y=\"1\" height=\"4\"/>\n\
+             </g>\n\
+             </svg>",
+            &TruncateColor::Truncate,
+            &Conversion::RequireExact,
+        )
+        .unwrap_err();
+    assert_eq!(error.to_string(), "line 3: <rect> missing `width`");
+}
@@ -0,0 +1,69 @@
+use rand::{SeedableRng, rngs::StdRng};
+use svg2pdc::{
+    pack::PdcPack,
+    prelude::*,
+    test_utils::{assert_images_eq, random_image},
+};
+
+#[test]
+fn test_pack_round_trip() -> anyhow::Result<()> {
+    let mut rng = StdRng::seed_from_u64(0x9ac4);
+    let mut pack = PdcPack::new();
+    for key in ["sunny", "light-rain", "heavy-snow"] {
+        pack.insert(key, random_image(&mut rng));
+    }
+    pack.insert("sunny", random_image(&mut rng));
+    assert_eq!(pack.entries.len(), 3);
+
+    let mut buffer = Vec::new();
+    pack.serialize(&mut buffer)?;
+    let deserialized = PdcPack::deserialize(&mut buffer.as_slice())?;
+
+    assert_eq!(deserialized.entries.len(), pack.entries.len());
+    for ((key, image), (deserialized_key, deserialized_image)) in
+        pack.entries.iter().zip(&deserialized.entries)
+    {
+        assert_eq!(key, deserialized_key);
+        assert_images_eq(image, deserialized_image);
+    }
+
+    // The index points at complete PDC images inside the pack
+    for entry in pack.index()? {
+        let start = entry.offset as usize;
+        let mut image = &buffer[start..start + entry.length as usize];
+        let image = PebbleImage::deserialize(&mut image)?;
+        assert_images_eq(pack.get(&entry.key).unwrap(), &image);
+    }
+    Ok(())
+}
+
+#[test]
+fn test_deduplicated_pack_shares_identical_images() -> anyhow::Result<()> {
+    let mut rng = StdRng::seed_from_u64(0xd0b1);
+    let image = random_image(&mut rng);
+    let mut pack = PdcPack::new();
+    pack.insert("day", image.clone());
+    pack.insert("night", random_image(&mut rng));
+    pack.insert("day-copy", image);
+    assert_eq!(
+        pack.duplicates()?,
+        vec![vec!["day".to_string(), "day-copy".to_string()]]
+    );
+
+    let mut full = Vec::new();
+    pack.serialize(&mut full)?;
+    pack.deduplicate = true;
+    let mut deduplicated = Vec::new();
+    pack.serialize(&mut deduplicated)?;
+
+    let index = pack.index()?;
+    assert_eq!(index[0].offset, index[2].offset);
+    assert_eq!(deduplicated.len() + index[0].length as usize, full.len());
+
+    let deserialized = PdcPack::deserialize(&mut deduplicated.as_slice())?;
+    assert_images_eq(
+        deserialized.get("day").unwrap(),
+        deserialized.get("day-copy").unwrap(),
+    );
+    Ok(())
+}
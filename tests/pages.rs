@@ -0,0 +1,55 @@
+use svg2pdc::{
+    point::{Conversion, PebblePoint},
+    prelude::*,
+};
+
+const MULTI_PAGE_SVG: &str = r##"<svg xmlns="http://www.w3.org/2000/svg"
+    xmlns:inkscape="http://www.inkscape.org/namespaces/inkscape"
+    xmlns:sodipodi="http://sodipodi.sourceforge.net/DTD/sodipodi-0.dtd"
+    viewBox="0 0 20 20">
+    <sodipodi:namedview id="namedview1">
+        <inkscape:page x="0" y="0" width="20" height="20" id="page1" inkscape:label="sun" />
+        <inkscape:page x="30" y="0" width="20" height="10" id="page2" />
+    </sodipodi:namedview>
+    <line x1="2" y1="2" x2="10" y2="10" stroke="#ffffff" />
+    <rect x="35" y="2" width="5" height="5" fill="#ff0000" />
+</svg>"##;
+
+#[test]
+fn test_pages_are_split() -> anyhow::Result<()> {
+    let converter = SvgConverter::new(Precision::Normal);
+    let pages = converter.parse_svg_pages(
+        MULTI_PAGE_SVG,
+        &TruncateColor::Truncate,
+        &Conversion::RequireExact,
+    )?;
+
+    assert_eq!(pages.len(), 2);
+    let (sun, sun_image) = &pages[0];
+    assert_eq!(sun.name(), Some("sun"));
+    assert_eq!(sun_image.size, PebblePoint { x: 20, y: 20 });
+    assert_eq!(sun_image.commands.len(), 1);
+
+    let (second, second_image) = &pages[1];
+    assert_eq!(second.name(), Some("page2"));
+    assert_eq!(second_image.size, PebblePoint { x: 20, y: 10 });
+    match &second_image.commands[..] {
+        [DrawCommand::Path { points, .. }] => {
+            assert_eq!(points[0], PebblePoint { x: 5, y: 2 });
+        }
+        commands => panic!("unexpected commands {commands:?}"),
+    }
+    Ok(())
+}
+
+#[test]
+fn test_document_without_pages() -> anyhow::Result<()> {
+    let svg_content = std::fs::read_to_string("tests/resources/svg/pencil-inkscape.svg")?;
+    let pages = SvgConverter::new(Precision::Normal).parse_svg_pages(
+        &svg_content,
+        &TruncateColor::Truncate,
+        &Conversion::ConvertNoWarn,
+    )?;
+    assert!(pages.is_empty());
+    Ok(())
+}
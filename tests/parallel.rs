@@ -0,0 +1,22 @@
+use svg2pdc::{point::Conversion, prelude::*};
+
+#[test]
+fn test_parallel_conversion_keeps_document_order() -> anyhow::Result<()> {
+    for entry in std::fs::read_dir("tests/resources/svg")? {
+        let svg_content = std::fs::read_to_string(entry?.path())?;
+        let convert = |parallel| -> anyhow::Result<Vec<u8>> {
+            let image = SvgConverter::new(Precision::Normal)
+                .with_parallel(parallel)
+                .parse_svg_image(
+                    &svg_content,
+                    &TruncateColor::Truncate,
+                    &Conversion::ConvertNoWarn,
+                )?;
+            let mut bytes = Vec::new();
+            image.serialize(&mut bytes)?;
+            Ok(bytes)
+        };
+        assert_eq!(convert(true)?, convert(false)?);
+    }
+    Ok(())
+}
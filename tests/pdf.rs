@@ -0,0 +1,97 @@
+use svg2pdc::{
+    color::PebbleColor,
+    pdf::pdf_to_svg,
+    point::{Conversion, PebblePoint},
+    prelude::*,
+};
+
+/// A single-page PDF with `content` as its uncompressed content stream.
+fn pdf(content: &str) -> Vec<u8> {
+    let objects = [
+        "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+        "<< /Type /Pages /Kids [3 0 R] /Count 1 /MediaBox [0 0 50 50] >>".to_string(),
+        "<< /Type /Page /Parent 2 0 R /Contents 4 0 R >>".to_string(),
+        format!(
+            "<< /Length {} >>\nstream\n{content}\nendstream",
+            content.len() + 1
+        ),
+    ];
+    let mut pdf = b"%PDF-1.4\n".to_vec();
+    let mut offsets = Vec::new();
+    for (index, object) in objects.iter().enumerate() {
+        offsets.push(pdf.len());
+        pdf.extend_from_slice(format!("{} 0 obj\n{object}\nendobj\n", index + 1).as_bytes());
+    }
+    let xref = pdf.len();
+    pdf.extend_from_slice(
+        format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1).as_bytes(),
+    );
+    for offset in offsets {
+        pdf.extend_from_slice(format!("{offset:010} 00000 n \n").as_bytes());
+    }
+    pdf.extend_from_slice(
+        format!(
+            "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{xref}\n%%EOF\n",
+            objects.len() + 1
+        )
+        .as_bytes(),
+    );
+    pdf
+}
+
+#[test]
+fn test_pdf_paths_are_converted_like_svg() -> anyhow::Result<()> {
+    let svg = pdf_to_svg(&pdf(
+        "q 1 0 0 1 5 0 cm 1 0 0 rg 5 10 20 20 re f Q 0 0 1 RG 2 w 5 5 m 45 5 l S",
+    ))?;
+    let image = SvgConverter::new(Precision::Normal).parse_svg_image(
+        &svg,
+        &TruncateColor::Keep,
+        &Conversion::RequireExact,
+    )?;
+    assert_eq!(image.size, PebblePoint { x: 50, y: 50 });
+
+    let [
+        DrawCommand::Path {
+            points: square,
+            open: false,
+            options: square_options,
+        },
+        DrawCommand::Path {
+            points: line,
+            open: true,
+            options: line_options,
+        },
+    ] = image.commands.as_slice()
+    else {
+        panic!(
+            "expected a closed and an open path, got {:?}",
+            image.commands
+        );
+    };
+
+    // The page is flipped, PDF coordinates point up
+    let corner = |x, y| PebblePoint { x, y };
+    assert_eq!(
+        square,
+        &[
+            corner(10, 40),
+            corner(30, 40),
+            corner(30, 20),
+            corner(10, 20)
+        ]
+    );
+    assert_eq!(
+        square_options.fill_color,
+        PebbleColor::from_argb(3, 3, 0, 0).inner()
+    );
+    assert_eq!(square_options.stroke_width, 0);
+
+    assert_eq!(line, &[corner(5, 45), corner(45, 45)]);
+    assert_eq!(
+        line_options.stroke_color,
+        PebbleColor::from_argb(3, 0, 0, 3).inner()
+    );
+    assert_eq!(line_options.stroke_width, 2);
+    Ok(())
+}
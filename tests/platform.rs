@@ -0,0 +1,42 @@
+use svg2pdc::{platform::Platform, point::Conversion, prelude::*};
+
+const ROUND_SVG: &str = r##"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 180 180">
+    <rect x="0" y="0" width="180" height="180" fill="#ffffff"/>
+    <rect x="0" y="0" width="10" height="10" fill="#ff0000"/>
+    <circle cx="90" cy="90" r="20" fill="#00ff00"/>
+</svg>"##;
+
+#[test]
+fn test_round_display_only_warns_by_default() -> anyhow::Result<()> {
+    let image = SvgConverter::new(Precision::Normal)
+        .with_platform(Platform::Chalk)
+        .parse_svg_image(ROUND_SVG, &TruncateColor::Keep, &Conversion::ConvertNoWarn)?;
+    assert_eq!(image.commands.len(), 3);
+    Ok(())
+}
+
+#[test]
+fn test_round_display_clipping() -> anyhow::Result<()> {
+    let image = SvgConverter::new(Precision::Normal)
+        .with_platform(Platform::Chalk)
+        .with_clip_to_display(true)
+        .parse_svg_image(ROUND_SVG, &TruncateColor::Keep, &Conversion::ConvertNoWarn)?;
+
+    // The corner rectangle is entirely off-screen
+    assert_eq!(image.commands.len(), 2);
+    let DrawCommand::Path { points, .. } = &image.commands[0] else {
+        panic!("unexpected command {:?}", image.commands[0]);
+    };
+    for point in points {
+        let (dx, dy) = (point.x as f32 - 89.5, point.y as f32 - 89.5);
+        assert!(
+            (dx * dx + dy * dy).sqrt() <= 91.0,
+            "{point:?} is off-screen"
+        );
+    }
+    assert!(matches!(
+        image.commands[1],
+        DrawCommand::Circle { radius: 20, .. }
+    ));
+    Ok(())
+}
@@ -0,0 +1,85 @@
+#![cfg(feature = "serde")]
+
+use std::path::PathBuf;
+
+use svg2pdc::project::{DEFAULT_SOURCE, PebbleProject, ResourceChange};
+
+/// A fresh project directory holding `package.json`.
+fn project(name: &str, manifest: &str) -> anyhow::Result<PathBuf> {
+    let root = std::env::temp_dir().join(format!("svg2pdc-project-{name}-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&root);
+    std::fs::create_dir_all(&root)?;
+    std::fs::write(root.join("package.json"), manifest)?;
+    Ok(root)
+}
+
+#[test]
+fn test_raw_resources_are_added_once() -> anyhow::Result<()> {
+    let root = project(
+        "resources",
+        r#"{
+  "name": "weather",
+  "pebble": {
+    "uuid": "00000000-0000-0000-0000-000000000000",
+    "resources": {
+      "media": [
+        { "type": "bitmap", "name": "IMAGE_LOGO", "file": "images/logo.pdc" }
+      ]
+    }
+  }
+}"#,
+    )?;
+    let mut project = PebbleProject::open(&root)?;
+    assert_eq!(project.source_dir(), root.join(DEFAULT_SOURCE));
+
+    let change = project.set_raw_resource("IMAGE_SUN", "images/sun.pdc")?;
+    assert_eq!(change, ResourceChange::Added);
+    let change = project.set_raw_resource("IMAGE_SUN", "images/sun.pdc")?;
+    assert_eq!(change, ResourceChange::Unchanged);
+    let change = project.set_raw_resource("IMAGE_OTHER", "images/logo.pdc")?;
+    assert_eq!(change, ResourceChange::Updated);
+    project.save()?;
+
+    let project = PebbleProject::open(&root)?;
+    let names: Vec<_> = project
+        .media()
+        .iter()
+        .map(|entry| (entry["name"].as_str(), entry["type"].as_str()))
+        .collect();
+    assert_eq!(
+        names,
+        vec![
+            (Some("IMAGE_LOGO"), Some("raw")),
+            (Some("IMAGE_SUN"), Some("raw"))
+        ]
+    );
+    // Keys keep their order, so the manifest diff only shows the changes
+    let manifest = std::fs::read_to_string(root.join("package.json"))?;
+    assert!(manifest.find("\"name\"") < manifest.find("\"pebble\""));
+    assert!(manifest.ends_with("}\n"));
+    std::fs::remove_dir_all(root)?;
+    Ok(())
+}
+
+#[test]
+fn test_source_directory_comes_from_the_settings() -> anyhow::Result<()> {
+    let root = project(
+        "source",
+        r#"{ "pebble": { "svg2pdc": { "source": "design/icons" } } }"#,
+    )?;
+    let mut project = PebbleProject::open(&root)?;
+    assert_eq!(project.source_dir(), root.join("design/icons"));
+    // A project without resources gets a list of them
+    project.set_raw_resource("IMAGE_SUN", "images/sun.pdc")?;
+    assert_eq!(project.media().len(), 1);
+    std::fs::remove_dir_all(root)?;
+    Ok(())
+}
+
+#[test]
+fn test_manifest_without_pebble_settings_is_rejected() -> anyhow::Result<()> {
+    let root = project("missing", r#"{ "name": "not-pebble" }"#)?;
+    assert!(PebbleProject::open(&root).is_err());
+    std::fs::remove_dir_all(root)?;
+    Ok(())
+}
@@ -0,0 +1,84 @@
+use svg2pdc::{
+    point::{Conversion, PebblePoint},
+    prelude::*,
+    svg_converter::FragmentOptions,
+};
+
+const OPTIONS: FragmentOptions = FragmentOptions {
+    size: PebblePoint { x: 40, y: 40 },
+    translate: FPoint::new(0.0, 0.0),
+    truncate_color: TruncateColor::Truncate,
+    conversion: Conversion::RequireExact,
+};
+
+fn rect_points(converter: &SvgConverter, attributes: &str) -> anyhow::Result<Vec<PebblePoint>> {
+    let image = converter.parse_svg_fragment(
+        &format!(r##"<rect x="10" y="10" width="20" height="10" {attributes} fill="#ffffff"/>"##),
+        &OPTIONS,
+    )?;
+    match image.commands.as_slice() {
+        [DrawCommand::Path { points, open, .. }] => {
+            assert!(!open);
+            Ok(points.clone())
+        }
+        commands => panic!("expected a single path, got {commands:?}"),
+    }
+}
+
+#[test]
+fn test_rect_without_radius_stays_sharp() -> anyhow::Result<()> {
+    let converter = SvgConverter::new(Precision::Precise);
+    assert_eq!(rect_points(&converter, "")?.len(), 4);
+    assert_eq!(rect_points(&converter, r#"rx="0" ry="4""#)?.len(), 4);
+    Ok(())
+}
+
+#[test]
+fn test_rounded_rect_cuts_its_corners() -> anyhow::Result<()> {
+    let converter = SvgConverter::new(Precision::Precise);
+    // A missing ry takes the value of rx
+    for attributes in [r#"rx="3""#, r#"rx="3" ry="auto""#, r#"ry="3""#] {
+        let points = rect_points(&converter, attributes)?;
+        assert!(points.len() > 8, "{attributes}: {points:?}");
+        // Stored coordinates are shifted by half a pixel, (10, 10) is stored at 76
+        let corner = PebblePoint { x: 76, y: 76 };
+        assert!(!points.contains(&corner), "{attributes}: {points:?}");
+        assert!(points.contains(&PebblePoint { x: 100, y: 76 }));
+        for point in &points {
+            assert!((76..=236).contains(&point.x) && (76..=156).contains(&point.y));
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn test_rounded_rect_radius_is_clamped_to_half_its_size() -> anyhow::Result<()> {
+    let converter = SvgConverter::new(Precision::Precise);
+    // The ends of the capsule are half circles reaching the middle of the short sides
+    let points = rect_points(&converter, r#"rx="100""#)?;
+    assert!(points.contains(&PebblePoint { x: 76, y: 116 }));
+    assert!(points.contains(&PebblePoint { x: 236, y: 116 }));
+    assert!(!points.contains(&PebblePoint { x: 76, y: 76 }));
+    Ok(())
+}
+
+#[test]
+fn test_rect_position_defaults_to_zero() -> anyhow::Result<()> {
+    let converter = SvgConverter::new(Precision::Precise);
+    let convert = |rect: &str| -> anyhow::Result<Vec<PebblePoint>> {
+        let image = converter.parse_svg_fragment(rect, &OPTIONS)?;
+        match image.commands.as_slice() {
+            [DrawCommand::Path { points, .. }] => Ok(points.clone()),
+            commands => panic!("expected a single path, got {commands:?}"),
+        }
+    };
+    assert_eq!(
+        convert(r##"<rect width="10" height="10" fill="#ffffff"/>"##)?,
+        convert(r##"<rect x="0" y="0" width="10" height="10" fill="#ffffff"/>"##)?
+    );
+    assert_eq!(
+        convert(r##"<rect x="5" width="10" height="10" fill="#ffffff"/>"##)?,
+        convert(r##"<rect x="5" y="0" width="10" height="10" fill="#ffffff"/>"##)?
+    );
+    Ok(())
+}
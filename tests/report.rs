@@ -0,0 +1,75 @@
+use svg2pdc::{diagnostic::DiagnosticCode, point::Conversion, prelude::*};
+
+const SVG: &str = r##"<svg xmlns="http://www.w3.org/2000/svg" width="20" height="20">
+    <g id="first">
+        <path id="off-grid" d="M0.3 0 L5 5" stroke="#ffffff"/>
+    </g>
+    <g id="second">
+        <ellipse id="oval" rx="2" ry="3"/>
+        <rect id="thin" x="1" y="1" width="4" height="4" stroke="#ffffff" stroke-width="2.9"/>
+    </g>
+</svg>"##;
+
+#[test]
+fn test_report_collects_warnings_with_their_elements() -> anyhow::Result<()> {
+    for parallel in [false, true] {
+        let converter = SvgConverter::new(Precision::Normal).with_parallel(parallel);
+        let (image, report) = converter.parse_svg_image_with_report(
+            SVG,
+            &TruncateColor::Keep,
+            &Conversion::ConvertWarn,
+        )?;
+        assert_eq!(image.commands.len(), 2);
+
+        let warnings: Vec<_> = report
+            .warnings
+            .iter()
+            .map(|warning| {
+                let element = warning.element.as_ref().unwrap();
+                (
+                    warning.code,
+                    element.tag.as_str(),
+                    element.id.as_deref(),
+                    element.line,
+                    element.column,
+                )
+            })
+            .collect();
+        assert_eq!(
+            warnings,
+            vec![
+                (DiagnosticCode::InvalidPoint, "path", Some("off-grid"), 3, 9),
+                (
+                    DiagnosticCode::UnsupportedTag,
+                    "ellipse",
+                    Some("oval"),
+                    6,
+                    9
+                ),
+                (
+                    DiagnosticCode::StrokeWidthChanged,
+                    "rect",
+                    Some("thin"),
+                    7,
+                    9
+                ),
+            ],
+            "parallel: {parallel}"
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn test_clean_conversion_has_an_empty_report() -> anyhow::Result<()> {
+    let svg = r##"<svg xmlns="http://www.w3.org/2000/svg" width="20" height="20">
+        <rect x="1" y="1" width="4" height="4" fill="#ffffff"/>
+    </svg>"##;
+    let (_, report) = SvgConverter::new(Precision::Normal).parse_svg_image_with_report(
+        svg,
+        &TruncateColor::Keep,
+        &Conversion::RequireExact,
+    )?;
+    assert!(report.is_empty());
+    Ok(())
+}
@@ -0,0 +1,35 @@
+use svg2pdc::{
+    color::PebbleColor,
+    point::{Conversion, PebblePoint},
+    prelude::*,
+};
+
+#[test]
+fn test_root_styles_and_translation_are_applied() -> anyhow::Result<()> {
+    let svg_content = r##"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 20 20"
+        fill="#ff0000" stroke-width="0" transform="translate(2, 3)">
+        <rect x="1" y="1" width="4" height="4"/>
+        <g><rect x="10" y="10" width="4" height="4"/></g>
+        <g fill="#0000ff"><rect x="10" y="1" width="4" height="4"/></g>
+    </svg>"##;
+    let image = SvgConverter::new(Precision::Normal).parse_svg_image(
+        svg_content,
+        &TruncateColor::Keep,
+        &Conversion::RequireExact,
+    )?;
+
+    let red = PebbleColor::from_argb(3, 3, 0, 0).inner();
+    let blue = PebbleColor::from_argb(3, 0, 0, 3).inner();
+    let fills: Vec<_> = image
+        .commands
+        .iter()
+        .map(|command| (command.options().fill_color, command.options().stroke_width))
+        .collect();
+    assert_eq!(fills, vec![(red, 0), (red, 0), (blue, 0)]);
+
+    let DrawCommand::Path { points, .. } = &image.commands[0] else {
+        panic!("expected a path, got {:?}", image.commands[0]);
+    };
+    assert_eq!(points[0], PebblePoint { x: 3, y: 4 });
+    Ok(())
+}
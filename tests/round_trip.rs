@@ -0,0 +1,133 @@
+use rand::{SeedableRng, rngs::StdRng};
+use svg2pdc::{
+    export::to_svg,
+    limits::LimitViolation,
+    point::{Conversion, PebblePoint},
+    prelude::*,
+    test_utils::{assert_round_trip, random_image},
+};
+
+#[test]
+fn test_random_images_round_trip() {
+    let mut rng = StdRng::seed_from_u64(0x5eed);
+    for _ in 0..256 {
+        assert_round_trip(&random_image(&mut rng));
+    }
+}
+
+#[test]
+fn test_serialized_size_and_limits() -> anyhow::Result<()> {
+    let mut rng = StdRng::seed_from_u64(0x1e57);
+    for _ in 0..64 {
+        let image = random_image(&mut rng);
+        let mut bytes = Vec::new();
+        image.serialize(&mut bytes)?;
+        assert_eq!(image.serialized_size(), bytes.len());
+    }
+
+    let long = PebbleImage {
+        size: PebblePoint { x: 20, y: 20 },
+        commands: vec![DrawCommand::Path {
+            points: vec![PebblePoint { x: 1, y: 1 }; u16::MAX as usize + 1],
+            open: true,
+            options: DrawOptions::default(),
+        }],
+    };
+    assert_eq!(
+        long.validate(None),
+        [LimitViolation::TooManyPoints {
+            command: 0,
+            count: u16::MAX as usize + 1
+        }]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_converted_svg_round_trips() -> anyhow::Result<()> {
+    let svg_content =
+        std::fs::read_to_string("tests/resources/svg/Pebble_50x50_Partly_cloudy.svg")?;
+    let converter = SvgConverter::new(Precision::Normal);
+    let image = converter.parse_svg_image(
+        &svg_content,
+        &TruncateColor::Truncate,
+        &Conversion::RequireExact,
+    )?;
+
+    assert_round_trip(&image);
+    Ok(())
+}
+
+#[test]
+fn test_exported_svg_converts_back_to_the_same_image() -> anyhow::Result<()> {
+    for (file, precision) in [
+        ("Pebble_50x50_Partly_cloudy.svg", Precision::Normal),
+        ("Pebble_50x50_Partly_cloudy.svg", Precision::Precise),
+        ("Pebble_50x50_Heavy_rain.svg", Precision::Precise),
+    ] {
+        let svg_content = std::fs::read_to_string(format!("tests/resources/svg/{file}"))?;
+        let converter = SvgConverter::new(precision);
+        let image = converter.parse_svg_image(
+            &svg_content,
+            &TruncateColor::Truncate,
+            &Conversion::ConvertNoWarn,
+        )?;
+        let exported = converter.parse_svg_image(
+            &to_svg(&image),
+            &TruncateColor::Truncate,
+            &Conversion::RequireExact,
+        )?;
+
+        let (mut expected, mut actual) = (Vec::new(), Vec::new());
+        image.serialize(&mut expected)?;
+        exported.serialize(&mut actual)?;
+        assert_eq!(actual, expected, "{file} at {precision:?}");
+    }
+    Ok(())
+}
+
+#[test]
+fn test_negative_coordinates_round_trip() {
+    let image = PebbleImage {
+        size: PebblePoint { x: 20, y: 20 },
+        commands: vec![DrawCommand::Path {
+            points: vec![PebblePoint { x: -5, y: 3 }, PebblePoint { x: 12, y: -300 }],
+            open: true,
+            options: DrawOptions::default(),
+        }],
+    };
+    assert_round_trip(&image);
+}
+
+#[test]
+fn test_json_round_trips() -> anyhow::Result<()> {
+    let mut rng = StdRng::seed_from_u64(0x150);
+    for _ in 0..64 {
+        let image = random_image(&mut rng);
+        let (mut expected, mut actual) = (Vec::new(), Vec::new());
+        image.serialize(&mut expected)?;
+        PebbleImage::from_json(&image.to_json())?.serialize(&mut actual)?;
+        assert_eq!(actual, expected);
+    }
+
+    // Hand-written commands may leave their options out
+    let image = PebbleImage::from_json(
+        r#"{
+            "size": {"x": 10, "y": 10},
+            "commands": [
+                {"path": {"points": [{"x": 1, "y": 1}, {"x": 8, "y": 8}], "open": true,
+                          "options": {"stroke_width": 2, "stroke_color": 192}}}
+            ]
+        }"#,
+    )?;
+    let DrawCommand::Path {
+        points, options, ..
+    } = &image.commands[0]
+    else {
+        panic!("expected a path, found {:?}", image.commands[0]);
+    };
+    assert_eq!(points.len(), 2);
+    assert_eq!((options.stroke_width, options.fill_color), (2, 0));
+    assert!(PebbleImage::from_json(r#"{"size": {"x": 10}, "commands": []}"#).is_err());
+    Ok(())
+}
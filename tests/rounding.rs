@@ -0,0 +1,89 @@
+use svg2pdc::{
+    point::{Conversion, PebblePoint},
+    prelude::*,
+    svg_converter::FragmentOptions,
+};
+
+const OPTIONS: FragmentOptions = FragmentOptions {
+    size: PebblePoint { x: 20, y: 20 },
+    translate: FPoint::new(0.0, 0.0),
+    truncate_color: TruncateColor::Truncate,
+    conversion: Conversion::RequireExact,
+};
+
+/// The x coordinates of a line through pixel corners, which end up halfway between two
+/// Pebble coordinates after the half pixel shift.
+fn line_xs(converter: SvgConverter) -> anyhow::Result<Vec<i16>> {
+    let image = converter.parse_svg_fragment(
+        r##"<line x1="1" y1="4" x2="2" y2="4" stroke="#ffffff"/>"##,
+        &OPTIONS,
+    )?;
+    match &image.commands[..] {
+        [DrawCommand::Path { points, .. }] => Ok(points.iter().map(|point| point.x).collect()),
+        commands => panic!("unexpected commands {commands:?}"),
+    }
+}
+
+#[test]
+fn test_legacy_rounding_is_the_default() -> anyhow::Result<()> {
+    let converter = SvgConverter::new(Precision::Normal);
+    assert_eq!(line_xs(converter)?, vec![1, 2]);
+    Ok(())
+}
+
+#[test]
+fn test_rounding_modes() -> anyhow::Result<()> {
+    for (rounding_mode, expected) in [
+        (RoundingMode::Legacy, vec![1, 2]),
+        (RoundingMode::Floor, vec![0, 1]),
+        (RoundingMode::Round, vec![1, 2]),
+        (RoundingMode::HalfEven, vec![0, 2]),
+    ] {
+        let converter = SvgConverter::new(Precision::Normal).with_rounding_mode(rounding_mode);
+        assert_eq!(line_xs(converter)?, expected, "{rounding_mode:?}");
+    }
+    Ok(())
+}
+
+#[test]
+fn test_elements_override_the_precision() -> anyhow::Result<()> {
+    let svg = r##"<svg xmlns="http://www.w3.org/2000/svg" width="20" height="20">
+        <line x1="1" y1="1" x2="5" y2="5" stroke="#ffffff"/>
+        <g data-pdc-precision="precise">
+            <line x1="1" y1="1" x2="5" y2="5" stroke="#ffffff"/>
+            <line class="hand pdc-normal" x1="1" y1="1" x2="5" y2="5" stroke="#ffffff"/>
+        </g>
+        <line class="pdc-precise" x1="1" y1="1" x2="5" y2="5" stroke="#ffffff"/>
+    </svg>"##;
+    let precisions = |precision| -> anyhow::Result<Vec<Precision>> {
+        let image = SvgConverter::new(precision).parse_svg_image(
+            svg,
+            &TruncateColor::Truncate,
+            &Conversion::RequireExact,
+        )?;
+        Ok(image
+            .commands
+            .iter()
+            .map(|command| command.options().precision)
+            .collect())
+    };
+
+    use Precision::{Normal, Precise};
+    assert_eq!(precisions(Normal)?, vec![Normal, Precise, Normal, Precise]);
+    assert_eq!(
+        precisions(Precise)?,
+        vec![Precise, Precise, Normal, Precise]
+    );
+
+    let invalid = svg.replace(r#""precise""#, r#""smooth""#);
+    assert!(
+        SvgConverter::new(Normal)
+            .parse_svg_image(
+                &invalid,
+                &TruncateColor::Truncate,
+                &Conversion::RequireExact
+            )
+            .is_err()
+    );
+    Ok(())
+}
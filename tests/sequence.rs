@@ -0,0 +1,128 @@
+use std::time::Duration;
+
+use svg2pdc::{
+    point::{Conversion, PebblePoint},
+    prelude::*,
+};
+
+fn frame(width: u32, x: u32) -> anyhow::Result<PebbleImage> {
+    let svg = format!(
+        r##"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="20">
+            <rect x="{x}" y="1" width="4" height="4" fill="#ffffff"/>
+        </svg>"##
+    );
+    Ok(SvgConverter::new(Precision::Normal).parse_svg_image(
+        &svg,
+        &TruncateColor::Keep,
+        &Conversion::RequireExact,
+    )?)
+}
+
+#[test]
+fn test_sequence_round_trip() -> anyhow::Result<()> {
+    let frames = vec![frame(20, 1)?, frame(20, 5)?, frame(20, 9)?];
+    let sequence = PebbleSequence::from_images(frames, Duration::from_secs_f32(1.5), 3)?;
+    assert_eq!(sequence.size, PebblePoint { x: 20, y: 20 });
+    assert!(sequence.frames.iter().all(|frame| frame.duration == 500));
+
+    let mut bytes = Vec::new();
+    sequence.serialize(&mut bytes)?;
+    assert_eq!(&bytes[..4], b"PDCS");
+    assert_eq!(
+        u32::from_le_bytes(bytes[4..8].try_into()?) as usize,
+        bytes.len() - 8
+    );
+    // Version, reserved byte, size, play count and frame count
+    assert_eq!(bytes[8..18], [1, 0, 20, 0, 20, 0, 3, 0, 3, 0]);
+
+    let deserialized = PebbleSequence::deserialize(&mut bytes.as_slice())?;
+    assert_eq!(deserialized.play_count, 3);
+    assert_eq!(deserialized.frames.len(), 3);
+    let mut again = Vec::new();
+    deserialized.serialize(&mut again)?;
+    assert_eq!(again, bytes);
+    Ok(())
+}
+
+#[test]
+fn test_sequence_frames_must_share_their_size() -> anyhow::Result<()> {
+    let frames = vec![frame(20, 1)?, frame(20, 5)?, frame(30, 9)?];
+    let error = PebbleSequence::from_images(frames, Duration::from_secs(1), 1).unwrap_err();
+    assert!(matches!(
+        &error,
+        Svg2PdcError::IncompatibleCanvas(message) if message.starts_with("frame 2 is 30x20")
+    ));
+    assert!(PebbleSequence::from_images(Vec::new(), Duration::from_secs(1), 1).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_sequence_frames_keep_their_own_durations() -> anyhow::Result<()> {
+    let frames = vec![(frame(20, 1)?, 2000), (frame(20, 5)?, 100)];
+    let sequence = PebbleSequence::from_timed_images(frames, PebbleSequence::PLAY_FOREVER)?;
+
+    let mut bytes = Vec::new();
+    sequence.serialize(&mut bytes)?;
+    let deserialized = PebbleSequence::deserialize(&mut bytes.as_slice())?;
+    let durations: Vec<_> = deserialized
+        .frames
+        .iter()
+        .map(|frame| frame.duration)
+        .collect();
+    assert_eq!(durations, vec![2000, 100]);
+    assert_eq!(deserialized.play_count, u16::MAX);
+    Ok(())
+}
+
+#[test]
+fn test_top_level_groups_are_frames_without_layers() -> anyhow::Result<()> {
+    let svg = r##"<svg xmlns="http://www.w3.org/2000/svg" width="20" height="20">
+        <g id="first"><rect x="1" y="1" width="4" height="4" fill="#ffffff"/></g>
+        <g id="second" transform="translate(4, 0)">
+            <rect x="1" y="1" width="4" height="4" fill="#ffffff"/>
+            <g><rect x="9" y="9" width="4" height="4" fill="#ff0000"/></g>
+        </g>
+        <g id="third" display="none"/>
+    </svg>"##;
+    let frames = SvgConverter::new(Precision::Normal).parse_svg_layers(
+        svg,
+        &TruncateColor::Keep,
+        &Conversion::RequireExact,
+    )?;
+    let names: Vec<_> = frames.iter().map(|(name, _)| name.as_deref()).collect();
+    assert_eq!(names, vec![Some("first"), Some("second"), Some("third")]);
+    let command_counts: Vec<_> = frames
+        .iter()
+        .map(|(_, image)| image.commands.len())
+        .collect();
+    assert_eq!(command_counts, vec![1, 2, 0]);
+    assert!(frames.iter().all(|(_, image)| image.size.x == 20));
+    Ok(())
+}
+
+#[test]
+fn test_smil_animation_samples_into_frames() -> anyhow::Result<()> {
+    let svg = r##"<svg xmlns="http://www.w3.org/2000/svg" width="20" height="20">
+        <circle cx="2" cy="10" r="2" fill="#ffffff">
+            <animate attributeName="cx" values="2;18;2" dur="1s" repeatCount="indefinite"/>
+        </circle>
+    </svg>"##;
+    let length = svg2pdc::smil::animation_duration(svg)?.unwrap();
+    let converter = SvgConverter::new(Precision::Normal);
+    let centers = (0..4)
+        .map(|index| {
+            let sampled = svg2pdc::smil::sample_svg(svg, length * index as f64 / 4.0)?;
+            let image = converter.parse_svg_image(
+                &sampled,
+                &TruncateColor::Keep,
+                &Conversion::RequireExact,
+            )?;
+            match &image.commands[..] {
+                [DrawCommand::Circle { center, .. }] => Ok(center.x),
+                commands => anyhow::bail!("unexpected commands {commands:?}"),
+            }
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    assert_eq!(centers, vec![2, 10, 18, 10]);
+    Ok(())
+}
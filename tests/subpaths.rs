@@ -0,0 +1,132 @@
+use svg2pdc::{
+    point::{Conversion, PebblePoint},
+    prelude::*,
+    svg_converter::{FragmentOptions, HoleHandling},
+};
+
+const OPTIONS: FragmentOptions = FragmentOptions {
+    size: PebblePoint { x: 40, y: 40 },
+    translate: FPoint::new(0.0, 0.0),
+    truncate_color: TruncateColor::Truncate,
+    conversion: Conversion::RequireExact,
+};
+
+/// The points of a path command in pixels and whether it is open.
+type Subpath = (Vec<(i16, i16)>, bool);
+
+fn subpaths(d: &str) -> anyhow::Result<Vec<Subpath>> {
+    let image = SvgConverter::new(Precision::Normal)
+        .with_pixel_convention(PixelConvention::PixelCenter)
+        .parse_svg_fragment(
+            &format!(r##"<path d="{d}" stroke="#ffffff" fill="none"/>"##),
+            &OPTIONS,
+        )?;
+    Ok(image
+        .commands
+        .iter()
+        .map(|command| match command {
+            DrawCommand::Path { points, open, .. } => (
+                points.iter().map(|point| (point.x, point.y)).collect(),
+                *open,
+            ),
+            command => panic!("expected a path, got {command:?}"),
+        })
+        .collect())
+}
+
+#[test]
+fn test_every_move_starts_a_new_command() -> anyhow::Result<()> {
+    assert_eq!(
+        subpaths("M0 0 L10 0 L10 10 Z M20 20 L30 20 L30 30")?,
+        vec![
+            (vec![(0, 0), (10, 0), (10, 10)], false),
+            (vec![(20, 20), (30, 20), (30, 30)], true),
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_relative_move_after_close_starts_at_the_subpath_start() -> anyhow::Result<()> {
+    assert_eq!(
+        subpaths("M10 10 l5 0 l0 5 z m10 0 l5 0")?,
+        vec![
+            (vec![(10, 10), (15, 10), (15, 15)], false),
+            (vec![(20, 10), (25, 10)], true),
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_segment_after_close_starts_a_new_subpath() -> anyhow::Result<()> {
+    assert_eq!(
+        subpaths("M0 0 L10 0 L10 10 Z L5 5")?,
+        vec![
+            (vec![(0, 0), (10, 0), (10, 10)], false),
+            (vec![(0, 0), (5, 5)], true),
+        ]
+    );
+    // A close right after another one draws nothing more
+    assert_eq!(
+        subpaths("M0 0 L10 0 L10 10 Z Z")?,
+        vec![(vec![(0, 0), (10, 0), (10, 10)], false)]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_lone_moves_draw_nothing() -> anyhow::Result<()> {
+    assert_eq!(
+        subpaths("M0 0 M5 5 L10 10 M20 20")?,
+        vec![(vec![(5, 5), (10, 10)], true)]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_holes_of_filled_paths_are_reported_or_bridged() -> anyhow::Result<()> {
+    let donut = |fill_rule: &str, inner: &str| {
+        format!(
+            r##"<svg xmlns="http://www.w3.org/2000/svg" width="40" height="40">
+                <path d="M4 4 H36 V36 H4 Z {inner}" fill="#ff0000" fill-rule="{fill_rule}"/>
+            </svg>"##
+        )
+    };
+    let convert = |holes, svg: &str| {
+        SvgConverter::new(Precision::Normal)
+            .with_holes(holes)
+            .parse_svg_image_with_report(svg, &TruncateColor::Keep, &Conversion::RequireExact)
+    };
+    let clockwise = "M12 12 H28 V28 H12 Z";
+    let counterclockwise = "M12 12 V28 H28 V12 Z";
+
+    // Without a hole both subpaths are filled, as in SVG
+    let (image, report) = convert(HoleHandling::Warn, &donut("nonzero", clockwise))?;
+    assert_eq!(image.commands.len(), 2);
+    assert!(report.is_empty());
+
+    for svg in [
+        donut("evenodd", clockwise),
+        donut("nonzero", counterclockwise),
+    ] {
+        let (image, report) = convert(HoleHandling::Warn, &svg)?;
+        assert_eq!(image.commands.len(), 2);
+        assert_eq!(report.count(DiagnosticCode::FilledHoles), 1);
+
+        // One polygon walking around the hole and back
+        let (image, report) = convert(HoleHandling::Bridge, &svg)?;
+        assert!(report.is_empty());
+        match &image.commands[..] {
+            [DrawCommand::Path { points, .. }] => assert_eq!(points.len(), 4 + 4 + 2),
+            commands => panic!("unexpected commands {commands:?}"),
+        }
+
+        let (image, _) = convert(HoleHandling::Outline, &svg)?;
+        assert!(image.commands.iter().all(|command| {
+            let options = command.options();
+            options.fill_color == PebbleColor::nothing().inner() && options.stroke_width == 1
+        }));
+    }
+    Ok(())
+}
@@ -0,0 +1,150 @@
+use svg2pdc::{point::Conversion, prelude::*};
+
+const DOCUMENT: &str = r##"<svg xmlns="http://www.w3.org/2000/svg"
+    xmlns:sodipodi="http://sodipodi.sourceforge.net/DTD/sodipodi-0.dtd"
+    width="20" height="20">
+    <metadata>editor data</metadata>
+    <sodipodi:namedview pagecolor="#ffffff"/>
+    <text x="1" y="1">label</text>
+    <rect x="1" y="1" width="4" height="4" fill="#ffffff"/>
+</svg>"##;
+
+#[test]
+fn test_strict_mode_fails_only_on_unknown_tags() -> anyhow::Result<()> {
+    let convert = |converter: SvgConverter| {
+        converter.parse_svg_image(DOCUMENT, &TruncateColor::Keep, &Conversion::RequireExact)
+    };
+
+    let error = convert(SvgConverter::new(Precision::Normal).with_strict(true)).unwrap_err();
+    assert!(matches!(
+        &error,
+        Svg2PdcError::UnsupportedTag(element) if element.tag == "text" && element.line == 6
+    ));
+
+    let image = convert(
+        SvgConverter::new(Precision::Normal)
+            .with_strict(true)
+            .with_ignored_tags(["metadata", "sodipodi:namedview", "text"]),
+    )?;
+    assert_eq!(image.commands.len(), 1);
+
+    // The prefixed name doesn't match elements of other namespaces
+    let error = convert(
+        SvgConverter::new(Precision::Normal)
+            .with_strict(true)
+            .with_ignored_tags(["metadata", "inkscape:namedview", "text"]),
+    )
+    .unwrap_err();
+    assert!(matches!(error, Svg2PdcError::UnsupportedTag(_)));
+    Ok(())
+}
+
+#[test]
+fn test_invalid_colors_and_simplified_curves_are_warnings_unless_strict() -> anyhow::Result<()> {
+    let document = r##"<svg xmlns="http://www.w3.org/2000/svg" width="20" height="20">
+    <rect x="1" y="1" width="4" height="4" fill="#ffffff" stroke="blurple"/>
+    <path d="M0 0 C 5 0 10 5 10 10 A 2 2 0 0 1 14 10" stroke="#ffffff"/>
+</svg>"##;
+    let convert = |converter: SvgConverter| {
+        converter.parse_svg_image_with_report(
+            document,
+            &TruncateColor::Keep,
+            &Conversion::ConvertNoWarn,
+        )
+    };
+
+    let (image, report) = convert(SvgConverter::new(Precision::Normal))?;
+    assert_eq!(image.commands.len(), 2);
+    assert_eq!(report.count(DiagnosticCode::InvalidColor), 1);
+    assert_eq!(report.count(DiagnosticCode::CurveSimplified), 1);
+    assert_eq!(report.warnings[0].element.as_ref().unwrap().line, 2);
+
+    let (_, report) = convert(SvgConverter::new(Precision::Normal).with_curve_tolerance(0.5))?;
+    assert_eq!(report.count(DiagnosticCode::CurveSimplified), 0);
+
+    let error = convert(SvgConverter::new(Precision::Normal).with_strict(true)).unwrap_err();
+    assert!(matches!(
+        &error,
+        Svg2PdcError::InvalidAttribute { attribute, element, .. }
+            if attribute == "stroke" && element.line == 2
+    ));
+
+    let error = SvgConverter::new(Precision::Normal)
+        .with_strict(true)
+        .parse_svg_image(
+            &document.replace("blurple", "none"),
+            &TruncateColor::Keep,
+            &Conversion::ConvertNoWarn,
+        )
+        .unwrap_err();
+    assert!(matches!(
+        &error,
+        Svg2PdcError::InvalidAttribute { attribute, element, .. }
+            if attribute == "d" && element.line == 3
+    ));
+    Ok(())
+}
+
+#[test]
+fn test_clip_paths_masks_and_markers_are_omitted_with_one_warning() -> anyhow::Result<()> {
+    let document = r##"<svg xmlns="http://www.w3.org/2000/svg" width="20" height="20">
+    <clipPath id="clip"><rect x="0" y="0" width="5" height="5"/></clipPath>
+    <mask id="fade"><g><rect x="0" y="0" width="5" height="5" fill="#ffffff"/></g></mask>
+    <marker id="arrow"><path d="M0 0 L4 2 L0 4 Z" fill="#ffffff"/></marker>
+    <rect x="1" y="1" width="8" height="8" fill="#ffffff" clip-path="url(#clip)"/>
+    <rect x="1" y="1" width="8" height="8" fill="#ffffff" style="mask: url(#fade)"/>
+    <line x1="1" y1="1" x2="8" y2="8" stroke="#ffffff" marker-end="url(#arrow)"/>
+</svg>"##;
+
+    // Strict mode accepts them, they are never drawn by themselves
+    let (image, report) = SvgConverter::new(Precision::Normal)
+        .with_strict(true)
+        .parse_svg_image_with_report(document, &TruncateColor::Keep, &Conversion::RequireExact)?;
+    assert_eq!(image.commands.len(), 3);
+    assert_eq!(report.warnings.len(), 1);
+    assert_eq!(report.count(DiagnosticCode::ClippingOmitted), 1);
+    assert!(report.warnings[0].message.starts_with("3 elements"));
+
+    let (_, report) = SvgConverter::new(Precision::Normal).parse_svg_image_with_report(
+        r##"<svg xmlns="http://www.w3.org/2000/svg" width="20" height="20">
+    <clipPath id="unused"><rect x="0" y="0" width="5" height="5"/></clipPath>
+    <rect x="1" y="1" width="8" height="8" fill="#ffffff" clip-path="none"/>
+</svg>"##,
+        &TruncateColor::Keep,
+        &Conversion::RequireExact,
+    )?;
+    assert!(report.is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_raster_images_are_reported_with_their_position() -> anyhow::Result<()> {
+    let document = r##"<svg xmlns="http://www.w3.org/2000/svg" width="20" height="20">
+    <rect x="1" y="1" width="4" height="4" fill="#ffffff"/>
+    <image id="photo" href="photo.png" x="0" y="0" width="20" height="20"/>
+</svg>"##;
+
+    let (image, report) = SvgConverter::new(Precision::Normal).parse_svg_image_with_report(
+        document,
+        &TruncateColor::Keep,
+        &Conversion::RequireExact,
+    )?;
+    assert_eq!(image.commands.len(), 1);
+    assert_eq!(report.warnings.len(), 1);
+    assert_eq!(report.count(DiagnosticCode::RasterImage), 1);
+    assert!(
+        report.warnings[0]
+            .message
+            .starts_with(r#"line 3: <image id="photo">"#)
+    );
+
+    let error = SvgConverter::new(Precision::Normal)
+        .with_strict(true)
+        .parse_svg_image(document, &TruncateColor::Keep, &Conversion::RequireExact)
+        .unwrap_err();
+    assert!(matches!(
+        &error,
+        Svg2PdcError::RasterImage(element) if element.line == 3 && element.column == 5
+    ));
+    Ok(())
+}